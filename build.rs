@@ -1,4 +1,19 @@
+use std::{env, path::PathBuf};
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tonic_build::compile_protos("proto/authentication.proto")?;
+    let out_dir = PathBuf::from(env::var("OUT_DIR")?);
+
+    tonic_build::configure()
+        // Lets the protoc 3.12 to 3.14 of older distributions compile the `optional` fields.
+        .protoc_arg("--experimental_allow_proto3_optional")
+        .file_descriptor_set_path(out_dir.join("authentication_descriptor.bin"))
+        .compile(
+            &[
+                "proto/authentication.proto",
+                "proto/google/rpc/status.proto",
+                "proto/google/rpc/error_details.proto",
+            ],
+            &["proto"],
+        )?;
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,10 @@
+CREATE TABLE "user_events_outbox" (
+  id BIGSERIAL PRIMARY KEY,
+  event_type VARCHAR(255) NOT NULL,
+  user_id VARCHAR(255) NOT NULL,
+  payload TEXT NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  published_at TIMESTAMP
+);
+
+CREATE INDEX idx_user_events_outbox_unpublished ON user_events_outbox (id) WHERE published_at IS NULL;
@@ -0,0 +1,25 @@
+CREATE TABLE "webhooks" (
+  id VARCHAR(255) PRIMARY KEY,
+  url TEXT NOT NULL,
+  secret VARCHAR(255) NOT NULL,
+  event_types TEXT[] NOT NULL DEFAULT '{}',
+  created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE "webhook_deliveries" (
+  id BIGSERIAL PRIMARY KEY,
+  webhook_id VARCHAR(255) NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+  event_id BIGINT NOT NULL,
+  event_type VARCHAR(255) NOT NULL,
+  user_id VARCHAR(255) NOT NULL,
+  payload TEXT NOT NULL,
+  status VARCHAR(32) NOT NULL DEFAULT 'pending',
+  attempts INTEGER NOT NULL DEFAULT 0,
+  next_attempt_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  last_error TEXT,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  delivered_at TIMESTAMP,
+  CONSTRAINT unique_webhook_event UNIQUE (webhook_id, event_id)
+);
+
+CREATE INDEX idx_webhook_deliveries_pending ON webhook_deliveries (next_attempt_at) WHERE status = 'pending';
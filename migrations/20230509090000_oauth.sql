@@ -0,0 +1,28 @@
+CREATE TABLE oauth_clients (
+  id VARCHAR(255) PRIMARY KEY,
+  name VARCHAR(255) NOT NULL,
+  secret_hash VARCHAR(255),
+  redirect_uris TEXT[] NOT NULL DEFAULT '{}',
+  scopes TEXT[] NOT NULL DEFAULT '{}',
+  created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE oauth_consents (
+  user_id VARCHAR(255) NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+  client_id VARCHAR(255) NOT NULL REFERENCES oauth_clients (id) ON DELETE CASCADE,
+  scopes TEXT[] NOT NULL DEFAULT '{}',
+  granted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (user_id, client_id)
+);
+
+CREATE TABLE oauth_refresh_tokens (
+  token_hash VARCHAR(64) PRIMARY KEY,
+  client_id VARCHAR(255) NOT NULL REFERENCES oauth_clients (id) ON DELETE CASCADE,
+  user_id VARCHAR(255) NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+  scopes TEXT[] NOT NULL DEFAULT '{}',
+  expires_at TIMESTAMP NOT NULL,
+  revoked_at TIMESTAMP,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_oauth_refresh_tokens_user_id ON oauth_refresh_tokens (user_id);
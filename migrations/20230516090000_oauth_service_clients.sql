@@ -0,0 +1,2 @@
+ALTER TABLE oauth_clients
+ADD COLUMN service BOOLEAN NOT NULL DEFAULT false;
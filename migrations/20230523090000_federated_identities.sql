@@ -0,0 +1,10 @@
+CREATE TABLE federated_identities (
+  issuer VARCHAR(255) NOT NULL,
+  subject VARCHAR(255) NOT NULL,
+  user_id VARCHAR(255) NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+  email VARCHAR(255),
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (issuer, subject)
+);
+
+CREATE INDEX idx_federated_identities_user_id ON federated_identities (user_id);
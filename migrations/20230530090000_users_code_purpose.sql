@@ -0,0 +1,5 @@
+ALTER TABLE users_code
+ADD COLUMN purpose VARCHAR(32) NOT NULL DEFAULT 'account',
+ADD COLUMN attempts INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX idx_users_code_user_id_purpose ON users_code (user_id, purpose);
@@ -0,0 +1,22 @@
+CREATE TABLE users (
+  id TEXT NOT NULL PRIMARY KEY,
+  username TEXT NOT NULL CONSTRAINT unique_username UNIQUE,
+  email TEXT NOT NULL CONSTRAINT unique_email UNIQUE,
+  password TEXT NOT NULL,
+  activated BOOLEAN NOT NULL DEFAULT false,
+  blocked BOOLEAN NOT NULL DEFAULT false,
+  createdat TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+  updatedat TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE users_code (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  code TEXT NOT NULL,
+  expire_at TIMESTAMP NOT NULL,
+  user_id TEXT NOT NULL REFERENCES users(id),
+  purpose TEXT NOT NULL DEFAULT 'account',
+  attempts INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX idx_code_user_id ON users_code (code, user_id);
+CREATE INDEX idx_users_code_user_id_purpose ON users_code (user_id, purpose);
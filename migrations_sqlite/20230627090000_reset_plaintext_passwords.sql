@@ -0,0 +1,3 @@
+-- RecoverUserPassword stored the new password in plaintext. No password verifies against
+-- the value they are replaced with: these users recover their account again to log in.
+UPDATE users SET password = '!' WHERE password NOT LIKE '$2_$%';
@@ -0,0 +1,996 @@
+use crate::{
+    error::*,
+    security::{
+        federation::{FederatedProvider, FederatedProviders},
+        jwt::{
+            JwtConfig, DEFAULT_JWT_AUDIENCE, DEFAULT_JWT_ISSUER, DEFAULT_JWT_LEEWAY_SECONDS,
+            DEFAULT_JWT_LIFETIME_SECONDS,
+        },
+        webauthn::RelyingParty,
+    },
+    utils::{
+        generate_code::secret_generator::secret_generator, hash::password::DEFAULT_BCRYPT_COST,
+    },
+};
+use serde::Deserialize;
+use std::{env, fmt::Display, fs, net::SocketAddr, str::FromStr};
+
+/// Env var holding the path of the optional TOML config file.
+pub const CONFIG_FILE_ENV_VAR: &str = "CONFIG_FILE";
+
+pub const DEFAULT_DATABASE_MAX_CONNECTIONS: u32 = 100;
+pub const DEFAULT_ACCOUNT_CODE_TTL_MINUTES: i64 = 30;
+pub const DEFAULT_LOGIN_CODE_TTL_MINUTES: i64 = 10;
+pub const DEFAULT_GRPC_ADDRESS: &str = "0.0.0.0:50051";
+pub const DEFAULT_HTTP_ADDRESS: &str = "0.0.0.0:8080";
+pub const DEFAULT_MAIL_FROM: &str = "no-reply@localhost";
+pub const DEFAULT_MAIL_TIMEOUT_SECONDS: u64 = 10;
+pub const DEFAULT_METRICS_ADDRESS: &str = "0.0.0.0:9090";
+pub const DEFAULT_TLS_RELOAD_INTERVAL_SECONDS: u64 = 60;
+pub const DEFAULT_REDIS_COMMAND_TIMEOUT_MS: u64 = 1000;
+pub const DEFAULT_REDIS_CIRCUIT_BREAKER_THRESHOLD: u32 = 5;
+pub const DEFAULT_REDIS_CIRCUIT_BREAKER_OPEN_SECONDS: u64 = 10;
+pub const DEFAULT_SERVICE_NAME: &str = "authentication";
+pub const DEFAULT_SQLITE_URL: &str = "sqlite://authentication.db";
+
+/// Settings of the server, read once at startup and shared through `AppState`.
+///
+/// Every value comes from its env var, then from the TOML file named by `CONFIG_FILE`,
+/// then from its default. See `config.example.toml` for the file layout.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub database: DatabaseConfig,
+    pub redis: RedisConfig,
+    pub jwt: JwtConfig,
+    pub codes: CodesConfig,
+    pub password: PasswordConfig,
+    pub server: ServerConfig,
+    /// TLS of the gRPC server, which serves plaintext without it.
+    pub tls: Option<TlsConfig>,
+    pub telemetry: TelemetryConfig,
+    pub storage: StorageConfig,
+    pub mail: MailConfig,
+    pub oidc: OidcConfig,
+    /// Relying party of the passkeys, the WebAuthn RPCs fail without it.
+    pub webauthn: Option<RelyingParty>,
+    pub federation: FederationConfig,
+    pub tokens: TokensConfig,
+}
+
+#[derive(Debug, Clone)]
+pub struct DatabaseConfig {
+    /// Only required with `STORAGE_BACKEND=postgres`, no other storage reaches Postgres.
+    pub url: Option<String>,
+    pub max_connections: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct RedisConfig {
+    /// Only required when the users are in Postgres or their codes in Redis.
+    pub url: Option<String>,
+    /// Longest wait for a connection or the answer to a command.
+    pub command_timeout_ms: u64,
+    /// Consecutive failures after which calls fail fast without trying Redis.
+    pub circuit_breaker_threshold: u32,
+    /// Seconds calls keep failing fast before Redis is tried again.
+    pub circuit_breaker_open_seconds: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct CodesConfig {
+    /// Lifetime of the activation and recovery codes.
+    pub account_code_ttl_minutes: i64,
+    pub login_code_ttl_minutes: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct PasswordConfig {
+    pub bcrypt_cost: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    pub grpc_address: SocketAddr,
+    pub http_address: SocketAddr,
+    /// Address of the Prometheus `/metrics` endpoint.
+    pub metrics_address: SocketAddr,
+}
+
+#[derive(Debug, Clone)]
+pub struct TlsConfig {
+    /// PEM certificate chain and private key of the server.
+    pub cert_path: String,
+    pub key_path: String,
+    /// PEM bundle of the CAs trusted to sign client certificates, enabling mutual TLS.
+    pub client_ca_path: Option<String>,
+    /// Refuse clients without a certificate instead of just verifying the ones presented.
+    pub require_client_cert: bool,
+    /// Seconds between checks of the files for a renewed certificate.
+    pub reload_interval_seconds: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TelemetryConfig {
+    /// Base URL of the OTLP/HTTP collector the spans are exported to, none to not export.
+    pub otlp_endpoint: Option<String>,
+    /// `service.name` of the exported spans.
+    pub service_name: String,
+}
+
+/// Where users and their codes are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    Postgres,
+    /// A single SQLite file, for embedded deployments and local runs without Postgres.
+    Sqlite,
+    /// The memory of the process, for demos and tests. Everything is lost on exit.
+    Memory,
+}
+
+impl FromStr for StorageBackend {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "postgres" => Ok(StorageBackend::Postgres),
+            "sqlite" => Ok(StorageBackend::Sqlite),
+            "memory" => Ok(StorageBackend::Memory),
+            _ => Err(format!(
+                "expected postgres, sqlite or memory, got {}",
+                value
+            )),
+        }
+    }
+}
+
+/// Where the activation, recovery and login codes are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CodesBackend {
+    Redis,
+    /// The database of the users, whichever `StorageBackend` that is.
+    Database,
+}
+
+impl FromStr for CodesBackend {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "redis" => Ok(CodesBackend::Redis),
+            "database" => Ok(CodesBackend::Database),
+            _ => Err(format!("expected redis or database, got {}", value)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StorageConfig {
+    pub backend: StorageBackend,
+    pub codes: CodesBackend,
+    /// Database file of the SQLite backend, created with its tables on first start.
+    pub sqlite_url: String,
+}
+
+/// How the emails with the codes are sent.
+#[derive(Debug, Clone)]
+pub struct MailConfig {
+    /// Endpoint the emails are posted to as JSON, required unless `log_only`.
+    pub api_url: Option<String>,
+    /// Bearer token of the mail API.
+    pub api_key: Option<String>,
+    pub from: String,
+    pub timeout_seconds: u64,
+    /// Without a mail API, log the recipient and subject of each email instead of sending
+    /// it. For development only: the codes are not delivered.
+    pub log_only: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct OidcConfig {
+    /// Issuer of the ID tokens and base URL of the discovery document, without a trailing
+    /// `/`. The OpenID Connect endpoints fail without it.
+    pub issuer: Option<String>,
+    /// PEM PKCS#8 P-256 key signing the ID tokens, an ephemeral key is generated without it.
+    pub signing_key_path: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FederationConfig {
+    /// Providers trusted for `ExternalLogin`, none to refuse every external ID token.
+    pub providers: Vec<FederatedProvider>,
+}
+
+/// Static tokens of the administration RPCs and of the user events subscribers, nothing
+/// matches those left unset.
+#[derive(Debug, Clone)]
+pub struct TokensConfig {
+    pub admin: Option<String>,
+    pub events_subscriber: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    database: DatabaseFile,
+    redis: RedisFile,
+    jwt: JwtFile,
+    codes: CodesFile,
+    password: PasswordFile,
+    server: ServerFile,
+    tls: TlsFile,
+    telemetry: TelemetryFile,
+    storage: StorageFile,
+    mail: MailFile,
+    oidc: OidcFile,
+    webauthn: WebauthnFile,
+    federation: FederationFile,
+    tokens: TokensFile,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct DatabaseFile {
+    url: Option<String>,
+    max_connections: Option<u32>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct RedisFile {
+    url: Option<String>,
+    command_timeout_ms: Option<u64>,
+    circuit_breaker_threshold: Option<u32>,
+    circuit_breaker_open_seconds: Option<u64>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct JwtFile {
+    secret: Option<String>,
+    lifetime_seconds: Option<u64>,
+    issuer: Option<String>,
+    audience: Option<String>,
+    leeway_seconds: Option<u64>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct CodesFile {
+    account_code_ttl_minutes: Option<i64>,
+    login_code_ttl_minutes: Option<i64>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct PasswordFile {
+    bcrypt_cost: Option<u32>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ServerFile {
+    grpc_address: Option<SocketAddr>,
+    http_address: Option<SocketAddr>,
+    metrics_address: Option<SocketAddr>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct TlsFile {
+    cert_path: Option<String>,
+    key_path: Option<String>,
+    client_ca_path: Option<String>,
+    require_client_cert: Option<bool>,
+    reload_interval_seconds: Option<u64>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct TelemetryFile {
+    otlp_endpoint: Option<String>,
+    service_name: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct StorageFile {
+    backend: Option<StorageBackend>,
+    codes: Option<CodesBackend>,
+    sqlite_url: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct MailFile {
+    api_url: Option<String>,
+    api_key: Option<String>,
+    from: Option<String>,
+    timeout_seconds: Option<u64>,
+    log_only: Option<bool>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct OidcFile {
+    issuer: Option<String>,
+    signing_key_path: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct WebauthnFile {
+    rp_id: Option<String>,
+    rp_name: Option<String>,
+    origin: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct FederationFile {
+    providers: Option<Vec<FederatedProvider>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct TokensFile {
+    admin: Option<String>,
+    events_subscriber: Option<String>,
+}
+
+/// Resolves each setting from the env first and the file second, collecting every
+/// problem so a bad configuration is reported at once.
+struct Sources<'a> {
+    env: &'a dyn Fn(&str) -> Option<String>,
+    errors: Vec<String>,
+}
+
+impl Sources<'_> {
+    fn value<T: FromStr>(&mut self, env_var: &str, file_value: Option<T>, default: T) -> T
+    where
+        T::Err: Display,
+    {
+        match (self.env)(env_var).filter(|value| !value.is_empty()) {
+            Some(value) => match value.parse() {
+                Ok(value) => value,
+                Err(error) => {
+                    self.errors
+                        .push(format!("{} has an invalid value ({})", env_var, error));
+                    default
+                }
+            },
+            None => file_value.unwrap_or(default),
+        }
+    }
+
+    fn required(&mut self, env_var: &str, file_value: Option<String>) -> String {
+        let value = self.value(env_var, file_value, String::new());
+        if value.is_empty() {
+            self.errors.push(format!("{} is required", env_var));
+        }
+        value
+    }
+
+    fn optional(&mut self, env_var: &str, file_value: Option<String>) -> Option<String> {
+        Some(self.value(env_var, file_value, String::new())).filter(|value| !value.is_empty())
+    }
+
+    fn check(&mut self, valid: bool, message: &str) {
+        if !valid {
+            self.errors.push(message.to_string());
+        }
+    }
+
+    fn required_if(
+        &mut self,
+        needed: bool,
+        env_var: &str,
+        file_value: Option<String>,
+    ) -> Option<String> {
+        if needed {
+            Some(self.required(env_var, file_value))
+        } else {
+            self.optional(env_var, file_value)
+        }
+    }
+
+    fn tls(&mut self, file: TlsFile) -> Option<TlsConfig> {
+        let cert_path = self.optional("TLS_CERT_PATH", file.cert_path);
+        let key_path = self.optional("TLS_KEY_PATH", file.key_path);
+        let client_ca_path = self.optional("TLS_CLIENT_CA_PATH", file.client_ca_path);
+        let require_client_cert =
+            self.value("TLS_REQUIRE_CLIENT_CERT", file.require_client_cert, false);
+        let reload_interval_seconds = self.value(
+            "TLS_RELOAD_INTERVAL_SECONDS",
+            file.reload_interval_seconds,
+            DEFAULT_TLS_RELOAD_INTERVAL_SECONDS,
+        );
+
+        let (cert_path, key_path) = match (cert_path, key_path) {
+            (Some(cert_path), Some(key_path)) => (cert_path, key_path),
+            (None, None) => {
+                self.check(
+                    client_ca_path.is_none(),
+                    "TLS_CLIENT_CA_PATH requires TLS_CERT_PATH and TLS_KEY_PATH",
+                );
+                return None;
+            }
+            _ => {
+                self.check(false, "TLS_CERT_PATH and TLS_KEY_PATH must be set together");
+                return None;
+            }
+        };
+        self.check(
+            client_ca_path.is_some() || !require_client_cert,
+            "TLS_REQUIRE_CLIENT_CERT requires TLS_CLIENT_CA_PATH",
+        );
+        self.check(
+            reload_interval_seconds > 0,
+            "TLS_RELOAD_INTERVAL_SECONDS must be greater than zero",
+        );
+
+        Some(TlsConfig {
+            cert_path,
+            key_path,
+            client_ca_path,
+            require_client_cert,
+            reload_interval_seconds,
+        })
+    }
+
+    fn webauthn(&mut self, file: WebauthnFile) -> Option<RelyingParty> {
+        let id = self.optional("WEBAUTHN_RP_ID", file.rp_id);
+        let name = self.optional("WEBAUTHN_RP_NAME", file.rp_name);
+        let origin = self.optional("WEBAUTHN_ORIGIN", file.origin);
+
+        match (id, name, origin) {
+            (Some(id), Some(name), Some(origin)) => Some(RelyingParty { id, name, origin }),
+            (None, None, None) => None,
+            _ => {
+                self.check(
+                    false,
+                    "WEBAUTHN_RP_ID, WEBAUTHN_RP_NAME and WEBAUTHN_ORIGIN must be set together",
+                );
+                None
+            }
+        }
+    }
+
+    /// Without `JWT_SECRET`, users kept in memory get tokens signed with a secret drawn at
+    /// startup: neither outlives the process.
+    fn jwt(&mut self, file: JwtFile, users_in_memory: bool) -> JwtConfig {
+        let secret = if users_in_memory {
+            self.optional("JWT_SECRET", file.secret)
+                .unwrap_or_else(secret_generator)
+        } else {
+            self.required("JWT_SECRET", file.secret)
+        };
+        let jwt = JwtConfig {
+            secret,
+            lifetime: self.value(
+                "JWT_LIFETIME_SECONDS",
+                file.lifetime_seconds,
+                DEFAULT_JWT_LIFETIME_SECONDS,
+            ),
+            issuer: self.value("JWT_ISSUER", file.issuer, DEFAULT_JWT_ISSUER.to_string()),
+            audience: self.value(
+                "JWT_AUDIENCE",
+                file.audience,
+                DEFAULT_JWT_AUDIENCE.to_string(),
+            ),
+            leeway: self.value(
+                "JWT_LEEWAY_SECONDS",
+                file.leeway_seconds,
+                DEFAULT_JWT_LEEWAY_SECONDS,
+            ),
+        };
+        if !jwt.secret.is_empty() {
+            if let Err(error) = jwt.validate() {
+                self.errors.push(error.message);
+            }
+        }
+        jwt
+    }
+
+    fn finish<T>(self, value: T) -> Result<T, AppError> {
+        if self.errors.is_empty() {
+            return Ok(value);
+        }
+
+        Err(AppError::new(
+            Code::Internal,
+            format!("Invalid configuration: {}", self.errors.join("; ")),
+        ))
+    }
+}
+
+fn env_lookup(name: &str) -> Option<String> {
+    env::var(name).ok()
+}
+
+impl Config {
+    /// Read the config file named by `CONFIG_FILE`, if any, and the process env.
+    pub fn load() -> Result<Config, AppError> {
+        let file = match env::var(CONFIG_FILE_ENV_VAR) {
+            Ok(path) if !path.is_empty() => match fs::read_to_string(&path) {
+                Ok(file) => Some(file),
+                Err(error) => {
+                    return Err(AppError::new(
+                        Code::Internal,
+                        format!("Unable to read the config file {}: {}", path, error),
+                    ))
+                }
+            },
+            _ => None,
+        };
+
+        Config::from_sources(file.as_deref(), &env_lookup)
+    }
+
+    /// Build the config from the contents of a TOML file and an env lookup.
+    pub fn from_sources(
+        toml: Option<&str>,
+        env: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<Config, AppError> {
+        let file: ConfigFile = match toml.map(toml::from_str).transpose() {
+            Ok(file) => file.unwrap_or_default(),
+            Err(error) => {
+                return Err(AppError::new(
+                    Code::Internal,
+                    format!("Invalid config file: {}", error),
+                ))
+            }
+        };
+
+        let mut sources = Sources {
+            env,
+            errors: vec![],
+        };
+
+        let storage = StorageConfig {
+            backend: sources.value(
+                "STORAGE_BACKEND",
+                file.storage.backend,
+                StorageBackend::Postgres,
+            ),
+            codes: sources.value("CODES_BACKEND", file.storage.codes, CodesBackend::Redis),
+            sqlite_url: sources.value(
+                "SQLITE_URL",
+                file.storage.sqlite_url,
+                DEFAULT_SQLITE_URL.to_string(),
+            ),
+        };
+
+        let database = DatabaseConfig {
+            url: sources.required_if(
+                storage.backend == StorageBackend::Postgres,
+                "DATABASE_URL",
+                file.database.url,
+            ),
+            max_connections: sources.value(
+                "DATABASE_MAX_CONNECTIONS",
+                file.database.max_connections,
+                DEFAULT_DATABASE_MAX_CONNECTIONS,
+            ),
+        };
+        sources.check(
+            database.max_connections > 0,
+            "DATABASE_MAX_CONNECTIONS must be greater than zero",
+        );
+
+        let redis = RedisConfig {
+            url: sources.required_if(
+                match storage.backend {
+                    // The outbox dispatcher publishes the user events to Redis.
+                    StorageBackend::Postgres => true,
+                    StorageBackend::Sqlite => storage.codes == CodesBackend::Redis,
+                    StorageBackend::Memory => false,
+                },
+                "REDIS_CLIENT",
+                file.redis.url,
+            ),
+            command_timeout_ms: sources.value(
+                "REDIS_COMMAND_TIMEOUT_MS",
+                file.redis.command_timeout_ms,
+                DEFAULT_REDIS_COMMAND_TIMEOUT_MS,
+            ),
+            circuit_breaker_threshold: sources.value(
+                "REDIS_CIRCUIT_BREAKER_THRESHOLD",
+                file.redis.circuit_breaker_threshold,
+                DEFAULT_REDIS_CIRCUIT_BREAKER_THRESHOLD,
+            ),
+            circuit_breaker_open_seconds: sources.value(
+                "REDIS_CIRCUIT_BREAKER_OPEN_SECONDS",
+                file.redis.circuit_breaker_open_seconds,
+                DEFAULT_REDIS_CIRCUIT_BREAKER_OPEN_SECONDS,
+            ),
+        };
+        sources.check(
+            redis.command_timeout_ms > 0 && redis.circuit_breaker_threshold > 0,
+            "REDIS_COMMAND_TIMEOUT_MS and REDIS_CIRCUIT_BREAKER_THRESHOLD must be greater than zero",
+        );
+
+        let jwt = sources.jwt(file.jwt, storage.backend == StorageBackend::Memory);
+
+        let codes = CodesConfig {
+            account_code_ttl_minutes: sources.value(
+                "ACCOUNT_CODE_TTL_MINUTES",
+                file.codes.account_code_ttl_minutes,
+                DEFAULT_ACCOUNT_CODE_TTL_MINUTES,
+            ),
+            login_code_ttl_minutes: sources.value(
+                "LOGIN_CODE_TTL_MINUTES",
+                file.codes.login_code_ttl_minutes,
+                DEFAULT_LOGIN_CODE_TTL_MINUTES,
+            ),
+        };
+        sources.check(
+            codes.account_code_ttl_minutes > 0 && codes.login_code_ttl_minutes > 0,
+            "Code TTLs must be greater than zero",
+        );
+
+        let password = PasswordConfig {
+            bcrypt_cost: sources.value(
+                "BCRYPT_COST",
+                file.password.bcrypt_cost,
+                DEFAULT_BCRYPT_COST,
+            ),
+        };
+        sources.check(
+            (4..=31).contains(&password.bcrypt_cost),
+            "BCRYPT_COST must be between 4 and 31",
+        );
+
+        let server = ServerConfig {
+            grpc_address: sources.value(
+                "GRPC_LISTEN_ADDRESS",
+                file.server.grpc_address,
+                DEFAULT_GRPC_ADDRESS.parse().unwrap(),
+            ),
+            http_address: sources.value(
+                "HTTP_LISTEN_ADDRESS",
+                file.server.http_address,
+                DEFAULT_HTTP_ADDRESS.parse().unwrap(),
+            ),
+            metrics_address: sources.value(
+                "METRICS_LISTEN_ADDRESS",
+                file.server.metrics_address,
+                DEFAULT_METRICS_ADDRESS.parse().unwrap(),
+            ),
+        };
+
+        let tls = sources.tls(file.tls);
+
+        let telemetry = TelemetryConfig {
+            otlp_endpoint: sources
+                .optional("OTEL_EXPORTER_OTLP_ENDPOINT", file.telemetry.otlp_endpoint),
+            service_name: sources.value(
+                "OTEL_SERVICE_NAME",
+                file.telemetry.service_name,
+                DEFAULT_SERVICE_NAME.to_string(),
+            ),
+        };
+
+        let mail = MailConfig {
+            api_url: sources.optional("MAIL_API_URL", file.mail.api_url),
+            api_key: sources.optional("MAIL_API_KEY", file.mail.api_key),
+            from: sources.value("MAIL_FROM", file.mail.from, DEFAULT_MAIL_FROM.to_string()),
+            timeout_seconds: sources.value(
+                "MAIL_TIMEOUT_SECONDS",
+                file.mail.timeout_seconds,
+                DEFAULT_MAIL_TIMEOUT_SECONDS,
+            ),
+            log_only: sources.value("MAIL_LOG_ONLY", file.mail.log_only, false),
+        };
+        sources.check(
+            mail.timeout_seconds > 0,
+            "MAIL_TIMEOUT_SECONDS must be greater than zero",
+        );
+        sources.check(
+            mail.api_url.is_some() || mail.log_only,
+            "MAIL_API_URL is required, or MAIL_LOG_ONLY=true to only log the emails in development",
+        );
+
+        let oidc = OidcConfig {
+            issuer: sources
+                .optional("OIDC_ISSUER", file.oidc.issuer)
+                .map(|issuer| issuer.trim_end_matches('/').to_string()),
+            signing_key_path: sources.optional("OIDC_SIGNING_KEY_PATH", file.oidc.signing_key_path),
+        };
+
+        let webauthn = sources.webauthn(file.webauthn);
+
+        let federation = FederationConfig {
+            providers: sources
+                .value(
+                    "FEDERATED_IDENTITY_PROVIDERS",
+                    file.federation.providers.map(FederatedProviders),
+                    FederatedProviders(vec![]),
+                )
+                .0,
+        };
+
+        let tokens = TokensConfig {
+            admin: sources.optional("ADMIN_TOKEN", file.tokens.admin),
+            events_subscriber: sources
+                .optional("EVENTS_SUBSCRIBER_TOKEN", file.tokens.events_subscriber),
+        };
+
+        sources.finish(Config {
+            database,
+            redis,
+            jwt,
+            codes,
+            password,
+            server,
+            tls,
+            telemetry,
+            storage,
+            mail,
+            oidc,
+            webauthn,
+            federation,
+            tokens,
+        })
+    }
+}
+
+impl JwtConfig {
+    /// Read just the token settings from the process env.
+    pub fn from_env() -> Result<JwtConfig, AppError> {
+        let mut sources = Sources {
+            env: &env_lookup,
+            errors: vec![],
+        };
+        let jwt = sources.jwt(JwtFile::default(), false);
+
+        sources.finish(jwt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env_of(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    const FILE: &str = r#"
+        [database]
+        url = "postgres://file/authentication"
+        max_connections = 10
+
+        [redis]
+        url = "redis://file:6379/"
+
+        [jwt]
+        secret = "file secret"
+        lifetime_seconds = 900
+
+        [server]
+        grpc_address = "127.0.0.1:50051"
+
+        [mail]
+        log_only = true
+    "#;
+
+    #[test]
+    fn test_env_overrides_file_and_defaults_fill_the_rest() {
+        let config = Config::from_sources(
+            Some(FILE),
+            &env_of(&[("DATABASE_URL", "postgres://env/authentication")]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.database.url.as_deref(),
+            Some("postgres://env/authentication")
+        );
+        assert_eq!(config.database.max_connections, 10);
+        assert_eq!(config.redis.url.as_deref(), Some("redis://file:6379/"));
+        assert_eq!(
+            config.redis.command_timeout_ms,
+            DEFAULT_REDIS_COMMAND_TIMEOUT_MS
+        );
+        assert_eq!(config.jwt.lifetime, 900);
+        assert_eq!(config.jwt.issuer, DEFAULT_JWT_ISSUER);
+        assert_eq!(
+            config.codes.account_code_ttl_minutes,
+            DEFAULT_ACCOUNT_CODE_TTL_MINUTES
+        );
+        assert_eq!(config.password.bcrypt_cost, DEFAULT_BCRYPT_COST);
+        assert_eq!(config.server.grpc_address.to_string(), "127.0.0.1:50051");
+        assert_eq!(config.server.http_address.to_string(), DEFAULT_HTTP_ADDRESS);
+        assert_eq!(
+            config.server.metrics_address.to_string(),
+            DEFAULT_METRICS_ADDRESS
+        );
+        assert!(config.tls.is_none());
+        assert!(config.telemetry.otlp_endpoint.is_none());
+        assert_eq!(config.telemetry.service_name, DEFAULT_SERVICE_NAME);
+        assert!(config.mail.api_url.is_none());
+        assert!(config.mail.log_only);
+        assert_eq!(config.mail.from, DEFAULT_MAIL_FROM);
+    }
+
+    #[test]
+    fn test_tls_needs_certificate_and_key() {
+        let env = [
+            ("DATABASE_URL", "postgres://env/authentication"),
+            ("REDIS_CLIENT", "redis://env:6379/"),
+            ("JWT_SECRET", "secret"),
+            ("MAIL_LOG_ONLY", "true"),
+            ("TLS_CERT_PATH", "server.pem"),
+        ];
+
+        let error = Config::from_sources(None, &env_of(&env)).unwrap_err();
+        assert!(error
+            .message
+            .contains("TLS_CERT_PATH and TLS_KEY_PATH must be set together"));
+
+        let config = Config::from_sources(
+            Some("[tls]\nkey_path = \"server.key\"\nclient_ca_path = \"ca.pem\""),
+            &env_of(&env),
+        )
+        .unwrap();
+        let tls = config.tls.unwrap();
+        assert_eq!(tls.cert_path, "server.pem");
+        assert_eq!(tls.client_ca_path.as_deref(), Some("ca.pem"));
+        assert!(!tls.require_client_cert);
+    }
+
+    #[test]
+    fn test_oidc_webauthn_federation_and_tokens() {
+        let env = [
+            ("DATABASE_URL", "postgres://env/authentication"),
+            ("REDIS_CLIENT", "redis://env:6379/"),
+            ("JWT_SECRET", "secret"),
+            ("MAIL_API_URL", "https://mail.example.com/v1/send"),
+            ("OIDC_ISSUER", "https://auth.example.com/"),
+            ("WEBAUTHN_RP_ID", "example.com"),
+            ("ADMIN_TOKEN", "admin token"),
+        ];
+        let file = r#"
+            [webauthn]
+            rp_name = "Example"
+            origin = "https://example.com"
+
+            [[federation.providers]]
+            name = "corporate"
+            issuer = "https://sso.example.com"
+            jwks_uri = "https://sso.example.com/jwks"
+            client_id = "authentication"
+        "#;
+
+        let config = Config::from_sources(Some(file), &env_of(&env)).unwrap();
+        assert_eq!(
+            config.oidc.issuer.as_deref(),
+            Some("https://auth.example.com")
+        );
+        assert!(config.oidc.signing_key_path.is_none());
+        assert_eq!(config.webauthn.unwrap().origin, "https://example.com");
+        assert_eq!(config.federation.providers[0].name, "corporate");
+        assert_eq!(config.tokens.admin.as_deref(), Some("admin token"));
+        assert!(config.tokens.events_subscriber.is_none());
+
+        let error = Config::from_sources(
+            None,
+            &env_of(&[env.as_slice(), &[("FEDERATED_IDENTITY_PROVIDERS", "{")]].concat()),
+        )
+        .unwrap_err();
+        assert!(error
+            .message
+            .contains("WEBAUTHN_RP_ID, WEBAUTHN_RP_NAME and WEBAUTHN_ORIGIN must be set together"));
+        assert!(error
+            .message
+            .contains("FEDERATED_IDENTITY_PROVIDERS has an invalid value"));
+    }
+
+    #[test]
+    fn test_invalid_config_reports_every_problem() {
+        let error = Config::from_sources(
+            None,
+            &env_of(&[
+                ("REDIS_CLIENT", "redis://env:6379/"),
+                ("JWT_SECRET", "secret"),
+                ("BCRYPT_COST", "2"),
+                ("GRPC_LISTEN_ADDRESS", "not an address"),
+            ]),
+        )
+        .unwrap_err();
+
+        assert_eq!(error.code, Code::Internal);
+        assert!(error.message.contains("DATABASE_URL is required"));
+        assert!(error
+            .message
+            .contains("BCRYPT_COST must be between 4 and 31"));
+        assert!(error
+            .message
+            .contains("GRPC_LISTEN_ADDRESS has an invalid value"));
+    }
+
+    #[test]
+    fn test_storage_backends_are_chosen_by_name() {
+        let env = [
+            ("DATABASE_URL", "postgres://env/authentication"),
+            ("REDIS_CLIENT", "redis://env:6379/"),
+            ("JWT_SECRET", "secret"),
+            ("MAIL_LOG_ONLY", "true"),
+        ];
+
+        let config = Config::from_sources(None, &env_of(&env)).unwrap();
+        assert_eq!(config.storage.backend, StorageBackend::Postgres);
+        assert_eq!(config.storage.codes, CodesBackend::Redis);
+
+        let config = Config::from_sources(
+            Some("[storage]\nbackend = \"sqlite\"\ncodes = \"database\""),
+            &env_of(&env),
+        )
+        .unwrap();
+        assert_eq!(config.storage.backend, StorageBackend::Sqlite);
+        assert_eq!(config.storage.codes, CodesBackend::Database);
+        assert_eq!(config.storage.sqlite_url, DEFAULT_SQLITE_URL);
+
+        let error = Config::from_sources(
+            None,
+            &env_of(&[env[0], env[1], env[2], env[3], ("STORAGE_BACKEND", "mysql")]),
+        )
+        .unwrap_err();
+        assert!(error.message.contains(
+            "STORAGE_BACKEND has an invalid value (expected postgres, sqlite or memory, got mysql)"
+        ));
+    }
+
+    #[test]
+    fn test_memory_storage_needs_neither_postgres_nor_redis_nor_jwt_secret() {
+        let config = Config::from_sources(
+            None,
+            &env_of(&[("STORAGE_BACKEND", "memory"), ("MAIL_LOG_ONLY", "true")]),
+        )
+        .unwrap();
+        assert!(config.database.url.is_none());
+        assert!(config.redis.url.is_none());
+        assert!(!config.jwt.secret.is_empty());
+
+        let error =
+            Config::from_sources(None, &env_of(&[("STORAGE_BACKEND", "sqlite")])).unwrap_err();
+        assert!(!error.message.contains("DATABASE_URL is required"));
+        assert!(error.message.contains("REDIS_CLIENT is required"));
+        assert!(error.message.contains("JWT_SECRET is required"));
+    }
+
+    #[test]
+    fn test_mail_api_is_required_unless_emails_are_only_logged() {
+        let env = [
+            ("DATABASE_URL", "postgres://env/authentication"),
+            ("REDIS_CLIENT", "redis://env:6379/"),
+            ("JWT_SECRET", "secret"),
+        ];
+
+        let error = Config::from_sources(None, &env_of(&env)).unwrap_err();
+        assert!(error.message.contains("MAIL_API_URL is required"));
+
+        let config = Config::from_sources(
+            None,
+            &env_of(&[env.as_slice(), &[("MAIL_LOG_ONLY", "true")]].concat()),
+        )
+        .unwrap();
+        assert!(config.mail.api_url.is_none());
+        assert!(config.mail.log_only);
+    }
+
+    #[test]
+    fn test_unknown_file_keys_are_refused() {
+        let error =
+            Config::from_sources(Some("[database]\nurll = \"typo\""), &env_of(&[])).unwrap_err();
+
+        assert!(error.message.starts_with("Invalid config file"));
+    }
+}
@@ -9,13 +9,15 @@ use crate::{
     models::authentication_model::AuthenticationModel,
 };
 use async_trait::async_trait;
+use tracing::{field, instrument, Span};
 
 use crate::{
     dtos::controllers::dtos_controller_user::*, security::jwt::JwtEncode,
     services::sanitizer::sanitize_authentication_input::SanitizeAuthentication,
 };
 use crate::{
-    error::{AppError, Code},
+    error::{AppError, Code, Reason},
+    i18n::{t, Message},
     security::jwt::{JWTAuthenticateToken, JwtDecode},
 };
 
@@ -28,6 +30,7 @@ pub trait AuthenticationController: Sync + Send {
         &self,
         token: String,
     ) -> Result<UserControllerAuthenticationReturn, AppError>;
+    async fn user_info(&self, token: String) -> Result<UserControllerUserInfoReturn, AppError>;
     async fn update(&self, token: String, req: UpdateParams) -> Result<String, AppError>;
     async fn update_email(&self, token: String, email: String) -> Result<String, AppError>;
     async fn update_password(
@@ -50,12 +53,15 @@ pub struct UserController<M, S> {
     pub sanitize_user: S,
     pub jwt_encode: JwtEncode,
     pub jwt_decode: JwtDecode,
+    /// Decodes the tokens UserInfo accepts, which include the access tokens of OAuth clients.
+    pub jwt_decode_access: JwtDecode,
 }
 
 #[async_trait]
 impl<M: AuthenticationModel, S: SanitizeAuthentication> AuthenticationController
     for UserController<M, S>
 {
+    #[instrument(skip_all, fields(user_id = field::Empty), err(level = "warn", Debug))]
     async fn register(
         &self,
         req: RegisterParams,
@@ -73,6 +79,7 @@ impl<M: AuthenticationModel, S: SanitizeAuthentication> AuthenticationController
             })
             .await?;
 
+        Span::current().record("user_id", user.id.as_str());
         let token = (self.jwt_encode)(user.id.clone(), user.activated, user.blocked)?;
 
         Ok(UserControllerRegisterReturn {
@@ -87,6 +94,7 @@ impl<M: AuthenticationModel, S: SanitizeAuthentication> AuthenticationController
         })
     }
 
+    #[instrument(skip_all, fields(user_id = field::Empty), err(level = "warn", Debug))]
     async fn login(&self, req: LoginParams) -> Result<UserControllerLoginReturn, AppError> {
         let username_sanitized = self.sanitize_user.sanitize_username_input(req.username)?;
         let password_sanitized = self.sanitize_user.sanitize_password_input(req.password)?;
@@ -96,6 +104,7 @@ impl<M: AuthenticationModel, S: SanitizeAuthentication> AuthenticationController
             .login_verification(username_sanitized, password_sanitized)
             .await?;
 
+        Span::current().record("user_id", user.id.as_str());
         let token = (self.jwt_encode)(user.id.clone(), user.activated, user.blocked)?;
 
         Ok(UserControllerLoginReturn {
@@ -110,11 +119,13 @@ impl<M: AuthenticationModel, S: SanitizeAuthentication> AuthenticationController
         })
     }
 
+    #[instrument(skip_all, fields(user_id = field::Empty), err(level = "warn", Debug))]
     async fn recover_user_data(
         &self,
         token: String,
     ) -> Result<UserControllerAuthenticationReturn, AppError> {
         let JWTAuthenticateToken { sub: user_id, .. } = (self.jwt_decode)(&token)?;
+        Span::current().record("user_id", user_id.as_str());
 
         let user = self.model.recover_user_data(user_id.clone()).await?;
 
@@ -129,20 +140,32 @@ impl<M: AuthenticationModel, S: SanitizeAuthentication> AuthenticationController
         })
     }
 
+    #[instrument(skip_all, fields(user_id = field::Empty), err(level = "warn", Debug))]
+    async fn user_info(&self, token: String) -> Result<UserControllerUserInfoReturn, AppError> {
+        let token = (self.jwt_decode_access)(&token)?;
+        Span::current().record("user_id", token.sub.as_str());
+
+        let user = self.model.recover_user_data(token.sub.clone()).await?;
+        let profile = token.grants("profile");
+        let email = token.grants("email");
+
+        Ok(UserControllerUserInfoReturn {
+            sub: token.sub,
+            preferred_username: profile.then_some(user.username),
+            email: email.then_some(user.email),
+            email_verified: email.then_some(user.activated),
+        })
+    }
+
+    #[instrument(skip_all, fields(user_id = field::Empty), err(level = "warn", Debug))]
     async fn update(&self, token: String, req: UpdateParams) -> Result<String, AppError> {
         let username_sanitized = match req.username {
-            Some(username) => match self.sanitize_user.sanitize_username_input(username) {
-                Ok(username) => Some(username),
-                Err(_) => None,
-            },
+            Some(username) => self.sanitize_user.sanitize_username_input(username).ok(),
             None => None,
         };
 
         let email_sanitized = match req.email {
-            Some(email) => match self.sanitize_user.sanitize_email_input(email) {
-                Ok(email) => Some(email),
-                Err(_) => None,
-            },
+            Some(email) => self.sanitize_user.sanitize_email_input(email).ok(),
             None => None,
         };
 
@@ -152,13 +175,20 @@ impl<M: AuthenticationModel, S: SanitizeAuthentication> AuthenticationController
             blocked,
             ..
         } = (self.jwt_decode)(&token)?;
+        Span::current().record("user_id", user_id.as_str());
 
         if blocked {
-            return Err(AppError::new(Code::PermissionDenied, "User are blocked"));
+            return Err(
+                AppError::new(Code::PermissionDenied, t(Message::UserBlocked))
+                    .with_reason(Reason::UserBlocked),
+            );
         }
 
         if !activated {
-            return Err(AppError::new(Code::PermissionDenied, "User not activated"));
+            return Err(
+                AppError::new(Code::PermissionDenied, t(Message::UserNotActivated))
+                    .with_reason(Reason::UserNotActivated),
+            );
         }
 
         let message = self
@@ -175,10 +205,12 @@ impl<M: AuthenticationModel, S: SanitizeAuthentication> AuthenticationController
         Ok(message)
     }
 
+    #[instrument(skip_all, fields(user_id = field::Empty), err(level = "warn", Debug))]
     async fn update_email(&self, token: String, email: String) -> Result<String, AppError> {
         let email_sanitized = self.sanitize_user.sanitize_email_input(email)?;
 
         let JWTAuthenticateToken { sub: user_id, .. } = (self.jwt_decode)(&token)?;
+        Span::current().record("user_id", user_id.as_str());
 
         let message = self
             .model
@@ -194,6 +226,7 @@ impl<M: AuthenticationModel, S: SanitizeAuthentication> AuthenticationController
         Ok(message)
     }
 
+    #[instrument(skip_all, fields(user_id = field::Empty), err(level = "warn", Debug))]
     async fn update_password(
         &self,
         token: String,
@@ -212,13 +245,20 @@ impl<M: AuthenticationModel, S: SanitizeAuthentication> AuthenticationController
             blocked,
             ..
         } = (self.jwt_decode)(&token)?;
+        Span::current().record("user_id", user_id.as_str());
 
         if blocked {
-            return Err(AppError::new(Code::PermissionDenied, "User are blocked"));
+            return Err(
+                AppError::new(Code::PermissionDenied, t(Message::UserBlocked))
+                    .with_reason(Reason::UserBlocked),
+            );
         }
 
         if !activated {
-            return Err(AppError::new(Code::PermissionDenied, "User not activated"));
+            return Err(
+                AppError::new(Code::PermissionDenied, t(Message::UserNotActivated))
+                    .with_reason(Reason::UserNotActivated),
+            );
         }
 
         let message = self
@@ -229,18 +269,20 @@ impl<M: AuthenticationModel, S: SanitizeAuthentication> AuthenticationController
         Ok(message)
     }
 
+    #[instrument(skip_all, fields(user_id = field::Empty), err(level = "warn", Debug))]
     async fn create_activation_code(&self, token: String) -> Result<String, AppError> {
         let JWTAuthenticateToken {
             sub: user_id,
             activated,
             ..
         } = (self.jwt_decode)(&token)?;
+        Span::current().record("user_id", user_id.as_str());
 
         if activated {
-            return Err(AppError::new(
-                Code::PermissionDenied,
-                "User already activated",
-            ));
+            return Err(
+                AppError::new(Code::PermissionDenied, t(Message::UserAlreadyActivated))
+                    .with_reason(Reason::UserAlreadyActivated),
+            );
         }
 
         let code_key = self.model.create_code_by_user_id(user_id).await?;
@@ -248,25 +290,28 @@ impl<M: AuthenticationModel, S: SanitizeAuthentication> AuthenticationController
         Ok(code_key)
     }
 
+    #[instrument(skip_all, fields(user_id = field::Empty), err(level = "warn", Debug))]
     async fn activate_user(&self, token: String, code_key: String) -> Result<String, AppError> {
         let JWTAuthenticateToken {
             sub: user_id,
             activated,
             ..
         } = (self.jwt_decode)(&token)?;
+        Span::current().record("user_id", user_id.as_str());
 
         if activated {
-            return Err(AppError::new(
-                Code::PermissionDenied,
-                "User already activated",
-            ));
+            return Err(
+                AppError::new(Code::PermissionDenied, t(Message::UserAlreadyActivated))
+                    .with_reason(Reason::UserAlreadyActivated),
+            );
         }
 
         self.model.activate_user(user_id, code_key).await?;
 
-        Ok(String::from("User activated successfully"))
+        Ok(t(Message::UserActivatedSuccessfully))
     }
 
+    #[instrument(skip_all, fields(user_id = field::Empty), err(level = "warn", Debug))]
     async fn create_recovery_code(&self, email: String) -> Result<String, AppError> {
         let email_sanitized = self.sanitize_user.sanitize_email_input(email)?;
 
@@ -275,6 +320,7 @@ impl<M: AuthenticationModel, S: SanitizeAuthentication> AuthenticationController
         Ok(code)
     }
 
+    #[instrument(skip_all, fields(user_id = field::Empty), err(level = "warn", Debug))]
     async fn recover_user_password(
         &self,
         req: UserControllerRecoverPasswordReq,
@@ -288,11 +334,13 @@ impl<M: AuthenticationModel, S: SanitizeAuthentication> AuthenticationController
             .recover_user_password(email_sanitized, password_sanitized, req.code_key)
             .await?;
 
-        Ok(String::from("Password recovered successfully"))
+        Ok(t(Message::PasswordRecoveredSuccessfully))
     }
 
+    #[instrument(skip_all, fields(user_id = field::Empty), err(level = "warn", Debug))]
     async fn delete_user(&self, token: String) -> Result<String, AppError> {
         let JWTAuthenticateToken { sub: user_id, .. } = (self.jwt_decode)(&token)?;
+        Span::current().record("user_id", user_id.as_str());
 
         Ok(self.model.delete_user(user_id).await?)
     }
@@ -0,0 +1,49 @@
+use crate::{
+    dtos::controllers::{dtos_controller_federation::*, dtos_controller_user::UserResponse},
+    error::*,
+    models::federation_model::FederatedLoginModel,
+    security::jwt::JwtEncode,
+};
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait FederatedLoginController: Sync + Send {
+    async fn external_login(
+        &self,
+        req: ExternalLoginParams,
+    ) -> Result<FederationControllerLoginReturn, AppError>;
+}
+
+pub struct FederationController<M> {
+    pub model: M,
+    pub jwt_encode: JwtEncode,
+}
+
+#[async_trait]
+impl<M: FederatedLoginModel> FederatedLoginController for FederationController<M> {
+    async fn external_login(
+        &self,
+        req: ExternalLoginParams,
+    ) -> Result<FederationControllerLoginReturn, AppError> {
+        let id_token = req.id_token.trim().to_string();
+        if id_token.is_empty() {
+            return Err(AppError::new(Code::InvalidArgument, "ID token is empty"));
+        }
+
+        let user = self.model.external_login(id_token).await?;
+
+        let token = (self.jwt_encode)(user.id.clone(), user.activated, user.blocked)?;
+
+        Ok(FederationControllerLoginReturn {
+            user: UserResponse {
+                id: user.id,
+                username: user.username,
+                email: user.email,
+                activated: user.activated,
+                blocked: user.blocked,
+            },
+            token,
+            created: user.created,
+        })
+    }
+}
@@ -1 +1,7 @@
 pub mod authentication_controller;
+pub mod federation_controller;
+pub mod oauth_controller;
+pub mod passwordless_controller;
+pub mod personal_access_token_controller;
+pub mod webauthn_controller;
+pub mod webhook_controller;
@@ -0,0 +1,219 @@
+use crate::{
+    config::app_config::OidcConfig,
+    dtos::{controllers::dtos_controller_oauth::*, models::dtos_model_oauth::*},
+    error::AppError,
+    models::oauth_model::AuthorizationServerModel,
+    security::{
+        admin::AuthorizeAdmin,
+        jwt::{JwtEncodeAccess, JwtEncodeService},
+        oidc::{IdTokenEncode, IdTokenParams},
+    },
+};
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait AuthorizationServerController: Sync + Send {
+    async fn register_client(
+        &self,
+        token: String,
+        req: RegisterOAuthClientParams,
+    ) -> Result<OAuthControllerRegisterClientReturn, AppError>;
+    async fn validate_authorization_request(
+        &self,
+        req: AuthorizationRequestParams,
+    ) -> Result<AuthorizationRequestClient, AppError>;
+    async fn authorize(&self, req: AuthorizeParams) -> Result<String, AppError>;
+    async fn exchange_authorization_code(
+        &self,
+        req: AuthorizationCodeGrantParams,
+    ) -> Result<OAuthTokenResponse, AppError>;
+    async fn refresh_access_token(
+        &self,
+        req: RefreshTokenGrantParams,
+    ) -> Result<OAuthTokenResponse, AppError>;
+    async fn issue_service_token(
+        &self,
+        req: ClientCredentialsGrantParams,
+    ) -> Result<OAuthTokenResponse, AppError>;
+}
+
+pub struct OAuthController<M> {
+    pub model: M,
+    pub jwt_encode_access: JwtEncodeAccess,
+    pub jwt_encode_service: JwtEncodeService,
+    pub id_token_encode: IdTokenEncode,
+    pub oidc: OidcConfig,
+    pub authorize_admin: AuthorizeAdmin,
+    pub admin_token: Option<String>,
+}
+
+pub const OPENID_SCOPE: &str = "openid";
+
+/// Split the space-delimited `scope` parameter of OAuth2 requests.
+pub fn parse_scope(scope: &str) -> Vec<String> {
+    scope.split_whitespace().map(str::to_string).collect()
+}
+
+impl<M: AuthorizationServerModel> OAuthController<M> {
+    fn token_response(
+        &self,
+        tokens: OAuthModelTokenReturn,
+    ) -> Result<OAuthTokenResponse, AppError> {
+        let has_scope = |scope: &str| tokens.scopes.iter().any(|granted| granted == scope);
+
+        // ID tokens are only issued with the authorization code, where the user signed in.
+        let id_token = match (&tokens.authentication, has_scope(OPENID_SCOPE)) {
+            (Some(authentication), true) => {
+                let profile = has_scope("profile");
+                let email = has_scope("email");
+
+                Some((self.id_token_encode)(
+                    &self.oidc,
+                    IdTokenParams {
+                        sub: tokens.user_id.clone(),
+                        aud: tokens.client_id.clone(),
+                        auth_time: authentication.auth_time,
+                        nonce: authentication.nonce.clone(),
+                        preferred_username: profile.then(|| tokens.username.clone()),
+                        email: email.then(|| tokens.email.clone()),
+                        email_verified: email.then_some(tokens.activated),
+                    },
+                )?)
+            }
+            _ => None,
+        };
+
+        let access_token = (self.jwt_encode_access)(
+            tokens.user_id,
+            tokens.activated,
+            tokens.blocked,
+            tokens.scopes.clone(),
+        )?;
+
+        Ok(OAuthTokenResponse {
+            access_token,
+            token_type: String::from("Bearer"),
+            refresh_token: Some(tokens.refresh_token),
+            scope: tokens.scopes.join(" "),
+            id_token,
+        })
+    }
+}
+
+fn map_client_credentials(client: ClientCredentialsParams) -> OAuthModelClientCredentials {
+    OAuthModelClientCredentials {
+        client_id: client.client_id,
+        client_secret: client.client_secret.filter(|secret| !secret.is_empty()),
+    }
+}
+
+#[async_trait]
+impl<M: AuthorizationServerModel> AuthorizationServerController for OAuthController<M> {
+    async fn register_client(
+        &self,
+        token: String,
+        req: RegisterOAuthClientParams,
+    ) -> Result<OAuthControllerRegisterClientReturn, AppError> {
+        (self.authorize_admin)(&token, self.admin_token.as_deref())?;
+
+        let client = self
+            .model
+            .register_client(OAuthModelRegisterClientParams {
+                name: req.name.trim().to_string(),
+                redirect_uris: req.redirect_uris,
+                scopes: req.scopes,
+                confidential: req.confidential,
+                service: req.service,
+            })
+            .await?;
+
+        Ok(OAuthControllerRegisterClientReturn {
+            client_id: client.client_id,
+            client_secret: client.client_secret,
+        })
+    }
+
+    async fn validate_authorization_request(
+        &self,
+        req: AuthorizationRequestParams,
+    ) -> Result<AuthorizationRequestClient, AppError> {
+        let client = self
+            .model
+            .validate_authorization_request(
+                req.client_id,
+                req.redirect_uri,
+                parse_scope(&req.scope),
+            )
+            .await?;
+
+        Ok(AuthorizationRequestClient {
+            client_id: client.id,
+            client_name: client.name,
+            scopes: client.scopes,
+        })
+    }
+
+    async fn authorize(&self, req: AuthorizeParams) -> Result<String, AppError> {
+        self.model
+            .authorize(OAuthModelAuthorizeParams {
+                client_id: req.request.client_id,
+                redirect_uri: req.request.redirect_uri,
+                scopes: parse_scope(&req.request.scope),
+                code_challenge: req.request.code_challenge,
+                code_challenge_method: req.request.code_challenge_method,
+                username: req.username,
+                password: req.password,
+                consent_granted: req.consent_granted,
+                nonce: req.request.nonce,
+            })
+            .await
+    }
+
+    async fn exchange_authorization_code(
+        &self,
+        req: AuthorizationCodeGrantParams,
+    ) -> Result<OAuthTokenResponse, AppError> {
+        let tokens = self
+            .model
+            .exchange_authorization_code(OAuthModelExchangeCodeParams {
+                client: map_client_credentials(req.client),
+                code: req.code,
+                redirect_uri: req.redirect_uri,
+                code_verifier: req.code_verifier,
+            })
+            .await?;
+
+        self.token_response(tokens)
+    }
+
+    async fn refresh_access_token(
+        &self,
+        req: RefreshTokenGrantParams,
+    ) -> Result<OAuthTokenResponse, AppError> {
+        let tokens = self
+            .model
+            .refresh_access_token(map_client_credentials(req.client), req.refresh_token)
+            .await?;
+
+        self.token_response(tokens)
+    }
+
+    async fn issue_service_token(
+        &self,
+        req: ClientCredentialsGrantParams,
+    ) -> Result<OAuthTokenResponse, AppError> {
+        let token = self
+            .model
+            .issue_service_token(map_client_credentials(req.client), parse_scope(&req.scope))
+            .await?;
+        let scope = token.scopes.join(" ");
+
+        Ok(OAuthTokenResponse {
+            access_token: (self.jwt_encode_service)(token.client_id, token.scopes)?,
+            token_type: String::from("Bearer"),
+            refresh_token: None,
+            scope,
+            id_token: None,
+        })
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{
+    dtos::controllers::{
+        dtos_controller_passwordless::*,
+        dtos_controller_user::{UserControllerLoginReturn, UserResponse},
+    },
+    error::*,
+    i18n::{t, Message},
+    models::passwordless_model::PasswordlessLoginModel,
+    security::jwt::JwtEncode,
+    services::sanitizer::sanitize_authentication_input::SanitizeAuthentication,
+};
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait PasswordlessLoginController: Sync + Send {
+    async fn request_login_code(
+        &self,
+        req: RequestLoginCodeParams,
+    ) -> Result<PasswordlessControllerLoginCodeReturn, AppError>;
+    async fn login_with_code(
+        &self,
+        req: LoginWithCodeParams,
+    ) -> Result<UserControllerLoginReturn, AppError>;
+}
+
+pub struct PasswordlessController<M, S> {
+    pub model: M,
+    pub sanitize_user: S,
+    pub jwt_encode: JwtEncode,
+}
+
+#[async_trait]
+impl<M: PasswordlessLoginModel, S: SanitizeAuthentication> PasswordlessLoginController
+    for PasswordlessController<M, S>
+{
+    async fn request_login_code(
+        &self,
+        req: RequestLoginCodeParams,
+    ) -> Result<PasswordlessControllerLoginCodeReturn, AppError> {
+        let email_sanitized = self.sanitize_user.sanitize_email_input(req.email)?;
+
+        let code = self.model.create_login_code(email_sanitized).await?;
+
+        Ok(PasswordlessControllerLoginCodeReturn {
+            expires_in: code.expires_in,
+        })
+    }
+
+    async fn login_with_code(
+        &self,
+        req: LoginWithCodeParams,
+    ) -> Result<UserControllerLoginReturn, AppError> {
+        let email_sanitized = self.sanitize_user.sanitize_email_input(req.email)?;
+        let code = req.code.trim().to_string();
+        if code.is_empty() {
+            return Err(AppError::new(Code::InvalidArgument, t(Message::CodeEmpty)));
+        }
+
+        let user = self.model.login_with_code(email_sanitized, code).await?;
+
+        let token = (self.jwt_encode)(user.id.clone(), user.activated, user.blocked)?;
+
+        Ok(UserControllerLoginReturn {
+            user: UserResponse {
+                id: user.id,
+                username: user.username,
+                email: user.email,
+                activated: user.activated,
+                blocked: user.blocked,
+            },
+            token,
+        })
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{
+    dtos::{
+        controllers::dtos_controller_personal_access_token::*,
+        models::dtos_model_personal_access_token::{
+            PersonalAccessTokenModelConsultReturn, PersonalAccessTokenModelCreateParams,
+        },
+    },
+    error::*,
+    models::personal_access_token_model::PersonalAccessTokenManagementModel,
+    security::jwt::{JwtDecode, JwtEncode},
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+const TOKEN_NAME_MAX_LENGTH: usize = 255;
+
+#[async_trait]
+pub trait PersonalAccessTokenManagementController: Sync + Send {
+    async fn create_token(
+        &self,
+        token: String,
+        req: CreatePersonalAccessTokenParams,
+    ) -> Result<PersonalAccessTokenControllerCreateReturn, AppError>;
+    async fn list_tokens(
+        &self,
+        token: String,
+    ) -> Result<Vec<PersonalAccessTokenResponse>, AppError>;
+    async fn revoke_token(&self, token: String, id: String) -> Result<String, AppError>;
+    /// Exchange a personal access token holding `scope` for a user token accepted by the
+    /// other controllers.
+    async fn authenticate(
+        &self,
+        personal_access_token: String,
+        scope: &str,
+    ) -> Result<String, AppError>;
+}
+
+pub struct PersonalAccessTokenController<M> {
+    pub model: M,
+    pub jwt_encode: JwtEncode,
+    pub jwt_decode: JwtDecode,
+}
+
+fn to_response(token: PersonalAccessTokenModelConsultReturn) -> PersonalAccessTokenResponse {
+    PersonalAccessTokenResponse {
+        id: token.id,
+        name: token.name,
+        prefix: token.prefix,
+        scopes: token.scopes,
+        expires_at: token.expires_at.map(|expires_at| expires_at.timestamp()),
+        last_used_at: token
+            .last_used_at
+            .map(|last_used_at| last_used_at.timestamp()),
+        created_at: token.created_at.timestamp(),
+    }
+}
+
+#[async_trait]
+impl<M: PersonalAccessTokenManagementModel> PersonalAccessTokenManagementController
+    for PersonalAccessTokenController<M>
+{
+    async fn create_token(
+        &self,
+        token: String,
+        req: CreatePersonalAccessTokenParams,
+    ) -> Result<PersonalAccessTokenControllerCreateReturn, AppError> {
+        let user_id = (self.jwt_decode)(&token)?.sub;
+
+        let name = req.name.trim().to_string();
+        if name.is_empty() {
+            return Err(AppError::new(Code::InvalidArgument, "Token name is empty"));
+        }
+        if name.chars().count() > TOKEN_NAME_MAX_LENGTH {
+            return Err(AppError::new(
+                Code::InvalidArgument,
+                "Token name is too long",
+            ));
+        }
+
+        let expires_at = match req.expires_at {
+            Some(expires_at) => match NaiveDateTime::from_timestamp_opt(expires_at, 0) {
+                Some(expires_at) => Some(expires_at),
+                None => return Err(AppError::new(Code::InvalidArgument, "Invalid expiration")),
+            },
+            None => None,
+        };
+
+        let created = self
+            .model
+            .create(
+                user_id,
+                PersonalAccessTokenModelCreateParams {
+                    name,
+                    scopes: req.scopes,
+                    expires_at,
+                },
+            )
+            .await?;
+
+        Ok(PersonalAccessTokenControllerCreateReturn {
+            personal_access_token: to_response(created.personal_access_token),
+            token: created.token,
+        })
+    }
+
+    async fn list_tokens(
+        &self,
+        token: String,
+    ) -> Result<Vec<PersonalAccessTokenResponse>, AppError> {
+        let user_id = (self.jwt_decode)(&token)?.sub;
+
+        let tokens = self.model.list(user_id).await?;
+
+        Ok(tokens.into_iter().map(to_response).collect())
+    }
+
+    async fn revoke_token(&self, token: String, id: String) -> Result<String, AppError> {
+        let user_id = (self.jwt_decode)(&token)?.sub;
+
+        self.model.revoke(user_id, id).await
+    }
+
+    async fn authenticate(
+        &self,
+        personal_access_token: String,
+        scope: &str,
+    ) -> Result<String, AppError> {
+        let authenticated = self.model.authenticate(personal_access_token).await?;
+
+        if !authenticated.scopes.iter().any(|granted| granted == scope) {
+            return Err(AppError::new(
+                Code::PermissionDenied,
+                format!("The personal access token lacks the {} scope", scope),
+            ));
+        }
+
+        let user = authenticated.user;
+        (self.jwt_encode)(user.id, user.activated, user.blocked)
+    }
+}
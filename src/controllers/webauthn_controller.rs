@@ -0,0 +1,235 @@
+use crate::{
+    dtos::{
+        controllers::{
+            dtos_controller_user::{UserControllerLoginReturn, UserResponse},
+            dtos_controller_webauthn::*,
+        },
+        models::dtos_model_webauthn::{
+            WebauthnModelFinishAuthenticationParams, WebauthnModelFinishRegistrationParams,
+        },
+    },
+    error::*,
+    models::webauthn_model::PasskeyAuthenticationModel,
+    security::{
+        jwt::{JWTAuthenticateToken, JwtDecode, JwtEncode},
+        webauthn::{encode_credential_id, SUPPORTED_ALGORITHMS},
+    },
+    services::sanitizer::sanitize_authentication_input::SanitizeAuthentication,
+};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::Serialize;
+
+const DEFAULT_PASSKEY_NAME: &str = "Passkey";
+const PASSKEY_NAME_MAX_LENGTH: usize = 255;
+const PUBLIC_KEY_CREDENTIAL_TYPE: &str = "public-key";
+
+#[async_trait]
+pub trait PasskeyAuthenticationController: Sync + Send {
+    async fn begin_registration(
+        &self,
+        token: String,
+    ) -> Result<PasskeyControllerOptionsReturn, AppError>;
+    async fn finish_registration(
+        &self,
+        token: String,
+        req: FinishPasskeyRegistrationParams,
+    ) -> Result<PasskeyResponse, AppError>;
+    async fn begin_login(
+        &self,
+        req: BeginPasskeyLoginParams,
+    ) -> Result<PasskeyControllerOptionsReturn, AppError>;
+    async fn finish_login(
+        &self,
+        req: FinishPasskeyLoginParams,
+    ) -> Result<UserControllerLoginReturn, AppError>;
+}
+
+pub struct WebauthnController<M, S> {
+    pub model: M,
+    pub sanitize_user: S,
+    pub jwt_encode: JwtEncode,
+    pub jwt_decode: JwtDecode,
+}
+
+fn credential_descriptors(ids: Vec<String>) -> Vec<PublicKeyCredentialDescriptor> {
+    ids.into_iter()
+        .map(|id| PublicKeyCredentialDescriptor {
+            credential_type: PUBLIC_KEY_CREDENTIAL_TYPE,
+            id,
+        })
+        .collect()
+}
+
+fn options_to_json<T: Serialize>(options: &T) -> Result<PasskeyControllerOptionsReturn, AppError> {
+    match serde_json::to_string(options) {
+        Ok(options) => Ok(PasskeyControllerOptionsReturn { options }),
+        Err(error) => Err(AppError::new(
+            Code::Internal,
+            "Unable to serialize the WebAuthn options",
+        )
+        .with_source(error)),
+    }
+}
+
+impl<M, S> WebauthnController<M, S> {
+    fn authenticated_user(&self, token: &str) -> Result<String, AppError> {
+        let JWTAuthenticateToken {
+            sub: user_id,
+            blocked,
+            ..
+        } = (self.jwt_decode)(token)?;
+
+        if blocked {
+            return Err(AppError::new(Code::PermissionDenied, "User are blocked"));
+        }
+
+        Ok(user_id)
+    }
+}
+
+#[async_trait]
+impl<M: PasskeyAuthenticationModel, S: SanitizeAuthentication> PasskeyAuthenticationController
+    for WebauthnController<M, S>
+{
+    async fn begin_registration(
+        &self,
+        token: String,
+    ) -> Result<PasskeyControllerOptionsReturn, AppError> {
+        let user_id = self.authenticated_user(&token)?;
+
+        let registration = self.model.start_registration(user_id).await?;
+
+        options_to_json(&PublicKeyCredentialCreationOptions {
+            rp: RelyingPartyEntity {
+                id: registration.relying_party.id,
+                name: registration.relying_party.name,
+            },
+            user: UserEntity {
+                id: URL_SAFE_NO_PAD.encode(registration.user_id),
+                name: registration.username.clone(),
+                display_name: registration.username,
+            },
+            challenge: registration.challenge,
+            pub_key_cred_params: SUPPORTED_ALGORITHMS
+                .iter()
+                .map(|alg| PublicKeyCredentialParameters {
+                    credential_type: PUBLIC_KEY_CREDENTIAL_TYPE,
+                    alg: *alg,
+                })
+                .collect(),
+            timeout: registration.timeout_seconds * 1000,
+            exclude_credentials: credential_descriptors(registration.exclude_credentials),
+            authenticator_selection: AuthenticatorSelection {
+                resident_key: "preferred",
+                user_verification: "preferred",
+            },
+            attestation: "none",
+        })
+    }
+
+    async fn finish_registration(
+        &self,
+        token: String,
+        req: FinishPasskeyRegistrationParams,
+    ) -> Result<PasskeyResponse, AppError> {
+        let user_id = self.authenticated_user(&token)?;
+
+        let name = match req.name.trim() {
+            "" => DEFAULT_PASSKEY_NAME.to_string(),
+            name if name.chars().count() > PASSKEY_NAME_MAX_LENGTH => {
+                return Err(AppError::new(
+                    Code::InvalidArgument,
+                    "Passkey name is too long",
+                ))
+            }
+            name => name.to_string(),
+        };
+
+        let credential = self
+            .model
+            .finish_registration(
+                user_id,
+                WebauthnModelFinishRegistrationParams {
+                    name,
+                    client_data_json: req.client_data_json,
+                    attestation_object: req.attestation_object,
+                },
+            )
+            .await?;
+
+        Ok(PasskeyResponse {
+            id: credential.id,
+            name: credential.name,
+            created_at: credential.created_at.timestamp(),
+        })
+    }
+
+    async fn begin_login(
+        &self,
+        req: BeginPasskeyLoginParams,
+    ) -> Result<PasskeyControllerOptionsReturn, AppError> {
+        let username_sanitized = match req.username {
+            Some(username) => Some(self.sanitize_user.sanitize_username_input(username)?),
+            None => None,
+        };
+
+        let authentication = self
+            .model
+            .start_authentication(username_sanitized, req.second_factor)
+            .await?;
+
+        options_to_json(&PublicKeyCredentialRequestOptions {
+            challenge: authentication.challenge,
+            timeout: authentication.timeout_seconds * 1000,
+            rp_id: authentication.relying_party_id,
+            allow_credentials: credential_descriptors(authentication.allow_credentials),
+            user_verification: if authentication.user_verification_required {
+                "required"
+            } else {
+                "discouraged"
+            },
+        })
+    }
+
+    async fn finish_login(
+        &self,
+        req: FinishPasskeyLoginParams,
+    ) -> Result<UserControllerLoginReturn, AppError> {
+        if req.credential_id.is_empty() {
+            return Err(AppError::new(
+                Code::InvalidArgument,
+                "Credential id is empty",
+            ));
+        }
+
+        let password_sanitized = match req.password {
+            Some(password) => Some(self.sanitize_user.sanitize_password_input(password)?),
+            None => None,
+        };
+
+        let user = self
+            .model
+            .finish_authentication(WebauthnModelFinishAuthenticationParams {
+                credential_id: encode_credential_id(&req.credential_id),
+                client_data_json: req.client_data_json,
+                authenticator_data: req.authenticator_data,
+                signature: req.signature,
+                password: password_sanitized,
+            })
+            .await?;
+
+        let token = (self.jwt_encode)(user.id.clone(), user.activated, user.blocked)?;
+
+        Ok(UserControllerLoginReturn {
+            user: UserResponse {
+                id: user.id,
+                username: user.username,
+                email: user.email,
+                activated: user.activated,
+                blocked: user.blocked,
+            },
+            token,
+        })
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{
+    dtos::{
+        controllers::dtos_controller_webhook::*,
+        models::dtos_model_webhook::WebhookModelRegisterParams,
+    },
+    error::AppError,
+    models::webhook_model::WebhookAdministrationModel,
+    security::admin::AuthorizeAdmin,
+};
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait WebhookAdministrationController: Sync + Send {
+    async fn register_webhook(
+        &self,
+        token: String,
+        req: RegisterWebhookParams,
+    ) -> Result<WebhookControllerRegisterReturn, AppError>;
+    async fn list_webhooks(&self, token: String) -> Result<Vec<WebhookResponse>, AppError>;
+    async fn test_webhook(&self, token: String, id: String) -> Result<u16, AppError>;
+    async fn delete_webhook(&self, token: String, id: String) -> Result<String, AppError>;
+}
+
+pub struct WebhookController<M> {
+    pub model: M,
+    pub authorize_admin: AuthorizeAdmin,
+    pub admin_token: Option<String>,
+}
+
+#[async_trait]
+impl<M: WebhookAdministrationModel> WebhookAdministrationController for WebhookController<M> {
+    async fn register_webhook(
+        &self,
+        token: String,
+        req: RegisterWebhookParams,
+    ) -> Result<WebhookControllerRegisterReturn, AppError> {
+        (self.authorize_admin)(&token, self.admin_token.as_deref())?;
+
+        let webhook = self
+            .model
+            .register(WebhookModelRegisterParams {
+                url: req.url.trim().to_string(),
+                secret: req.secret,
+                event_types: req.event_types,
+            })
+            .await?;
+
+        Ok(WebhookControllerRegisterReturn {
+            webhook: WebhookResponse {
+                id: webhook.id,
+                url: webhook.url,
+                event_types: webhook.event_types,
+                created_at: webhook.created_at.timestamp(),
+            },
+            secret: webhook.secret,
+        })
+    }
+
+    async fn list_webhooks(&self, token: String) -> Result<Vec<WebhookResponse>, AppError> {
+        (self.authorize_admin)(&token, self.admin_token.as_deref())?;
+
+        let webhooks = self.model.list().await?;
+
+        Ok(webhooks
+            .into_iter()
+            .map(|webhook| WebhookResponse {
+                id: webhook.id,
+                url: webhook.url,
+                event_types: webhook.event_types,
+                created_at: webhook.created_at.timestamp(),
+            })
+            .collect())
+    }
+
+    async fn test_webhook(&self, token: String, id: String) -> Result<u16, AppError> {
+        (self.authorize_admin)(&token, self.admin_token.as_deref())?;
+
+        self.model.test(id).await
+    }
+
+    async fn delete_webhook(&self, token: String, id: String) -> Result<String, AppError> {
+        (self.authorize_admin)(&token, self.admin_token.as_deref())?;
+
+        self.model.delete(id).await
+    }
+}
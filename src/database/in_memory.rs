@@ -0,0 +1,37 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use crate::repositories::{
+    user_repository::UserRepositoryConsultReturn, users_code_repository::UsersCode,
+};
+
+/// A code and the wrong guesses made against it.
+pub struct StoredCode {
+    pub code: UsersCode,
+    pub attempts: i64,
+}
+
+#[derive(Default)]
+pub struct InMemoryData {
+    /// Users by id.
+    pub users: HashMap<String, UserRepositoryConsultReturn>,
+    /// The current code of each user id and purpose.
+    pub codes: HashMap<(String, &'static str), StoredCode>,
+}
+
+/// Users and their codes kept in the process, lost when it stops. Clones share the data.
+#[derive(Clone, Default)]
+pub struct InMemoryStore {
+    data: Arc<Mutex<InMemoryData>>,
+}
+
+impl InMemoryStore {
+    /// The data, locked until the guard is dropped. Never held across an `await`.
+    pub fn lock(&self) -> MutexGuard<'_, InMemoryData> {
+        // The data stays consistent even if a panic poisoned the lock, every change being
+        // made in one step.
+        self.data.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -1,2 +1,5 @@
 pub mod connection;
+pub mod in_memory;
+pub mod redis_pool;
+pub mod storage;
 pub mod utils;
\ No newline at end of file
@@ -0,0 +1,304 @@
+use redis::{
+    aio::{ConnectionLike, MultiplexedConnection},
+    Cmd, ConnectionAddr, ConnectionInfo, Pipeline, RedisError, RedisFuture, RedisResult, Value,
+};
+use std::{
+    future::Future,
+    io,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    config::app_config::RedisConfig, error::*,
+    utils::adapters::redis_error_to_app_error::redis_error_to_app_error,
+};
+
+/// Connection attempts made before giving up on a request, waiting twice as long after
+/// each failure.
+const RECONNECT_ATTEMPTS: u32 = 3;
+const RECONNECT_INITIAL_BACKOFF: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone)]
+pub struct RedisPoolOptions {
+    /// Longest wait for a connection or the answer to a command.
+    pub command_timeout: Duration,
+    /// Consecutive failures that open the circuit breaker.
+    pub failure_threshold: u32,
+    /// Time the breaker stays open, failing calls without trying Redis.
+    pub open_duration: Duration,
+}
+
+impl Default for RedisPoolOptions {
+    fn default() -> Self {
+        RedisPoolOptions {
+            command_timeout: Duration::from_secs(1),
+            failure_threshold: 5,
+            open_duration: Duration::from_secs(10),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct CircuitBreaker {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+struct Shared {
+    client: redis::Client,
+    options: RedisPoolOptions,
+    connection: tokio::sync::Mutex<Option<MultiplexedConnection>>,
+    breaker: Mutex<CircuitBreaker>,
+}
+
+/// One multiplexed Redis connection shared by every request, opened on first use and
+/// opened again after it broke. Commands time out, and once Redis failed
+/// `failure_threshold` times in a row calls fail fast with `Unavailable` until
+/// `open_duration` has passed, when the next call probes Redis again.
+#[derive(Clone)]
+pub struct RedisPool {
+    shared: Arc<Shared>,
+}
+
+fn timed_out() -> RedisError {
+    RedisError::from(io::Error::new(
+        io::ErrorKind::TimedOut,
+        "Redis did not answer in time",
+    ))
+}
+
+/// Failures of Redis itself or of the way to it, rather than of the command sent.
+fn is_unavailable(error: &RedisError) -> bool {
+    error.is_io_error() || error.is_timeout() || error.is_connection_dropped()
+}
+
+impl RedisPool {
+    pub fn new(client: redis::Client, options: RedisPoolOptions) -> RedisPool {
+        RedisPool {
+            shared: Arc::new(Shared {
+                client,
+                options,
+                connection: tokio::sync::Mutex::new(None),
+                breaker: Mutex::new(CircuitBreaker::default()),
+            }),
+        }
+    }
+
+    /// Pool described by the loaded `Config`. No connection is opened before the first call.
+    ///
+    /// Without `REDIS_CLIENT`, as when nothing is kept in Redis, it is never meant to be
+    /// called and points at a local Redis on the default port.
+    pub fn open(config: &RedisConfig) -> Result<RedisPool, AppError> {
+        let client = match &config.url {
+            Some(url) => redis::Client::open(url.as_str()),
+            None => redis::Client::open(ConnectionInfo {
+                addr: ConnectionAddr::Tcp("127.0.0.1".to_string(), 6379),
+                redis: Default::default(),
+            }),
+        }
+        .map_err(|error| {
+            AppError::new(Code::Internal, "Invalid REDIS_CLIENT").with_source(error)
+        })?;
+
+        Ok(RedisPool::new(
+            client,
+            RedisPoolOptions {
+                command_timeout: Duration::from_millis(config.command_timeout_ms),
+                failure_threshold: config.circuit_breaker_threshold,
+                open_duration: Duration::from_secs(config.circuit_breaker_open_seconds),
+            },
+        ))
+    }
+
+    /// Client for the callers that need a connection of their own, like blocking reads.
+    pub fn client(&self) -> &redis::Client {
+        &self.shared.client
+    }
+
+    pub async fn connection(&self) -> Result<RedisConnection, AppError> {
+        self.check_breaker()?;
+
+        let mut shared_connection = self.shared.connection.lock().await;
+        if let Some(connection) = shared_connection.as_ref() {
+            return Ok(RedisConnection {
+                connection: connection.clone(),
+                pool: self.clone(),
+            });
+        }
+
+        match self.connect_with_backoff().await {
+            Ok(connection) => {
+                *shared_connection = Some(connection.clone());
+                self.record_success();
+                Ok(RedisConnection {
+                    connection,
+                    pool: self.clone(),
+                })
+            }
+            Err(error) => {
+                self.record_failure();
+                Err(redis_error_to_app_error(error))
+            }
+        }
+    }
+
+    async fn connect_with_backoff(&self) -> RedisResult<MultiplexedConnection> {
+        let mut backoff = RECONNECT_INITIAL_BACKOFF;
+        let mut attempt = 1;
+        loop {
+            let connect = self.shared.client.get_multiplexed_tokio_connection();
+            let error =
+                match tokio::time::timeout(self.shared.options.command_timeout, connect).await {
+                    Ok(Ok(connection)) => return Ok(connection),
+                    Ok(Err(error)) => error,
+                    Err(_) => timed_out(),
+                };
+            if attempt >= RECONNECT_ATTEMPTS {
+                return Err(error);
+            }
+
+            tracing::warn!(%error, attempt, "Unable to connect to Redis, retrying");
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+            attempt += 1;
+        }
+    }
+
+    fn check_breaker(&self) -> Result<(), AppError> {
+        let breaker = self.shared.breaker.lock().unwrap();
+        match breaker.open_until {
+            Some(open_until) if open_until > Instant::now() => {
+                Err(AppError::new(Code::Unavailable, "Redis is unavailable")
+                    .with_reason(Reason::ServiceBusy)
+                    .with_retry_after(open_until - Instant::now()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn record_success(&self) {
+        *self.shared.breaker.lock().unwrap() = CircuitBreaker::default();
+    }
+
+    fn record_failure(&self) {
+        let mut breaker = self.shared.breaker.lock().unwrap();
+        breaker.consecutive_failures += 1;
+        if breaker.consecutive_failures >= self.shared.options.failure_threshold {
+            if breaker.open_until.is_none() {
+                tracing::error!(
+                    failures = breaker.consecutive_failures,
+                    "Redis circuit breaker opened"
+                );
+            }
+            breaker.open_until = Some(Instant::now() + self.shared.options.open_duration);
+        }
+    }
+
+    /// Run a command with the timeout, dropping the shared connection when it broke so
+    /// the next call opens a new one.
+    async fn guard<T>(&self, command: impl Future<Output = RedisResult<T>>) -> RedisResult<T> {
+        let result = match tokio::time::timeout(self.shared.options.command_timeout, command).await
+        {
+            Ok(result) => result,
+            Err(_) => Err(timed_out()),
+        };
+
+        match &result {
+            Err(error) if is_unavailable(error) => {
+                self.record_failure();
+                *self.shared.connection.lock().await = None;
+            }
+            _ => self.record_success(),
+        }
+        result
+    }
+}
+
+/// Handle on the shared connection, used like any async Redis connection.
+pub struct RedisConnection {
+    connection: MultiplexedConnection,
+    pool: RedisPool,
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        Box::pin(async move {
+            let command = self.connection.req_packed_command(cmd);
+            self.pool.guard(command).await
+        })
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        Box::pin(async move {
+            let commands = self.connection.req_packed_commands(cmd, offset, count);
+            self.pool.guard(commands).await
+        })
+    }
+
+    fn get_db(&self) -> i64 {
+        self.connection.get_db()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use redis::AsyncCommands;
+
+    /// Nothing listens on port 1, every connection is refused.
+    fn unreachable_pool(failure_threshold: u32) -> RedisPool {
+        RedisPool::new(
+            redis::Client::open("redis://127.0.0.1:1/").unwrap(),
+            RedisPoolOptions {
+                command_timeout: Duration::from_millis(200),
+                failure_threshold,
+                open_duration: Duration::from_secs(60),
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_breaker_opens_after_repeated_failures() {
+        let pool = unreachable_pool(2);
+
+        for _ in 0..2 {
+            let error = pool.connection().await.err().unwrap();
+            assert_eq!(error.code, Code::Unavailable);
+            assert!(error.source.is_some());
+        }
+
+        let started_at = Instant::now();
+        let error = pool.connection().await.err().unwrap();
+        assert!(started_at.elapsed() < Duration::from_millis(10));
+        assert_eq!(error.code, Code::Unavailable);
+        assert_eq!(error.message, "Redis is unavailable");
+        assert_eq!(error.reason, Some(Reason::ServiceBusy));
+        assert!(error.retry_after.unwrap() > Duration::from_secs(50));
+    }
+
+    #[tokio::test]
+    async fn test_connection_is_shared_and_reused() {
+        dotenv::from_filename(".env.test").ok();
+        let pool = RedisPool::new(
+            redis::Client::open(std::env::var("REDIS_CLIENT").unwrap()).unwrap(),
+            RedisPoolOptions::default(),
+        );
+
+        let mut connection = pool.connection().await.unwrap();
+        connection
+            .set::<_, _, ()>("redis_pool_test", "shared")
+            .await
+            .unwrap();
+
+        let mut other = pool.connection().await.unwrap();
+        let value: String = other.get("redis_pool_test").await.unwrap();
+        assert_eq!(value, "shared");
+        other.del::<_, ()>("redis_pool_test").await.unwrap();
+    }
+}
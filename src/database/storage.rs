@@ -0,0 +1,66 @@
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    config::app_config::{StorageBackend, StorageConfig},
+    database::{connection::connect_sqlite_pool, in_memory::InMemoryStore},
+    error::{AppError, Code},
+};
+
+/// Database holding the users and, unless they go to Redis, their codes.
+#[derive(Clone)]
+pub enum Storage {
+    /// The `db_pg_pool` of `AppState`.
+    Postgres,
+    Sqlite(Pool<Sqlite>),
+    /// Codes included, whatever `CODES_BACKEND` says.
+    InMemory(InMemoryStore),
+}
+
+impl Storage {
+    /// Open the backend chosen by `STORAGE_BACKEND`.
+    pub async fn open(config: &StorageConfig) -> Result<Storage, AppError> {
+        match config.backend {
+            StorageBackend::Postgres => Ok(Storage::Postgres),
+            StorageBackend::Sqlite => Ok(Storage::Sqlite(
+                connect_sqlite_pool(&config.sqlite_url).await?,
+            )),
+            StorageBackend::Memory => Ok(Storage::InMemory(InMemoryStore::default())),
+        }
+    }
+
+    /// Refuse `feature` unless the users are in Postgres: its tables reference them there,
+    /// and the user events it relies on come from the Postgres outbox.
+    pub fn require_postgres(&self, feature: &str) -> Result<(), AppError> {
+        match self {
+            Storage::Postgres => Ok(()),
+            Storage::Sqlite(_) | Storage::InMemory(_) => Err(AppError::new(
+                Code::FailedPrecondition,
+                format!("{} need STORAGE_BACKEND=postgres", feature),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_postgres_only_services_are_refused_without_postgres() {
+        assert!(Storage::Postgres.require_postgres("Webhooks").is_ok());
+
+        let sqlite = Storage::Sqlite(
+            SqlitePoolOptions::new()
+                .connect_lazy("sqlite::memory:")
+                .unwrap(),
+        );
+        let error = sqlite.require_postgres("Webhooks").unwrap_err();
+        assert_eq!(error.code, Code::FailedPrecondition);
+        assert_eq!(error.message, "Webhooks need STORAGE_BACKEND=postgres");
+
+        let in_memory = Storage::InMemory(InMemoryStore::default());
+        let error = in_memory.require_postgres("Webhooks").unwrap_err();
+        assert_eq!(error.code, Code::FailedPrecondition);
+    }
+}
@@ -2,7 +2,13 @@ use std::{future::Future, process::Command};
 
 use sqlx::{Pool, Postgres};
 
-use crate::{database::connection::get_postgres_pool, error::AppError};
+use crate::{
+    database::{
+        connection::get_postgres_pool,
+        redis_pool::{RedisPool, RedisPoolOptions},
+    },
+    error::AppError,
+};
 
 pub async fn test_with_database<T, F>(
     test_name: &str,
@@ -16,19 +22,29 @@ where
     let mut db_name = std::env::var("DATABASE_NAME").expect("Unable to read DATABASE_NAME env var");
     db_name = format!("{db_name}_{test_name}");
 
-    let pool = &get_postgres_pool(Some(pg_url.clone())).await;
+    let pool = &get_postgres_pool(&pg_url).await;
 
     drop_database(pool, &db_name).await;
     setup_database(pool, &pg_url, &db_name).await;
 
     let db_url = format!("{pg_url}/{db_name}");
-    let result = callback(get_postgres_pool(Some(db_url)).await).await;
+    let result = callback(get_postgres_pool(&db_url).await).await;
 
     drop_database(pool, &db_name).await;
 
     result
 }
 
+/// Pool on the `REDIS_CLIENT` of `.env.test`, with the default timeouts and breaker.
+pub fn test_redis_pool() -> RedisPool {
+    dotenv::from_filename(".env.test").ok();
+    let redis_url = std::env::var("REDIS_CLIENT").expect("Unable to read REDIS_CLIENT env var");
+    RedisPool::new(
+        redis::Client::open(redis_url).unwrap(),
+        RedisPoolOptions::default(),
+    )
+}
+
 async fn drop_database(pool: &Pool<Postgres>, db_name: &str) {
     sqlx::query(
         "SELECT pg_terminate_backend(pg_stat_activity.pid)
@@ -1 +1,2 @@
-pub mod integration_test;
\ No newline at end of file
+pub mod integration_test;
+pub mod repository_contract;
\ No newline at end of file
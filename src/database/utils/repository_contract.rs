@@ -0,0 +1,331 @@
+//! Behavior every `UserRepository` and `UsersCodeRepository` must share, whatever keeps the
+//! data. Each backend, and any repository written later, runs these checks on an empty
+//! store, so that the mocks of the tests can be kept to what the checks pin down.
+
+use chrono::{Duration, Utc};
+
+use crate::{
+    error::{AppError, Code, Reason},
+    repositories::{
+        user_repository::{
+            UserRepository, UserRepositoryStoreParams, UserRepositoryStoreReturn,
+            UserRepositoryUpdateParams,
+        },
+        users_code_repository::{UsersCode, UsersCodePurpose, UsersCodeRepository},
+    },
+};
+
+/// Id of the user `store_contract_user` stores, owner of the codes of the codes contract.
+pub const CONTRACT_USER_ID: &str = "contractUserId";
+const PASSWORD: &str = "password";
+const CODE: &str = "123456";
+
+fn user(name: &str) -> UserRepositoryStoreParams {
+    UserRepositoryStoreParams {
+        id: format!("{name}Id"),
+        username: format!("{name}_username"),
+        email: format!("{name}@test.com"),
+        password: PASSWORD.to_string(),
+    }
+}
+
+fn expect_error<T>(result: Result<T, AppError>, code: Code, rule: &str) -> AppError {
+    match result {
+        Ok(_) => panic!("{rule}: expected {code:?}, got a success"),
+        Err(error) => {
+            assert_eq!(error.code, code, "{rule}: {}", error.message);
+            error
+        }
+    }
+}
+
+/// Stores the user whose id is `CONTRACT_USER_ID`, needed first where codes reference
+/// their user.
+pub async fn store_contract_user(repository: &impl UserRepository) -> UserRepositoryStoreReturn {
+    repository.store(user("contractUser")).await.unwrap()
+}
+
+/// Runs every check of the users contract on an empty repository.
+pub async fn user_repository_contract(repository: &impl UserRepository) {
+    stores_and_consults_users(repository).await;
+    refuses_taken_identifiers(repository).await;
+    updates_only_the_given_fields(repository).await;
+    refuses_updates_to_taken_identifiers(repository).await;
+    reports_missing_users(repository).await;
+    deletes_users(repository).await;
+}
+
+async fn stores_and_consults_users(repository: &impl UserRepository) {
+    let params = user("stored");
+    let stored = repository.store(params.clone()).await.unwrap();
+    assert_eq!(stored.id, params.id);
+    assert_eq!(stored.username, params.username);
+    assert_eq!(stored.email, params.email);
+    assert!(
+        !stored.activated && !stored.blocked,
+        "a new user is neither activated nor blocked"
+    );
+
+    for consulted in [
+        repository.consult_by_id(params.id.clone()).await,
+        repository
+            .consult_by_username(params.username.clone())
+            .await,
+        repository.consult_by_email(params.email.clone()).await,
+    ] {
+        let consulted = consulted.unwrap();
+        assert_eq!(consulted.id, params.id);
+        assert_eq!(consulted.username, params.username);
+        assert_eq!(consulted.email, params.email);
+        assert_eq!(consulted.password, params.password);
+    }
+}
+
+async fn refuses_taken_identifiers(repository: &impl UserRepository) {
+    let taken = user("taken");
+    repository.store(taken.clone()).await.unwrap();
+
+    let result = repository
+        .store(UserRepositoryStoreParams {
+            id: taken.id.clone(),
+            ..user("sameId")
+        })
+        .await;
+    expect_error(result, Code::AlreadyExists, "a taken id");
+
+    let result = repository
+        .store(UserRepositoryStoreParams {
+            username: taken.username.clone(),
+            ..user("sameUsername")
+        })
+        .await;
+    let error = expect_error(result, Code::AlreadyExists, "a taken username");
+    assert_eq!(error.reason, Some(Reason::UsernameTaken));
+
+    let result = repository
+        .store(UserRepositoryStoreParams {
+            email: taken.email.clone(),
+            ..user("sameEmail")
+        })
+        .await;
+    let error = expect_error(result, Code::AlreadyExists, "a taken email");
+    assert_eq!(error.reason, Some(Reason::EmailTaken));
+
+    for refused in ["sameUsername", "sameEmail"] {
+        let result = repository.consult_by_id(user(refused).id).await;
+        expect_error(result, Code::NotFound, "a refused user is not stored");
+    }
+}
+
+async fn updates_only_the_given_fields(repository: &impl UserRepository) {
+    let params = user("updated");
+    repository.store(params.clone()).await.unwrap();
+
+    let response = repository
+        .store_update(
+            params.id.clone(),
+            UserRepositoryUpdateParams {
+                username: Some("updated_username_2".to_string()),
+                password: Some("updated_password".to_string()),
+                activated: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(response, "User updated successfully");
+
+    let updated = repository.consult_by_id(params.id.clone()).await.unwrap();
+    assert_eq!(updated.username, "updated_username_2");
+    assert_eq!(updated.email, params.email);
+    assert_eq!(updated.password, "updated_password");
+    assert!(updated.activated && !updated.blocked);
+
+    let result = repository
+        .consult_by_username(params.username.clone())
+        .await;
+    expect_error(result, Code::NotFound, "the former username is free");
+
+    let result = repository
+        .store_update(params.id, UserRepositoryUpdateParams::default())
+        .await;
+    expect_error(result, Code::InvalidArgument, "an update without fields");
+}
+
+async fn refuses_updates_to_taken_identifiers(repository: &impl UserRepository) {
+    let taken = user("takenByUpdate");
+    let params = user("updating");
+    repository.store(taken.clone()).await.unwrap();
+    repository.store(params.clone()).await.unwrap();
+
+    let result = repository
+        .store_update(
+            params.id.clone(),
+            UserRepositoryUpdateParams {
+                username: Some(taken.username.clone()),
+                ..Default::default()
+            },
+        )
+        .await;
+    let error = expect_error(result, Code::AlreadyExists, "an update to a taken username");
+    assert_eq!(error.reason, Some(Reason::UsernameTaken));
+
+    let result = repository
+        .store_update(
+            params.id.clone(),
+            UserRepositoryUpdateParams {
+                email: Some(taken.email.clone()),
+                ..Default::default()
+            },
+        )
+        .await;
+    let error = expect_error(result, Code::AlreadyExists, "an update to a taken email");
+    assert_eq!(error.reason, Some(Reason::EmailTaken));
+
+    let unchanged = repository.consult_by_id(params.id.clone()).await.unwrap();
+    assert_eq!(unchanged.username, params.username);
+    assert_eq!(unchanged.email, params.email);
+
+    // Keeping its own username and email is not taking them from someone else.
+    repository
+        .store_update(
+            params.id,
+            UserRepositoryUpdateParams {
+                username: Some(params.username),
+                email: Some(params.email),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+}
+
+async fn reports_missing_users(repository: &impl UserRepository) {
+    let missing = user("missing");
+
+    let result = repository.consult_by_id(missing.id).await;
+    expect_error(result, Code::NotFound, "an unknown id");
+    let result = repository.consult_by_username(missing.username).await;
+    expect_error(result, Code::NotFound, "an unknown username");
+    let result = repository.consult_by_email(missing.email).await;
+    expect_error(result, Code::NotFound, "an unknown email");
+}
+
+async fn deletes_users(repository: &impl UserRepository) {
+    let params = user("deleted");
+    repository.store(params.clone()).await.unwrap();
+
+    let response = repository.delete(params.id.clone()).await.unwrap();
+    assert_eq!(response, "User deleted successfully");
+    let result = repository.consult_by_id(params.id.clone()).await;
+    expect_error(result, Code::NotFound, "a deleted user");
+
+    // Deleting is idempotent, and frees the id, username and email.
+    repository.delete(params.id.clone()).await.unwrap();
+    repository.store(params).await.unwrap();
+}
+
+/// Runs every check of the codes contract on repositories without codes, `codes` giving
+/// the repository of each purpose. The user must exist beforehand where codes reference
+/// their user.
+pub async fn users_code_repository_contract<R: UsersCodeRepository>(
+    codes: impl Fn(UsersCodePurpose) -> R,
+    user_id: &str,
+) {
+    let account = codes(UsersCodePurpose::Account);
+    let login = codes(UsersCodePurpose::Login);
+
+    stores_and_gets_codes(&account, user_id).await;
+    reports_missing_codes(&account, user_id).await;
+    keeps_purposes_apart(&account, &login, user_id).await;
+    counts_attempts(&account, user_id).await;
+    deletes_codes(&account, user_id).await;
+    never_gives_an_expired_code(&account, user_id).await;
+}
+
+fn code(user_id: &str, expire_at: Duration) -> UsersCode {
+    UsersCode {
+        code: CODE.to_string(),
+        expire_at: Utc::now().naive_utc() + expire_at,
+        user_id: user_id.to_string(),
+    }
+}
+
+async fn stores_and_gets_codes(codes: &impl UsersCodeRepository, user_id: &str) {
+    codes
+        .store(code(user_id, Duration::minutes(10)))
+        .await
+        .unwrap();
+
+    let stored = codes
+        .get(user_id.to_string(), CODE.to_string())
+        .await
+        .unwrap();
+    assert_eq!(stored.code, CODE);
+    assert_eq!(stored.user_id, user_id);
+    assert!(stored.expire_at > Utc::now().naive_utc());
+}
+
+async fn reports_missing_codes(codes: &impl UsersCodeRepository, user_id: &str) {
+    let result = codes.get(user_id.to_string(), "654321".to_string()).await;
+    expect_error(result, Code::NotFound, "a wrong code");
+
+    let result = codes
+        .get("unknownUserId".to_string(), CODE.to_string())
+        .await;
+    expect_error(result, Code::NotFound, "the code of an unknown user");
+    let result = codes.increment_attempts("unknownUserId".to_string()).await;
+    expect_error(result, Code::NotFound, "the attempts of an unknown user");
+}
+
+async fn keeps_purposes_apart(
+    account: &impl UsersCodeRepository,
+    login: &impl UsersCodeRepository,
+    user_id: &str,
+) {
+    let result = login.get(user_id.to_string(), CODE.to_string()).await;
+    expect_error(result, Code::NotFound, "the code of another purpose");
+    let result = login.increment_attempts(user_id.to_string()).await;
+    expect_error(result, Code::NotFound, "the attempts of another purpose");
+
+    login.delete(user_id.to_string()).await.unwrap();
+    account
+        .get(user_id.to_string(), CODE.to_string())
+        .await
+        .unwrap();
+}
+
+async fn counts_attempts(codes: &impl UsersCodeRepository, user_id: &str) {
+    for expected in 1..=2 {
+        let attempts = codes.increment_attempts(user_id.to_string()).await.unwrap();
+        assert_eq!(attempts, expected);
+    }
+}
+
+async fn deletes_codes(codes: &impl UsersCodeRepository, user_id: &str) {
+    codes.delete(user_id.to_string()).await.unwrap();
+
+    let result = codes.get(user_id.to_string(), CODE.to_string()).await;
+    expect_error(result, Code::NotFound, "a deleted code");
+    let result = codes.increment_attempts(user_id.to_string()).await;
+    expect_error(result, Code::NotFound, "the attempts of a deleted code");
+
+    // Deleting is idempotent.
+    codes.delete(user_id.to_string()).await.unwrap();
+}
+
+async fn never_gives_an_expired_code(codes: &impl UsersCodeRepository, user_id: &str) {
+    codes
+        .store(code(user_id, Duration::minutes(-1)))
+        .await
+        .unwrap();
+
+    // The databases keep expired codes and let the models check `expire_at`, the others
+    // forget them.
+    match codes.get(user_id.to_string(), CODE.to_string()).await {
+        Ok(expired) => assert!(expired.expire_at <= Utc::now().naive_utc()),
+        Err(error) => assert_eq!(error.code, Code::NotFound, "an expired code"),
+    }
+
+    codes.delete(user_id.to_string()).await.unwrap();
+}
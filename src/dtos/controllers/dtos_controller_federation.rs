@@ -0,0 +1,11 @@
+use super::dtos_controller_user::UserResponse;
+
+pub struct ExternalLoginParams {
+    pub id_token: String,
+}
+
+pub struct FederationControllerLoginReturn {
+    pub user: UserResponse,
+    pub token: String,
+    pub created: bool,
+}
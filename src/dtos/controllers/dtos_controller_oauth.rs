@@ -0,0 +1,64 @@
+pub struct RegisterOAuthClientParams {
+    pub name: String,
+    pub redirect_uris: Vec<String>,
+    pub scopes: Vec<String>,
+    pub confidential: bool,
+    pub service: bool,
+}
+
+pub struct OAuthControllerRegisterClientReturn {
+    pub client_id: String,
+    pub client_secret: Option<String>,
+}
+
+pub struct AuthorizationRequestParams {
+    pub client_id: String,
+    pub redirect_uri: String,
+    pub scope: String,
+    pub code_challenge: String,
+    pub code_challenge_method: String,
+    pub nonce: Option<String>,
+}
+
+pub struct AuthorizationRequestClient {
+    pub client_id: String,
+    pub client_name: String,
+    pub scopes: Vec<String>,
+}
+
+pub struct AuthorizeParams {
+    pub request: AuthorizationRequestParams,
+    pub username: String,
+    pub password: String,
+    pub consent_granted: bool,
+}
+
+pub struct ClientCredentialsParams {
+    pub client_id: String,
+    pub client_secret: Option<String>,
+}
+
+pub struct AuthorizationCodeGrantParams {
+    pub client: ClientCredentialsParams,
+    pub code: String,
+    pub redirect_uri: String,
+    pub code_verifier: String,
+}
+
+pub struct RefreshTokenGrantParams {
+    pub client: ClientCredentialsParams,
+    pub refresh_token: String,
+}
+
+pub struct ClientCredentialsGrantParams {
+    pub client: ClientCredentialsParams,
+    pub scope: String,
+}
+
+pub struct OAuthTokenResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub refresh_token: Option<String>,
+    pub scope: String,
+    pub id_token: Option<String>,
+}
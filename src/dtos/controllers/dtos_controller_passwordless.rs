@@ -0,0 +1,12 @@
+pub struct RequestLoginCodeParams {
+    pub email: String,
+}
+
+pub struct PasswordlessControllerLoginCodeReturn {
+    pub expires_in: i64,
+}
+
+pub struct LoginWithCodeParams {
+    pub email: String,
+    pub code: String,
+}
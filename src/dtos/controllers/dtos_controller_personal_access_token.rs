@@ -0,0 +1,21 @@
+pub struct CreatePersonalAccessTokenParams {
+    pub name: String,
+    pub scopes: Vec<String>,
+    /// Unix timestamp, the token never expires without it.
+    pub expires_at: Option<i64>,
+}
+
+pub struct PersonalAccessTokenResponse {
+    pub id: String,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<i64>,
+    pub last_used_at: Option<i64>,
+    pub created_at: i64,
+}
+
+pub struct PersonalAccessTokenControllerCreateReturn {
+    pub personal_access_token: PersonalAccessTokenResponse,
+    pub token: String,
+}
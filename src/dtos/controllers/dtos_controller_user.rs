@@ -31,6 +31,15 @@ pub struct UserControllerAuthenticationReturn {
     pub user: UserResponse,
 }
 
+/// Standard OpenID Connect claims about the user, the profile and email ones only when the
+/// token grants the `profile` and `email` scopes.
+pub struct UserControllerUserInfoReturn {
+    pub sub: String,
+    pub preferred_username: Option<String>,
+    pub email: Option<String>,
+    pub email_verified: Option<bool>,
+}
+
 pub struct UpdateParams {
     pub username: Option<String>,
     pub email: Option<String>,
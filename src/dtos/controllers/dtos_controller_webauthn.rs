@@ -0,0 +1,90 @@
+use serde::Serialize;
+
+pub struct FinishPasskeyRegistrationParams {
+    pub name: String,
+    pub client_data_json: Vec<u8>,
+    pub attestation_object: Vec<u8>,
+}
+
+pub struct BeginPasskeyLoginParams {
+    pub username: Option<String>,
+    pub second_factor: bool,
+}
+
+pub struct FinishPasskeyLoginParams {
+    pub credential_id: Vec<u8>,
+    pub client_data_json: Vec<u8>,
+    pub authenticator_data: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub password: Option<String>,
+}
+
+pub struct PasskeyResponse {
+    pub id: String,
+    pub name: String,
+    pub created_at: i64,
+}
+
+/// Options handed to `navigator.credentials`, in the JSON form of WebAuthn Level 3
+/// where binary fields are base64url encoded.
+pub struct PasskeyControllerOptionsReturn {
+    pub options: String,
+}
+
+#[derive(Serialize)]
+pub struct PublicKeyCredentialDescriptor {
+    #[serde(rename = "type")]
+    pub credential_type: &'static str,
+    pub id: String,
+}
+
+#[derive(Serialize)]
+pub struct RelyingPartyEntity {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserEntity {
+    pub id: String,
+    pub name: String,
+    pub display_name: String,
+}
+
+#[derive(Serialize)]
+pub struct PublicKeyCredentialParameters {
+    #[serde(rename = "type")]
+    pub credential_type: &'static str,
+    pub alg: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticatorSelection {
+    pub resident_key: &'static str,
+    pub user_verification: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicKeyCredentialCreationOptions {
+    pub rp: RelyingPartyEntity,
+    pub user: UserEntity,
+    pub challenge: String,
+    pub pub_key_cred_params: Vec<PublicKeyCredentialParameters>,
+    pub timeout: i64,
+    pub exclude_credentials: Vec<PublicKeyCredentialDescriptor>,
+    pub authenticator_selection: AuthenticatorSelection,
+    pub attestation: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicKeyCredentialRequestOptions {
+    pub challenge: String,
+    pub timeout: i64,
+    pub rp_id: String,
+    pub allow_credentials: Vec<PublicKeyCredentialDescriptor>,
+    pub user_verification: &'static str,
+}
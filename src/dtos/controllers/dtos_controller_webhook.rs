@@ -0,0 +1,17 @@
+pub struct RegisterWebhookParams {
+    pub url: String,
+    pub secret: Option<String>,
+    pub event_types: Vec<String>,
+}
+
+pub struct WebhookResponse {
+    pub id: String,
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub created_at: i64,
+}
+
+pub struct WebhookControllerRegisterReturn {
+    pub webhook: WebhookResponse,
+    pub secret: String,
+}
@@ -1 +1,7 @@
+pub mod dtos_controller_federation;
+pub mod dtos_controller_oauth;
+pub mod dtos_controller_passwordless;
+pub mod dtos_controller_personal_access_token;
 pub mod dtos_controller_user;
+pub mod dtos_controller_webauthn;
+pub mod dtos_controller_webhook;
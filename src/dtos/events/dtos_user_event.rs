@@ -0,0 +1,55 @@
+use chrono::NaiveDateTime;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UserEventType {
+    UserRegistered,
+    UserActivated,
+    UserEmailChanged,
+    UserPasswordChanged,
+    UserBlocked,
+    UserDeleted,
+}
+
+impl UserEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserEventType::UserRegistered => "UserRegistered",
+            UserEventType::UserActivated => "UserActivated",
+            UserEventType::UserEmailChanged => "UserEmailChanged",
+            UserEventType::UserPasswordChanged => "UserPasswordChanged",
+            UserEventType::UserBlocked => "UserBlocked",
+            UserEventType::UserDeleted => "UserDeleted",
+        }
+    }
+
+    pub fn parse(event_type: &str) -> Option<UserEventType> {
+        match event_type {
+            "UserRegistered" => Some(UserEventType::UserRegistered),
+            "UserActivated" => Some(UserEventType::UserActivated),
+            "UserEmailChanged" => Some(UserEventType::UserEmailChanged),
+            "UserPasswordChanged" => Some(UserEventType::UserPasswordChanged),
+            "UserBlocked" => Some(UserEventType::UserBlocked),
+            "UserDeleted" => Some(UserEventType::UserDeleted),
+            _ => None,
+        }
+    }
+}
+
+/// Event to be written in the outbox together with the user mutation that originated it.
+#[derive(Debug, PartialEq)]
+pub struct NewUserEvent {
+    pub event_type: UserEventType,
+    pub user_id: String,
+    pub payload: Value,
+}
+
+/// Event as stored in the outbox, `payload` is the JSON document serialized as text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserEvent {
+    pub id: i64,
+    pub event_type: String,
+    pub user_id: String,
+    pub payload: String,
+    pub created_at: NaiveDateTime,
+}
@@ -0,0 +1 @@
+pub mod dtos_user_event;
@@ -1,3 +1,4 @@
 pub mod controllers;
+pub mod events;
 pub mod models;
 pub mod repositories;
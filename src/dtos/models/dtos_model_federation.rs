@@ -0,0 +1,10 @@
+#[derive(Debug)]
+pub struct FederationModelLoginReturn {
+    pub id: String,
+    pub username: String,
+    pub email: String,
+    pub activated: bool,
+    pub blocked: bool,
+    /// The account was provisioned by this login.
+    pub created: bool,
+}
@@ -0,0 +1,69 @@
+#[derive(Debug, PartialEq)]
+pub struct OAuthModelRegisterClientParams {
+    pub name: String,
+    pub redirect_uris: Vec<String>,
+    pub scopes: Vec<String>,
+    pub confidential: bool,
+    pub service: bool,
+}
+
+pub struct OAuthModelRegisterClientReturn {
+    pub client_id: String,
+    pub client_secret: Option<String>,
+}
+
+pub struct OAuthModelClientReturn {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct OAuthModelAuthorizeParams {
+    pub client_id: String,
+    pub redirect_uri: String,
+    pub scopes: Vec<String>,
+    pub code_challenge: String,
+    pub code_challenge_method: String,
+    pub username: String,
+    pub password: String,
+    pub consent_granted: bool,
+    pub nonce: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct OAuthModelClientCredentials {
+    pub client_id: String,
+    pub client_secret: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct OAuthModelExchangeCodeParams {
+    pub client: OAuthModelClientCredentials,
+    pub code: String,
+    pub redirect_uri: String,
+    pub code_verifier: String,
+}
+
+/// End-user authentication event behind an authorization code, used for the ID token.
+pub struct OAuthModelAuthentication {
+    pub auth_time: i64,
+    pub nonce: Option<String>,
+}
+
+pub struct OAuthModelTokenReturn {
+    pub client_id: String,
+    pub user_id: String,
+    pub username: String,
+    pub email: String,
+    pub activated: bool,
+    pub blocked: bool,
+    pub refresh_token: String,
+    pub scopes: Vec<String>,
+    pub authentication: Option<OAuthModelAuthentication>,
+}
+
+pub struct OAuthModelServiceTokenReturn {
+    pub client_id: String,
+    pub scopes: Vec<String>,
+}
@@ -0,0 +1,3 @@
+pub struct PasswordlessModelLoginCodeReturn {
+    pub expires_in: i64,
+}
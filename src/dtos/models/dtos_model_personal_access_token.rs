@@ -0,0 +1,33 @@
+use chrono::NaiveDateTime;
+
+use super::dtos_model_user::UserModelLoginVerificationReturn;
+
+pub struct PersonalAccessTokenModelCreateParams {
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug)]
+pub struct PersonalAccessTokenModelConsultReturn {
+    pub id: String,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug)]
+pub struct PersonalAccessTokenModelCreateReturn {
+    /// The only time the full token is available, just its hash is kept.
+    pub token: String,
+    pub personal_access_token: PersonalAccessTokenModelConsultReturn,
+}
+
+#[derive(Debug)]
+pub struct PersonalAccessTokenModelAuthenticateReturn {
+    pub user: UserModelLoginVerificationReturn,
+    pub scopes: Vec<String>,
+}
@@ -0,0 +1,45 @@
+use chrono::NaiveDateTime;
+
+use crate::security::webauthn::RelyingParty;
+
+pub struct WebauthnModelRegistrationStart {
+    pub challenge: String,
+    pub relying_party: RelyingParty,
+    pub user_id: String,
+    pub username: String,
+    /// Credentials already registered, so the authenticator does not enroll twice.
+    pub exclude_credentials: Vec<String>,
+    pub timeout_seconds: i64,
+}
+
+pub struct WebauthnModelFinishRegistrationParams {
+    pub name: String,
+    pub client_data_json: Vec<u8>,
+    pub attestation_object: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct WebauthnModelCredentialReturn {
+    pub id: String,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+}
+
+pub struct WebauthnModelAuthenticationStart {
+    pub challenge: String,
+    pub relying_party_id: String,
+    /// Empty when the user is not known upfront, letting the authenticator offer its
+    /// discoverable credentials.
+    pub allow_credentials: Vec<String>,
+    pub user_verification_required: bool,
+    pub timeout_seconds: i64,
+}
+
+pub struct WebauthnModelFinishAuthenticationParams {
+    pub credential_id: String,
+    pub client_data_json: Vec<u8>,
+    pub authenticator_data: Vec<u8>,
+    pub signature: Vec<u8>,
+    /// Required when the ceremony was started as a second factor.
+    pub password: Option<String>,
+}
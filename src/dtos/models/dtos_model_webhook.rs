@@ -0,0 +1,23 @@
+use chrono::NaiveDateTime;
+
+#[derive(Debug, PartialEq)]
+pub struct WebhookModelRegisterParams {
+    pub url: String,
+    pub secret: Option<String>,
+    pub event_types: Vec<String>,
+}
+
+pub struct WebhookModelRegisterReturn {
+    pub id: String,
+    pub url: String,
+    pub secret: String,
+    pub event_types: Vec<String>,
+    pub created_at: NaiveDateTime,
+}
+
+pub struct WebhookModelConsultReturn {
+    pub id: String,
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub created_at: NaiveDateTime,
+}
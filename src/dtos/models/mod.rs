@@ -1 +1,7 @@
+pub mod dtos_model_federation;
+pub mod dtos_model_oauth;
+pub mod dtos_model_passwordless;
+pub mod dtos_model_personal_access_token;
 pub mod dtos_model_user;
+pub mod dtos_model_webauthn;
+pub mod dtos_model_webhook;
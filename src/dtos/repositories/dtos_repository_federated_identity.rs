@@ -0,0 +1,18 @@
+use chrono::NaiveDateTime;
+
+#[derive(Debug, PartialEq)]
+pub struct FederatedIdentityStoreParams {
+    pub issuer: String,
+    pub subject: String,
+    pub user_id: String,
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FederatedIdentity {
+    pub issuer: String,
+    pub subject: String,
+    pub user_id: String,
+    pub email: Option<String>,
+    pub created_at: NaiveDateTime,
+}
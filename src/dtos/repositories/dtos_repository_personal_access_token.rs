@@ -0,0 +1,26 @@
+use chrono::NaiveDateTime;
+
+#[derive(Debug, PartialEq)]
+pub struct PersonalAccessTokenStoreParams {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub prefix: String,
+    pub token_hash: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersonalAccessToken {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    /// First characters of the token, shown so users can tell their tokens apart.
+    pub prefix: String,
+    pub token_hash: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
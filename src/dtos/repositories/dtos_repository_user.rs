@@ -1,4 +1,4 @@
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct UserRepositoryStoreParams {
     pub id: String,
     pub username: String,
@@ -14,6 +14,7 @@ pub struct UserRepositoryStoreReturn {
     pub blocked: bool,
 }
 
+#[derive(Clone, sqlx::FromRow)]
 pub struct UserRepositoryConsultReturn {
     pub id: String,
     pub username: String,
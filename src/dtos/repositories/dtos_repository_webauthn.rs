@@ -0,0 +1,43 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq)]
+pub struct WebauthnCredentialStoreParams {
+    /// Base64url encoded credential id.
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub public_key: Vec<u8>,
+    pub algorithm: i32,
+    pub sign_count: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebauthnCredential {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub public_key: Vec<u8>,
+    pub algorithm: i32,
+    pub sign_count: i64,
+    pub created_at: NaiveDateTime,
+    pub last_used_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum WebauthnCeremony {
+    Registration,
+    Authentication,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebauthnChallenge {
+    /// Base64url encoded, as echoed back in the client data.
+    pub challenge: String,
+    pub ceremony: WebauthnCeremony,
+    /// Registering user, or the user expected to authenticate when known upfront.
+    pub user_id: Option<String>,
+    /// The assertion completes a password login instead of replacing it.
+    pub second_factor: bool,
+    pub expire_at: NaiveDateTime,
+}
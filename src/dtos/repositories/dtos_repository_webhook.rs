@@ -0,0 +1,47 @@
+use chrono::NaiveDateTime;
+
+#[derive(Debug, PartialEq)]
+pub struct WebhookRepositoryStoreParams {
+    pub id: String,
+    pub url: String,
+    pub secret: String,
+    pub event_types: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookRepositoryConsultReturn {
+    pub id: String,
+    pub url: String,
+    pub secret: String,
+    pub event_types: Vec<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: String,
+    pub url: String,
+    pub secret: String,
+    pub event_id: i64,
+    pub event_type: String,
+    pub user_id: String,
+    pub payload: String,
+    pub attempts: i32,
+}
+
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    Dead,
+}
+
+impl WebhookDeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookDeliveryStatus::Pending => "pending",
+            WebhookDeliveryStatus::Delivered => "delivered",
+            WebhookDeliveryStatus::Dead => "dead",
+        }
+    }
+}
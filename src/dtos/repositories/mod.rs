@@ -1 +1,6 @@
-pub mod dtos_repository_user;
\ No newline at end of file
+pub mod dtos_repository_federated_identity;
+pub mod dtos_repository_oauth;
+pub mod dtos_repository_personal_access_token;
+pub mod dtos_repository_user;
+pub mod dtos_repository_webauthn;
+pub mod dtos_repository_webhook;
@@ -1,3 +1,5 @@
+use std::{error::Error, fmt, time::Duration};
+
 #[derive(Debug, PartialEq)]
 pub enum Code {
     Unknown,
@@ -9,12 +11,63 @@ pub enum Code {
     Unauthenticated,
     DatabaseError,
     SQLError,
+    /// A service the request depends on is down, trying again later may succeed.
+    Unavailable,
+    /// The server is not set up for the operation, trying again will not help.
+    FailedPrecondition,
+}
+
+/// Stable cause of an error that clients can branch on, sent as the `ErrorInfo` reason.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reason {
+    UsernameTaken,
+    EmailTaken,
+    InvalidCredentials,
+    UserBlocked,
+    UserNotActivated,
+    UserAlreadyActivated,
+    CodeNotFound,
+    CodeExpired,
+    InvalidCode,
+    TooManyAttempts,
+    ServiceBusy,
+}
+
+impl Reason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Reason::UsernameTaken => "USERNAME_TAKEN",
+            Reason::EmailTaken => "EMAIL_TAKEN",
+            Reason::InvalidCredentials => "INVALID_CREDENTIALS",
+            Reason::UserBlocked => "USER_BLOCKED",
+            Reason::UserNotActivated => "USER_NOT_ACTIVATED",
+            Reason::UserAlreadyActivated => "USER_ALREADY_ACTIVATED",
+            Reason::CodeNotFound => "CODE_NOT_FOUND",
+            Reason::CodeExpired => "CODE_EXPIRED",
+            Reason::InvalidCode => "INVALID_CODE",
+            Reason::TooManyAttempts => "TOO_MANY_ATTEMPTS",
+            Reason::ServiceBusy => "SERVICE_BUSY",
+        }
+    }
+}
+
+/// A request field that was refused, and why.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldViolation {
+    pub field: String,
+    pub description: String,
 }
 
 #[derive(Debug)]
 pub struct AppError {
     pub code: Code,
     pub message: String,
+    pub reason: Option<Reason>,
+    pub field_violations: Vec<FieldViolation>,
+    /// How long the client should wait before trying the same request again.
+    pub retry_after: Option<Duration>,
+    /// Error of the library or service that caused this one, kept for the server logs.
+    pub source: Option<Box<dyn Error + Send + Sync>>,
 }
 
 impl AppError {
@@ -22,6 +75,101 @@ impl AppError {
         AppError {
             code,
             message: message.into(),
+            reason: None,
+            field_violations: vec![],
+            retry_after: None,
+            source: None,
         }
     }
+
+    pub fn with_source(mut self, source: impl Into<Box<dyn Error + Send + Sync>>) -> AppError {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Errors whose message is only for the server logs, clients get a generic one.
+    pub fn is_internal(&self) -> bool {
+        matches!(
+            self.code,
+            Code::Internal
+                | Code::Unknown
+                | Code::DatabaseError
+                | Code::SQLError
+                | Code::Unavailable
+        )
+    }
+
+    /// The message followed by the message of every error that led to it.
+    pub fn chain(&self) -> String {
+        let mut chain = self.message.clone();
+        let mut source = self.source();
+        while let Some(error) = source {
+            chain.push_str(": ");
+            chain.push_str(&error.to_string());
+            source = error.source();
+        }
+        chain
+    }
+
+    pub fn with_reason(mut self, reason: Reason) -> AppError {
+        self.reason = Some(reason);
+        self
+    }
+
+    pub fn with_field_violation(
+        mut self,
+        field: impl Into<String>,
+        description: impl Into<String>,
+    ) -> AppError {
+        self.field_violations.push(FieldViolation {
+            field: field.into(),
+            description: description.into(),
+        });
+        self
+    }
+
+    pub fn with_retry_after(mut self, retry_after: Duration) -> AppError {
+        self.retry_after = Some(retry_after);
+        self
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for AppError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source
+            .as_ref()
+            .map(|source| source.as_ref() as &(dyn Error + 'static))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    fn test_chain_lists_every_cause() {
+        let io_error = io::Error::new(io::ErrorKind::ConnectionReset, "connection reset");
+        let error = AppError::new(Code::Internal, "Unable to reach Redis").with_source(
+            AppError::new(Code::Internal, "Error communicating with Redis").with_source(io_error),
+        );
+
+        assert_eq!(error.to_string(), "Unable to reach Redis");
+        assert_eq!(
+            error.source().unwrap().to_string(),
+            "Error communicating with Redis"
+        );
+        assert_eq!(
+            error.chain(),
+            "Unable to reach Redis: Error communicating with Redis: connection reset"
+        );
+        assert!(error.is_internal());
+        assert!(!AppError::new(Code::NotFound, "Code not found").is_internal());
+    }
 }
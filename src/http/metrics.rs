@@ -0,0 +1,67 @@
+use axum::{extract::State, http::header, response::IntoResponse, routing::get, Router};
+
+use crate::{telemetry::metrics::metrics, AppState};
+
+/// Prometheus scrape endpoint, served apart from the OAuth routes so it can stay internal.
+pub fn metrics_router(app_state: AppState) -> Router {
+    Router::new()
+        .route("/metrics", get(render_metrics))
+        .with_state(app_state)
+}
+
+async fn render_metrics(State(app_state): State<AppState>) -> impl IntoResponse {
+    let metrics = metrics();
+    metrics.observe_db_pool(
+        &app_state.db_pg_pool,
+        app_state.config.database.max_connections,
+    );
+
+    (
+        [(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)],
+        metrics.render(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        config::app_config::Config,
+        database::{connection::lazy_postgres_pool, redis_pool::RedisPool, storage::Storage},
+        utils::hash::password::PASSWORD_HASHER,
+    };
+    use axum::{body::Body, http::Request, http::StatusCode};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_metrics_are_served_in_the_prometheus_format() {
+        dotenv::from_filename(".env.test").ok();
+        let config = Config::load().unwrap();
+        let app_state = AppState {
+            db_pg_pool: lazy_postgres_pool(&config.database).unwrap(),
+            redis: RedisPool::open(&config.redis).unwrap(),
+            storage: Storage::Postgres,
+            config: Arc::new(config),
+        };
+        PASSWORD_HASHER("password".to_string()).unwrap();
+
+        let response = metrics_router(app_state)
+            .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            prometheus::TEXT_FORMAT
+        );
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body
+            .contains("authentication_password_hash_duration_seconds_count{operation=\"hash\"}"));
+        assert!(body.contains("authentication_db_pool_connections{state=\"in_use\"} 0"));
+        assert!(body.contains("authentication_db_pool_max_connections"));
+    }
+}
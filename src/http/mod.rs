@@ -0,0 +1,3 @@
+pub mod metrics;
+pub mod oauth;
+pub mod oidc;
@@ -0,0 +1,798 @@
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Redirect, Response},
+    routing::{get, post},
+    Form, Json, Router,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    controllers::oauth_controller::{parse_scope, AuthorizationServerController},
+    dtos::controllers::dtos_controller_oauth::*,
+    error::*,
+    http::oidc,
+    rpc::authentication::create_oauth_controller,
+    utils::adapters::app_error_to_oauth_error::app_error_to_oauth_error,
+    AppState,
+};
+
+pub fn oauth_router(app_state: AppState) -> Router {
+    Router::new()
+        .route("/oauth/authorize", get(authorization_page).post(authorize))
+        .route("/oauth/token", post(token))
+        .route(
+            "/oauth/userinfo",
+            get(oidc::user_info).post(oidc::user_info),
+        )
+        .route(
+            "/.well-known/openid-configuration",
+            get(oidc::openid_configuration),
+        )
+        .route("/.well-known/jwks.json", get(oidc::jwks))
+        .with_state(app_state)
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct AuthorizationQuery {
+    pub response_type: String,
+    pub client_id: String,
+    pub redirect_uri: String,
+    pub scope: String,
+    pub state: Option<String>,
+    pub code_challenge: String,
+    pub code_challenge_method: String,
+    pub nonce: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct AuthorizeForm {
+    #[serde(flatten)]
+    pub query: AuthorizationQuery,
+    pub username: String,
+    pub password: String,
+    pub consent: Option<String>,
+    pub decision: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct TokenForm {
+    pub grant_type: String,
+    pub code: String,
+    pub redirect_uri: String,
+    pub code_verifier: String,
+    pub refresh_token: String,
+    pub scope: String,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+}
+
+fn authorization_request(query: &AuthorizationQuery) -> AuthorizationRequestParams {
+    AuthorizationRequestParams {
+        client_id: query.client_id.clone(),
+        redirect_uri: query.redirect_uri.clone(),
+        scope: query.scope.clone(),
+        code_challenge: query.code_challenge.clone(),
+        code_challenge_method: query.code_challenge_method.clone(),
+        nonce: query.nonce.clone().filter(|nonce| !nonce.is_empty()),
+    }
+}
+
+/// Send the user agent back to the client. The redirect uri was checked against the
+/// registered ones before any call to this function.
+fn redirect_to_client(query: &AuthorizationQuery, params: &[(&str, &str)]) -> Response {
+    let mut url = match reqwest::Url::parse(&query.redirect_uri) {
+        Ok(url) => url,
+        Err(_) => return (StatusCode::BAD_REQUEST, "Invalid redirect uri").into_response(),
+    };
+
+    {
+        let mut pairs = url.query_pairs_mut();
+        pairs.extend_pairs(params);
+        if let Some(state) = &query.state {
+            pairs.append_pair("state", state);
+        }
+    }
+
+    Redirect::to(url.as_str()).into_response()
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn render_authorization_page(
+    query: &AuthorizationQuery,
+    client: &AuthorizationRequestClient,
+    error: Option<&str>,
+) -> Html<String> {
+    let hidden_fields: String = [
+        ("response_type", query.response_type.as_str()),
+        ("client_id", query.client_id.as_str()),
+        ("redirect_uri", query.redirect_uri.as_str()),
+        ("scope", query.scope.as_str()),
+        ("state", query.state.as_deref().unwrap_or_default()),
+        ("code_challenge", query.code_challenge.as_str()),
+        (
+            "code_challenge_method",
+            query.code_challenge_method.as_str(),
+        ),
+        ("nonce", query.nonce.as_deref().unwrap_or_default()),
+    ]
+    .iter()
+    .map(|(name, value)| {
+        format!(
+            r#"<input type="hidden" name="{}" value="{}">"#,
+            name,
+            escape_html(value)
+        )
+    })
+    .collect();
+    let scopes: String = parse_scope(&query.scope)
+        .iter()
+        .map(|scope| format!("<li>{}</li>", escape_html(scope)))
+        .collect();
+    let error = error
+        .map(|error| format!(r#"<p class="error">{}</p>"#, escape_html(error)))
+        .unwrap_or_default();
+
+    Html(format!(
+        r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"><title>Sign in</title></head>
+<body>
+<h1>Sign in to continue to {client_name}</h1>
+{error}
+<form method="post" action="/oauth/authorize">
+{hidden_fields}
+<label>Username <input name="username" autocomplete="username" required></label>
+<label>Password <input name="password" type="password" autocomplete="current-password" required></label>
+<p>{client_name} is requesting access to:</p>
+<ul>{scopes}</ul>
+<label><input type="checkbox" name="consent" value="approve"> Allow access</label>
+<button type="submit" name="decision" value="approve">Continue</button>
+<button type="submit" name="decision" value="deny" formnovalidate>Deny</button>
+</form>
+</body>
+</html>"#,
+        client_name = escape_html(&client.client_name),
+    ))
+}
+
+pub async fn authorization_page(
+    State(app_state): State<AppState>,
+    Query(query): Query<AuthorizationQuery>,
+) -> Response {
+    let controller = create_oauth_controller(&app_state);
+
+    let client = match controller
+        .validate_authorization_request(authorization_request(&query))
+        .await
+    {
+        Ok(client) => client,
+        Err(error) => return (StatusCode::BAD_REQUEST, error.message).into_response(),
+    };
+
+    if query.response_type != "code" {
+        return redirect_to_client(&query, &[("error", "unsupported_response_type")]);
+    }
+
+    render_authorization_page(&query, &client, None).into_response()
+}
+
+pub async fn authorize(
+    State(app_state): State<AppState>,
+    Form(form): Form<AuthorizeForm>,
+) -> Response {
+    let controller = create_oauth_controller(&app_state);
+    let query = &form.query;
+
+    let client = match controller
+        .validate_authorization_request(authorization_request(query))
+        .await
+    {
+        Ok(client) => client,
+        Err(error) => return (StatusCode::BAD_REQUEST, error.message).into_response(),
+    };
+
+    if query.response_type != "code" {
+        return redirect_to_client(query, &[("error", "unsupported_response_type")]);
+    }
+
+    if form.decision.as_deref() == Some("deny") {
+        return redirect_to_client(query, &[("error", "access_denied")]);
+    }
+
+    match controller
+        .authorize(AuthorizeParams {
+            request: authorization_request(query),
+            username: form.username.clone(),
+            password: form.password.clone(),
+            consent_granted: form.consent.as_deref() == Some("approve"),
+        })
+        .await
+    {
+        Ok(code) => redirect_to_client(query, &[("code", &code)]),
+        Err(error) => match error.code {
+            Code::Unauthenticated => (
+                StatusCode::UNAUTHORIZED,
+                render_authorization_page(query, &client, Some(&error.message)),
+            )
+                .into_response(),
+            Code::PermissionDenied => (
+                StatusCode::FORBIDDEN,
+                render_authorization_page(query, &client, Some(&error.message)),
+            )
+                .into_response(),
+            Code::InvalidArgument => redirect_to_client(
+                query,
+                &[
+                    ("error", "invalid_request"),
+                    ("error_description", &error.message),
+                ],
+            ),
+            _ => redirect_to_client(query, &[("error", "server_error")]),
+        },
+    }
+}
+
+/// Client credentials sent with HTTP Basic authentication or in the form body (RFC 6749, 2.3.1).
+fn client_credentials(headers: &HeaderMap, form: &TokenForm) -> ClientCredentialsParams {
+    let basic_credentials = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Basic "))
+        .and_then(|encoded| STANDARD.decode(encoded).ok())
+        .and_then(|decoded| String::from_utf8(decoded).ok())
+        .and_then(|decoded| {
+            decoded
+                .split_once(':')
+                .map(|(id, secret)| (id.to_string(), secret.to_string()))
+        });
+
+    match basic_credentials {
+        Some((client_id, client_secret)) => ClientCredentialsParams {
+            client_id,
+            client_secret: Some(client_secret),
+        },
+        None => ClientCredentialsParams {
+            client_id: form.client_id.clone().unwrap_or_default(),
+            client_secret: form.client_secret.clone(),
+        },
+    }
+}
+
+fn token_error(status: StatusCode, error: &str, description: &str) -> Response {
+    (
+        status,
+        [(header::CACHE_CONTROL, "no-store")],
+        Json(json!({ "error": error, "error_description": description })),
+    )
+        .into_response()
+}
+
+pub async fn token(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    Form(form): Form<TokenForm>,
+) -> Response {
+    let controller = create_oauth_controller(&app_state);
+    let client = client_credentials(&headers, &form);
+
+    let result = match form.grant_type.as_str() {
+        "authorization_code" => {
+            controller
+                .exchange_authorization_code(AuthorizationCodeGrantParams {
+                    client,
+                    code: form.code,
+                    redirect_uri: form.redirect_uri,
+                    code_verifier: form.code_verifier,
+                })
+                .await
+        }
+        "refresh_token" => {
+            controller
+                .refresh_access_token(RefreshTokenGrantParams {
+                    client,
+                    refresh_token: form.refresh_token,
+                })
+                .await
+        }
+        "client_credentials" => {
+            controller
+                .issue_service_token(ClientCredentialsGrantParams {
+                    client,
+                    scope: form.scope,
+                })
+                .await
+        }
+        _ => {
+            return token_error(
+                StatusCode::BAD_REQUEST,
+                "unsupported_grant_type",
+                "Supported grant types: authorization_code, refresh_token, client_credentials",
+            )
+        }
+    };
+
+    match result {
+        Ok(response) => {
+            let mut body = json!({
+                "access_token": response.access_token,
+                "token_type": response.token_type,
+                "scope": response.scope,
+            });
+            if let Some(refresh_token) = response.refresh_token {
+                body["refresh_token"] = json!(refresh_token);
+            }
+            if let Some(id_token) = response.id_token {
+                body["id_token"] = json!(id_token);
+            }
+
+            (
+                StatusCode::OK,
+                [(header::CACHE_CONTROL, "no-store")],
+                Json(body),
+            )
+                .into_response()
+        }
+        Err(error) => {
+            let (status, oauth_error) = app_error_to_oauth_error(&error);
+            let description = match status {
+                StatusCode::INTERNAL_SERVER_ERROR => "Internal error",
+                _ => error.message.as_str(),
+            };
+
+            token_error(status, oauth_error, description)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        config::app_config::Config,
+        database::{
+            storage::Storage,
+            utils::integration_test::{test_redis_pool, test_with_database},
+        },
+        dtos::models::{
+            dtos_model_oauth::OAuthModelRegisterClientParams,
+            dtos_model_user::UserModelCreateParams,
+        },
+        models::{
+            authentication_model::AuthenticationModel, oauth_model::AuthorizationServerModel,
+        },
+        rpc::authentication::create_user_model,
+        security::{
+            jwt::{jwt_decode, jwt_decode_access, jwt_decode_service},
+            oidc::{oidc_issuer, IdTokenClaims},
+            pkce::pkce_challenge,
+        },
+    };
+    use axum::{body::Body, http::Request};
+    use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+    use serde_json::Value;
+    use sqlx::{Pool, Postgres};
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    const REDIRECT_URI: &str = "https://app.example.com/callback";
+    const CODE_VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+    fn form_body(params: &[(&str, &str)]) -> String {
+        reqwest::Url::parse_with_params("http://localhost", params)
+            .unwrap()
+            .query()
+            .unwrap()
+            .to_string()
+    }
+
+    async fn post_form(
+        router: &Router,
+        uri: &str,
+        params: &[(&str, &str)],
+        basic_credentials: Option<(&str, &str)>,
+    ) -> Response {
+        let mut request =
+            Request::post(uri).header(header::CONTENT_TYPE, "application/x-www-form-urlencoded");
+        if let Some((client_id, client_secret)) = basic_credentials {
+            request = request.header(
+                header::AUTHORIZATION,
+                format!(
+                    "Basic {}",
+                    STANDARD.encode(format!("{}:{}", client_id, client_secret))
+                ),
+            );
+        }
+
+        router
+            .clone()
+            .oneshot(request.body(Body::from(form_body(params))).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn get(router: &Router, uri: &str, bearer_token: Option<&str>) -> Response {
+        let mut request = Request::get(uri);
+        if let Some(token) = bearer_token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+
+        router
+            .clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn json_body(response: Response) -> Value {
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn query_param(location: &str, name: &str) -> Option<String> {
+        reqwest::Url::parse(location)
+            .unwrap()
+            .query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_authorization_code_flow_with_pkce() {
+        async fn authorization_code_flow(pool: Pool<Postgres>) -> Result<(), AppError> {
+            let app_state = AppState {
+                db_pg_pool: pool,
+                redis: test_redis_pool(),
+                storage: Storage::Postgres,
+                config: Arc::new(Config::load().unwrap()),
+            };
+
+            let user = create_user_model(&app_state)
+                .create(UserModelCreateParams {
+                    username: "oauthuser".to_string(),
+                    email: "oauth@email.com".to_string(),
+                    password: "password".to_string(),
+                })
+                .await?;
+            let client = create_oauth_controller(&app_state)
+                .model
+                .register_client(OAuthModelRegisterClientParams {
+                    name: "Web app".to_string(),
+                    redirect_uris: vec![REDIRECT_URI.to_string()],
+                    scopes: vec!["profile".to_string()],
+                    confidential: true,
+                    service: false,
+                })
+                .await?;
+            let client_secret = client.client_secret.unwrap();
+            let router = oauth_router(app_state);
+
+            let code_challenge = pkce_challenge(CODE_VERIFIER);
+            let authorize_params = [
+                ("response_type", "code"),
+                ("client_id", client.client_id.as_str()),
+                ("redirect_uri", REDIRECT_URI),
+                ("scope", "profile"),
+                ("state", "xyz"),
+                ("code_challenge", code_challenge.as_str()),
+                ("code_challenge_method", "S256"),
+                ("username", "oauthuser"),
+                ("password", "password"),
+            ];
+
+            let response = post_form(&router, "/oauth/authorize", &authorize_params, None).await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+            let mut consented_params = authorize_params.to_vec();
+            consented_params.push(("consent", "approve"));
+            let response = post_form(&router, "/oauth/authorize", &consented_params, None).await;
+            assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+            let location = response.headers()[header::LOCATION].to_str().unwrap();
+            assert!(location.starts_with(REDIRECT_URI));
+            assert_eq!(query_param(location, "state").unwrap(), "xyz");
+            let code = query_param(location, "code").unwrap();
+
+            let token_params = [
+                ("grant_type", "authorization_code"),
+                ("code", code.as_str()),
+                ("redirect_uri", REDIRECT_URI),
+                ("code_verifier", CODE_VERIFIER),
+            ];
+            let credentials = Some((client.client_id.as_str(), client_secret.as_str()));
+
+            let response = post_form(&router, "/oauth/token", &token_params, credentials).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let tokens = json_body(response).await;
+            assert_eq!(tokens["token_type"], "Bearer");
+            assert_eq!(tokens["scope"], "profile");
+            let claims = jwt_decode_access(tokens["access_token"].as_str().unwrap())?;
+            assert_eq!(claims.sub, user.id);
+
+            let response = post_form(&router, "/oauth/token", &token_params, credentials).await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            assert_eq!(json_body(response).await["error"], "invalid_grant");
+
+            let refresh_token = tokens["refresh_token"].as_str().unwrap();
+            let refresh_params = [
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token),
+            ];
+
+            let response = post_form(&router, "/oauth/token", &refresh_params, credentials).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_ne!(json_body(response).await["refresh_token"], refresh_token);
+
+            let response = post_form(&router, "/oauth/token", &refresh_params, credentials).await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+            let response = post_form(
+                &router,
+                "/oauth/token",
+                &refresh_params,
+                Some((client.client_id.as_str(), "wrong secret")),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            assert_eq!(json_body(response).await["error"], "invalid_client");
+
+            Ok(())
+        }
+
+        test_with_database(
+            "test_authorization_code_flow_with_pkce",
+            authorization_code_flow,
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_authorization_page_rejects_unregistered_redirect_uri() {
+        async fn authorization_page_request(pool: Pool<Postgres>) -> Result<StatusCode, AppError> {
+            let app_state = AppState {
+                db_pg_pool: pool,
+                redis: test_redis_pool(),
+                storage: Storage::Postgres,
+                config: Arc::new(Config::load().unwrap()),
+            };
+            let client = create_oauth_controller(&app_state)
+                .model
+                .register_client(OAuthModelRegisterClientParams {
+                    name: "Web app".to_string(),
+                    redirect_uris: vec![REDIRECT_URI.to_string()],
+                    scopes: vec![],
+                    confidential: false,
+                    service: false,
+                })
+                .await?;
+
+            let uri = format!(
+                "/oauth/authorize?response_type=code&client_id={}&redirect_uri=https%3A%2F%2Fevil.example.com",
+                client.client_id
+            );
+            let response = oauth_router(app_state)
+                .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+
+            Ok(response.status())
+        }
+
+        let status =
+            test_with_database("test_unregistered_redirect_uri", authorization_page_request)
+                .await
+                .unwrap();
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_client_credentials_grant() {
+        async fn client_credentials_grant(pool: Pool<Postgres>) -> Result<(), AppError> {
+            let app_state = AppState {
+                db_pg_pool: pool,
+                redis: test_redis_pool(),
+                storage: Storage::Postgres,
+                config: Arc::new(Config::load().unwrap()),
+            };
+            let model = create_oauth_controller(&app_state).model;
+            let service_client = model
+                .register_client(OAuthModelRegisterClientParams {
+                    name: "Billing".to_string(),
+                    redirect_uris: vec![],
+                    scopes: vec!["user_events:read".to_string()],
+                    confidential: true,
+                    service: true,
+                })
+                .await?;
+            let web_client = model
+                .register_client(OAuthModelRegisterClientParams {
+                    name: "Web app".to_string(),
+                    redirect_uris: vec![REDIRECT_URI.to_string()],
+                    scopes: vec![],
+                    confidential: true,
+                    service: false,
+                })
+                .await?;
+            let router = oauth_router(app_state);
+            let token_params = [("grant_type", "client_credentials")];
+
+            let response = post_form(
+                &router,
+                "/oauth/token",
+                &token_params,
+                Some((
+                    service_client.client_id.as_str(),
+                    service_client.client_secret.as_deref().unwrap(),
+                )),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let tokens = json_body(response).await;
+            assert_eq!(tokens["scope"], "user_events:read");
+            assert!(tokens.get("refresh_token").is_none());
+
+            let access_token = tokens["access_token"].as_str().unwrap();
+            let claims = jwt_decode_service(access_token)?;
+            assert_eq!(claims.sub, service_client.client_id);
+            assert_eq!(
+                jwt_decode(access_token).err().unwrap().code,
+                Code::PermissionDenied
+            );
+
+            let response = post_form(
+                &router,
+                "/oauth/token",
+                &token_params,
+                Some((
+                    web_client.client_id.as_str(),
+                    web_client.client_secret.as_deref().unwrap(),
+                )),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+            let uri = format!(
+                "/oauth/authorize?response_type=code&client_id={}&redirect_uri=",
+                service_client.client_id
+            );
+            let response = router
+                .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+            Ok(())
+        }
+
+        test_with_database("test_client_credentials_grant", client_credentials_grant)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_openid_connect_flow() {
+        async fn openid_connect_flow(pool: Pool<Postgres>) -> Result<(), AppError> {
+            let app_state = AppState {
+                db_pg_pool: pool,
+                redis: test_redis_pool(),
+                storage: Storage::Postgres,
+                config: Arc::new(Config::load().unwrap()),
+            };
+
+            let user = create_user_model(&app_state)
+                .create(UserModelCreateParams {
+                    username: "oidcuser".to_string(),
+                    email: "oidc@email.com".to_string(),
+                    password: "password".to_string(),
+                })
+                .await?;
+            let client = create_oauth_controller(&app_state)
+                .model
+                .register_client(OAuthModelRegisterClientParams {
+                    name: "Web app".to_string(),
+                    redirect_uris: vec![REDIRECT_URI.to_string()],
+                    scopes: vec!["openid".to_string(), "email".to_string()],
+                    confidential: false,
+                    service: false,
+                })
+                .await?;
+            let issuer = oidc_issuer(&app_state.config.oidc)?.to_string();
+            let router = oauth_router(app_state);
+
+            let response = get(&router, "/.well-known/openid-configuration", None).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let configuration = json_body(response).await;
+            assert_eq!(configuration["issuer"], issuer.as_str());
+            assert_eq!(
+                configuration["jwks_uri"],
+                format!("{}/.well-known/jwks.json", issuer)
+            );
+
+            let code_challenge = pkce_challenge(CODE_VERIFIER);
+            let authorize_params = [
+                ("response_type", "code"),
+                ("client_id", client.client_id.as_str()),
+                ("redirect_uri", REDIRECT_URI),
+                ("scope", "openid email"),
+                ("nonce", "n-0S6_WzA2Mj"),
+                ("code_challenge", code_challenge.as_str()),
+                ("code_challenge_method", "S256"),
+                ("username", "oidcuser"),
+                ("password", "password"),
+                ("consent", "approve"),
+            ];
+            let response = post_form(&router, "/oauth/authorize", &authorize_params, None).await;
+            assert_eq!(response.status(), StatusCode::SEE_OTHER);
+            let location = response.headers()[header::LOCATION].to_str().unwrap();
+            let code = query_param(location, "code").unwrap();
+
+            let token_params = [
+                ("grant_type", "authorization_code"),
+                ("code", code.as_str()),
+                ("redirect_uri", REDIRECT_URI),
+                ("code_verifier", CODE_VERIFIER),
+                ("client_id", client.client_id.as_str()),
+            ];
+            let response = post_form(&router, "/oauth/token", &token_params, None).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let tokens = json_body(response).await;
+
+            let jwks = json_body(get(&router, "/.well-known/jwks.json", None).await).await;
+            let jwk = &jwks["keys"][0];
+            let mut validation = Validation::new(Algorithm::ES256);
+            validation.set_audience(&[client.client_id.as_str()]);
+            validation.set_issuer(&[issuer.as_str()]);
+            let id_token = jsonwebtoken::decode::<IdTokenClaims>(
+                tokens["id_token"].as_str().unwrap(),
+                &DecodingKey::from_ec_components(
+                    jwk["x"].as_str().unwrap(),
+                    jwk["y"].as_str().unwrap(),
+                )
+                .unwrap(),
+                &validation,
+            )
+            .unwrap()
+            .claims;
+            assert_eq!(id_token.sub, user.id);
+            assert_eq!(id_token.nonce.as_deref(), Some("n-0S6_WzA2Mj"));
+            assert_eq!(id_token.email.as_deref(), Some("oidc@email.com"));
+            assert_eq!(id_token.email_verified, Some(false));
+            assert!(id_token.preferred_username.is_none());
+            assert!(id_token.auth_time <= id_token.iat as i64);
+
+            let access_token = tokens["access_token"].as_str().unwrap();
+            let response = get(&router, "/oauth/userinfo", Some(access_token)).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let user_info = json_body(response).await;
+            assert_eq!(user_info["sub"], user.id.as_str());
+            assert_eq!(user_info["email"], "oidc@email.com");
+            assert_eq!(user_info["email_verified"], false);
+            assert!(user_info.get("preferred_username").is_none());
+
+            let response = get(&router, "/oauth/userinfo", Some("invalid")).await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+            Ok(())
+        }
+
+        test_with_database("test_openid_connect_flow", openid_connect_flow)
+            .await
+            .unwrap();
+    }
+}
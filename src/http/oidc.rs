@@ -0,0 +1,114 @@
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+
+use crate::{
+    controllers::authentication_controller::AuthenticationController,
+    error::*,
+    rpc::authentication::{authorize_user_token, create_user_controller},
+    security::{
+        oidc::{oidc_issuer, oidc_jwks, ID_TOKEN_ALGORITHM},
+        personal_access_token::USER_READ_SCOPE,
+    },
+    AppState,
+};
+
+fn server_error() -> Response {
+    (StatusCode::INTERNAL_SERVER_ERROR, "Internal error").into_response()
+}
+
+/// OpenID Provider metadata (OpenID Connect Discovery 1.0, section 3).
+pub async fn openid_configuration(State(app_state): State<AppState>) -> Response {
+    let issuer = match oidc_issuer(&app_state.config.oidc) {
+        Ok(issuer) => issuer,
+        Err(_) => return server_error(),
+    };
+
+    Json(json!({
+        "issuer": issuer,
+        "authorization_endpoint": format!("{}/oauth/authorize", issuer),
+        "token_endpoint": format!("{}/oauth/token", issuer),
+        "userinfo_endpoint": format!("{}/oauth/userinfo", issuer),
+        "jwks_uri": format!("{}/.well-known/jwks.json", issuer),
+        "response_types_supported": ["code"],
+        "grant_types_supported": ["authorization_code", "refresh_token", "client_credentials"],
+        "subject_types_supported": ["public"],
+        "id_token_signing_alg_values_supported": [ID_TOKEN_ALGORITHM],
+        "scopes_supported": ["openid", "profile", "email"],
+        "token_endpoint_auth_methods_supported": ["client_secret_basic", "client_secret_post", "none"],
+        "code_challenge_methods_supported": ["S256"],
+        "claims_supported": [
+            "iss", "sub", "aud", "exp", "iat", "auth_time", "nonce",
+            "preferred_username", "email", "email_verified"
+        ],
+    }))
+    .into_response()
+}
+
+pub async fn jwks(State(app_state): State<AppState>) -> Response {
+    match oidc_jwks(&app_state.config.oidc) {
+        Ok(jwks) => Json(jwks).into_response(),
+        Err(_) => server_error(),
+    }
+}
+
+fn invalid_token(description: &str) -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        [(
+            header::WWW_AUTHENTICATE,
+            format!(
+                r#"Bearer error="invalid_token", error_description="{}""#,
+                description
+            ),
+        )],
+    )
+        .into_response()
+}
+
+pub async fn user_info(State(app_state): State<AppState>, headers: HeaderMap) -> Response {
+    let token = match headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    {
+        Some(token) => token.to_string(),
+        None => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Bearer")],
+            )
+                .into_response()
+        }
+    };
+
+    let user_info = match authorize_user_token(&app_state, token, Some(USER_READ_SCOPE)).await {
+        Ok(token) => create_user_controller(&app_state).user_info(token).await,
+        Err(error) => Err(error),
+    };
+
+    match user_info {
+        Ok(user) => {
+            let mut claims = json!({ "sub": user.sub });
+            if let Some(preferred_username) = user.preferred_username {
+                claims["preferred_username"] = json!(preferred_username);
+            }
+            if let (Some(email), Some(email_verified)) = (user.email, user.email_verified) {
+                claims["email"] = json!(email);
+                claims["email_verified"] = json!(email_verified);
+            }
+            Json(claims).into_response()
+        }
+        Err(error) => match error.code {
+            Code::InvalidArgument
+            | Code::Unauthenticated
+            | Code::PermissionDenied
+            | Code::NotFound => invalid_token("The access token is invalid"),
+            _ => server_error(),
+        },
+    }
+}
@@ -0,0 +1,48 @@
+use super::{EmailTemplate, Message};
+
+pub fn message(message: Message) -> &'static str {
+    match message {
+        Message::UserBlocked => "User are blocked",
+        Message::UserNotActivated => "User not activated",
+        Message::UserAlreadyActivated => "User already activated",
+        Message::UserActivated => "User activated",
+        Message::UserActivatedSuccessfully => "User activated successfully",
+        Message::UserDeletedSuccessfully => "User deleted successfully",
+        Message::PasswordUpdated => "Password updated",
+        Message::PasswordRecoveredSuccessfully => "Password recovered successfully",
+        Message::IncorrectPassword => "Incorrect password",
+        Message::OldPasswordInvalid => "Old password is invalid",
+        Message::CodeNotFound => "Code not found",
+        Message::CodeExpired => "Code expired",
+        Message::CodeEmpty => "Code is empty",
+        Message::InvalidLoginCode => "Invalid login code",
+        Message::TooManyLoginAttempts => "Too many attempts, request a new login code",
+        Message::UsernameEmpty => "Username is empty",
+        Message::UsernameEmptyAfterSanitize => "Username is empty after sanitize",
+        Message::EmailEmpty => "Email is empty",
+        Message::EmailEmptyAfterSanitize => "Email is empty after sanitize",
+        Message::PasswordEmpty => "Password is empty",
+        Message::PasswordEmptyAfterSanitize => "Password is empty after sanitize",
+        Message::InternalError => "Internal error",
+        Message::UnknownError => "Unknown error",
+        Message::ServiceUnavailable => "Service temporarily unavailable, try again later",
+    }
+}
+
+pub fn email(template: EmailTemplate) -> (&'static str, &'static str) {
+    match template {
+        EmailTemplate::ActivationCode => (
+            "Activate your account",
+            "Your activation code is {code}. It expires in {minutes} minutes.",
+        ),
+        EmailTemplate::RecoveryCode => (
+            "Recover your password",
+            "Your password recovery code is {code}. It expires in {minutes} minutes. \
+             If you did not ask for it, you can ignore this email.",
+        ),
+        EmailTemplate::LoginCode => (
+            "Your login code",
+            "Your login code is {code}. It expires in {minutes} minutes.",
+        ),
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    cmp::Ordering,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tonic::codegen::http::{Request, Response};
+use tower::{Layer, Service};
+
+pub const ACCEPT_LANGUAGE_HEADER: &str = "accept-language";
+
+/// Languages the messages are translated to.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    Pt,
+}
+
+impl Locale {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Pt => "pt",
+        }
+    }
+
+    /// Any regional variant maps to its language, `pt-BR` and `pt-PT` both to `Pt`.
+    fn from_language_tag(tag: &str) -> Option<Locale> {
+        let language = tag.split('-').next()?.trim().to_ascii_lowercase();
+        match language.as_str() {
+            "en" => Some(Locale::En),
+            "pt" => Some(Locale::Pt),
+            _ => None,
+        }
+    }
+
+    /// Supported locale the caller prefers the most, from an `accept-language` value
+    /// such as `pt-BR,pt;q=0.9,en;q=0.8`.
+    pub fn from_accept_language(value: &str) -> Option<Locale> {
+        let mut ranges: Vec<(f32, &str)> = value
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let quality = match parts.find_map(|part| part.trim().strip_prefix("q=")) {
+                    Some(quality) => quality.trim().parse().ok()?,
+                    None => 1.0,
+                };
+                Some((quality, tag))
+            })
+            .collect();
+        // Stable, so ranges of the same quality keep the order the caller sent them in.
+        ranges.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal));
+
+        ranges
+            .into_iter()
+            .filter(|(quality, _)| *quality > 0.0)
+            .find_map(|(_, tag)| Locale::from_language_tag(tag))
+    }
+}
+
+tokio::task_local! {
+    static REQUEST_LOCALE: Locale;
+}
+
+/// Locale of the gRPC request being served, English outside of one.
+pub fn current_locale() -> Locale {
+    REQUEST_LOCALE
+        .try_with(|locale| *locale)
+        .unwrap_or_default()
+}
+
+/// Serves each gRPC request in the locale asked for by its `accept-language` metadata.
+#[derive(Debug, Clone, Default)]
+pub struct LocaleLayer;
+
+impl<S> Layer<S> for LocaleLayer {
+    type Service = LocaleService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        LocaleService { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LocaleService<S> {
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for LocaleService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let locale = request
+            .headers()
+            .get(ACCEPT_LANGUAGE_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(Locale::from_accept_language)
+            .unwrap_or_default();
+        let response = self.inner.call(request);
+
+        Box::pin(REQUEST_LOCALE.scope(locale, response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_picks_the_preferred_supported_locale() {
+        assert_eq!(Locale::from_accept_language("pt-BR"), Some(Locale::Pt));
+        assert_eq!(
+            Locale::from_accept_language("fr-FR, pt;q=0.8, en;q=0.9"),
+            Some(Locale::En)
+        );
+        assert_eq!(
+            Locale::from_accept_language("en;q=0, PT-pt;q=0.5"),
+            Some(Locale::Pt)
+        );
+        assert_eq!(Locale::from_accept_language("de, fr;q=0.5"), None);
+        assert_eq!(Locale::from_accept_language("*"), None);
+        assert_eq!(current_locale(), Locale::En);
+    }
+}
@@ -0,0 +1,91 @@
+pub mod en;
+pub mod locale;
+pub mod pt;
+
+pub use locale::{current_locale, Locale};
+
+/// User-facing text, written out by the catalog of each locale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Message {
+    UserBlocked,
+    UserNotActivated,
+    UserAlreadyActivated,
+    UserActivated,
+    UserActivatedSuccessfully,
+    UserDeletedSuccessfully,
+    PasswordUpdated,
+    PasswordRecoveredSuccessfully,
+    IncorrectPassword,
+    OldPasswordInvalid,
+    CodeNotFound,
+    CodeExpired,
+    CodeEmpty,
+    InvalidLoginCode,
+    TooManyLoginAttempts,
+    UsernameEmpty,
+    UsernameEmptyAfterSanitize,
+    EmailEmpty,
+    EmailEmptyAfterSanitize,
+    PasswordEmpty,
+    PasswordEmptyAfterSanitize,
+    InternalError,
+    UnknownError,
+    ServiceUnavailable,
+}
+
+/// Emails sent with a code, their text has `{code}` and `{minutes}` placeholders.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EmailTemplate {
+    ActivationCode,
+    RecoveryCode,
+    LoginCode,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Email {
+    pub subject: String,
+    pub body: String,
+}
+
+pub fn message(locale: Locale, message: Message) -> &'static str {
+    match locale {
+        Locale::En => en::message(message),
+        Locale::Pt => pt::message(message),
+    }
+}
+
+/// The message in the locale of the request being served.
+pub fn t(key: Message) -> String {
+    message(current_locale(), key).to_string()
+}
+
+pub fn render_email(locale: Locale, template: EmailTemplate, code: &str, minutes: i64) -> Email {
+    let (subject, body) = match locale {
+        Locale::En => en::email(template),
+        Locale::Pt => pt::email(template),
+    };
+
+    Email {
+        subject: subject.to_string(),
+        body: body
+            .replace("{code}", code)
+            .replace("{minutes}", &minutes.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_and_emails_follow_the_locale() {
+        assert_eq!(message(Locale::En, Message::CodeExpired), "Code expired");
+        assert_eq!(message(Locale::Pt, Message::CodeExpired), "Código expirado");
+        assert_eq!(t(Message::CodeExpired), "Code expired");
+
+        let email = render_email(Locale::Pt, EmailTemplate::LoginCode, "481516", 10);
+        assert_eq!(email.subject, "Seu código de acesso");
+        assert!(email.body.contains("481516"));
+        assert!(email.body.contains("10 minutos"));
+    }
+}
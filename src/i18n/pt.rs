@@ -0,0 +1,50 @@
+use super::{EmailTemplate, Message};
+
+pub fn message(message: Message) -> &'static str {
+    match message {
+        Message::UserBlocked => "Usuário bloqueado",
+        Message::UserNotActivated => "Usuário não ativado",
+        Message::UserAlreadyActivated => "Usuário já ativado",
+        Message::UserActivated => "Usuário ativado",
+        Message::UserActivatedSuccessfully => "Usuário ativado com sucesso",
+        Message::UserDeletedSuccessfully => "Usuário excluído com sucesso",
+        Message::PasswordUpdated => "Senha atualizada",
+        Message::PasswordRecoveredSuccessfully => "Senha recuperada com sucesso",
+        Message::IncorrectPassword => "Senha incorreta",
+        Message::OldPasswordInvalid => "A senha antiga é inválida",
+        Message::CodeNotFound => "Código não encontrado",
+        Message::CodeExpired => "Código expirado",
+        Message::CodeEmpty => "O código está vazio",
+        Message::InvalidLoginCode => "Código de acesso inválido",
+        Message::TooManyLoginAttempts => "Muitas tentativas, solicite um novo código de acesso",
+        Message::UsernameEmpty => "O nome de usuário está vazio",
+        Message::UsernameEmptyAfterSanitize => "O nome de usuário ficou vazio após a limpeza",
+        Message::EmailEmpty => "O email está vazio",
+        Message::EmailEmptyAfterSanitize => "O email ficou vazio após a limpeza",
+        Message::PasswordEmpty => "A senha está vazia",
+        Message::PasswordEmptyAfterSanitize => "A senha ficou vazia após a limpeza",
+        Message::InternalError => "Erro interno",
+        Message::UnknownError => "Erro desconhecido",
+        Message::ServiceUnavailable => {
+            "Serviço temporariamente indisponível, tente novamente mais tarde"
+        }
+    }
+}
+
+pub fn email(template: EmailTemplate) -> (&'static str, &'static str) {
+    match template {
+        EmailTemplate::ActivationCode => (
+            "Ative sua conta",
+            "Seu código de ativação é {code}. Ele expira em {minutes} minutos.",
+        ),
+        EmailTemplate::RecoveryCode => (
+            "Recupere sua senha",
+            "Seu código de recuperação de senha é {code}. Ele expira em {minutes} minutos. \
+             Se você não o solicitou, ignore este email.",
+        ),
+        EmailTemplate::LoginCode => (
+            "Seu código de acesso",
+            "Seu código de acesso é {code}. Ele expira em {minutes} minutos.",
+        ),
+    }
+}
@@ -1,16 +1,29 @@
+#![allow(non_snake_case)]
+
+use config::app_config::Config;
+use database::{redis_pool::RedisPool, storage::Storage};
 use sqlx::{Pool, Postgres};
+use std::sync::Arc;
+pub mod config;
 pub mod controllers;
 pub mod database;
 pub mod dtos;
 pub mod error;
+pub mod http;
+pub mod i18n;
 pub mod models;
 pub mod repositories;
 pub mod rpc;
 pub mod security;
 pub mod services;
+pub mod telemetry;
 pub mod utils;
 
+#[derive(Clone)]
 pub struct AppState {
-    db_pg_pool: Pool<Postgres>,
-    redis_client: redis::Client,
+    pub db_pg_pool: Pool<Postgres>,
+    pub redis: RedisPool,
+    /// Backend of the users and their codes, Postgres going through `db_pg_pool`.
+    pub storage: Storage,
+    pub config: Arc<Config>,
 }
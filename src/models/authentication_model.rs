@@ -5,14 +5,17 @@ use crate::{
 };
 use crate::{
     error::*,
+    i18n::{current_locale, render_email, t, EmailTemplate, Message},
     repositories::{
         user_repository::UserRepositoryUpdateParams,
         users_code_repository::{UsersCode, UsersCodeRepository},
     },
+    services::mail::mail_sender::MailSender,
 };
 use async_trait::async_trait;
 use chrono::{Duration, Utc};
 use mockall::automock;
+use tracing::{field, instrument, Span};
 
 #[async_trait]
 #[automock]
@@ -46,19 +49,27 @@ pub trait AuthenticationModel: Sync + Send {
     async fn delete_user(&self, user_id: String) -> Result<String, AppError>;
 }
 
-pub struct UserModel<R, C> {
+pub struct UserModel<R, C, M> {
     pub user_repository: R,
     pub user_code_repository: C,
+    /// Delivers the activation and recovery codes to the user's email.
+    pub mail_sender: M,
     pub password_hasher: PasswordHasher,
     pub password_verify: PasswordVerify,
     pub new_id: fn() -> String,
     pub generate_code: fn() -> String,
+    /// Minutes the activation and recovery codes stay valid.
+    pub code_ttl_minutes: i64,
 }
 
 #[async_trait]
-impl<R: UserRepository, C: UsersCodeRepository> AuthenticationModel for UserModel<R, C> {
+impl<R: UserRepository, C: UsersCodeRepository, M: MailSender> AuthenticationModel
+    for UserModel<R, C, M>
+{
+    #[instrument(skip_all, fields(user_id = field::Empty), err(level = "debug", Debug))]
     async fn create(&self, user: UserModelCreateParams) -> Result<UserModelInsertReturn, AppError> {
         let id = (self.new_id)();
+        Span::current().record("user_id", id.as_str());
         let hashed_password = (self.password_hasher)(user.password)?;
 
         let user = self
@@ -79,15 +90,20 @@ impl<R: UserRepository, C: UsersCodeRepository> AuthenticationModel for UserMode
             blocked: user.blocked,
         })
     }
+    #[instrument(skip_all, fields(user_id = field::Empty), err(level = "debug", Debug))]
     async fn login_verification(
         &self,
         username: String,
         password: String,
     ) -> Result<UserModelLoginVerificationReturn, AppError> {
         let user = self.user_repository.consult_by_username(username).await?;
+        Span::current().record("user_id", user.id.as_str());
 
         if !(self.password_verify)(user.password, password)? {
-            return Err(AppError::new(Code::Unauthenticated, "Incorrect password"));
+            return Err(
+                AppError::new(Code::Unauthenticated, t(Message::IncorrectPassword))
+                    .with_reason(Reason::InvalidCredentials),
+            );
         }
 
         Ok(UserModelLoginVerificationReturn {
@@ -99,6 +115,7 @@ impl<R: UserRepository, C: UsersCodeRepository> AuthenticationModel for UserMode
         })
     }
 
+    #[instrument(skip_all, fields(user_id = %id), err(level = "debug", Debug))]
     async fn recover_user_data(
         &self,
         id: String,
@@ -113,6 +130,7 @@ impl<R: UserRepository, C: UsersCodeRepository> AuthenticationModel for UserMode
         })
     }
 
+    #[instrument(skip_all, fields(user_id = %id), err(level = "debug", Debug))]
     async fn update(&self, id: String, user: UserModelUpdateParams) -> Result<String, AppError> {
         let user_to_be_updated = UserRepositoryUpdateParams {
             username: user.username,
@@ -125,6 +143,7 @@ impl<R: UserRepository, C: UsersCodeRepository> AuthenticationModel for UserMode
             .await
     }
 
+    #[instrument(skip_all, fields(user_id = %id), err(level = "debug", Debug))]
     async fn update_password(
         &self,
         id: String,
@@ -136,7 +155,7 @@ impl<R: UserRepository, C: UsersCodeRepository> AuthenticationModel for UserMode
         if !(self.password_verify)(user.password, old_password)? {
             return Err(AppError::new(
                 Code::InvalidArgument,
-                "Old password is invalid",
+                t(Message::OldPasswordInvalid),
             ));
         }
 
@@ -151,11 +170,12 @@ impl<R: UserRepository, C: UsersCodeRepository> AuthenticationModel for UserMode
             .store_update(id, user_to_be_updated)
             .await
     }
+    #[instrument(skip_all, fields(user_id = field::Empty), err(level = "debug", Debug))]
     async fn create_code_by_email(&self, email: String) -> Result<String, AppError> {
-        let expire_minutes = 30;
-        let expire_at = Utc::now().naive_utc() + Duration::minutes(expire_minutes.into());
+        let expire_at = Utc::now().naive_utc() + Duration::minutes(self.code_ttl_minutes);
 
         let user = self.user_repository.consult_by_email(email).await?;
+        Span::current().record("user_id", user.id.as_str());
 
         let code_key = (self.generate_code)();
 
@@ -167,38 +187,60 @@ impl<R: UserRepository, C: UsersCodeRepository> AuthenticationModel for UserMode
 
         self.user_code_repository.store(code).await?;
 
+        let email = render_email(
+            current_locale(),
+            EmailTemplate::RecoveryCode,
+            &code_key,
+            self.code_ttl_minutes,
+        );
+        self.mail_sender.send(user.email, email).await?;
+
         Ok(code_key)
     }
+    #[instrument(skip_all, fields(user_id = %user_id), err(level = "debug", Debug))]
     async fn create_code_by_user_id(&self, user_id: String) -> Result<String, AppError> {
-        let expire_minutes = 30;
+        let expire_at = Utc::now().naive_utc() + Duration::minutes(self.code_ttl_minutes);
 
-        let expire_at = Utc::now().naive_utc() + Duration::minutes(expire_minutes.into());
+        let user = self.user_repository.consult_by_id(user_id).await?;
 
         let code_key = (self.generate_code)();
 
         let code = UsersCode {
             code: code_key.clone(),
             expire_at,
-            user_id,
+            user_id: user.id,
         };
 
         self.user_code_repository.store(code).await?;
 
+        let email = render_email(
+            current_locale(),
+            EmailTemplate::ActivationCode,
+            &code_key,
+            self.code_ttl_minutes,
+        );
+        self.mail_sender.send(user.email, email).await?;
+
         Ok(code_key)
     }
 
+    #[instrument(skip_all, fields(user_id = %user_id), err(level = "debug", Debug))]
     async fn activate_user(&self, user_id: String, code_key: String) -> Result<String, AppError> {
         let code = self
             .user_code_repository
             .get(user_id.clone(), code_key)
             .await
             .map_err(|error| match error.code {
-                Code::NotFound => AppError::new(Code::NotFound, "Code not found"),
-                _ => AppError::new(Code::Internal, "internal error"),
+                Code::NotFound => AppError::new(Code::NotFound, t(Message::CodeNotFound))
+                    .with_reason(Reason::CodeNotFound),
+                _ => AppError::new(Code::Internal, "internal error").with_source(error),
             })?;
 
         if code.expire_at < Utc::now().naive_utc() {
-            return Err(AppError::new(Code::InvalidArgument, "Code expired"));
+            return Err(
+                AppError::new(Code::InvalidArgument, t(Message::CodeExpired))
+                    .with_reason(Reason::CodeExpired),
+            );
         }
 
         let user_to_be_updated = UserRepositoryUpdateParams {
@@ -210,8 +252,9 @@ impl<R: UserRepository, C: UsersCodeRepository> AuthenticationModel for UserMode
             .store_update(user_id, user_to_be_updated)
             .await?;
 
-        Ok(String::from("User activated"))
+        Ok(t(Message::UserActivated))
     }
+    #[instrument(skip_all, fields(user_id = field::Empty), err(level = "debug", Debug))]
     async fn recover_user_password(
         &self,
         email: String,
@@ -219,22 +262,29 @@ impl<R: UserRepository, C: UsersCodeRepository> AuthenticationModel for UserMode
         code_key: String,
     ) -> Result<String, AppError> {
         let user = self.user_repository.consult_by_email(email).await?;
+        Span::current().record("user_id", user.id.as_str());
 
         let code = self
             .user_code_repository
             .get(user.id.clone(), code_key)
             .await
             .map_err(|error| match error.code {
-                Code::NotFound => AppError::new(Code::NotFound, "Code not found"),
-                _ => AppError::new(Code::Internal, "internal error"),
+                Code::NotFound => AppError::new(Code::NotFound, t(Message::CodeNotFound))
+                    .with_reason(Reason::CodeNotFound),
+                _ => AppError::new(Code::Internal, "internal error").with_source(error),
             })?;
 
         if code.expire_at < Utc::now().naive_utc() {
-            return Err(AppError::new(Code::InvalidArgument, "Code expired"));
+            return Err(
+                AppError::new(Code::InvalidArgument, t(Message::CodeExpired))
+                    .with_reason(Reason::CodeExpired),
+            );
         }
 
+        let hashed_password = (self.password_hasher)(new_password)?;
+
         let user_to_be_updated = UserRepositoryUpdateParams {
-            password: Some(new_password),
+            password: Some(hashed_password),
             ..Default::default()
         };
 
@@ -242,11 +292,12 @@ impl<R: UserRepository, C: UsersCodeRepository> AuthenticationModel for UserMode
             .store_update(user.id, user_to_be_updated)
             .await?;
 
-        Ok(String::from("Password updated"))
+        Ok(t(Message::PasswordUpdated))
     }
+    #[instrument(skip_all, fields(user_id = %user_id), err(level = "debug", Debug))]
     async fn delete_user(&self, user_id: String) -> Result<String, AppError> {
         self.user_repository.delete(user_id).await?;
 
-        Ok(String::from("User deleted successfully"))
+        Ok(t(Message::UserDeletedSuccessfully))
     }
 }
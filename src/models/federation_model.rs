@@ -0,0 +1,235 @@
+use crate::{
+    dtos::models::dtos_model_federation::*,
+    error::*,
+    repositories::{
+        federated_identity_repository::{
+            FederatedIdentityRepository, FederatedIdentityStoreParams,
+        },
+        user_repository::{
+            UserRepository, UserRepositoryConsultReturn, UserRepositoryStoreParams,
+            UserRepositoryUpdateParams,
+        },
+    },
+    security::federation::{
+        unverified_issuer, verify_external_id_token, ExternalIdTokenClaims, FederatedProvider,
+    },
+    services::federation::jwks_fetcher::JwksFetcher,
+    utils::hash::password::PasswordHasher,
+};
+use async_trait::async_trait;
+use mockall::automock;
+
+const USERNAME_ATTEMPTS: usize = 5;
+
+#[async_trait]
+#[automock]
+pub trait FederatedLoginModel: Sync + Send {
+    /// Sign in with an ID token issued by a trusted provider, linking the external
+    /// identity to an account with the same verified email or provisioning a new one.
+    async fn external_login(
+        &self,
+        id_token: String,
+    ) -> Result<FederationModelLoginReturn, AppError>;
+}
+
+pub struct FederationModel<R, F, J> {
+    pub user_repository: R,
+    pub identity_repository: F,
+    pub jwks_fetcher: J,
+    /// Providers trusted for `external_login`, none when federation is not configured.
+    pub providers: Vec<FederatedProvider>,
+    pub password_hasher: PasswordHasher,
+    pub new_id: fn() -> String,
+    pub generate_secret: fn() -> String,
+}
+
+fn login_return(user: UserRepositoryConsultReturn, created: bool) -> FederationModelLoginReturn {
+    FederationModelLoginReturn {
+        id: user.id,
+        username: user.username,
+        email: user.email,
+        activated: user.activated,
+        blocked: user.blocked,
+        created,
+    }
+}
+
+impl<R, F, J> FederationModel<R, F, J>
+where
+    R: UserRepository,
+    F: FederatedIdentityRepository,
+    J: JwksFetcher,
+{
+    async fn verify_id_token(&self, id_token: &str) -> Result<ExternalIdTokenClaims, AppError> {
+        let issuer = unverified_issuer(id_token)?;
+        let provider = match self
+            .providers
+            .iter()
+            .find(|provider| provider.issuer == issuer)
+        {
+            Some(provider) => provider,
+            None => {
+                return Err(AppError::new(
+                    Code::Unauthenticated,
+                    "Untrusted identity provider",
+                ))
+            }
+        };
+
+        let jwks = self.jwks_fetcher.fetch(provider.jwks_uri.clone()).await?;
+
+        verify_external_id_token(id_token, provider, &jwks)
+    }
+
+    /// Pick a free username from the claims, the same characters as the sanitizer
+    /// keeps for registrations.
+    async fn available_username(&self, claims: &ExternalIdTokenClaims) -> Result<String, AppError> {
+        let base: String = claims
+            .preferred_username
+            .as_deref()
+            .or_else(|| {
+                claims
+                    .email
+                    .as_deref()
+                    .and_then(|email| email.split('@').next())
+            })
+            .unwrap_or_default()
+            .chars()
+            .filter(|char| char.is_alphanumeric())
+            .collect();
+        let base = match base.is_empty() {
+            true => String::from("user"),
+            false => base,
+        };
+
+        let mut username = base.clone();
+        for _ in 0..USERNAME_ATTEMPTS {
+            match self
+                .user_repository
+                .consult_by_username(username.clone())
+                .await
+            {
+                Ok(_) => (),
+                Err(error) if error.code == Code::NotFound => return Ok(username),
+                Err(error) => return Err(error),
+            }
+
+            let suffix: String = (self.new_id)()
+                .chars()
+                .filter(|char| char.is_alphanumeric())
+                .take(6)
+                .collect();
+            username = format!("{}{}", base, suffix);
+        }
+
+        Err(AppError::new(
+            Code::AlreadyExists,
+            "Unable to find an available username",
+        ))
+    }
+
+    async fn provision_user(
+        &self,
+        claims: &ExternalIdTokenClaims,
+        email: String,
+    ) -> Result<UserRepositoryConsultReturn, AppError> {
+        let username = self.available_username(claims).await?;
+        // Federated accounts sign in through their provider, the password can only be
+        // set later with the recovery flow.
+        let password = (self.password_hasher)((self.generate_secret)())?;
+
+        let user = self
+            .user_repository
+            .store(UserRepositoryStoreParams {
+                id: (self.new_id)(),
+                username,
+                email,
+                password: password.clone(),
+            })
+            .await?;
+
+        if claims.email_verified {
+            self.user_repository
+                .store_update(
+                    user.id.clone(),
+                    UserRepositoryUpdateParams {
+                        activated: Some(true),
+                        ..Default::default()
+                    },
+                )
+                .await?;
+        }
+
+        Ok(UserRepositoryConsultReturn {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            password,
+            activated: user.activated || claims.email_verified,
+            blocked: user.blocked,
+        })
+    }
+}
+
+#[async_trait]
+impl<R, F, J> FederatedLoginModel for FederationModel<R, F, J>
+where
+    R: UserRepository,
+    F: FederatedIdentityRepository,
+    J: JwksFetcher,
+{
+    async fn external_login(
+        &self,
+        id_token: String,
+    ) -> Result<FederationModelLoginReturn, AppError> {
+        let claims = self.verify_id_token(&id_token).await?;
+
+        match self
+            .identity_repository
+            .consult(claims.iss.clone(), claims.sub.clone())
+            .await
+        {
+            Ok(identity) => {
+                let user = self.user_repository.consult_by_id(identity.user_id).await?;
+                return Ok(login_return(user, false));
+            }
+            Err(error) if error.code == Code::NotFound => (),
+            Err(error) => return Err(error),
+        }
+
+        let email = match &claims.email {
+            Some(email) if !email.trim().is_empty() => email.trim().to_lowercase(),
+            _ => {
+                return Err(AppError::new(
+                    Code::InvalidArgument,
+                    "The identity provider did not share an email address",
+                ))
+            }
+        };
+
+        let (user, created) = match self.user_repository.consult_by_email(email.clone()).await {
+            Ok(user) if claims.email_verified => (user, false),
+            Ok(_) => {
+                return Err(AppError::new(
+                    Code::AlreadyExists,
+                    "Email already registered, it must be verified by the identity provider to link the accounts",
+                ))
+            }
+            Err(error) if error.code == Code::NotFound => {
+                (self.provision_user(&claims, email.clone()).await?, true)
+            }
+            Err(error) => return Err(error),
+        };
+
+        self.identity_repository
+            .store(FederatedIdentityStoreParams {
+                issuer: claims.iss,
+                subject: claims.sub,
+                user_id: user.id.clone(),
+                email: Some(email),
+            })
+            .await?;
+
+        Ok(login_return(user, created))
+    }
+}
@@ -1 +1,7 @@
 pub mod authentication_model;
+pub mod federation_model;
+pub mod oauth_model;
+pub mod passwordless_model;
+pub mod personal_access_token_model;
+pub mod webauthn_model;
+pub mod webhook_model;
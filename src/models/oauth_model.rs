@@ -0,0 +1,462 @@
+use crate::{
+    dtos::models::dtos_model_oauth::*,
+    error::*,
+    models::authentication_model::AuthenticationModel,
+    repositories::{
+        oauth_authorization_code_repository::{
+            OAuthAuthorizationCode, OAuthAuthorizationCodeRepository,
+        },
+        oauth_client_repository::{
+            OAuthClientRepository, OAuthClientRepositoryConsultReturn,
+            OAuthClientRepositoryStoreParams,
+        },
+        oauth_consent_repository::OAuthConsentRepository,
+        oauth_refresh_token_repository::{
+            OAuthRefreshTokenRepository, OAuthRefreshTokenStoreParams,
+        },
+    },
+    security::pkce::{verify_pkce, PKCE_METHOD_S256},
+    utils::hash::{
+        password::{PasswordHasher, PasswordVerify},
+        token::hash_token,
+    },
+};
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use mockall::automock;
+
+pub const AUTHORIZATION_CODE_TTL_MINUTES: i64 = 5;
+pub const REFRESH_TOKEN_TTL_DAYS: i64 = 30;
+
+#[async_trait]
+#[automock]
+pub trait AuthorizationServerModel: Sync + Send {
+    async fn register_client(
+        &self,
+        client: OAuthModelRegisterClientParams,
+    ) -> Result<OAuthModelRegisterClientReturn, AppError>;
+    /// Check the client, redirect uri and scopes of an authorization request before
+    /// anything is redirected back to the client.
+    async fn validate_authorization_request(
+        &self,
+        client_id: String,
+        redirect_uri: String,
+        scopes: Vec<String>,
+    ) -> Result<OAuthModelClientReturn, AppError>;
+    async fn authorize(&self, params: OAuthModelAuthorizeParams) -> Result<String, AppError>;
+    async fn exchange_authorization_code(
+        &self,
+        params: OAuthModelExchangeCodeParams,
+    ) -> Result<OAuthModelTokenReturn, AppError>;
+    async fn refresh_access_token(
+        &self,
+        client: OAuthModelClientCredentials,
+        refresh_token: String,
+    ) -> Result<OAuthModelTokenReturn, AppError>;
+    /// Client credentials grant, only available to service clients.
+    async fn issue_service_token(
+        &self,
+        client: OAuthModelClientCredentials,
+        scopes: Vec<String>,
+    ) -> Result<OAuthModelServiceTokenReturn, AppError>;
+}
+
+pub struct OAuthModel<M, C, S, A, T> {
+    pub user_model: M,
+    pub client_repository: C,
+    pub consent_repository: S,
+    pub code_repository: A,
+    pub refresh_token_repository: T,
+    pub password_hasher: PasswordHasher,
+    pub password_verify: PasswordVerify,
+    pub new_id: fn() -> String,
+    pub generate_secret: fn() -> String,
+}
+
+impl<M, C, S, A, T> OAuthModel<M, C, S, A, T>
+where
+    M: AuthenticationModel,
+    C: OAuthClientRepository,
+    S: OAuthConsentRepository,
+    A: OAuthAuthorizationCodeRepository,
+    T: OAuthRefreshTokenRepository,
+{
+    async fn authenticate_client(
+        &self,
+        credentials: OAuthModelClientCredentials,
+    ) -> Result<OAuthClientRepositoryConsultReturn, AppError> {
+        let client = match self
+            .client_repository
+            .consult_by_id(credentials.client_id)
+            .await
+        {
+            Ok(client) => client,
+            Err(error) if error.code == Code::NotFound => {
+                return Err(AppError::new(Code::Unauthenticated, "Unknown client"))
+            }
+            Err(error) => return Err(error),
+        };
+
+        if let Some(secret_hash) = &client.secret_hash {
+            let secret = match credentials.client_secret {
+                Some(secret) => secret,
+                None => {
+                    return Err(AppError::new(
+                        Code::Unauthenticated,
+                        "Client authentication required",
+                    ))
+                }
+            };
+
+            if !(self.password_verify)(secret_hash.clone(), secret)? {
+                return Err(AppError::new(
+                    Code::Unauthenticated,
+                    "Invalid client secret",
+                ));
+            }
+        }
+
+        Ok(client)
+    }
+
+    async fn issue_tokens(
+        &self,
+        client_id: String,
+        user_id: String,
+        scopes: Vec<String>,
+        authentication: Option<OAuthModelAuthentication>,
+    ) -> Result<OAuthModelTokenReturn, AppError> {
+        let user = self.user_model.recover_user_data(user_id.clone()).await?;
+
+        if user.blocked {
+            return Err(AppError::new(Code::PermissionDenied, "User is blocked"));
+        }
+
+        let refresh_token = (self.generate_secret)();
+
+        self.refresh_token_repository
+            .store(OAuthRefreshTokenStoreParams {
+                token_hash: hash_token(&refresh_token),
+                client_id: client_id.clone(),
+                user_id: user_id.clone(),
+                scopes: scopes.clone(),
+                expires_at: Utc::now().naive_utc() + Duration::days(REFRESH_TOKEN_TTL_DAYS),
+            })
+            .await?;
+
+        Ok(OAuthModelTokenReturn {
+            client_id,
+            user_id,
+            username: user.username,
+            email: user.email,
+            activated: user.activated,
+            blocked: user.blocked,
+            refresh_token,
+            scopes,
+            authentication,
+        })
+    }
+}
+
+#[async_trait]
+impl<M, C, S, A, T> AuthorizationServerModel for OAuthModel<M, C, S, A, T>
+where
+    M: AuthenticationModel,
+    C: OAuthClientRepository,
+    S: OAuthConsentRepository,
+    A: OAuthAuthorizationCodeRepository,
+    T: OAuthRefreshTokenRepository,
+{
+    async fn register_client(
+        &self,
+        client: OAuthModelRegisterClientParams,
+    ) -> Result<OAuthModelRegisterClientReturn, AppError> {
+        if client.redirect_uris.is_empty() && !client.service {
+            return Err(AppError::new(
+                Code::InvalidArgument,
+                "At least one redirect uri is required",
+            ));
+        }
+
+        for redirect_uri in &client.redirect_uris {
+            match reqwest::Url::parse(redirect_uri) {
+                Ok(url) if url.fragment().is_none() => (),
+                _ => {
+                    return Err(AppError::new(
+                        Code::InvalidArgument,
+                        format!("Invalid redirect uri: {}", redirect_uri),
+                    ))
+                }
+            }
+        }
+
+        let client_secret = match client.confidential || client.service {
+            true => Some((self.generate_secret)()),
+            false => None,
+        };
+        let secret_hash = match &client_secret {
+            Some(secret) => Some((self.password_hasher)(secret.clone())?),
+            None => None,
+        };
+
+        let stored_client = self
+            .client_repository
+            .store(OAuthClientRepositoryStoreParams {
+                id: (self.new_id)(),
+                name: client.name,
+                secret_hash,
+                redirect_uris: client.redirect_uris,
+                scopes: client.scopes,
+                service: client.service,
+            })
+            .await?;
+
+        Ok(OAuthModelRegisterClientReturn {
+            client_id: stored_client.id,
+            client_secret,
+        })
+    }
+
+    async fn validate_authorization_request(
+        &self,
+        client_id: String,
+        redirect_uri: String,
+        scopes: Vec<String>,
+    ) -> Result<OAuthModelClientReturn, AppError> {
+        let client = match self.client_repository.consult_by_id(client_id).await {
+            Ok(client) => client,
+            Err(error) if error.code == Code::NotFound => {
+                return Err(AppError::new(Code::InvalidArgument, "Unknown client"))
+            }
+            Err(error) => return Err(error),
+        };
+
+        if client.service {
+            return Err(AppError::new(
+                Code::InvalidArgument,
+                "Service clients cannot use the authorization code grant",
+            ));
+        }
+
+        if !client.redirect_uris.contains(&redirect_uri) {
+            return Err(AppError::new(
+                Code::InvalidArgument,
+                "Redirect uri not registered for this client",
+            ));
+        }
+
+        if let Some(scope) = scopes.iter().find(|scope| !client.scopes.contains(scope)) {
+            return Err(AppError::new(
+                Code::InvalidArgument,
+                format!("Scope not allowed for this client: {}", scope),
+            ));
+        }
+
+        Ok(OAuthModelClientReturn {
+            id: client.id,
+            name: client.name,
+            scopes: client.scopes,
+        })
+    }
+
+    async fn authorize(&self, params: OAuthModelAuthorizeParams) -> Result<String, AppError> {
+        let client = self
+            .validate_authorization_request(
+                params.client_id,
+                params.redirect_uri.clone(),
+                params.scopes.clone(),
+            )
+            .await?;
+
+        if params.code_challenge_method != PKCE_METHOD_S256 || params.code_challenge.is_empty() {
+            return Err(AppError::new(
+                Code::InvalidArgument,
+                "A S256 PKCE code challenge is required",
+            ));
+        }
+
+        let user = match self
+            .user_model
+            .login_verification(params.username, params.password)
+            .await
+        {
+            Ok(user) => user,
+            Err(error) if error.code == Code::NotFound => {
+                return Err(AppError::new(
+                    Code::Unauthenticated,
+                    "Incorrect username or password",
+                ))
+            }
+            Err(error) => return Err(error),
+        };
+
+        if user.blocked {
+            return Err(AppError::new(Code::PermissionDenied, "User is blocked"));
+        }
+
+        if params.consent_granted {
+            self.consent_repository
+                .store(user.id.clone(), client.id.clone(), params.scopes.clone())
+                .await?;
+        } else {
+            let consented = match self
+                .consent_repository
+                .consult(user.id.clone(), client.id.clone())
+                .await
+            {
+                Ok(consent) => params
+                    .scopes
+                    .iter()
+                    .all(|scope| consent.scopes.contains(scope)),
+                Err(error) if error.code == Code::NotFound => false,
+                Err(error) => return Err(error),
+            };
+
+            if !consented {
+                return Err(AppError::new(Code::PermissionDenied, "Consent required"));
+            }
+        }
+
+        let code = (self.generate_secret)();
+
+        self.code_repository
+            .store(OAuthAuthorizationCode {
+                code: code.clone(),
+                client_id: client.id,
+                user_id: user.id,
+                redirect_uri: params.redirect_uri,
+                code_challenge: params.code_challenge,
+                scopes: params.scopes,
+                expire_at: Utc::now().naive_utc()
+                    + Duration::minutes(AUTHORIZATION_CODE_TTL_MINUTES),
+                nonce: params.nonce,
+                auth_time: Utc::now().timestamp(),
+            })
+            .await?;
+
+        Ok(code)
+    }
+
+    async fn exchange_authorization_code(
+        &self,
+        params: OAuthModelExchangeCodeParams,
+    ) -> Result<OAuthModelTokenReturn, AppError> {
+        let client = self.authenticate_client(params.client).await?;
+
+        let code = match self.code_repository.take(params.code).await {
+            Ok(code) => code,
+            Err(error) if error.code == Code::NotFound => {
+                return Err(AppError::new(
+                    Code::PermissionDenied,
+                    "Invalid authorization code",
+                ))
+            }
+            Err(error) => return Err(error),
+        };
+
+        if code.client_id != client.id
+            || code.redirect_uri != params.redirect_uri
+            || code.expire_at < Utc::now().naive_utc()
+        {
+            return Err(AppError::new(
+                Code::PermissionDenied,
+                "Invalid authorization code",
+            ));
+        }
+
+        if !verify_pkce(&params.code_verifier, &code.code_challenge) {
+            return Err(AppError::new(
+                Code::PermissionDenied,
+                "Invalid PKCE code verifier",
+            ));
+        }
+
+        let authentication = OAuthModelAuthentication {
+            auth_time: code.auth_time,
+            nonce: code.nonce,
+        };
+
+        self.issue_tokens(client.id, code.user_id, code.scopes, Some(authentication))
+            .await
+    }
+
+    async fn refresh_access_token(
+        &self,
+        client: OAuthModelClientCredentials,
+        refresh_token: String,
+    ) -> Result<OAuthModelTokenReturn, AppError> {
+        let client = self.authenticate_client(client).await?;
+        let token_hash = hash_token(&refresh_token);
+
+        let token = match self
+            .refresh_token_repository
+            .consult_by_hash(token_hash.clone())
+            .await
+        {
+            Ok(token) => token,
+            Err(error) if error.code == Code::NotFound => {
+                return Err(AppError::new(
+                    Code::PermissionDenied,
+                    "Invalid refresh token",
+                ))
+            }
+            Err(error) => return Err(error),
+        };
+
+        if token.client_id != client.id
+            || token.revoked_at.is_some()
+            || token.expires_at < Utc::now().naive_utc()
+        {
+            return Err(AppError::new(
+                Code::PermissionDenied,
+                "Invalid refresh token",
+            ));
+        }
+
+        match self.refresh_token_repository.revoke(token_hash).await {
+            Ok(_) => (),
+            Err(error) if error.code == Code::NotFound => {
+                return Err(AppError::new(
+                    Code::PermissionDenied,
+                    "Invalid refresh token",
+                ))
+            }
+            Err(error) => return Err(error),
+        }
+
+        self.issue_tokens(client.id, token.user_id, token.scopes, None)
+            .await
+    }
+
+    async fn issue_service_token(
+        &self,
+        client: OAuthModelClientCredentials,
+        scopes: Vec<String>,
+    ) -> Result<OAuthModelServiceTokenReturn, AppError> {
+        let client = self.authenticate_client(client).await?;
+
+        if !client.service || client.secret_hash.is_none() {
+            return Err(AppError::new(
+                Code::PermissionDenied,
+                "Client not allowed to use the client credentials grant",
+            ));
+        }
+
+        if let Some(scope) = scopes.iter().find(|scope| !client.scopes.contains(scope)) {
+            return Err(AppError::new(
+                Code::InvalidArgument,
+                format!("Scope not allowed for this client: {}", scope),
+            ));
+        }
+
+        let scopes = match scopes.is_empty() {
+            true => client.scopes,
+            false => scopes,
+        };
+
+        Ok(OAuthModelServiceTokenReturn {
+            client_id: client.id,
+            scopes,
+        })
+    }
+}
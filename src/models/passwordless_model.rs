@@ -0,0 +1,142 @@
+use crate::{
+    dtos::models::{dtos_model_passwordless::*, dtos_model_user::UserModelLoginVerificationReturn},
+    error::*,
+    i18n::{current_locale, render_email, t, EmailTemplate, Message},
+    repositories::{
+        user_repository::UserRepository,
+        users_code_repository::{UsersCode, UsersCodeRepository},
+    },
+    services::mail::mail_sender::MailSender,
+};
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use mockall::automock;
+
+pub const LOGIN_CODE_MAX_ATTEMPTS: i64 = 5;
+
+#[async_trait]
+#[automock]
+pub trait PasswordlessLoginModel: Sync + Send {
+    /// Store a new login code and email it to the user, the code is never returned.
+    async fn create_login_code(
+        &self,
+        email: String,
+    ) -> Result<PasswordlessModelLoginCodeReturn, AppError>;
+    /// Redeem a login code, it is single use and discarded after too many wrong guesses.
+    async fn login_with_code(
+        &self,
+        email: String,
+        code_key: String,
+    ) -> Result<UserModelLoginVerificationReturn, AppError>;
+}
+
+/// `login_code_repository` must be scoped to `UsersCodePurpose::Login`, so login codes
+/// never activate accounts nor reset passwords.
+pub struct PasswordlessModel<R, C, M> {
+    pub user_repository: R,
+    pub login_code_repository: C,
+    pub mail_sender: M,
+    pub generate_code: fn() -> String,
+    pub code_ttl_minutes: i64,
+}
+
+#[async_trait]
+impl<R: UserRepository, C: UsersCodeRepository, M: MailSender> PasswordlessLoginModel
+    for PasswordlessModel<R, C, M>
+{
+    async fn create_login_code(
+        &self,
+        email: String,
+    ) -> Result<PasswordlessModelLoginCodeReturn, AppError> {
+        let user = self.user_repository.consult_by_email(email).await?;
+
+        let code_key = (self.generate_code)();
+        let expire_at = Utc::now().naive_utc() + Duration::minutes(self.code_ttl_minutes);
+
+        self.login_code_repository
+            .store(UsersCode {
+                code: code_key.clone(),
+                expire_at,
+                user_id: user.id,
+            })
+            .await?;
+
+        let email = render_email(
+            current_locale(),
+            EmailTemplate::LoginCode,
+            &code_key,
+            self.code_ttl_minutes,
+        );
+        self.mail_sender.send(user.email, email).await?;
+
+        Ok(PasswordlessModelLoginCodeReturn {
+            expires_in: self.code_ttl_minutes * 60,
+        })
+    }
+
+    async fn login_with_code(
+        &self,
+        email: String,
+        code_key: String,
+    ) -> Result<UserModelLoginVerificationReturn, AppError> {
+        let invalid_code = || {
+            AppError::new(Code::Unauthenticated, t(Message::InvalidLoginCode))
+                .with_reason(Reason::InvalidCode)
+        };
+
+        let user = match self.user_repository.consult_by_email(email).await {
+            Ok(user) => user,
+            Err(error) if error.code == Code::NotFound => return Err(invalid_code()),
+            Err(error) => return Err(error),
+        };
+
+        let code = match self
+            .login_code_repository
+            .get(user.id.clone(), code_key)
+            .await
+        {
+            Ok(code) => code,
+            Err(error) if error.code == Code::NotFound => {
+                let attempts = match self
+                    .login_code_repository
+                    .increment_attempts(user.id.clone())
+                    .await
+                {
+                    Ok(attempts) => attempts,
+                    Err(error) if error.code == Code::NotFound => return Err(invalid_code()),
+                    Err(error) => return Err(error),
+                };
+
+                if attempts >= LOGIN_CODE_MAX_ATTEMPTS {
+                    self.login_code_repository.delete(user.id).await?;
+
+                    return Err(AppError::new(
+                        Code::PermissionDenied,
+                        t(Message::TooManyLoginAttempts),
+                    )
+                    .with_reason(Reason::TooManyAttempts));
+                }
+
+                return Err(invalid_code());
+            }
+            Err(error) => return Err(error),
+        };
+
+        self.login_code_repository.delete(user.id.clone()).await?;
+
+        if code.expire_at < Utc::now().naive_utc() {
+            return Err(
+                AppError::new(Code::InvalidArgument, t(Message::CodeExpired))
+                    .with_reason(Reason::CodeExpired),
+            );
+        }
+
+        Ok(UserModelLoginVerificationReturn {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            activated: user.activated,
+            blocked: user.blocked,
+        })
+    }
+}
@@ -0,0 +1,172 @@
+use crate::{
+    dtos::models::{
+        dtos_model_personal_access_token::*, dtos_model_user::UserModelLoginVerificationReturn,
+    },
+    error::*,
+    repositories::{
+        personal_access_token_repository::{
+            PersonalAccessToken, PersonalAccessTokenRepository, PersonalAccessTokenStoreParams,
+        },
+        user_repository::UserRepository,
+    },
+    security::personal_access_token::{
+        parse_personal_access_token_scopes, PERSONAL_ACCESS_TOKEN_PREFIX,
+        PERSONAL_ACCESS_TOKEN_VISIBLE_LENGTH,
+    },
+    utils::hash::token::hash_token,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use mockall::automock;
+
+#[async_trait]
+#[automock]
+pub trait PersonalAccessTokenManagementModel: Sync + Send {
+    async fn create(
+        &self,
+        user_id: String,
+        params: PersonalAccessTokenModelCreateParams,
+    ) -> Result<PersonalAccessTokenModelCreateReturn, AppError>;
+    async fn list(
+        &self,
+        user_id: String,
+    ) -> Result<Vec<PersonalAccessTokenModelConsultReturn>, AppError>;
+    async fn revoke(&self, user_id: String, id: String) -> Result<String, AppError>;
+    /// Resolve the owner and scopes of a token presented in place of a login token.
+    async fn authenticate(
+        &self,
+        token: String,
+    ) -> Result<PersonalAccessTokenModelAuthenticateReturn, AppError>;
+}
+
+pub struct PersonalAccessTokenModel<R, P> {
+    pub user_repository: R,
+    pub token_repository: P,
+    pub new_id: fn() -> String,
+    pub generate_secret: fn() -> String,
+}
+
+fn to_consult_return(token: PersonalAccessToken) -> PersonalAccessTokenModelConsultReturn {
+    PersonalAccessTokenModelConsultReturn {
+        id: token.id,
+        name: token.name,
+        prefix: token.prefix,
+        scopes: token.scopes,
+        expires_at: token.expires_at,
+        last_used_at: token.last_used_at,
+        created_at: token.created_at,
+    }
+}
+
+#[async_trait]
+impl<R: UserRepository, P: PersonalAccessTokenRepository> PersonalAccessTokenManagementModel
+    for PersonalAccessTokenModel<R, P>
+{
+    async fn create(
+        &self,
+        user_id: String,
+        params: PersonalAccessTokenModelCreateParams,
+    ) -> Result<PersonalAccessTokenModelCreateReturn, AppError> {
+        let scopes = parse_personal_access_token_scopes(params.scopes)?;
+
+        if params
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now().naive_utc())
+        {
+            return Err(AppError::new(
+                Code::InvalidArgument,
+                "Expiration must be in the future",
+            ));
+        }
+
+        let token = format!(
+            "{}{}",
+            PERSONAL_ACCESS_TOKEN_PREFIX,
+            (self.generate_secret)()
+        );
+
+        let personal_access_token = self
+            .token_repository
+            .store(PersonalAccessTokenStoreParams {
+                id: (self.new_id)(),
+                user_id,
+                name: params.name,
+                prefix: token
+                    .chars()
+                    .take(PERSONAL_ACCESS_TOKEN_VISIBLE_LENGTH)
+                    .collect(),
+                token_hash: hash_token(&token),
+                scopes,
+                expires_at: params.expires_at,
+            })
+            .await?;
+
+        Ok(PersonalAccessTokenModelCreateReturn {
+            token,
+            personal_access_token: to_consult_return(personal_access_token),
+        })
+    }
+
+    async fn list(
+        &self,
+        user_id: String,
+    ) -> Result<Vec<PersonalAccessTokenModelConsultReturn>, AppError> {
+        let tokens = self.token_repository.list_by_user(user_id).await?;
+
+        Ok(tokens.into_iter().map(to_consult_return).collect())
+    }
+
+    async fn revoke(&self, user_id: String, id: String) -> Result<String, AppError> {
+        self.token_repository.delete(user_id, id).await
+    }
+
+    async fn authenticate(
+        &self,
+        token: String,
+    ) -> Result<PersonalAccessTokenModelAuthenticateReturn, AppError> {
+        let personal_access_token = match self
+            .token_repository
+            .consult_by_hash(hash_token(&token))
+            .await
+        {
+            Ok(personal_access_token) => personal_access_token,
+            Err(error) if error.code == Code::NotFound => {
+                return Err(AppError::new(
+                    Code::Unauthenticated,
+                    "Invalid personal access token",
+                ))
+            }
+            Err(error) => return Err(error),
+        };
+
+        if personal_access_token
+            .expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now().naive_utc())
+        {
+            return Err(AppError::new(
+                Code::Unauthenticated,
+                "Personal access token expired",
+            ));
+        }
+
+        self.token_repository
+            .touch(personal_access_token.id)
+            .await?;
+
+        let user = self
+            .user_repository
+            .consult_by_id(personal_access_token.user_id)
+            .await?;
+
+        Ok(PersonalAccessTokenModelAuthenticateReturn {
+            user: UserModelLoginVerificationReturn {
+                id: user.id,
+                username: user.username,
+                email: user.email,
+                activated: user.activated,
+                blocked: user.blocked,
+            },
+            scopes: personal_access_token.scopes,
+        })
+    }
+}
@@ -0,0 +1,321 @@
+use crate::{
+    dtos::models::{dtos_model_user::UserModelLoginVerificationReturn, dtos_model_webauthn::*},
+    error::*,
+    repositories::{
+        user_repository::UserRepository,
+        webauthn_challenge_repository::{
+            WebauthnCeremony, WebauthnChallenge, WebauthnChallengeRepository,
+        },
+        webauthn_credential_repository::{
+            WebauthnCredentialRepository, WebauthnCredentialStoreParams,
+        },
+    },
+    security::webauthn::{
+        client_data_challenge, encode_credential_id, verify_assertion, verify_registration,
+        RelyingParty,
+    },
+    utils::hash::password::PasswordVerify,
+};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{Duration, Utc};
+use mockall::automock;
+
+pub const WEBAUTHN_CHALLENGE_TTL_MINUTES: i64 = 5;
+
+#[async_trait]
+#[automock]
+pub trait PasskeyAuthenticationModel: Sync + Send {
+    async fn start_registration(
+        &self,
+        user_id: String,
+    ) -> Result<WebauthnModelRegistrationStart, AppError>;
+    async fn finish_registration(
+        &self,
+        user_id: String,
+        params: WebauthnModelFinishRegistrationParams,
+    ) -> Result<WebauthnModelCredentialReturn, AppError>;
+    /// Without a username any discoverable credential may answer. As a second factor
+    /// the username is required and the password is checked when finishing.
+    async fn start_authentication(
+        &self,
+        username: Option<String>,
+        second_factor: bool,
+    ) -> Result<WebauthnModelAuthenticationStart, AppError>;
+    async fn finish_authentication(
+        &self,
+        params: WebauthnModelFinishAuthenticationParams,
+    ) -> Result<UserModelLoginVerificationReturn, AppError>;
+}
+
+pub struct WebauthnModel<R, K, C> {
+    pub user_repository: R,
+    pub credential_repository: K,
+    pub challenge_repository: C,
+    /// None when the relying party is not configured, the ceremonies then fail.
+    pub relying_party: Option<RelyingParty>,
+    pub password_verify: PasswordVerify,
+    pub generate_challenge: fn() -> String,
+}
+
+impl<R, K, C: WebauthnChallengeRepository> WebauthnModel<R, K, C> {
+    fn relying_party(&self) -> Result<&RelyingParty, AppError> {
+        self.relying_party.as_ref().ok_or_else(|| {
+            AppError::new(
+                Code::Internal,
+                "WEBAUTHN_RP_ID, WEBAUTHN_RP_NAME and WEBAUTHN_ORIGIN are not configured",
+            )
+        })
+    }
+
+    async fn create_challenge(
+        &self,
+        ceremony: WebauthnCeremony,
+        user_id: Option<String>,
+        second_factor: bool,
+    ) -> Result<String, AppError> {
+        let challenge = URL_SAFE_NO_PAD.encode((self.generate_challenge)());
+
+        self.challenge_repository
+            .store(WebauthnChallenge {
+                challenge: challenge.clone(),
+                ceremony,
+                user_id,
+                second_factor,
+                expire_at: Utc::now().naive_utc()
+                    + Duration::minutes(WEBAUTHN_CHALLENGE_TTL_MINUTES),
+            })
+            .await?;
+
+        Ok(challenge)
+    }
+
+    /// Redeem the challenge the client data answers, it must belong to `ceremony`.
+    async fn take_challenge(
+        &self,
+        client_data_json: &[u8],
+        ceremony: WebauthnCeremony,
+    ) -> Result<WebauthnChallenge, AppError> {
+        let invalid_challenge =
+            || AppError::new(Code::InvalidArgument, "Invalid WebAuthn challenge");
+
+        let challenge = match self
+            .challenge_repository
+            .take(client_data_challenge(client_data_json)?)
+            .await
+        {
+            Ok(challenge) => challenge,
+            Err(error) if error.code == Code::NotFound => return Err(invalid_challenge()),
+            Err(error) => return Err(error),
+        };
+
+        if challenge.ceremony != ceremony || challenge.expire_at < Utc::now().naive_utc() {
+            return Err(invalid_challenge());
+        }
+
+        Ok(challenge)
+    }
+}
+
+#[async_trait]
+impl<R: UserRepository, K: WebauthnCredentialRepository, C: WebauthnChallengeRepository>
+    PasskeyAuthenticationModel for WebauthnModel<R, K, C>
+{
+    async fn start_registration(
+        &self,
+        user_id: String,
+    ) -> Result<WebauthnModelRegistrationStart, AppError> {
+        let user = self.user_repository.consult_by_id(user_id).await?;
+        let credentials = self
+            .credential_repository
+            .list_by_user(user.id.clone())
+            .await?;
+
+        let challenge = self
+            .create_challenge(WebauthnCeremony::Registration, Some(user.id.clone()), false)
+            .await?;
+
+        Ok(WebauthnModelRegistrationStart {
+            challenge,
+            relying_party: self.relying_party()?.clone(),
+            user_id: user.id,
+            username: user.username,
+            exclude_credentials: credentials
+                .into_iter()
+                .map(|credential| credential.id)
+                .collect(),
+            timeout_seconds: WEBAUTHN_CHALLENGE_TTL_MINUTES * 60,
+        })
+    }
+
+    async fn finish_registration(
+        &self,
+        user_id: String,
+        params: WebauthnModelFinishRegistrationParams,
+    ) -> Result<WebauthnModelCredentialReturn, AppError> {
+        let challenge = self
+            .take_challenge(&params.client_data_json, WebauthnCeremony::Registration)
+            .await?;
+
+        if challenge.user_id.as_ref() != Some(&user_id) {
+            return Err(AppError::new(
+                Code::InvalidArgument,
+                "Invalid WebAuthn challenge",
+            ));
+        }
+
+        let credential = verify_registration(
+            self.relying_party()?,
+            &challenge.challenge,
+            &params.client_data_json,
+            &params.attestation_object,
+        )?;
+
+        let credential = self
+            .credential_repository
+            .store(WebauthnCredentialStoreParams {
+                id: encode_credential_id(&credential.credential_id),
+                user_id,
+                name: params.name,
+                public_key: credential.public_key,
+                algorithm: credential.algorithm as i32,
+                sign_count: credential.sign_count as i64,
+            })
+            .await?;
+
+        Ok(WebauthnModelCredentialReturn {
+            id: credential.id,
+            name: credential.name,
+            created_at: credential.created_at,
+        })
+    }
+
+    async fn start_authentication(
+        &self,
+        username: Option<String>,
+        second_factor: bool,
+    ) -> Result<WebauthnModelAuthenticationStart, AppError> {
+        let (user_id, allow_credentials) = match username {
+            Some(username) => {
+                let user = self.user_repository.consult_by_username(username).await?;
+                let credentials = self
+                    .credential_repository
+                    .list_by_user(user.id.clone())
+                    .await?;
+
+                if credentials.is_empty() {
+                    return Err(AppError::new(Code::NotFound, "No passkey registered"));
+                }
+
+                (
+                    Some(user.id),
+                    credentials
+                        .into_iter()
+                        .map(|credential| credential.id)
+                        .collect(),
+                )
+            }
+            None if second_factor => {
+                return Err(AppError::new(
+                    Code::InvalidArgument,
+                    "A username is required to use a passkey as second factor",
+                ))
+            }
+            None => (None, vec![]),
+        };
+
+        let challenge = self
+            .create_challenge(WebauthnCeremony::Authentication, user_id, second_factor)
+            .await?;
+
+        Ok(WebauthnModelAuthenticationStart {
+            challenge,
+            relying_party_id: self.relying_party()?.id.clone(),
+            allow_credentials,
+            user_verification_required: !second_factor,
+            timeout_seconds: WEBAUTHN_CHALLENGE_TTL_MINUTES * 60,
+        })
+    }
+
+    async fn finish_authentication(
+        &self,
+        params: WebauthnModelFinishAuthenticationParams,
+    ) -> Result<UserModelLoginVerificationReturn, AppError> {
+        let unknown_passkey = || AppError::new(Code::Unauthenticated, "Unknown passkey");
+
+        let challenge = self
+            .take_challenge(&params.client_data_json, WebauthnCeremony::Authentication)
+            .await?;
+
+        let credential = match self
+            .credential_repository
+            .consult(params.credential_id)
+            .await
+        {
+            Ok(credential) => credential,
+            Err(error) if error.code == Code::NotFound => return Err(unknown_passkey()),
+            Err(error) => return Err(error),
+        };
+
+        if challenge
+            .user_id
+            .as_ref()
+            .is_some_and(|user_id| *user_id != credential.user_id)
+        {
+            return Err(unknown_passkey());
+        }
+
+        let user = self
+            .user_repository
+            .consult_by_id(credential.user_id.clone())
+            .await?;
+
+        // As second factor the passkey only proves possession, the password is the
+        // knowledge factor. Alone it must also prove the user was verified.
+        if challenge.second_factor {
+            let password = match params.password {
+                Some(password) => password,
+                None => {
+                    return Err(AppError::new(
+                        Code::InvalidArgument,
+                        "Password is required with a second factor passkey",
+                    ))
+                }
+            };
+
+            if !(self.password_verify)(user.password.clone(), password)? {
+                return Err(AppError::new(Code::Unauthenticated, "Incorrect password"));
+            }
+        }
+
+        let sign_count = verify_assertion(
+            self.relying_party()?,
+            &challenge.challenge,
+            &credential.public_key,
+            &params.client_data_json,
+            &params.authenticator_data,
+            &params.signature,
+            !challenge.second_factor,
+        )? as i64;
+
+        // Authenticators without a counter always report 0, otherwise it must grow.
+        if (sign_count != 0 || credential.sign_count != 0) && sign_count <= credential.sign_count {
+            return Err(AppError::new(
+                Code::PermissionDenied,
+                "Passkey sign counter did not increase, the authenticator may be cloned",
+            ));
+        }
+
+        self.credential_repository
+            .update_sign_count(credential.id, sign_count)
+            .await?;
+
+        Ok(UserModelLoginVerificationReturn {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            activated: user.activated,
+            blocked: user.blocked,
+        })
+    }
+}
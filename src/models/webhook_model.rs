@@ -0,0 +1,116 @@
+use crate::{
+    dtos::models::dtos_model_webhook::*,
+    error::*,
+    repositories::webhook_repository::{
+        WebhookDelivery, WebhookRepository, WebhookRepositoryStoreParams,
+    },
+    services::{
+        events::user_events_watcher::parse_event_types,
+        webhooks::webhook_sender::{WebhookRequest, WebhookSender},
+    },
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use mockall::automock;
+
+pub const WEBHOOK_TEST_EVENT: &str = "WebhookTest";
+
+#[async_trait]
+#[automock]
+pub trait WebhookAdministrationModel: Sync + Send {
+    async fn register(
+        &self,
+        webhook: WebhookModelRegisterParams,
+    ) -> Result<WebhookModelRegisterReturn, AppError>;
+    async fn list(&self) -> Result<Vec<WebhookModelConsultReturn>, AppError>;
+    async fn test(&self, id: String) -> Result<u16, AppError>;
+    async fn delete(&self, id: String) -> Result<String, AppError>;
+}
+
+pub struct WebhookModel<R, S> {
+    pub webhook_repository: R,
+    pub sender: S,
+    pub new_id: fn() -> String,
+    pub generate_secret: fn() -> String,
+}
+
+#[async_trait]
+impl<R: WebhookRepository, S: WebhookSender> WebhookAdministrationModel for WebhookModel<R, S> {
+    async fn register(
+        &self,
+        webhook: WebhookModelRegisterParams,
+    ) -> Result<WebhookModelRegisterReturn, AppError> {
+        match reqwest::Url::parse(&webhook.url) {
+            Ok(url) if url.scheme() == "http" || url.scheme() == "https" => (),
+            _ => {
+                return Err(AppError::new(
+                    Code::InvalidArgument,
+                    "Webhook url must be an absolute http(s) url",
+                ))
+            }
+        }
+
+        parse_event_types(webhook.event_types.clone())?;
+
+        let secret = match webhook.secret {
+            Some(secret) if !secret.is_empty() => secret,
+            _ => (self.generate_secret)(),
+        };
+
+        let webhook = self
+            .webhook_repository
+            .store(WebhookRepositoryStoreParams {
+                id: (self.new_id)(),
+                url: webhook.url,
+                secret,
+                event_types: webhook.event_types,
+            })
+            .await?;
+
+        Ok(WebhookModelRegisterReturn {
+            id: webhook.id,
+            url: webhook.url,
+            secret: webhook.secret,
+            event_types: webhook.event_types,
+            created_at: webhook.created_at,
+        })
+    }
+
+    async fn list(&self) -> Result<Vec<WebhookModelConsultReturn>, AppError> {
+        let webhooks = self.webhook_repository.list().await?;
+
+        Ok(webhooks
+            .into_iter()
+            .map(|webhook| WebhookModelConsultReturn {
+                id: webhook.id,
+                url: webhook.url,
+                event_types: webhook.event_types,
+                created_at: webhook.created_at,
+            })
+            .collect())
+    }
+
+    async fn test(&self, id: String) -> Result<u16, AppError> {
+        let webhook = self.webhook_repository.consult_by_id(id).await?;
+
+        let delivery = WebhookDelivery {
+            id: 0,
+            webhook_id: webhook.id,
+            url: webhook.url,
+            secret: webhook.secret,
+            event_id: 0,
+            event_type: WEBHOOK_TEST_EVENT.to_string(),
+            user_id: String::new(),
+            payload: String::from("{}"),
+            attempts: 0,
+        };
+
+        self.sender
+            .send(WebhookRequest::signed(&delivery, Utc::now().timestamp()))
+            .await
+    }
+
+    async fn delete(&self, id: String) -> Result<String, AppError> {
+        self.webhook_repository.delete(id).await
+    }
+}
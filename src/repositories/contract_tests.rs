@@ -0,0 +1,103 @@
+//! Runs the repository contract against each backend of `UserRepository` and
+//! `UsersCodeRepository`.
+
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    database::{
+        connection::connect_sqlite_pool,
+        in_memory::InMemoryStore,
+        utils::{
+            integration_test::{test_redis_pool, test_with_database},
+            repository_contract::{
+                store_contract_user, user_repository_contract, users_code_repository_contract,
+                CONTRACT_USER_ID,
+            },
+        },
+    },
+    error::AppError,
+    repositories::{
+        user_repository::*,
+        users_code_repository::{
+            UsersCodeRepositoryInMemory, UsersCodeRepositoryPostgres, UsersCodeRepositoryRedis,
+            UsersCodeRepositorySqlite,
+        },
+    },
+};
+
+#[tokio::test]
+async fn test_postgres_user_repository_contract() {
+    async fn run(pool: Pool<Postgres>) -> Result<(), AppError> {
+        user_repository_contract(&UserRepositoryPostgres { pool: &pool }).await;
+        Ok(())
+    }
+
+    test_with_database("contract_user_repository", run)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_sqlite_user_repository_contract() {
+    let pool = connect_sqlite_pool("sqlite::memory:").await.unwrap();
+
+    user_repository_contract(&UserRepositorySqlite { pool: &pool }).await;
+}
+
+#[tokio::test]
+async fn test_in_memory_user_repository_contract() {
+    let store = InMemoryStore::default();
+
+    user_repository_contract(&UserRepositoryInMemory { store: &store }).await;
+}
+
+#[tokio::test]
+async fn test_postgres_users_code_repository_contract() {
+    async fn run(pool: Pool<Postgres>) -> Result<(), AppError> {
+        store_contract_user(&UserRepositoryPostgres { pool: &pool }).await;
+        let codes = |purpose| UsersCodeRepositoryPostgres {
+            pool: &pool,
+            purpose,
+        };
+        users_code_repository_contract(codes, CONTRACT_USER_ID).await;
+        Ok(())
+    }
+
+    test_with_database("contract_users_code_repository", run)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_sqlite_users_code_repository_contract() {
+    let pool = connect_sqlite_pool("sqlite::memory:").await.unwrap();
+    store_contract_user(&UserRepositorySqlite { pool: &pool }).await;
+    let codes = |purpose| UsersCodeRepositorySqlite {
+        pool: &pool,
+        purpose,
+    };
+
+    users_code_repository_contract(codes, CONTRACT_USER_ID).await;
+}
+
+#[tokio::test]
+async fn test_redis_users_code_repository_contract() {
+    let redis = test_redis_pool();
+    let codes = |purpose| UsersCodeRepositoryRedis {
+        redis: &redis,
+        purpose,
+    };
+
+    users_code_repository_contract(codes, "contractRedisUserId").await;
+}
+
+#[tokio::test]
+async fn test_in_memory_users_code_repository_contract() {
+    let store = InMemoryStore::default();
+    let codes = |purpose| UsersCodeRepositoryInMemory {
+        store: &store,
+        purpose,
+    };
+
+    users_code_repository_contract(codes, CONTRACT_USER_ID).await;
+}
@@ -0,0 +1,144 @@
+pub use crate::dtos::repositories::dtos_repository_federated_identity::*;
+use crate::{error::*, utils::adapters::sqlx_error_to_app_error::sqlx_error_to_app_error};
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{Pool, Postgres};
+
+#[async_trait]
+#[automock]
+pub trait FederatedIdentityRepository: Sync + Send {
+    async fn store(
+        &self,
+        identity: FederatedIdentityStoreParams,
+    ) -> Result<FederatedIdentity, AppError>;
+    async fn consult(&self, issuer: String, subject: String)
+        -> Result<FederatedIdentity, AppError>;
+}
+
+pub struct FederatedIdentityRepositoryPostgres<'a> {
+    pub pool: &'a Pool<Postgres>,
+}
+
+#[async_trait]
+impl FederatedIdentityRepository for FederatedIdentityRepositoryPostgres<'_> {
+    async fn store(
+        &self,
+        identity: FederatedIdentityStoreParams,
+    ) -> Result<FederatedIdentity, AppError> {
+        match sqlx::query_as!(
+            FederatedIdentity,
+            "INSERT INTO federated_identities (issuer, subject, user_id, email)
+            VALUES ($1, $2, $3, $4)
+            RETURNING issuer, subject, user_id, email, created_at",
+            identity.issuer,
+            identity.subject,
+            identity.user_id,
+            identity.email,
+        )
+        .fetch_one(self.pool)
+        .await
+        {
+            Ok(identity) => Ok(identity),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn consult(
+        &self,
+        issuer: String,
+        subject: String,
+    ) -> Result<FederatedIdentity, AppError> {
+        match sqlx::query_as!(
+            FederatedIdentity,
+            "SELECT issuer, subject, user_id, email, created_at FROM federated_identities
+            WHERE issuer = $1 AND subject = $2",
+            issuer,
+            subject
+        )
+        .fetch_one(self.pool)
+        .await
+        {
+            Ok(identity) => Ok(identity),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::utils::integration_test::test_with_database;
+
+    use super::*;
+
+    const ISSUER: &str = "https://accounts.example.com";
+
+    async fn store_fake_user_for_test(pool: &Pool<Postgres>) {
+        sqlx::query!(
+            "INSERT INTO users (id, username, email, password) VALUES ($1, $2, $3, $4)",
+            "userFakeId",
+            "username",
+            "test@email.com",
+            "password",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    fn fake_identity() -> FederatedIdentityStoreParams {
+        FederatedIdentityStoreParams {
+            issuer: ISSUER.to_string(),
+            subject: "externalSubject".to_string(),
+            user_id: "userFakeId".to_string(),
+            email: Some("test@email.com".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_store_and_consult_federated_identity() {
+        async fn repository_store_and_consult(
+            pool: Pool<Postgres>,
+        ) -> Result<FederatedIdentity, AppError> {
+            store_fake_user_for_test(&pool).await;
+
+            let repository = FederatedIdentityRepositoryPostgres { pool: &pool };
+            repository.store(fake_identity()).await?;
+
+            repository
+                .consult(ISSUER.to_string(), "externalSubject".to_string())
+                .await
+        }
+
+        let identity = test_with_database(
+            "test_store_and_consult_federated_identity",
+            repository_store_and_consult,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(identity.user_id, "userFakeId");
+        assert_eq!(identity.email.as_deref(), Some("test@email.com"));
+    }
+
+    #[tokio::test]
+    async fn test_store_federated_identity_twice() {
+        async fn repository_store_twice(
+            pool: Pool<Postgres>,
+        ) -> Result<FederatedIdentity, AppError> {
+            store_fake_user_for_test(&pool).await;
+
+            let repository = FederatedIdentityRepositoryPostgres { pool: &pool };
+            repository.store(fake_identity()).await?;
+            repository.store(fake_identity()).await
+        }
+
+        let error = test_with_database(
+            "test_store_federated_identity_twice",
+            repository_store_twice,
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(error.code, Code::AlreadyExists);
+    }
+}
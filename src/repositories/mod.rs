@@ -1,2 +1,16 @@
+pub mod federated_identity_repository;
+pub mod oauth_authorization_code_repository;
+pub mod oauth_client_repository;
+pub mod oauth_consent_repository;
+pub mod oauth_refresh_token_repository;
+pub mod personal_access_token_repository;
+pub mod user_events_outbox_repository;
 pub mod user_repository;
 pub mod users_code_repository;
+pub mod webauthn_challenge_repository;
+pub mod webauthn_credential_repository;
+pub mod webhook_delivery_repository;
+pub mod webhook_repository;
+
+#[cfg(test)]
+mod contract_tests;
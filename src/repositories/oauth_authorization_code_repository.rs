@@ -0,0 +1,121 @@
+pub use crate::dtos::repositories::dtos_repository_oauth::*;
+use crate::{
+    database::redis_pool::RedisPool, error::*, telemetry::metrics::metrics,
+    utils::adapters::redis_error_to_app_error::redis_error_to_app_error,
+};
+use async_trait::async_trait;
+use mockall::automock;
+
+const AUTHORIZATION_CODE_KEY_PREFIX: &str = "oauth:authorization_code:";
+
+#[async_trait]
+#[automock]
+pub trait OAuthAuthorizationCodeRepository: Sync + Send {
+    async fn store(&self, code: OAuthAuthorizationCode) -> Result<String, AppError>;
+    /// Fetch and delete the code in a single step, so it can only be redeemed once.
+    async fn take(&self, code: String) -> Result<OAuthAuthorizationCode, AppError>;
+}
+
+pub struct OAuthAuthorizationCodeRepositoryRedis<'a> {
+    pub redis: &'a RedisPool,
+}
+
+#[async_trait]
+impl OAuthAuthorizationCodeRepository for OAuthAuthorizationCodeRepositoryRedis<'_> {
+    async fn store(&self, code: OAuthAuthorizationCode) -> Result<String, AppError> {
+        let _timer = metrics().redis_call_timer("oauth_authorization_code.store");
+        let mut connection = self.redis.connection().await?;
+        let key = format!("{}{}", AUTHORIZATION_CODE_KEY_PREFIX, code.code);
+        let value = match serde_json::to_string(&code) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(AppError::new(
+                    Code::Internal,
+                    "Unable to serialize the authorization code",
+                )
+                .with_source(error))
+            }
+        };
+
+        redis::pipe()
+            .atomic()
+            .set(&key, &value)
+            .ignore()
+            .cmd("EXPIREAT")
+            .arg(&key)
+            .arg(code.expire_at.timestamp())
+            .query_async::<_, ()>(&mut connection)
+            .await
+            .map_err(redis_error_to_app_error)?;
+
+        Ok(String::from("Authorization code stored successfully"))
+    }
+
+    async fn take(&self, code: String) -> Result<OAuthAuthorizationCode, AppError> {
+        let _timer = metrics().redis_call_timer("oauth_authorization_code.take");
+        let mut connection = self.redis.connection().await?;
+
+        let value: Option<String> = redis::cmd("GETDEL")
+            .arg(format!("{}{}", AUTHORIZATION_CODE_KEY_PREFIX, code))
+            .query_async(&mut connection)
+            .await
+            .map_err(redis_error_to_app_error)?;
+
+        match value {
+            Some(value) => match serde_json::from_str(&value) {
+                Ok(code) => Ok(code),
+                Err(error) => Err(AppError::new(
+                    Code::Internal,
+                    "Invalid stored authorization code",
+                )
+                .with_source(error)),
+            },
+            None => Err(AppError::new(
+                Code::NotFound,
+                "Authorization code not found",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use super::*;
+    use crate::database::utils::integration_test::test_redis_pool;
+
+    #[tokio::test]
+    async fn test_redis_take_authorization_code_once() {
+        let repository = OAuthAuthorizationCodeRepositoryRedis {
+            redis: &test_redis_pool(),
+        };
+        let code = OAuthAuthorizationCode {
+            code: "FAKE_AUTHORIZATION_CODE".to_string(),
+            client_id: "clientFakeId".to_string(),
+            user_id: "userFakeId".to_string(),
+            redirect_uri: "https://app.example.com/callback".to_string(),
+            code_challenge: "challenge".to_string(),
+            scopes: vec!["profile".to_string()],
+            expire_at: Utc::now().naive_utc() + Duration::minutes(5),
+            nonce: Some("n-0S6_WzA2Mj".to_string()),
+            auth_time: Utc::now().timestamp(),
+        };
+
+        repository.store(code.clone()).await.unwrap();
+
+        let taken = repository
+            .take("FAKE_AUTHORIZATION_CODE".to_string())
+            .await
+            .unwrap();
+        assert_eq!(taken.user_id, code.user_id);
+        assert_eq!(taken.code_challenge, code.code_challenge);
+
+        let error = repository
+            .take("FAKE_AUTHORIZATION_CODE".to_string())
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(error.code, Code::NotFound);
+    }
+}
@@ -0,0 +1,112 @@
+pub use crate::dtos::repositories::dtos_repository_oauth::*;
+use crate::{error::*, utils::adapters::sqlx_error_to_app_error::sqlx_error_to_app_error};
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{Pool, Postgres};
+
+#[async_trait]
+#[automock]
+pub trait OAuthClientRepository: Sync + Send {
+    async fn store(
+        &self,
+        client: OAuthClientRepositoryStoreParams,
+    ) -> Result<OAuthClientRepositoryConsultReturn, AppError>;
+    async fn consult_by_id(
+        &self,
+        id: String,
+    ) -> Result<OAuthClientRepositoryConsultReturn, AppError>;
+}
+
+pub struct OAuthClientRepositoryPostgres<'a> {
+    pub pool: &'a Pool<Postgres>,
+}
+
+#[async_trait]
+impl OAuthClientRepository for OAuthClientRepositoryPostgres<'_> {
+    async fn store(
+        &self,
+        client: OAuthClientRepositoryStoreParams,
+    ) -> Result<OAuthClientRepositoryConsultReturn, AppError> {
+        match sqlx::query_as!(
+            OAuthClientRepositoryConsultReturn,
+            "INSERT INTO oauth_clients (id, name, secret_hash, redirect_uris, scopes, service)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, name, secret_hash, redirect_uris, scopes, service, created_at",
+            client.id,
+            client.name,
+            client.secret_hash,
+            &client.redirect_uris,
+            &client.scopes,
+            client.service,
+        )
+        .fetch_one(self.pool)
+        .await
+        {
+            Ok(client) => Ok(client),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn consult_by_id(
+        &self,
+        id: String,
+    ) -> Result<OAuthClientRepositoryConsultReturn, AppError> {
+        match sqlx::query_as!(
+            OAuthClientRepositoryConsultReturn,
+            "SELECT id, name, secret_hash, redirect_uris, scopes, service, created_at
+            FROM oauth_clients WHERE id = $1",
+            id
+        )
+        .fetch_one(self.pool)
+        .await
+        {
+            Ok(client) => Ok(client),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::utils::integration_test::test_with_database;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_store_and_consult_oauth_client() {
+        async fn repository_store_and_consult(
+            pool: Pool<Postgres>,
+        ) -> Result<OAuthClientRepositoryConsultReturn, AppError> {
+            let repository = OAuthClientRepositoryPostgres { pool: &pool };
+
+            repository
+                .store(OAuthClientRepositoryStoreParams {
+                    id: "clientFakeId".to_string(),
+                    name: "Web app".to_string(),
+                    secret_hash: None,
+                    redirect_uris: vec!["https://app.example.com/callback".to_string()],
+                    scopes: vec!["profile".to_string()],
+                    service: false,
+                })
+                .await?;
+
+            repository.consult_by_id("clientFakeId".to_string()).await
+        }
+
+        let client = test_with_database(
+            "test_store_and_consult_oauth_client",
+            repository_store_and_consult,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(client.name, "Web app");
+        assert_eq!(client.secret_hash, None);
+        assert_eq!(
+            client.redirect_uris,
+            vec!["https://app.example.com/callback".to_string()]
+        );
+        assert_eq!(client.scopes, vec!["profile".to_string()]);
+        assert!(!client.service);
+    }
+}
@@ -0,0 +1,131 @@
+pub use crate::dtos::repositories::dtos_repository_oauth::*;
+use crate::{error::*, utils::adapters::sqlx_error_to_app_error::sqlx_error_to_app_error};
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{Pool, Postgres};
+
+#[async_trait]
+#[automock]
+pub trait OAuthConsentRepository: Sync + Send {
+    /// Record the scopes granted by the user to the client, replacing any previous consent.
+    async fn store(
+        &self,
+        user_id: String,
+        client_id: String,
+        scopes: Vec<String>,
+    ) -> Result<String, AppError>;
+    async fn consult(&self, user_id: String, client_id: String) -> Result<OAuthConsent, AppError>;
+}
+
+pub struct OAuthConsentRepositoryPostgres<'a> {
+    pub pool: &'a Pool<Postgres>,
+}
+
+#[async_trait]
+impl OAuthConsentRepository for OAuthConsentRepositoryPostgres<'_> {
+    async fn store(
+        &self,
+        user_id: String,
+        client_id: String,
+        scopes: Vec<String>,
+    ) -> Result<String, AppError> {
+        match sqlx::query!(
+            "INSERT INTO oauth_consents (user_id, client_id, scopes) VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, client_id)
+            DO UPDATE SET scopes = EXCLUDED.scopes, granted_at = NOW()",
+            user_id,
+            client_id,
+            &scopes,
+        )
+        .execute(self.pool)
+        .await
+        {
+            Ok(_) => Ok(String::from("Consent stored successfully")),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn consult(&self, user_id: String, client_id: String) -> Result<OAuthConsent, AppError> {
+        match sqlx::query_as!(
+            OAuthConsent,
+            "SELECT user_id, client_id, scopes, granted_at FROM oauth_consents
+            WHERE user_id = $1 AND client_id = $2",
+            user_id,
+            client_id
+        )
+        .fetch_one(self.pool)
+        .await
+        {
+            Ok(consent) => Ok(consent),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::utils::integration_test::test_with_database;
+
+    use super::*;
+
+    async fn store_fake_user_and_client_for_test(pool: &Pool<Postgres>) {
+        sqlx::query!(
+            "INSERT INTO users (id, username, email, password) VALUES ($1, $2, $3, $4)",
+            "userFakeId",
+            "username",
+            "test@email.com",
+            "password",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query!(
+            "INSERT INTO oauth_clients (id, name) VALUES ($1, $2)",
+            "clientFakeId",
+            "Web app",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_store_consent_replaces_previous_scopes() {
+        async fn repository_store_consent(pool: Pool<Postgres>) -> Result<OAuthConsent, AppError> {
+            store_fake_user_and_client_for_test(&pool).await;
+
+            let repository = OAuthConsentRepositoryPostgres { pool: &pool };
+
+            repository
+                .store(
+                    "userFakeId".to_string(),
+                    "clientFakeId".to_string(),
+                    vec!["profile".to_string()],
+                )
+                .await?;
+            repository
+                .store(
+                    "userFakeId".to_string(),
+                    "clientFakeId".to_string(),
+                    vec!["profile".to_string(), "email".to_string()],
+                )
+                .await?;
+
+            repository
+                .consult("userFakeId".to_string(), "clientFakeId".to_string())
+                .await
+        }
+
+        let consent = test_with_database(
+            "test_store_consent_replaces_previous_scopes",
+            repository_store_consent,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            consent.scopes,
+            vec!["profile".to_string(), "email".to_string()]
+        );
+    }
+}
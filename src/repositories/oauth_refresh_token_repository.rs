@@ -0,0 +1,141 @@
+pub use crate::dtos::repositories::dtos_repository_oauth::*;
+use crate::{error::*, utils::adapters::sqlx_error_to_app_error::sqlx_error_to_app_error};
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{Pool, Postgres};
+
+#[async_trait]
+#[automock]
+pub trait OAuthRefreshTokenRepository: Sync + Send {
+    async fn store(&self, token: OAuthRefreshTokenStoreParams) -> Result<String, AppError>;
+    async fn consult_by_hash(&self, token_hash: String) -> Result<OAuthRefreshToken, AppError>;
+    /// Revoke a token that is still active, failing with NotFound if it was already revoked,
+    /// so a refresh token can only be rotated once.
+    async fn revoke(&self, token_hash: String) -> Result<String, AppError>;
+}
+
+pub struct OAuthRefreshTokenRepositoryPostgres<'a> {
+    pub pool: &'a Pool<Postgres>,
+}
+
+#[async_trait]
+impl OAuthRefreshTokenRepository for OAuthRefreshTokenRepositoryPostgres<'_> {
+    async fn store(&self, token: OAuthRefreshTokenStoreParams) -> Result<String, AppError> {
+        match sqlx::query!(
+            "INSERT INTO oauth_refresh_tokens (token_hash, client_id, user_id, scopes, expires_at)
+            VALUES ($1, $2, $3, $4, $5)",
+            token.token_hash,
+            token.client_id,
+            token.user_id,
+            &token.scopes,
+            token.expires_at,
+        )
+        .execute(self.pool)
+        .await
+        {
+            Ok(_) => Ok(String::from("Refresh token stored successfully")),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn consult_by_hash(&self, token_hash: String) -> Result<OAuthRefreshToken, AppError> {
+        match sqlx::query_as!(
+            OAuthRefreshToken,
+            "SELECT token_hash, client_id, user_id, scopes, expires_at, revoked_at
+            FROM oauth_refresh_tokens WHERE token_hash = $1",
+            token_hash
+        )
+        .fetch_one(self.pool)
+        .await
+        {
+            Ok(token) => Ok(token),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn revoke(&self, token_hash: String) -> Result<String, AppError> {
+        match sqlx::query!(
+            "UPDATE oauth_refresh_tokens SET revoked_at = NOW()
+            WHERE token_hash = $1 AND revoked_at IS NULL",
+            token_hash
+        )
+        .execute(self.pool)
+        .await
+        {
+            Ok(result) if result.rows_affected() == 0 => Err(AppError::new(
+                Code::NotFound,
+                "Refresh token not found or already revoked",
+            )),
+            Ok(_) => Ok(String::from("Refresh token revoked")),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::utils::integration_test::test_with_database;
+    use chrono::{Duration, Utc};
+
+    use super::*;
+
+    async fn store_fake_user_and_client_for_test(pool: &Pool<Postgres>) {
+        sqlx::query!(
+            "INSERT INTO users (id, username, email, password) VALUES ($1, $2, $3, $4)",
+            "userFakeId",
+            "username",
+            "test@email.com",
+            "password",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query!(
+            "INSERT INTO oauth_clients (id, name) VALUES ($1, $2)",
+            "clientFakeId",
+            "Web app",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_revoke_refresh_token_only_once() {
+        async fn repository_revoke(pool: Pool<Postgres>) -> Result<OAuthRefreshToken, AppError> {
+            store_fake_user_and_client_for_test(&pool).await;
+
+            let repository = OAuthRefreshTokenRepositoryPostgres { pool: &pool };
+
+            repository
+                .store(OAuthRefreshTokenStoreParams {
+                    token_hash: "tokenFakeHash".to_string(),
+                    client_id: "clientFakeId".to_string(),
+                    user_id: "userFakeId".to_string(),
+                    scopes: vec!["profile".to_string()],
+                    expires_at: (Utc::now() + Duration::days(1)).naive_utc(),
+                })
+                .await?;
+
+            repository.revoke("tokenFakeHash".to_string()).await?;
+
+            let error = repository
+                .revoke("tokenFakeHash".to_string())
+                .await
+                .err()
+                .unwrap();
+            assert_eq!(error.code, Code::NotFound);
+
+            repository
+                .consult_by_hash("tokenFakeHash".to_string())
+                .await
+        }
+
+        let token = test_with_database("test_revoke_refresh_token_only_once", repository_revoke)
+            .await
+            .unwrap();
+
+        assert_eq!(token.user_id, "userFakeId");
+        assert!(token.revoked_at.is_some());
+    }
+}
@@ -0,0 +1,212 @@
+pub use crate::dtos::repositories::dtos_repository_personal_access_token::*;
+use crate::{error::*, utils::adapters::sqlx_error_to_app_error::sqlx_error_to_app_error};
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{Pool, Postgres};
+
+#[async_trait]
+#[automock]
+pub trait PersonalAccessTokenRepository: Sync + Send {
+    async fn store(
+        &self,
+        token: PersonalAccessTokenStoreParams,
+    ) -> Result<PersonalAccessToken, AppError>;
+    async fn consult_by_hash(&self, token_hash: String) -> Result<PersonalAccessToken, AppError>;
+    async fn list_by_user(&self, user_id: String) -> Result<Vec<PersonalAccessToken>, AppError>;
+    async fn touch(&self, id: String) -> Result<String, AppError>;
+    /// Delete a token of the user, failing with NotFound for tokens of other users.
+    async fn delete(&self, user_id: String, id: String) -> Result<String, AppError>;
+}
+
+pub struct PersonalAccessTokenRepositoryPostgres<'a> {
+    pub pool: &'a Pool<Postgres>,
+}
+
+#[async_trait]
+impl PersonalAccessTokenRepository for PersonalAccessTokenRepositoryPostgres<'_> {
+    async fn store(
+        &self,
+        token: PersonalAccessTokenStoreParams,
+    ) -> Result<PersonalAccessToken, AppError> {
+        match sqlx::query_as!(
+            PersonalAccessToken,
+            "INSERT INTO personal_access_tokens
+            (id, user_id, name, prefix, token_hash, scopes, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, user_id, name, prefix, token_hash, scopes, expires_at, last_used_at,
+            created_at",
+            token.id,
+            token.user_id,
+            token.name,
+            token.prefix,
+            token.token_hash,
+            &token.scopes,
+            token.expires_at,
+        )
+        .fetch_one(self.pool)
+        .await
+        {
+            Ok(token) => Ok(token),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn consult_by_hash(&self, token_hash: String) -> Result<PersonalAccessToken, AppError> {
+        match sqlx::query_as!(
+            PersonalAccessToken,
+            "SELECT id, user_id, name, prefix, token_hash, scopes, expires_at, last_used_at,
+            created_at FROM personal_access_tokens WHERE token_hash = $1",
+            token_hash
+        )
+        .fetch_one(self.pool)
+        .await
+        {
+            Ok(token) => Ok(token),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn list_by_user(&self, user_id: String) -> Result<Vec<PersonalAccessToken>, AppError> {
+        match sqlx::query_as!(
+            PersonalAccessToken,
+            "SELECT id, user_id, name, prefix, token_hash, scopes, expires_at, last_used_at,
+            created_at FROM personal_access_tokens WHERE user_id = $1 ORDER BY created_at",
+            user_id
+        )
+        .fetch_all(self.pool)
+        .await
+        {
+            Ok(tokens) => Ok(tokens),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn touch(&self, id: String) -> Result<String, AppError> {
+        match sqlx::query!(
+            "UPDATE personal_access_tokens SET last_used_at = NOW() WHERE id = $1",
+            id
+        )
+        .execute(self.pool)
+        .await
+        {
+            Ok(_) => Ok(String::from("Personal access token used")),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn delete(&self, user_id: String, id: String) -> Result<String, AppError> {
+        match sqlx::query!(
+            "DELETE FROM personal_access_tokens WHERE id = $1 AND user_id = $2",
+            id,
+            user_id
+        )
+        .execute(self.pool)
+        .await
+        {
+            Ok(result) if result.rows_affected() == 0 => Err(AppError::new(
+                Code::NotFound,
+                "Personal access token not found",
+            )),
+            Ok(_) => Ok(String::from("Personal access token revoked successfully")),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::utils::integration_test::test_with_database;
+
+    use super::*;
+
+    async fn store_fake_users_for_test(pool: &Pool<Postgres>) {
+        for (id, username) in [("userFakeId", "username"), ("otherUserId", "other")] {
+            sqlx::query!(
+                "INSERT INTO users (id, username, email, password) VALUES ($1, $2, $3, $4)",
+                id,
+                username,
+                format!("{}@email.com", username),
+                "password",
+            )
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+    }
+
+    fn fake_token() -> PersonalAccessTokenStoreParams {
+        PersonalAccessTokenStoreParams {
+            id: "tokenFakeId".to_string(),
+            user_id: "userFakeId".to_string(),
+            name: "CI".to_string(),
+            prefix: "pat_AbCd1234".to_string(),
+            token_hash: "tokenFakeHash".to_string(),
+            scopes: vec!["user:read".to_string()],
+            expires_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_store_and_consult_personal_access_token() {
+        async fn repository_store_and_consult(
+            pool: Pool<Postgres>,
+        ) -> Result<(PersonalAccessToken, Vec<PersonalAccessToken>), AppError> {
+            store_fake_users_for_test(&pool).await;
+
+            let repository = PersonalAccessTokenRepositoryPostgres { pool: &pool };
+            repository.store(fake_token()).await?;
+            repository.touch("tokenFakeId".to_string()).await?;
+
+            Ok((
+                repository
+                    .consult_by_hash("tokenFakeHash".to_string())
+                    .await?,
+                repository.list_by_user("userFakeId".to_string()).await?,
+            ))
+        }
+
+        let (token, tokens) = test_with_database(
+            "test_store_and_consult_personal_access_token",
+            repository_store_and_consult,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(token.scopes, vec!["user:read"]);
+        assert!(token.last_used_at.is_some());
+        assert_eq!(tokens.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_delete_personal_access_token_of_other_user() {
+        async fn repository_delete(pool: Pool<Postgres>) -> Result<String, AppError> {
+            store_fake_users_for_test(&pool).await;
+
+            let repository = PersonalAccessTokenRepositoryPostgres { pool: &pool };
+            repository.store(fake_token()).await?;
+
+            if let Ok(message) = repository
+                .delete("otherUserId".to_string(), "tokenFakeId".to_string())
+                .await
+            {
+                panic!("Should have failed: {}", message);
+            }
+
+            repository
+                .delete("userFakeId".to_string(), "tokenFakeId".to_string())
+                .await?;
+            repository
+                .delete("userFakeId".to_string(), "tokenFakeId".to_string())
+                .await
+        }
+
+        let error = test_with_database(
+            "test_delete_personal_access_token_of_other_user",
+            repository_delete,
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(error.code, Code::NotFound);
+    }
+}
@@ -0,0 +1,232 @@
+pub use crate::dtos::events::dtos_user_event::*;
+use crate::{error::*, utils::adapters::sqlx_error_to_app_error::sqlx_error_to_app_error};
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{Pool, Postgres, Transaction};
+
+#[async_trait]
+#[automock]
+pub trait UserEventsOutboxRepository: Sync + Send {
+    async fn fetch_unpublished(&self, limit: i64) -> Result<Vec<UserEvent>, AppError>;
+    async fn mark_published(&self, id: i64) -> Result<String, AppError>;
+    async fn fetch_after(&self, offset: i64, limit: i64) -> Result<Vec<UserEvent>, AppError>;
+}
+
+pub struct UserEventsOutboxRepositoryPostgres<'a> {
+    pub pool: &'a Pool<Postgres>,
+}
+
+#[async_trait]
+impl UserEventsOutboxRepository for UserEventsOutboxRepositoryPostgres<'_> {
+    async fn fetch_unpublished(&self, limit: i64) -> Result<Vec<UserEvent>, AppError> {
+        match sqlx::query_as!(
+            UserEvent,
+            "SELECT id, event_type, user_id, payload, created_at FROM user_events_outbox
+            WHERE published_at IS NULL ORDER BY id LIMIT $1",
+            limit
+        )
+        .fetch_all(self.pool)
+        .await
+        {
+            Ok(events) => Ok(events),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn mark_published(&self, id: i64) -> Result<String, AppError> {
+        match sqlx::query!(
+            "UPDATE user_events_outbox SET published_at = NOW() WHERE id = $1",
+            id
+        )
+        .execute(self.pool)
+        .await
+        {
+            Ok(_) => Ok(String::from("Event marked as published")),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn fetch_after(&self, offset: i64, limit: i64) -> Result<Vec<UserEvent>, AppError> {
+        match sqlx::query_as!(
+            UserEvent,
+            "SELECT id, event_type, user_id, payload, created_at FROM user_events_outbox
+            WHERE id > $1 ORDER BY id LIMIT $2",
+            offset,
+            limit
+        )
+        .fetch_all(self.pool)
+        .await
+        {
+            Ok(events) => Ok(events),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+}
+
+/// Key of the advisory lock taken by every transaction writing in the outbox.
+const USER_EVENTS_OUTBOX_LOCK: i64 = 0x7573_6572_5f65_7674;
+
+/// Write an event in the outbox using the transaction of the user mutation,
+/// so the event is only recorded if the mutation is committed.
+///
+/// The lock is held until the transaction ends, so the next id is only taken once the
+/// event before it is committed or rolled back. Readers going by id then never see an
+/// event while one with a lower id may still appear.
+pub async fn store_user_event(
+    transaction: &mut Transaction<'_, Postgres>,
+    event: NewUserEvent,
+) -> Result<(), AppError> {
+    sqlx::query("SELECT pg_advisory_xact_lock($1)")
+        .bind(USER_EVENTS_OUTBOX_LOCK)
+        .execute(&mut *transaction)
+        .await
+        .map_err(sqlx_error_to_app_error)?;
+
+    match sqlx::query!(
+        "INSERT INTO user_events_outbox (event_type, user_id, payload) VALUES ($1, $2, $3)",
+        event.event_type.as_str(),
+        event.user_id,
+        event.payload.to_string(),
+    )
+    .execute(transaction)
+    .await
+    {
+        Ok(_) => Ok(()),
+        Err(error) => Err(sqlx_error_to_app_error(error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::utils::integration_test::test_with_database;
+    use serde_json::json;
+
+    use super::*;
+
+    const FAKE_USER_ID: &str = "userFakeId";
+
+    async fn store_fake_events_for_test(pool: &Pool<Postgres>) {
+        let mut transaction = pool.begin().await.unwrap();
+
+        for event_type in [UserEventType::UserRegistered, UserEventType::UserActivated] {
+            store_user_event(
+                &mut transaction,
+                NewUserEvent {
+                    event_type,
+                    user_id: FAKE_USER_ID.to_string(),
+                    payload: json!({ "id": FAKE_USER_ID }),
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        transaction.commit().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fetch_unpublished() {
+        async fn repository_fetch_unpublished(
+            pool: Pool<Postgres>,
+        ) -> Result<Vec<UserEvent>, AppError> {
+            store_fake_events_for_test(&pool).await;
+
+            let repository = UserEventsOutboxRepositoryPostgres { pool: &pool };
+
+            repository.fetch_unpublished(10).await
+        }
+
+        let events = test_with_database("test_fetch_unpublished", repository_fetch_unpublished)
+            .await
+            .unwrap();
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_type, "UserRegistered");
+        assert_eq!(events[1].event_type, "UserActivated");
+        assert_eq!(events[0].user_id, FAKE_USER_ID);
+        assert_eq!(events[0].payload, r#"{"id":"userFakeId"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_mark_published() {
+        async fn repository_mark_published(
+            pool: Pool<Postgres>,
+        ) -> Result<Vec<UserEvent>, AppError> {
+            store_fake_events_for_test(&pool).await;
+
+            let repository = UserEventsOutboxRepositoryPostgres { pool: &pool };
+
+            let events = repository.fetch_unpublished(10).await?;
+            repository.mark_published(events[0].id).await?;
+
+            repository.fetch_unpublished(10).await
+        }
+
+        let events = test_with_database("test_mark_published", repository_mark_published)
+            .await
+            .unwrap();
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, "UserActivated");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_after() {
+        async fn repository_fetch_after(pool: Pool<Postgres>) -> Result<Vec<UserEvent>, AppError> {
+            store_fake_events_for_test(&pool).await;
+
+            let repository = UserEventsOutboxRepositoryPostgres { pool: &pool };
+
+            let events = repository.fetch_unpublished(10).await?;
+            repository.mark_published(events[0].id).await?;
+
+            repository.fetch_after(events[0].id - 1, 10).await
+        }
+
+        let events = test_with_database("test_fetch_after", repository_fetch_after)
+            .await
+            .unwrap();
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_type, "UserRegistered");
+        assert!(events[0].id < events[1].id);
+    }
+
+    #[tokio::test]
+    async fn test_event_waits_for_the_commit_of_the_event_before() {
+        async fn run(pool: Pool<Postgres>) -> Result<Vec<UserEvent>, AppError> {
+            let event = || NewUserEvent {
+                event_type: UserEventType::UserRegistered,
+                user_id: FAKE_USER_ID.to_string(),
+                payload: json!({ "id": FAKE_USER_ID }),
+            };
+            let mut first = pool.begin().await.unwrap();
+            store_user_event(&mut first, event()).await?;
+
+            let second_pool = pool.clone();
+            let second = tokio::spawn(async move {
+                let mut second = second_pool.begin().await.unwrap();
+                store_user_event(&mut second, event()).await.unwrap();
+                second.commit().await.unwrap();
+            });
+
+            // Committed first, the second event would be read before the first one exists.
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+            assert!(!second.is_finished());
+
+            first.commit().await.unwrap();
+            second.await.unwrap();
+
+            UserEventsOutboxRepositoryPostgres { pool: &pool }
+                .fetch_after(0, 10)
+                .await
+        }
+
+        let events = test_with_database("test_event_waits_for_the_commit", run)
+            .await
+            .unwrap();
+
+        assert_eq!(events.len(), 2);
+        assert!(events[0].id < events[1].id);
+    }
+}
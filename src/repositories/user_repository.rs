@@ -1,10 +1,17 @@
-pub use crate::{
-    database::connection::get_postgres_pool, dtos::repositories::dtos_repository_user::*,
+pub use crate::dtos::repositories::dtos_repository_user::*;
+use crate::{
+    database::in_memory::InMemoryStore,
+    dtos::events::dtos_user_event::{NewUserEvent, UserEventType},
+    error::*,
+    repositories::user_events_outbox_repository::store_user_event,
+    telemetry::spans::{db_span, sqlite_span},
+    utils::adapters::sqlx_error_to_app_error::sqlx_error_to_app_error,
 };
-use crate::{error::*, utils::adapters::sqlx_error_to_app_error::sqlx_error_to_app_error};
 use async_trait::async_trait;
 use mockall::automock;
-use sqlx::{Pool, Postgres};
+use serde_json::json;
+use sqlx::{Pool, Postgres, QueryBuilder, Sqlite};
+use tracing::{instrument, Instrument};
 
 #[async_trait]
 #[automock]
@@ -32,61 +39,85 @@ pub struct UserRepositoryPostgres<'a> {
 
 #[async_trait]
 impl UserRepository for UserRepositoryPostgres<'_> {
+    #[instrument(skip_all, fields(user_id = %user.id), err(level = "debug", Debug))]
     async fn store(
         &self,
         user: UserRepositoryStoreParams,
     ) -> Result<UserRepositoryStoreReturn, AppError> {
-        match sqlx::query!(
+        let mut transaction = self.pool.begin().await.map_err(sqlx_error_to_app_error)?;
+
+        sqlx::query!(
             "INSERT INTO users (id, username, email, password) VALUES ($1, $2, $3, $4)",
             user.id,
             user.username,
             user.email,
             user.password, 
         )
-        .execute(self.pool)
+        .execute(&mut transaction)
+        .instrument(db_span("INSERT", "users"))
         .await
-        {
-            Ok(_) => Ok(UserRepositoryStoreReturn {
-                id: user.id,
-                username: user.username,
-                email: user.email,
-                activated: false,
-                blocked: false,
-            }),
-            Err(error) => Err(sqlx_error_to_app_error(error)),
-        }
+        .map_err(sqlx_error_to_app_error)?;
+
+        store_user_event(
+            &mut transaction,
+            NewUserEvent {
+                event_type: UserEventType::UserRegistered,
+                user_id: user.id.clone(),
+                payload: json!({
+                    "id": user.id,
+                    "username": user.username,
+                    "email": user.email,
+                }),
+            },
+        )
+        .await?;
+
+        transaction.commit().await.map_err(sqlx_error_to_app_error)?;
+
+        Ok(UserRepositoryStoreReturn {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            activated: false,
+            blocked: false,
+        })
     }
 
+    #[instrument(skip_all, err(level = "debug", Debug))]
     async fn consult_by_username(
         &self,
         username: String,
     ) -> Result<UserRepositoryConsultReturn, AppError> {
-        match sqlx::query_as!(UserRepositoryConsultReturn, "SELECT id, username, email, password, activated, blocked FROM users WHERE username = $1", username).fetch_one(self.pool).await {
+        match sqlx::query_as!(UserRepositoryConsultReturn, "SELECT id, username, email, password, activated, blocked FROM users WHERE username = $1", username).fetch_one(self.pool).instrument(db_span("SELECT", "users")).await {
             Ok(user) => Ok(user),
             Err(error) => Err(sqlx_error_to_app_error(error)), 
         }
     }
 
+    #[instrument(skip_all, fields(user_id = %id), err(level = "debug", Debug))]
     async fn consult_by_id(&self, id: String) -> Result<UserRepositoryConsultReturn, AppError> {
-        match sqlx::query_as!(UserRepositoryConsultReturn, "SELECT id, username, email, password, activated, blocked FROM users WHERE id = $1", id).fetch_one(self.pool).await {
+        match sqlx::query_as!(UserRepositoryConsultReturn, "SELECT id, username, email, password, activated, blocked FROM users WHERE id = $1", id).fetch_one(self.pool).instrument(db_span("SELECT", "users")).await {
             Ok(user) => Ok(user),
             Err(error) => Err(sqlx_error_to_app_error(error)), 
         }
     }
 
+    #[instrument(skip_all, err(level = "debug", Debug))]
     async fn consult_by_email(&self, email: String) -> Result<UserRepositoryConsultReturn, AppError> {
-        match sqlx::query_as!(UserRepositoryConsultReturn, "SELECT id, username, email, password, activated, blocked FROM users WHERE email = $1", email).fetch_one(self.pool).await {
+        match sqlx::query_as!(UserRepositoryConsultReturn, "SELECT id, username, email, password, activated, blocked FROM users WHERE email = $1", email).fetch_one(self.pool).instrument(db_span("SELECT", "users")).await {
             Ok(user) => Ok(user),
             Err(error) => Err(sqlx_error_to_app_error(error)),
         }
     }
 
+    #[instrument(skip_all, fields(user_id = %id), err(level = "debug", Debug))]
     async fn store_update(
         &self,
         id: String,
         user_to_be_updated: UserRepositoryUpdateParams,
     ) -> Result<String, AppError> {
         let mut set_clauses = Vec::new();
+        let mut events = Vec::new();
     
         if let Some(username) = user_to_be_updated.username {
             set_clauses.push(format!("username = '{}'", username));
@@ -94,18 +125,42 @@ impl UserRepository for UserRepositoryPostgres<'_> {
     
         if let Some(email) = user_to_be_updated.email {
             set_clauses.push(format!("email = '{}'", email));
+            events.push(NewUserEvent {
+                event_type: UserEventType::UserEmailChanged,
+                user_id: id.clone(),
+                payload: json!({ "id": id, "email": email }),
+            });
         }
     
         if let Some(password) = user_to_be_updated.password {
             set_clauses.push(format!("password = '{}'", password));
+            events.push(NewUserEvent {
+                event_type: UserEventType::UserPasswordChanged,
+                user_id: id.clone(),
+                payload: json!({ "id": id }),
+            });
         }
     
         if let Some(activated) = user_to_be_updated.activated {
             set_clauses.push(format!("activated = '{}'", activated));
+            if activated {
+                events.push(NewUserEvent {
+                    event_type: UserEventType::UserActivated,
+                    user_id: id.clone(),
+                    payload: json!({ "id": id }),
+                });
+            }
         }
     
         if let Some(blocked) = user_to_be_updated.blocked {
             set_clauses.push(format!("blocked = '{}'", blocked));
+            if blocked {
+                events.push(NewUserEvent {
+                    event_type: UserEventType::UserBlocked,
+                    user_id: id.clone(),
+                    payload: json!({ "id": id }),
+                });
+            }
         }
     
         if set_clauses.is_empty() {
@@ -119,26 +174,422 @@ impl UserRepository for UserRepositoryPostgres<'_> {
             set_clause,
         );
         
-        match sqlx::query(&query)
+        let mut transaction = self.pool.begin().await.map_err(sqlx_error_to_app_error)?;
+
+        sqlx::query(&query)
             .bind(id)
+            .execute(&mut transaction)
+            .instrument(db_span("UPDATE", "users"))
+            .await
+            .map_err(sqlx_error_to_app_error)?;
+
+        for event in events {
+            store_user_event(&mut transaction, event).await?;
+        }
+
+        transaction.commit().await.map_err(sqlx_error_to_app_error)?;
+
+        Ok(String::from("User updated successfully"))
+    }
+
+    #[instrument(skip_all, fields(user_id = %id), err(level = "debug", Debug))]
+    async fn delete(&self, id: String) -> Result<String, AppError> {
+        let mut transaction = self.pool.begin().await.map_err(sqlx_error_to_app_error)?;
+
+        sqlx::query!("DELETE FROM users WHERE id = $1", id)
+            .execute(&mut transaction)
+            .instrument(db_span("DELETE", "users"))
+            .await
+            .map_err(sqlx_error_to_app_error)?;
+
+        store_user_event(
+            &mut transaction,
+            NewUserEvent {
+                event_type: UserEventType::UserDeleted,
+                user_id: id.clone(),
+                payload: json!({ "id": id }),
+            },
+        )
+        .await?;
+
+        transaction.commit().await.map_err(sqlx_error_to_app_error)?;
+
+        Ok(String::from("User deleted successfully"))
+    }
+
+}
+
+/// Users kept in SQLite. There is no events outbox there, so nothing is published.
+pub struct UserRepositorySqlite<'a> {
+    pub pool: &'a Pool<Sqlite>,
+}
+
+impl UserRepositorySqlite<'_> {
+    async fn consult_by(
+        &self,
+        column: &str,
+        value: String,
+    ) -> Result<UserRepositoryConsultReturn, AppError> {
+        let query = format!(
+            "SELECT id, username, email, password, activated, blocked FROM users WHERE {} = ?",
+            column
+        );
+
+        sqlx::query_as::<_, UserRepositoryConsultReturn>(&query)
+            .bind(value)
+            .fetch_one(self.pool)
+            .instrument(sqlite_span("SELECT", "users"))
+            .await
+            .map_err(sqlx_error_to_app_error)
+    }
+}
+
+#[async_trait]
+impl UserRepository for UserRepositorySqlite<'_> {
+    #[instrument(skip_all, fields(user_id = %user.id), err(level = "debug", Debug))]
+    async fn store(
+        &self,
+        user: UserRepositoryStoreParams,
+    ) -> Result<UserRepositoryStoreReturn, AppError> {
+        sqlx::query("INSERT INTO users (id, username, email, password) VALUES (?, ?, ?, ?)")
+            .bind(&user.id)
+            .bind(&user.username)
+            .bind(&user.email)
+            .bind(&user.password)
             .execute(self.pool)
+            .instrument(sqlite_span("INSERT", "users"))
             .await
-        {
-            Ok(_) => Ok(String::from("User updated successfully")),
-            Err(error) => Err(sqlx_error_to_app_error(error)),
+            .map_err(sqlx_error_to_app_error)?;
+
+        Ok(UserRepositoryStoreReturn {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            activated: false,
+            blocked: false,
+        })
+    }
+
+    #[instrument(skip_all, err(level = "debug", Debug))]
+    async fn consult_by_username(
+        &self,
+        username: String,
+    ) -> Result<UserRepositoryConsultReturn, AppError> {
+        self.consult_by("username", username).await
+    }
+
+    #[instrument(skip_all, fields(user_id = %id), err(level = "debug", Debug))]
+    async fn consult_by_id(&self, id: String) -> Result<UserRepositoryConsultReturn, AppError> {
+        self.consult_by("id", id).await
+    }
+
+    #[instrument(skip_all, err(level = "debug", Debug))]
+    async fn consult_by_email(
+        &self,
+        email: String,
+    ) -> Result<UserRepositoryConsultReturn, AppError> {
+        self.consult_by("email", email).await
+    }
+
+    #[instrument(skip_all, fields(user_id = %id), err(level = "debug", Debug))]
+    async fn store_update(
+        &self,
+        id: String,
+        user_to_be_updated: UserRepositoryUpdateParams,
+    ) -> Result<String, AppError> {
+        let mut query =
+            QueryBuilder::<Sqlite>::new("UPDATE users SET updatedat = CURRENT_TIMESTAMP");
+        let mut fields = 0;
+
+        if let Some(username) = user_to_be_updated.username {
+            query.push(", username = ").push_bind(username);
+            fields += 1;
+        }
+        if let Some(email) = user_to_be_updated.email {
+            query.push(", email = ").push_bind(email);
+            fields += 1;
+        }
+        if let Some(password) = user_to_be_updated.password {
+            query.push(", password = ").push_bind(password);
+            fields += 1;
+        }
+        if let Some(activated) = user_to_be_updated.activated {
+            query.push(", activated = ").push_bind(activated);
+            fields += 1;
+        }
+        if let Some(blocked) = user_to_be_updated.blocked {
+            query.push(", blocked = ").push_bind(blocked);
+            fields += 1;
         }
+
+        if fields == 0 {
+            return Err(AppError::new(Code::InvalidArgument, "No fields to update"));
+        }
+
+        query.push(" WHERE id = ").push_bind(id);
+        query
+            .build()
+            .execute(self.pool)
+            .instrument(sqlite_span("UPDATE", "users"))
+            .await
+            .map_err(sqlx_error_to_app_error)?;
+
+        Ok(String::from("User updated successfully"))
     }
 
+    #[instrument(skip_all, fields(user_id = %id), err(level = "debug", Debug))]
     async fn delete(&self, id: String) -> Result<String, AppError> {
-        match sqlx::query!("DELETE FROM users WHERE id = $1", id)
+        sqlx::query("DELETE FROM users WHERE id = ?")
+            .bind(id)
             .execute(self.pool)
+            .instrument(sqlite_span("DELETE", "users"))
             .await
+            .map_err(sqlx_error_to_app_error)?;
+
+        Ok(String::from("User deleted successfully"))
+    }
+}
+
+/// Users kept in an `InMemoryStore`, refusing a taken id, username or email like the
+/// unique constraints of the databases do.
+pub struct UserRepositoryInMemory<'a> {
+    pub store: &'a InMemoryStore,
+}
+
+fn unique_violation() -> AppError {
+    AppError::new(
+        Code::AlreadyExists,
+        "DB: insert or update on table violates unique constraint",
+    )
+}
+
+fn username_taken() -> AppError {
+    unique_violation()
+        .with_reason(Reason::UsernameTaken)
+        .with_field_violation("username", "is already taken")
+}
+
+fn email_taken() -> AppError {
+    unique_violation()
+        .with_reason(Reason::EmailTaken)
+        .with_field_violation("email", "is already taken")
+}
+
+fn user_not_found() -> AppError {
+    AppError::new(Code::NotFound, "DB: nothing found with given parameters")
+}
+
+impl UserRepositoryInMemory<'_> {
+    fn consult_by(
+        &self,
+        matches: impl Fn(&UserRepositoryConsultReturn) -> bool,
+    ) -> Result<UserRepositoryConsultReturn, AppError> {
+        let data = self.store.lock();
+        match data.users.values().find(|user| matches(user)) {
+            Some(user) => Ok(user.clone()),
+            None => Err(user_not_found()),
+        }
+    }
+}
+
+#[async_trait]
+impl UserRepository for UserRepositoryInMemory<'_> {
+    #[instrument(skip_all, fields(user_id = %user.id), err(level = "debug", Debug))]
+    async fn store(
+        &self,
+        user: UserRepositoryStoreParams,
+    ) -> Result<UserRepositoryStoreReturn, AppError> {
+        let mut data = self.store.lock();
+        if data.users.contains_key(&user.id) {
+            return Err(unique_violation());
+        }
+        if data
+            .users
+            .values()
+            .any(|stored| stored.username == user.username)
         {
-            Ok(_) => Ok(String::from("User deleted successfully")),
-            Err(error) => Err(sqlx_error_to_app_error(error)),
+            return Err(username_taken());
+        }
+        if data.users.values().any(|stored| stored.email == user.email) {
+            return Err(email_taken());
+        }
+
+        data.users.insert(
+            user.id.clone(),
+            UserRepositoryConsultReturn {
+                id: user.id.clone(),
+                username: user.username.clone(),
+                email: user.email.clone(),
+                password: user.password,
+                activated: false,
+                blocked: false,
+            },
+        );
+
+        Ok(UserRepositoryStoreReturn {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            activated: false,
+            blocked: false,
+        })
+    }
+
+    #[instrument(skip_all, err(level = "debug", Debug))]
+    async fn consult_by_username(
+        &self,
+        username: String,
+    ) -> Result<UserRepositoryConsultReturn, AppError> {
+        self.consult_by(|user| user.username == username)
+    }
+
+    #[instrument(skip_all, fields(user_id = %id), err(level = "debug", Debug))]
+    async fn consult_by_id(&self, id: String) -> Result<UserRepositoryConsultReturn, AppError> {
+        self.consult_by(|user| user.id == id)
+    }
+
+    #[instrument(skip_all, err(level = "debug", Debug))]
+    async fn consult_by_email(
+        &self,
+        email: String,
+    ) -> Result<UserRepositoryConsultReturn, AppError> {
+        self.consult_by(|user| user.email == email)
+    }
+
+    #[instrument(skip_all, fields(user_id = %id), err(level = "debug", Debug))]
+    async fn store_update(
+        &self,
+        id: String,
+        user_to_be_updated: UserRepositoryUpdateParams,
+    ) -> Result<String, AppError> {
+        if user_to_be_updated == UserRepositoryUpdateParams::default() {
+            return Err(AppError::new(Code::InvalidArgument, "No fields to update"));
+        }
+
+        let mut data = self.store.lock();
+        let others = || data.users.values().filter(|user| user.id != id);
+        if let Some(username) = &user_to_be_updated.username {
+            if others().any(|user| &user.username == username) {
+                return Err(username_taken());
+            }
+        }
+        if let Some(email) = &user_to_be_updated.email {
+            if others().any(|user| &user.email == email) {
+                return Err(email_taken());
+            }
+        }
+
+        // Like an UPDATE matching no row, updating an unknown user changes nothing.
+        if let Some(user) = data.users.get_mut(&id) {
+            if let Some(username) = user_to_be_updated.username {
+                user.username = username;
+            }
+            if let Some(email) = user_to_be_updated.email {
+                user.email = email;
+            }
+            if let Some(password) = user_to_be_updated.password {
+                user.password = password;
+            }
+            if let Some(activated) = user_to_be_updated.activated {
+                user.activated = activated;
+            }
+            if let Some(blocked) = user_to_be_updated.blocked {
+                user.blocked = blocked;
+            }
+        }
+
+        Ok(String::from("User updated successfully"))
+    }
+
+    #[instrument(skip_all, fields(user_id = %id), err(level = "debug", Debug))]
+    async fn delete(&self, id: String) -> Result<String, AppError> {
+        self.store.lock().users.remove(&id);
+
+        Ok(String::from("User deleted successfully"))
+    }
+}
+
+/// The user repository of the backend picked by `STORAGE_BACKEND`.
+pub enum UserRepositoryBackend<'a> {
+    Postgres(UserRepositoryPostgres<'a>),
+    Sqlite(UserRepositorySqlite<'a>),
+    InMemory(UserRepositoryInMemory<'a>),
+}
+
+#[async_trait]
+impl UserRepository for UserRepositoryBackend<'_> {
+    async fn store(
+        &self,
+        user: UserRepositoryStoreParams,
+    ) -> Result<UserRepositoryStoreReturn, AppError> {
+        match self {
+            UserRepositoryBackend::Postgres(repository) => repository.store(user).await,
+            UserRepositoryBackend::Sqlite(repository) => repository.store(user).await,
+            UserRepositoryBackend::InMemory(repository) => repository.store(user).await,
+        }
+    }
+
+    async fn consult_by_username(
+        &self,
+        username: String,
+    ) -> Result<UserRepositoryConsultReturn, AppError> {
+        match self {
+            UserRepositoryBackend::Postgres(repository) => {
+                repository.consult_by_username(username).await
+            }
+            UserRepositoryBackend::Sqlite(repository) => {
+                repository.consult_by_username(username).await
+            }
+            UserRepositoryBackend::InMemory(repository) => {
+                repository.consult_by_username(username).await
+            }
+        }
+    }
+
+    async fn consult_by_id(&self, id: String) -> Result<UserRepositoryConsultReturn, AppError> {
+        match self {
+            UserRepositoryBackend::Postgres(repository) => repository.consult_by_id(id).await,
+            UserRepositoryBackend::Sqlite(repository) => repository.consult_by_id(id).await,
+            UserRepositoryBackend::InMemory(repository) => repository.consult_by_id(id).await,
+        }
+    }
+
+    async fn consult_by_email(
+        &self,
+        email: String,
+    ) -> Result<UserRepositoryConsultReturn, AppError> {
+        match self {
+            UserRepositoryBackend::Postgres(repository) => repository.consult_by_email(email).await,
+            UserRepositoryBackend::Sqlite(repository) => repository.consult_by_email(email).await,
+            UserRepositoryBackend::InMemory(repository) => repository.consult_by_email(email).await,
+        }
+    }
+
+    async fn store_update(
+        &self,
+        id: String,
+        user_to_be_updated: UserRepositoryUpdateParams,
+    ) -> Result<String, AppError> {
+        match self {
+            UserRepositoryBackend::Postgres(repository) => {
+                repository.store_update(id, user_to_be_updated).await
+            }
+            UserRepositoryBackend::Sqlite(repository) => {
+                repository.store_update(id, user_to_be_updated).await
+            }
+            UserRepositoryBackend::InMemory(repository) => {
+                repository.store_update(id, user_to_be_updated).await
+            }
         }
     }
 
+    async fn delete(&self, id: String) -> Result<String, AppError> {
+        match self {
+            UserRepositoryBackend::Postgres(repository) => repository.delete(id).await,
+            UserRepositoryBackend::Sqlite(repository) => repository.delete(id).await,
+            UserRepositoryBackend::InMemory(repository) => repository.delete(id).await,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -282,7 +733,6 @@ mod tests {
     #[tokio::test]
     async fn test_store_update() {
         const FAKE_USERNAME_UPDATED: &str = "username_uptadated";
-        const FAKE_EMAIL_UPDATED: &str = "email@updated.com";
         const FAKE_PASSWORD_UPDATED: &str = "updated_password";
 
         async fn repository_store_update(
@@ -320,8 +770,8 @@ mod tests {
             assert_eq!(result.username, FAKE_USERNAME_UPDATED);
             assert_eq!(result.email, FAKE_EMAIL);
             assert_eq!(result.password, FAKE_PASSWORD_UPDATED);
-            assert_eq!(result.activated, false);
-            assert_eq!(result.blocked, false);
+            assert!(!result.activated);
+            assert!(!result.blocked);
  
             Ok(response)
         }
@@ -355,13 +805,11 @@ mod tests {
                 .delete(FAKE_ID.to_string())
                 .await?;
 
-            match sqlx::query!(
+            let user = sqlx::query!(
                 "SELECT username FROM users WHERE id = $1", FAKE_ID)
                 .fetch_one(&pool)
-                .await {
-                    Ok(_) => panic!("User should not exist"),
-                    Err(_) => (),
-            }
+                .await;
+            assert!(user.is_err(), "User should not exist");
 
             Ok(response)
         }
@@ -372,4 +820,107 @@ mod tests {
 
         assert_eq!(response, "User deleted successfully");
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_store_user_writes_outbox_event() {
+        async fn repository_store_with_event(
+            pool: Pool<Postgres>,
+        ) -> Result<Vec<(String, String)>, AppError> {
+            let repository = UserRepositoryPostgres { pool: &pool };
+
+            repository
+                .store(UserRepositoryStoreParams {
+                    id: FAKE_ID.to_string(),
+                    username: FAKE_USERNAME.to_string(),
+                    email: FAKE_EMAIL.to_string(),
+                    password: FAKE_PASSWORD.to_string(),
+                })
+                .await?;
+
+            repository
+                .store_update(FAKE_ID.to_string(), UserRepositoryUpdateParams {
+                    activated: Some(true),
+                    ..Default::default()
+                })
+                .await?;
+
+            let events = sqlx::query!(
+                "SELECT event_type, user_id FROM user_events_outbox ORDER BY id")
+                .fetch_all(&pool)
+                .await.unwrap();
+
+            Ok(events.into_iter().map(|event| (event.event_type, event.user_id)).collect())
+        }
+
+        let events = test_with_database("test_store_user_writes_outbox_event", repository_store_with_event)
+            .await
+            .unwrap();
+
+        assert_eq!(events, vec![
+            ("UserRegistered".to_string(), FAKE_ID.to_string()),
+            ("UserActivated".to_string(), FAKE_ID.to_string()),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_store_does_not_write_outbox_event() {
+        async fn repository_store_duplicated(
+            pool: Pool<Postgres>,
+        ) -> Result<i64, AppError> {
+            let repository = UserRepositoryPostgres { pool: &pool };
+            let user = || UserRepositoryStoreParams {
+                id: FAKE_ID.to_string(),
+                username: FAKE_USERNAME.to_string(),
+                email: FAKE_EMAIL.to_string(),
+                password: FAKE_PASSWORD.to_string(),
+            };
+
+            repository.store(user()).await?;
+            let error = repository.store(user()).await.err().unwrap();
+            assert_eq!(error.code, Code::AlreadyExists);
+
+            let events = sqlx::query_scalar!("SELECT COUNT(*) FROM user_events_outbox")
+                .fetch_one(&pool)
+                .await.unwrap();
+
+            Ok(events.unwrap_or_default())
+        }
+
+        let events = test_with_database("test_failed_store_does_not_write_outbox_event", repository_store_duplicated)
+            .await
+            .unwrap();
+
+        assert_eq!(events, 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_in_memory_username_is_only_taken_once() {
+        let store = InMemoryStore::default();
+
+        let stores: Vec<_> = (0..8)
+            .map(|index| {
+                let store = store.clone();
+                tokio::spawn(async move {
+                    UserRepositoryInMemory { store: &store }
+                        .store(UserRepositoryStoreParams {
+                            id: format!("{}{}", FAKE_ID, index),
+                            username: FAKE_USERNAME.to_string(),
+                            email: format!("{}{}", index, FAKE_EMAIL),
+                            password: FAKE_PASSWORD.to_string(),
+                        })
+                        .await
+                })
+            })
+            .collect();
+        let mut stored = 0;
+        for handle in stores {
+            match handle.await.unwrap() {
+                Ok(_) => stored += 1,
+                Err(error) => assert_eq!(error.reason, Some(Reason::UsernameTaken)),
+            }
+        }
+
+        assert_eq!(stored, 1);
+        assert_eq!(store.lock().users.len(), 1);
+    }
+}
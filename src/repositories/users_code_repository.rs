@@ -1,5 +1,13 @@
 use crate::{
+    database::{
+        in_memory::{InMemoryData, InMemoryStore, StoredCode},
+        redis_pool::RedisPool,
+    },
     error::{AppError, Code},
+    telemetry::{
+        metrics::metrics,
+        spans::{redis_span, sqlite_span},
+    },
     utils::adapters::{
         redis_error_to_app_error::redis_error_to_app_error,
         sqlx_error_to_app_error::sqlx_error_to_app_error,
@@ -9,7 +17,8 @@ use async_trait::async_trait;
 use chrono::{Duration, NaiveDateTime, Utc};
 use mockall::automock;
 use redis::AsyncCommands;
-use sqlx::{Pool, Postgres};
+use sqlx::{Pool, Postgres, Sqlite};
+use tracing::{instrument, Instrument};
 
 #[async_trait]
 #[automock]
@@ -17,8 +26,37 @@ pub trait UsersCodeRepository: Send + Sync {
     async fn store(&self, code: UsersCode) -> Result<String, AppError>;
     async fn get(&self, user_id: String, code: String) -> Result<UsersCode, AppError>;
     async fn delete(&self, user_id: String) -> Result<String, AppError>;
+    /// Count a wrong guess against the current code, returning the attempts made so far.
+    async fn increment_attempts(&self, user_id: String) -> Result<i64, AppError>;
+}
+
+/// What a code grants. Each purpose keeps its own code per user, so asking for a
+/// login code does not invalidate a pending activation code.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UsersCodePurpose {
+    /// Account activation and password recovery.
+    Account,
+    Login,
 }
 
+impl UsersCodePurpose {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UsersCodePurpose::Account => "account",
+            UsersCodePurpose::Login => "login",
+        }
+    }
+
+    /// Account codes keep the bare user id as key, like before purposes existed.
+    fn redis_key(&self, user_id: &str) -> String {
+        match self {
+            UsersCodePurpose::Account => user_id.to_string(),
+            UsersCodePurpose::Login => format!("login_code:{}", user_id),
+        }
+    }
+}
+
+#[derive(Clone, sqlx::FromRow)]
 pub struct UsersCode {
     pub code: String,
     pub expire_at: NaiveDateTime,
@@ -27,16 +65,19 @@ pub struct UsersCode {
 
 pub struct UsersCodeRepositoryPostgres<'a> {
     pub pool: &'a Pool<Postgres>,
+    pub purpose: UsersCodePurpose,
 }
 
 #[async_trait]
 impl UsersCodeRepository for UsersCodeRepositoryPostgres<'_> {
+    #[instrument(skip_all, fields(user_id = %code.user_id), err(level = "debug", Debug))]
     async fn store(&self, code: UsersCode) -> Result<String, AppError> {
         match sqlx::query!(
-            "INSERT INTO users_code (code, expire_at, user_id) VALUES ($1, $2, $3)",
+            "INSERT INTO users_code (code, expire_at, user_id, purpose) VALUES ($1, $2, $3, $4)",
             code.code,
             code.expire_at,
-            code.user_id
+            code.user_id,
+            self.purpose.as_str()
         )
         .execute(self.pool)
         .await
@@ -45,12 +86,15 @@ impl UsersCodeRepository for UsersCodeRepositoryPostgres<'_> {
             Err(error) => Err(sqlx_error_to_app_error(error)),
         }
     }
+    #[instrument(skip_all, fields(user_id = %user_id), err(level = "debug", Debug))]
     async fn get(&self, user_id: String, code_key: String) -> Result<UsersCode, AppError> {
         match sqlx::query_as!(
             UsersCode,
-            "SELECT code, expire_at, user_id FROM users_code WHERE code = $1 and user_id = $2",
+            "SELECT code, expire_at, user_id FROM users_code
+            WHERE code = $1 and user_id = $2 and purpose = $3",
             code_key,
-            user_id
+            user_id,
+            self.purpose.as_str()
         )
         .fetch_one(self.pool)
         .await
@@ -59,62 +103,172 @@ impl UsersCodeRepository for UsersCodeRepositoryPostgres<'_> {
             Err(error) => Err(sqlx_error_to_app_error(error)),
         }
     }
+    #[instrument(skip_all, fields(user_id = %user_id), err(level = "debug", Debug))]
     async fn delete(&self, user_id: String) -> Result<String, AppError> {
-        match sqlx::query!("DELETE FROM users_code WHERE user_id = $1", user_id)
-            .execute(self.pool)
-            .await
+        match sqlx::query!(
+            "DELETE FROM users_code WHERE user_id = $1 and purpose = $2",
+            user_id,
+            self.purpose.as_str()
+        )
+        .execute(self.pool)
+        .await
         {
             Ok(_) => Ok(String::from("codes from the given user id deleted")),
             Err(error) => Err(sqlx_error_to_app_error(error)),
         }
     }
+    #[instrument(skip_all, fields(user_id = %user_id), err(level = "debug", Debug))]
+    async fn increment_attempts(&self, user_id: String) -> Result<i64, AppError> {
+        match sqlx::query!(
+            "UPDATE users_code SET attempts = attempts + 1
+            WHERE user_id = $1 and purpose = $2 RETURNING attempts",
+            user_id,
+            self.purpose.as_str()
+        )
+        .fetch_all(self.pool)
+        .await
+        {
+            Ok(codes) => match codes.iter().map(|code| code.attempts).max() {
+                Some(attempts) => Ok(attempts.into()),
+                None => Err(AppError::new(Code::NotFound, "Code not found")),
+            },
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+}
+
+pub struct UsersCodeRepositorySqlite<'a> {
+    pub pool: &'a Pool<Sqlite>,
+    pub purpose: UsersCodePurpose,
+}
+
+#[async_trait]
+impl UsersCodeRepository for UsersCodeRepositorySqlite<'_> {
+    #[instrument(skip_all, fields(user_id = %code.user_id), err(level = "debug", Debug))]
+    async fn store(&self, code: UsersCode) -> Result<String, AppError> {
+        sqlx::query(
+            "INSERT INTO users_code (code, expire_at, user_id, purpose) VALUES (?, ?, ?, ?)",
+        )
+        .bind(code.code)
+        .bind(code.expire_at)
+        .bind(code.user_id)
+        .bind(self.purpose.as_str())
+        .execute(self.pool)
+        .instrument(sqlite_span("INSERT", "users_code"))
+        .await
+        .map_err(sqlx_error_to_app_error)?;
+
+        Ok(String::from("Code store successfully"))
+    }
+
+    #[instrument(skip_all, fields(user_id = %user_id), err(level = "debug", Debug))]
+    async fn get(&self, user_id: String, code_key: String) -> Result<UsersCode, AppError> {
+        sqlx::query_as::<_, UsersCode>(
+            "SELECT code, expire_at, user_id FROM users_code
+            WHERE code = ? AND user_id = ? AND purpose = ?",
+        )
+        .bind(code_key)
+        .bind(user_id)
+        .bind(self.purpose.as_str())
+        .fetch_one(self.pool)
+        .instrument(sqlite_span("SELECT", "users_code"))
+        .await
+        .map_err(sqlx_error_to_app_error)
+    }
+
+    #[instrument(skip_all, fields(user_id = %user_id), err(level = "debug", Debug))]
+    async fn delete(&self, user_id: String) -> Result<String, AppError> {
+        sqlx::query("DELETE FROM users_code WHERE user_id = ? AND purpose = ?")
+            .bind(user_id)
+            .bind(self.purpose.as_str())
+            .execute(self.pool)
+            .instrument(sqlite_span("DELETE", "users_code"))
+            .await
+            .map_err(sqlx_error_to_app_error)?;
+
+        Ok(String::from("codes from the given user id deleted"))
+    }
+
+    #[instrument(skip_all, fields(user_id = %user_id), err(level = "debug", Debug))]
+    async fn increment_attempts(&self, user_id: String) -> Result<i64, AppError> {
+        let attempts: Vec<i64> = sqlx::query_scalar(
+            "UPDATE users_code SET attempts = attempts + 1
+            WHERE user_id = ? AND purpose = ? RETURNING attempts",
+        )
+        .bind(user_id)
+        .bind(self.purpose.as_str())
+        .fetch_all(self.pool)
+        .instrument(sqlite_span("UPDATE", "users_code"))
+        .await
+        .map_err(sqlx_error_to_app_error)?;
+
+        match attempts.into_iter().max() {
+            Some(attempts) => Ok(attempts),
+            None => Err(AppError::new(Code::NotFound, "Code not found")),
+        }
+    }
 }
 
 pub struct UsersCodeRepositoryRedis<'a> {
-    pub client: &'a redis::Client,
+    pub redis: &'a RedisPool,
+    pub purpose: UsersCodePurpose,
+}
+
+fn attempts_key(code_key: &str) -> String {
+    format!("{}:attempts", code_key)
 }
 
 #[async_trait]
 impl UsersCodeRepository for UsersCodeRepositoryRedis<'_> {
+    #[instrument(skip_all, fields(user_id = %code.user_id), err(level = "debug", Debug))]
     async fn store(&self, code: UsersCode) -> Result<String, AppError> {
+        let _timer = metrics().redis_call_timer("users_code.store");
         let mut connection = self
-            .client
-            .get_async_connection()
-            .await
-            .map_err(redis_error_to_app_error)?;
-        let key = code.user_id;
+            .redis
+            .connection()
+            .instrument(redis_span("CONNECT"))
+            .await?;
+        let key = self.purpose.redis_key(&code.user_id);
         let value = code.code;
 
         redis::pipe()
             .atomic()
             .set(&key, &value)
             .ignore()
+            .del(attempts_key(&key))
+            .ignore()
             .cmd("EXPIREAT")
             .arg(&key)
             .arg(code.expire_at.timestamp())
-            .query_async(&mut connection)
+            .query_async::<_, ()>(&mut connection)
+            .instrument(redis_span("MULTI"))
             .await
             .map_err(redis_error_to_app_error)?;
 
         Ok(String::from("Code stored successfully"))
     }
 
+    #[instrument(skip_all, fields(user_id = %user_id), err(level = "debug", Debug))]
     async fn get(&self, user_id: String, code: String) -> Result<UsersCode, AppError> {
+        let _timer = metrics().redis_call_timer("users_code.get");
         let mut connection = self
-            .client
-            .get_async_connection()
-            .await
-            .map_err(redis_error_to_app_error)?;
+            .redis
+            .connection()
+            .instrument(redis_span("CONNECT"))
+            .await?;
 
+        let key = self.purpose.redis_key(&user_id);
         let value: Option<String> = connection
-            .get(user_id.clone())
+            .get(&key)
+            .instrument(redis_span("GET"))
             .await
             .map_err(redis_error_to_app_error)?;
 
         if let Some(stored_code) = value {
             if stored_code == code {
                 let expire_at_seconds: i64 = connection
-                    .ttl(user_id.clone())
+                    .ttl(&key)
+                    .instrument(redis_span("TTL"))
                     .await
                     .map_err(redis_error_to_app_error)?;
 
@@ -131,27 +285,187 @@ impl UsersCodeRepository for UsersCodeRepositoryRedis<'_> {
         Err(AppError::new(Code::NotFound, "Code not found"))
     }
 
+    #[instrument(skip_all, fields(user_id = %user_id), err(level = "debug", Debug))]
     async fn delete(&self, user_id: String) -> Result<String, AppError> {
+        let _timer = metrics().redis_call_timer("users_code.delete");
         let mut connection = self
-            .client
-            .get_async_connection()
+            .redis
+            .connection()
+            .instrument(redis_span("CONNECT"))
+            .await?;
+
+        let key = self.purpose.redis_key(&user_id);
+        connection
+            .del::<_, ()>(&[attempts_key(&key), key])
+            .instrument(redis_span("DEL"))
             .await
             .map_err(redis_error_to_app_error)?;
 
-        connection
-            .del(user_id)
+        Ok(String::from("Code deleted successfully"))
+    }
+
+    #[instrument(skip_all, fields(user_id = %user_id), err(level = "debug", Debug))]
+    async fn increment_attempts(&self, user_id: String) -> Result<i64, AppError> {
+        let _timer = metrics().redis_call_timer("users_code.increment_attempts");
+        let mut connection = self
+            .redis
+            .connection()
+            .instrument(redis_span("CONNECT"))
+            .await?;
+        let key = self.purpose.redis_key(&user_id);
+
+        // The counter expires together with the code it belongs to.
+        let ttl: i64 = connection
+            .ttl(&key)
+            .instrument(redis_span("TTL"))
             .await
             .map_err(redis_error_to_app_error)?;
+        if ttl == -2 {
+            return Err(AppError::new(Code::NotFound, "Code not found"));
+        }
+
+        let (attempts,): (i64,) = redis::pipe()
+            .atomic()
+            .cmd("INCR")
+            .arg(attempts_key(&key))
+            .expire(attempts_key(&key), ttl.max(1) as usize)
+            .ignore()
+            .query_async(&mut connection)
+            .instrument(redis_span("MULTI"))
+            .await
+            .map_err(redis_error_to_app_error)?;
+
+        Ok(attempts)
+    }
+}
+
+/// Codes kept in an `InMemoryStore`. Like in Redis, a user has one code per purpose, a
+/// new one replacing the previous, and a code is gone once expired.
+pub struct UsersCodeRepositoryInMemory<'a> {
+    pub store: &'a InMemoryStore,
+    pub purpose: UsersCodePurpose,
+}
+
+impl UsersCodeRepositoryInMemory<'_> {
+    fn live_code<'d>(
+        &self,
+        data: &'d mut InMemoryData,
+        user_id: String,
+    ) -> Option<&'d mut StoredCode> {
+        let key = (user_id, self.purpose.as_str());
+        let now = Utc::now().naive_utc();
+        if matches!(data.codes.get(&key), Some(stored) if stored.code.expire_at <= now) {
+            data.codes.remove(&key);
+        }
+        data.codes.get_mut(&key)
+    }
+}
+
+#[async_trait]
+impl UsersCodeRepository for UsersCodeRepositoryInMemory<'_> {
+    #[instrument(skip_all, fields(user_id = %code.user_id), err(level = "debug", Debug))]
+    async fn store(&self, code: UsersCode) -> Result<String, AppError> {
+        self.store.lock().codes.insert(
+            (code.user_id.clone(), self.purpose.as_str()),
+            StoredCode { code, attempts: 0 },
+        );
+
+        Ok(String::from("Code stored successfully"))
+    }
+
+    #[instrument(skip_all, fields(user_id = %user_id), err(level = "debug", Debug))]
+    async fn get(&self, user_id: String, code: String) -> Result<UsersCode, AppError> {
+        let mut data = self.store.lock();
+        match self.live_code(&mut data, user_id) {
+            Some(stored) if stored.code.code == code => Ok(stored.code.clone()),
+            _ => Err(AppError::new(Code::NotFound, "Code not found")),
+        }
+    }
+
+    #[instrument(skip_all, fields(user_id = %user_id), err(level = "debug", Debug))]
+    async fn delete(&self, user_id: String) -> Result<String, AppError> {
+        self.store
+            .lock()
+            .codes
+            .remove(&(user_id, self.purpose.as_str()));
 
         Ok(String::from("Code deleted successfully"))
     }
+
+    #[instrument(skip_all, fields(user_id = %user_id), err(level = "debug", Debug))]
+    async fn increment_attempts(&self, user_id: String) -> Result<i64, AppError> {
+        let mut data = self.store.lock();
+        match self.live_code(&mut data, user_id) {
+            Some(stored) => {
+                stored.attempts += 1;
+                Ok(stored.attempts)
+            }
+            None => Err(AppError::new(Code::NotFound, "Code not found")),
+        }
+    }
+}
+
+/// The code repository of the backend picked by `CODES_BACKEND` and `STORAGE_BACKEND`.
+pub enum UsersCodeRepositoryBackend<'a> {
+    Postgres(UsersCodeRepositoryPostgres<'a>),
+    Sqlite(UsersCodeRepositorySqlite<'a>),
+    Redis(UsersCodeRepositoryRedis<'a>),
+    InMemory(UsersCodeRepositoryInMemory<'a>),
+}
+
+#[async_trait]
+impl UsersCodeRepository for UsersCodeRepositoryBackend<'_> {
+    async fn store(&self, code: UsersCode) -> Result<String, AppError> {
+        match self {
+            UsersCodeRepositoryBackend::Postgres(repository) => repository.store(code).await,
+            UsersCodeRepositoryBackend::Sqlite(repository) => repository.store(code).await,
+            UsersCodeRepositoryBackend::Redis(repository) => repository.store(code).await,
+            UsersCodeRepositoryBackend::InMemory(repository) => repository.store(code).await,
+        }
+    }
+
+    async fn get(&self, user_id: String, code: String) -> Result<UsersCode, AppError> {
+        match self {
+            UsersCodeRepositoryBackend::Postgres(repository) => repository.get(user_id, code).await,
+            UsersCodeRepositoryBackend::Sqlite(repository) => repository.get(user_id, code).await,
+            UsersCodeRepositoryBackend::Redis(repository) => repository.get(user_id, code).await,
+            UsersCodeRepositoryBackend::InMemory(repository) => repository.get(user_id, code).await,
+        }
+    }
+
+    async fn delete(&self, user_id: String) -> Result<String, AppError> {
+        match self {
+            UsersCodeRepositoryBackend::Postgres(repository) => repository.delete(user_id).await,
+            UsersCodeRepositoryBackend::Sqlite(repository) => repository.delete(user_id).await,
+            UsersCodeRepositoryBackend::Redis(repository) => repository.delete(user_id).await,
+            UsersCodeRepositoryBackend::InMemory(repository) => repository.delete(user_id).await,
+        }
+    }
+
+    async fn increment_attempts(&self, user_id: String) -> Result<i64, AppError> {
+        match self {
+            UsersCodeRepositoryBackend::Postgres(repository) => {
+                repository.increment_attempts(user_id).await
+            }
+            UsersCodeRepositoryBackend::Sqlite(repository) => {
+                repository.increment_attempts(user_id).await
+            }
+            UsersCodeRepositoryBackend::Redis(repository) => {
+                repository.increment_attempts(user_id).await
+            }
+            UsersCodeRepositoryBackend::InMemory(repository) => {
+                repository.increment_attempts(user_id).await
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::env;
-
-    use crate::{database::utils::integration_test::test_with_database, error::Code};
+    use crate::{
+        database::utils::integration_test::{test_redis_pool, test_with_database},
+        error::Code,
+    };
 
     use super::*;
     use chrono::Duration;
@@ -196,7 +510,10 @@ mod tests {
         async fn repository_store_code(pool: Pool<Postgres>) -> Result<String, AppError> {
             store_fake_user_for_test(&pool).await;
 
-            let repository = UsersCodeRepositoryPostgres { pool: &pool };
+            let repository = UsersCodeRepositoryPostgres {
+                pool: &pool,
+                purpose: UsersCodePurpose::Account,
+            };
 
             let expire: NaiveDateTime = Utc::now().naive_utc() + Duration::minutes(30);
             repository
@@ -220,7 +537,10 @@ mod tests {
         async fn repository_store_code_without_user(
             pool: Pool<Postgres>,
         ) -> Result<String, AppError> {
-            let repository = UsersCodeRepositoryPostgres { pool: &pool };
+            let repository = UsersCodeRepositoryPostgres {
+                pool: &pool,
+                purpose: UsersCodePurpose::Account,
+            };
 
             let expire: NaiveDateTime = Utc::now().naive_utc() + Duration::minutes(30);
             repository
@@ -254,7 +574,10 @@ mod tests {
             store_fake_user_for_test(&pool).await;
             store_fake_code_for_test(&pool).await;
 
-            let repository = UsersCodeRepositoryPostgres { pool: &pool };
+            let repository = UsersCodeRepositoryPostgres {
+                pool: &pool,
+                purpose: UsersCodePurpose::Account,
+            };
 
             repository
                 .get(FAKE_USER_ID.to_string(), FAKE_CODE.to_string())
@@ -275,7 +598,10 @@ mod tests {
         async fn repository_test_get_nonexistent_code(
             pool: Pool<Postgres>,
         ) -> Result<UsersCode, AppError> {
-            let repository = UsersCodeRepositoryPostgres { pool: &pool };
+            let repository = UsersCodeRepositoryPostgres {
+                pool: &pool,
+                purpose: UsersCodePurpose::Account,
+            };
 
             repository
                 .get(FAKE_USER_ID.to_string(), FAKE_CODE.to_string())
@@ -302,7 +628,10 @@ mod tests {
 
             store_fake_code_for_test(&pool).await;
 
-            let repository = UsersCodeRepositoryPostgres { pool: &pool };
+            let repository = UsersCodeRepositoryPostgres {
+                pool: &pool,
+                purpose: UsersCodePurpose::Account,
+            };
 
             repository.delete(FAKE_USER_ID.to_string()).await
         }
@@ -316,9 +645,9 @@ mod tests {
 
     #[tokio::test]
     async fn test_redis_store_code() {
-        dotenv::from_filename(".env.test").ok();
         let repository = UsersCodeRepositoryRedis {
-            client: &redis::Client::open(env::var("REDIS_CLIENT").unwrap()).unwrap(),
+            redis: &test_redis_pool(),
+            purpose: UsersCodePurpose::Account,
         };
         let expire: NaiveDateTime = Utc::now().naive_utc() + Duration::minutes(30);
         let response = repository
@@ -335,11 +664,11 @@ mod tests {
 
     #[tokio::test]
     async fn test_redis_get_code() {
-        dotenv::from_filename(".env.test").ok();
         let repository = UsersCodeRepositoryRedis {
-            client: &redis::Client::open(env::var("REDIS_CLIENT").unwrap()).unwrap(),
+            redis: &test_redis_pool(),
+            purpose: UsersCodePurpose::Account,
         };
-        let mut connection = repository.client.get_async_connection().await.unwrap();
+        let mut connection = repository.redis.connection().await.unwrap();
         let expire: NaiveDateTime = Utc::now().naive_utc() + Duration::minutes(30);
 
         let result: Result<(), RedisError> = redis::pipe()
@@ -351,7 +680,7 @@ mod tests {
             .arg(expire.timestamp())
             .query_async(&mut connection)
             .await;
-        let _ = result.unwrap();
+        result.unwrap();
 
         let response = repository
             .get("FAKE_USER_ID".to_string(), "FAKE_CODE".to_string())
@@ -365,9 +694,9 @@ mod tests {
 
     #[tokio::test]
     async fn test_redis_get_nonexistent_code() {
-        dotenv::from_filename(".env.test").ok();
         let repository = UsersCodeRepositoryRedis {
-            client: &redis::Client::open(env::var("REDIS_CLIENT").unwrap()).unwrap(),
+            redis: &test_redis_pool(),
+            purpose: UsersCodePurpose::Account,
         };
 
         let error = match repository
@@ -383,11 +712,11 @@ mod tests {
 
     #[tokio::test]
     async fn test_redis_delete_code() {
-        dotenv::from_filename(".env.test").ok();
         let repository = UsersCodeRepositoryRedis {
-            client: &redis::Client::open(env::var("REDIS_CLIENT").unwrap()).unwrap(),
+            redis: &test_redis_pool(),
+            purpose: UsersCodePurpose::Account,
         };
-        let mut connection = repository.client.get_async_connection().await.unwrap();
+        let mut connection = repository.redis.connection().await.unwrap();
         let expire: NaiveDateTime = Utc::now().naive_utc() + Duration::minutes(30);
 
         let result: Result<(), RedisError> = redis::pipe()
@@ -399,10 +728,153 @@ mod tests {
             .arg(expire.timestamp())
             .query_async(&mut connection)
             .await;
-        let _ = result.unwrap();
+        result.unwrap();
 
         let response = repository.delete(FAKE_USER_ID.to_string()).await.unwrap();
 
         assert_eq!(response, "Code deleted successfully");
     }
+
+    #[tokio::test]
+    async fn test_increment_attempts() {
+        async fn repository_increment_attempts(pool: Pool<Postgres>) -> Result<i64, AppError> {
+            store_fake_user_for_test(&pool).await;
+            store_fake_code_for_test(&pool).await;
+
+            let repository = UsersCodeRepositoryPostgres {
+                pool: &pool,
+                purpose: UsersCodePurpose::Account,
+            };
+
+            repository
+                .increment_attempts(FAKE_USER_ID.to_string())
+                .await?;
+            repository
+                .increment_attempts(FAKE_USER_ID.to_string())
+                .await
+        }
+
+        let attempts = test_with_database("test_increment_attempts", repository_increment_attempts)
+            .await
+            .unwrap();
+
+        assert_eq!(attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_codes_are_scoped_by_purpose() {
+        async fn repository_get_other_purpose(pool: Pool<Postgres>) -> Result<UsersCode, AppError> {
+            store_fake_user_for_test(&pool).await;
+            store_fake_code_for_test(&pool).await;
+
+            let repository = UsersCodeRepositoryPostgres {
+                pool: &pool,
+                purpose: UsersCodePurpose::Login,
+            };
+
+            repository
+                .get(FAKE_USER_ID.to_string(), FAKE_CODE.to_string())
+                .await
+        }
+
+        let error = test_with_database(
+            "test_codes_are_scoped_by_purpose",
+            repository_get_other_purpose,
+        )
+        .await
+        .err()
+        .unwrap();
+
+        assert_eq!(error.code, Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_redis_login_code_attempts() {
+        let redis = test_redis_pool();
+        let account_codes = UsersCodeRepositoryRedis {
+            redis: &redis,
+            purpose: UsersCodePurpose::Account,
+        };
+        let login_codes = UsersCodeRepositoryRedis {
+            redis: &redis,
+            purpose: UsersCodePurpose::Login,
+        };
+        let user_id = "LOGIN_CODE_USER_ID".to_string();
+        let expire_at = Utc::now().naive_utc() + Duration::minutes(10);
+
+        for (repository, code) in [(&account_codes, "111111"), (&login_codes, "222222")] {
+            repository
+                .store(UsersCode {
+                    code: code.to_string(),
+                    expire_at,
+                    user_id: user_id.clone(),
+                })
+                .await
+                .unwrap();
+        }
+
+        assert!(account_codes
+            .get(user_id.clone(), "222222".to_string())
+            .await
+            .is_err());
+        assert_eq!(
+            login_codes
+                .get(user_id.clone(), "222222".to_string())
+                .await
+                .unwrap()
+                .code,
+            "222222"
+        );
+
+        login_codes
+            .increment_attempts(user_id.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            login_codes
+                .increment_attempts(user_id.clone())
+                .await
+                .unwrap(),
+            2
+        );
+
+        login_codes.delete(user_id.clone()).await.unwrap();
+        assert_eq!(
+            login_codes
+                .increment_attempts(user_id.clone())
+                .await
+                .unwrap_err()
+                .code,
+            Code::NotFound
+        );
+        assert!(account_codes
+            .get(user_id.clone(), "111111".to_string())
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_code_is_gone_once_expired() {
+        let store = InMemoryStore::default();
+        let repository = UsersCodeRepositoryInMemory {
+            store: &store,
+            purpose: UsersCodePurpose::Account,
+        };
+        repository
+            .store(UsersCode {
+                code: FAKE_CODE.to_string(),
+                expire_at: Utc::now().naive_utc() - Duration::seconds(1),
+                user_id: FAKE_USER_ID.to_string(),
+            })
+            .await
+            .unwrap();
+
+        let error = repository
+            .get(FAKE_USER_ID.to_string(), FAKE_CODE.to_string())
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(error.code, Code::NotFound);
+        assert!(store.lock().codes.is_empty());
+    }
 }
@@ -0,0 +1,111 @@
+pub use crate::dtos::repositories::dtos_repository_webauthn::*;
+use crate::{
+    database::redis_pool::RedisPool, error::*, telemetry::metrics::metrics,
+    utils::adapters::redis_error_to_app_error::redis_error_to_app_error,
+};
+use async_trait::async_trait;
+use mockall::automock;
+
+const WEBAUTHN_CHALLENGE_KEY_PREFIX: &str = "webauthn:challenge:";
+
+#[async_trait]
+#[automock]
+pub trait WebauthnChallengeRepository: Sync + Send {
+    async fn store(&self, challenge: WebauthnChallenge) -> Result<String, AppError>;
+    /// Fetch and delete the challenge in a single step, so each ceremony can only be
+    /// completed once.
+    async fn take(&self, challenge: String) -> Result<WebauthnChallenge, AppError>;
+}
+
+pub struct WebauthnChallengeRepositoryRedis<'a> {
+    pub redis: &'a RedisPool,
+}
+
+#[async_trait]
+impl WebauthnChallengeRepository for WebauthnChallengeRepositoryRedis<'_> {
+    async fn store(&self, challenge: WebauthnChallenge) -> Result<String, AppError> {
+        let _timer = metrics().redis_call_timer("webauthn_challenge.store");
+        let mut connection = self.redis.connection().await?;
+        let key = format!("{}{}", WEBAUTHN_CHALLENGE_KEY_PREFIX, challenge.challenge);
+        let value = match serde_json::to_string(&challenge) {
+            Ok(value) => value,
+            Err(error) => {
+                return Err(AppError::new(
+                    Code::Internal,
+                    "Unable to serialize the WebAuthn challenge",
+                )
+                .with_source(error))
+            }
+        };
+
+        redis::pipe()
+            .atomic()
+            .set(&key, &value)
+            .ignore()
+            .cmd("EXPIREAT")
+            .arg(&key)
+            .arg(challenge.expire_at.timestamp())
+            .query_async::<_, ()>(&mut connection)
+            .await
+            .map_err(redis_error_to_app_error)?;
+
+        Ok(String::from("WebAuthn challenge stored successfully"))
+    }
+
+    async fn take(&self, challenge: String) -> Result<WebauthnChallenge, AppError> {
+        let _timer = metrics().redis_call_timer("webauthn_challenge.take");
+        let mut connection = self.redis.connection().await?;
+
+        let value: Option<String> = redis::cmd("GETDEL")
+            .arg(format!("{}{}", WEBAUTHN_CHALLENGE_KEY_PREFIX, challenge))
+            .query_async(&mut connection)
+            .await
+            .map_err(redis_error_to_app_error)?;
+
+        match value {
+            Some(value) => match serde_json::from_str(&value) {
+                Ok(challenge) => Ok(challenge),
+                Err(error) => Err(AppError::new(
+                    Code::Internal,
+                    "Invalid stored WebAuthn challenge",
+                )
+                .with_source(error)),
+            },
+            None => Err(AppError::new(
+                Code::NotFound,
+                "WebAuthn challenge not found",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use super::*;
+    use crate::database::utils::integration_test::test_redis_pool;
+
+    #[tokio::test]
+    async fn test_redis_take_webauthn_challenge_once() {
+        let repository = WebauthnChallengeRepositoryRedis {
+            redis: &test_redis_pool(),
+        };
+        let challenge = WebauthnChallenge {
+            challenge: "RkFLRV9XRUJBVVRITl9DSEFMTEVOR0U".to_string(),
+            ceremony: WebauthnCeremony::Registration,
+            user_id: Some("userFakeId".to_string()),
+            second_factor: false,
+            expire_at: Utc::now().naive_utc() + Duration::minutes(5),
+        };
+
+        repository.store(challenge.clone()).await.unwrap();
+
+        let taken = repository.take(challenge.challenge.clone()).await.unwrap();
+        assert_eq!(taken.ceremony, WebauthnCeremony::Registration);
+        assert_eq!(taken.user_id, challenge.user_id);
+
+        let error = repository.take(challenge.challenge).await.err().unwrap();
+        assert_eq!(error.code, Code::NotFound);
+    }
+}
@@ -0,0 +1,182 @@
+pub use crate::dtos::repositories::dtos_repository_webauthn::*;
+use crate::{error::*, utils::adapters::sqlx_error_to_app_error::sqlx_error_to_app_error};
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{Pool, Postgres};
+
+#[async_trait]
+#[automock]
+pub trait WebauthnCredentialRepository: Sync + Send {
+    async fn store(
+        &self,
+        credential: WebauthnCredentialStoreParams,
+    ) -> Result<WebauthnCredential, AppError>;
+    async fn consult(&self, id: String) -> Result<WebauthnCredential, AppError>;
+    async fn list_by_user(&self, user_id: String) -> Result<Vec<WebauthnCredential>, AppError>;
+    /// Record a successful assertion along with the sign counter it reported.
+    async fn update_sign_count(&self, id: String, sign_count: i64) -> Result<String, AppError>;
+}
+
+pub struct WebauthnCredentialRepositoryPostgres<'a> {
+    pub pool: &'a Pool<Postgres>,
+}
+
+#[async_trait]
+impl WebauthnCredentialRepository for WebauthnCredentialRepositoryPostgres<'_> {
+    async fn store(
+        &self,
+        credential: WebauthnCredentialStoreParams,
+    ) -> Result<WebauthnCredential, AppError> {
+        match sqlx::query_as!(
+            WebauthnCredential,
+            "INSERT INTO webauthn_credentials (id, user_id, name, public_key, algorithm, sign_count)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, user_id, name, public_key, algorithm, sign_count, created_at, last_used_at",
+            credential.id,
+            credential.user_id,
+            credential.name,
+            credential.public_key,
+            credential.algorithm,
+            credential.sign_count,
+        )
+        .fetch_one(self.pool)
+        .await
+        {
+            Ok(credential) => Ok(credential),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn consult(&self, id: String) -> Result<WebauthnCredential, AppError> {
+        match sqlx::query_as!(
+            WebauthnCredential,
+            "SELECT id, user_id, name, public_key, algorithm, sign_count, created_at, last_used_at
+            FROM webauthn_credentials WHERE id = $1",
+            id
+        )
+        .fetch_one(self.pool)
+        .await
+        {
+            Ok(credential) => Ok(credential),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn list_by_user(&self, user_id: String) -> Result<Vec<WebauthnCredential>, AppError> {
+        match sqlx::query_as!(
+            WebauthnCredential,
+            "SELECT id, user_id, name, public_key, algorithm, sign_count, created_at, last_used_at
+            FROM webauthn_credentials WHERE user_id = $1 ORDER BY created_at",
+            user_id
+        )
+        .fetch_all(self.pool)
+        .await
+        {
+            Ok(credentials) => Ok(credentials),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn update_sign_count(&self, id: String, sign_count: i64) -> Result<String, AppError> {
+        match sqlx::query!(
+            "UPDATE webauthn_credentials SET sign_count = $2, last_used_at = NOW() WHERE id = $1",
+            id,
+            sign_count
+        )
+        .execute(self.pool)
+        .await
+        {
+            Ok(result) if result.rows_affected() == 0 => Err(AppError::new(
+                Code::NotFound,
+                "WebAuthn credential not found",
+            )),
+            Ok(_) => Ok(String::from("WebAuthn credential updated successfully")),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::utils::integration_test::test_with_database;
+
+    use super::*;
+
+    async fn store_fake_user_for_test(pool: &Pool<Postgres>) {
+        sqlx::query!(
+            "INSERT INTO users (id, username, email, password) VALUES ($1, $2, $3, $4)",
+            "userFakeId",
+            "username",
+            "test@email.com",
+            "password",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    fn fake_credential() -> WebauthnCredentialStoreParams {
+        WebauthnCredentialStoreParams {
+            id: "Y3JlZGVudGlhbC1pZA".to_string(),
+            user_id: "userFakeId".to_string(),
+            name: "Security key".to_string(),
+            public_key: vec![0xa5, 0x01, 0x02],
+            algorithm: -7,
+            sign_count: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_store_and_update_webauthn_credential() {
+        async fn repository_store_and_update(
+            pool: Pool<Postgres>,
+        ) -> Result<(Vec<WebauthnCredential>, WebauthnCredential), AppError> {
+            store_fake_user_for_test(&pool).await;
+
+            let repository = WebauthnCredentialRepositoryPostgres { pool: &pool };
+            repository.store(fake_credential()).await?;
+            repository
+                .update_sign_count("Y3JlZGVudGlhbC1pZA".to_string(), 12)
+                .await?;
+
+            Ok((
+                repository.list_by_user("userFakeId".to_string()).await?,
+                repository.consult("Y3JlZGVudGlhbC1pZA".to_string()).await?,
+            ))
+        }
+
+        let (credentials, credential) = test_with_database(
+            "test_store_and_update_webauthn_credential",
+            repository_store_and_update,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(credentials.len(), 1);
+        assert_eq!(credential.public_key, vec![0xa5, 0x01, 0x02]);
+        assert_eq!(credential.sign_count, 12);
+        assert!(credential.last_used_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_store_webauthn_credential_twice() {
+        async fn repository_store_twice(
+            pool: Pool<Postgres>,
+        ) -> Result<WebauthnCredential, AppError> {
+            store_fake_user_for_test(&pool).await;
+
+            let repository = WebauthnCredentialRepositoryPostgres { pool: &pool };
+            repository.store(fake_credential()).await?;
+            repository.store(fake_credential()).await
+        }
+
+        let error = test_with_database(
+            "test_store_webauthn_credential_twice",
+            repository_store_twice,
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(error.code, Code::AlreadyExists);
+    }
+}
@@ -0,0 +1,217 @@
+pub use crate::dtos::repositories::dtos_repository_webhook::*;
+use crate::{
+    dtos::events::dtos_user_event::UserEvent, error::*,
+    utils::adapters::sqlx_error_to_app_error::sqlx_error_to_app_error,
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use mockall::automock;
+use sqlx::{Pool, Postgres};
+
+#[async_trait]
+#[automock]
+pub trait WebhookDeliveryRepository: Sync + Send {
+    /// Create a pending delivery of the event for every webhook subscribed to its type,
+    /// enqueuing the same event twice is a no-op.
+    async fn enqueue(&self, event: &UserEvent) -> Result<u64, AppError>;
+    async fn fetch_due(&self, limit: i64) -> Result<Vec<WebhookDelivery>, AppError>;
+    async fn mark_delivered(&self, id: i64) -> Result<String, AppError>;
+    /// Record a failed attempt, `next_attempt_at` as None moves the delivery to the dead-letter state.
+    async fn mark_failed(
+        &self,
+        id: i64,
+        attempts: i32,
+        next_attempt_at: Option<NaiveDateTime>,
+        error: String,
+    ) -> Result<String, AppError>;
+}
+
+pub struct WebhookDeliveryRepositoryPostgres<'a> {
+    pub pool: &'a Pool<Postgres>,
+}
+
+#[async_trait]
+impl WebhookDeliveryRepository for WebhookDeliveryRepositoryPostgres<'_> {
+    async fn enqueue(&self, event: &UserEvent) -> Result<u64, AppError> {
+        match sqlx::query!(
+            "INSERT INTO webhook_deliveries (webhook_id, event_id, event_type, user_id, payload)
+            SELECT id, $1, $2::VARCHAR, $3, $4 FROM webhooks
+            WHERE cardinality(event_types) = 0 OR $2::TEXT = ANY(event_types)
+            ON CONFLICT (webhook_id, event_id) DO NOTHING",
+            event.id,
+            event.event_type,
+            event.user_id,
+            event.payload,
+        )
+        .execute(self.pool)
+        .await
+        {
+            Ok(result) => Ok(result.rows_affected()),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn fetch_due(&self, limit: i64) -> Result<Vec<WebhookDelivery>, AppError> {
+        match sqlx::query_as!(
+            WebhookDelivery,
+            "SELECT d.id, d.webhook_id, w.url, w.secret, d.event_id, d.event_type, d.user_id,
+            d.payload, d.attempts FROM webhook_deliveries d
+            INNER JOIN webhooks w ON w.id = d.webhook_id
+            WHERE d.status = $1 AND d.next_attempt_at <= NOW()
+            ORDER BY d.id LIMIT $2",
+            WebhookDeliveryStatus::Pending.as_str(),
+            limit
+        )
+        .fetch_all(self.pool)
+        .await
+        {
+            Ok(deliveries) => Ok(deliveries),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn mark_delivered(&self, id: i64) -> Result<String, AppError> {
+        match sqlx::query!(
+            "UPDATE webhook_deliveries SET status = $1, attempts = attempts + 1,
+            delivered_at = NOW(), last_error = NULL WHERE id = $2",
+            WebhookDeliveryStatus::Delivered.as_str(),
+            id
+        )
+        .execute(self.pool)
+        .await
+        {
+            Ok(_) => Ok(String::from("Delivery marked as delivered")),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn mark_failed(
+        &self,
+        id: i64,
+        attempts: i32,
+        next_attempt_at: Option<NaiveDateTime>,
+        error: String,
+    ) -> Result<String, AppError> {
+        let status = match next_attempt_at {
+            Some(_) => WebhookDeliveryStatus::Pending,
+            None => WebhookDeliveryStatus::Dead,
+        };
+
+        match sqlx::query!(
+            "UPDATE webhook_deliveries SET status = $1, attempts = $2,
+            next_attempt_at = COALESCE($3, next_attempt_at), last_error = $4 WHERE id = $5",
+            status.as_str(),
+            attempts,
+            next_attempt_at,
+            error,
+            id
+        )
+        .execute(self.pool)
+        .await
+        {
+            Ok(_) => Ok(String::from("Delivery failure recorded")),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        database::utils::integration_test::test_with_database,
+        repositories::webhook_repository::{WebhookRepository, WebhookRepositoryPostgres},
+    };
+    use chrono::{Duration, Utc};
+
+    use super::*;
+
+    async fn store_fake_webhooks_for_test(pool: &Pool<Postgres>) {
+        let repository = WebhookRepositoryPostgres { pool };
+
+        for (id, event_types) in [
+            ("all_events", vec![]),
+            ("deleted_only", vec!["UserDeleted".to_string()]),
+        ] {
+            repository
+                .store(WebhookRepositoryStoreParams {
+                    id: id.to_string(),
+                    url: format!("http://localhost:8080/{id}"),
+                    secret: "secret".to_string(),
+                    event_types,
+                })
+                .await
+                .unwrap();
+        }
+    }
+
+    fn fake_event(id: i64, event_type: &str) -> UserEvent {
+        UserEvent {
+            id,
+            event_type: event_type.to_string(),
+            user_id: "userFakeId".to_string(),
+            payload: r#"{"id":"userFakeId"}"#.to_string(),
+            created_at: Utc::now().naive_utc(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_enqueue_respects_event_filter() {
+        async fn repository_enqueue(pool: Pool<Postgres>) -> Result<Vec<WebhookDelivery>, AppError> {
+            store_fake_webhooks_for_test(&pool).await;
+
+            let repository = WebhookDeliveryRepositoryPostgres { pool: &pool };
+
+            assert_eq!(repository.enqueue(&fake_event(1, "UserRegistered")).await?, 1);
+            assert_eq!(repository.enqueue(&fake_event(2, "UserDeleted")).await?, 2);
+            assert_eq!(repository.enqueue(&fake_event(2, "UserDeleted")).await?, 0);
+
+            repository.fetch_due(10).await
+        }
+
+        let deliveries = test_with_database("test_enqueue_respects_event_filter", repository_enqueue)
+            .await
+            .unwrap();
+
+        assert_eq!(deliveries.len(), 3);
+        assert_eq!(deliveries[0].webhook_id, "all_events");
+        assert_eq!(deliveries[0].event_type, "UserRegistered");
+        assert_eq!(deliveries[0].url, "http://localhost:8080/all_events");
+    }
+
+    #[tokio::test]
+    async fn test_failed_deliveries_are_not_due() {
+        async fn repository_mark_failed(
+            pool: Pool<Postgres>,
+        ) -> Result<Vec<WebhookDelivery>, AppError> {
+            store_fake_webhooks_for_test(&pool).await;
+
+            let repository = WebhookDeliveryRepositoryPostgres { pool: &pool };
+            repository.enqueue(&fake_event(1, "UserDeleted")).await?;
+
+            let deliveries = repository.fetch_due(10).await?;
+            let retry_at = Utc::now().naive_utc() + Duration::minutes(1);
+            repository
+                .mark_failed(deliveries[0].id, 1, Some(retry_at), "HTTP 500".to_string())
+                .await?;
+            repository
+                .mark_failed(deliveries[1].id, 5, None, "HTTP 500".to_string())
+                .await?;
+
+            let dead = sqlx::query_scalar!(
+                "SELECT COUNT(*) FROM webhook_deliveries WHERE status = 'dead'"
+            )
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            assert_eq!(dead, Some(1));
+
+            repository.fetch_due(10).await
+        }
+
+        let deliveries = test_with_database("test_failed_deliveries_are_not_due", repository_mark_failed)
+            .await
+            .unwrap();
+
+        assert!(deliveries.is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+pub use crate::dtos::repositories::dtos_repository_webhook::*;
+use crate::{error::*, utils::adapters::sqlx_error_to_app_error::sqlx_error_to_app_error};
+use async_trait::async_trait;
+use mockall::automock;
+use sqlx::{Pool, Postgres};
+
+#[async_trait]
+#[automock]
+pub trait WebhookRepository: Sync + Send {
+    async fn store(
+        &self,
+        webhook: WebhookRepositoryStoreParams,
+    ) -> Result<WebhookRepositoryConsultReturn, AppError>;
+    async fn list(&self) -> Result<Vec<WebhookRepositoryConsultReturn>, AppError>;
+    async fn consult_by_id(&self, id: String) -> Result<WebhookRepositoryConsultReturn, AppError>;
+    async fn delete(&self, id: String) -> Result<String, AppError>;
+}
+
+pub struct WebhookRepositoryPostgres<'a> {
+    pub pool: &'a Pool<Postgres>,
+}
+
+#[async_trait]
+impl WebhookRepository for WebhookRepositoryPostgres<'_> {
+    async fn store(
+        &self,
+        webhook: WebhookRepositoryStoreParams,
+    ) -> Result<WebhookRepositoryConsultReturn, AppError> {
+        match sqlx::query_as!(
+            WebhookRepositoryConsultReturn,
+            "INSERT INTO webhooks (id, url, secret, event_types) VALUES ($1, $2, $3, $4)
+            RETURNING id, url, secret, event_types, created_at",
+            webhook.id,
+            webhook.url,
+            webhook.secret,
+            &webhook.event_types,
+        )
+        .fetch_one(self.pool)
+        .await
+        {
+            Ok(webhook) => Ok(webhook),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn list(&self) -> Result<Vec<WebhookRepositoryConsultReturn>, AppError> {
+        match sqlx::query_as!(
+            WebhookRepositoryConsultReturn,
+            "SELECT id, url, secret, event_types, created_at FROM webhooks ORDER BY created_at"
+        )
+        .fetch_all(self.pool)
+        .await
+        {
+            Ok(webhooks) => Ok(webhooks),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn consult_by_id(&self, id: String) -> Result<WebhookRepositoryConsultReturn, AppError> {
+        match sqlx::query_as!(
+            WebhookRepositoryConsultReturn,
+            "SELECT id, url, secret, event_types, created_at FROM webhooks WHERE id = $1",
+            id
+        )
+        .fetch_one(self.pool)
+        .await
+        {
+            Ok(webhook) => Ok(webhook),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+
+    async fn delete(&self, id: String) -> Result<String, AppError> {
+        match sqlx::query!("DELETE FROM webhooks WHERE id = $1", id)
+            .execute(self.pool)
+            .await
+        {
+            Ok(result) if result.rows_affected() == 0 => {
+                Err(AppError::new(Code::NotFound, "Webhook not found"))
+            }
+            Ok(_) => Ok(String::from("Webhook deleted successfully")),
+            Err(error) => Err(sqlx_error_to_app_error(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::utils::integration_test::test_with_database;
+
+    use super::*;
+
+    const FAKE_ID: &str = "webhookFakeId";
+    const FAKE_URL: &str = "http://localhost:8080/hooks";
+    const FAKE_SECRET: &str = "secret";
+
+    async fn store_fake_webhook_for_test(
+        pool: &Pool<Postgres>,
+    ) -> Result<WebhookRepositoryConsultReturn, AppError> {
+        let repository = WebhookRepositoryPostgres { pool };
+
+        repository
+            .store(WebhookRepositoryStoreParams {
+                id: FAKE_ID.to_string(),
+                url: FAKE_URL.to_string(),
+                secret: FAKE_SECRET.to_string(),
+                event_types: vec!["UserDeleted".to_string()],
+            })
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_store_and_list_webhooks() {
+        async fn repository_store_and_list(
+            pool: Pool<Postgres>,
+        ) -> Result<Vec<WebhookRepositoryConsultReturn>, AppError> {
+            store_fake_webhook_for_test(&pool).await?;
+
+            let repository = WebhookRepositoryPostgres { pool: &pool };
+
+            repository.list().await
+        }
+
+        let webhooks = test_with_database("test_store_and_list_webhooks", repository_store_and_list)
+            .await
+            .unwrap();
+
+        assert_eq!(webhooks.len(), 1);
+        assert_eq!(webhooks[0].id, FAKE_ID);
+        assert_eq!(webhooks[0].url, FAKE_URL);
+        assert_eq!(webhooks[0].event_types, vec!["UserDeleted".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_webhook() {
+        async fn repository_delete(pool: Pool<Postgres>) -> Result<String, AppError> {
+            store_fake_webhook_for_test(&pool).await?;
+
+            let repository = WebhookRepositoryPostgres { pool: &pool };
+
+            let response = repository.delete(FAKE_ID.to_string()).await?;
+
+            let error = repository.consult_by_id(FAKE_ID.to_string()).await.err().unwrap();
+            assert_eq!(error.code, Code::NotFound);
+
+            Ok(response)
+        }
+
+        let response = test_with_database("test_delete_webhook", repository_delete)
+            .await
+            .unwrap();
+
+        assert_eq!(response, "Webhook deleted successfully");
+    }
+
+    #[tokio::test]
+    async fn test_delete_nonexistent_webhook() {
+        async fn repository_delete_nonexistent(pool: Pool<Postgres>) -> Result<String, AppError> {
+            let repository = WebhookRepositoryPostgres { pool: &pool };
+
+            repository.delete(FAKE_ID.to_string()).await
+        }
+
+        let error = match test_with_database(
+            "test_delete_nonexistent_webhook",
+            repository_delete_nonexistent,
+        )
+        .await
+        {
+            Ok(_) => panic!("test should fail"),
+            Err(error) => error,
+        };
+
+        assert_eq!(error.code, Code::NotFound);
+    }
+}
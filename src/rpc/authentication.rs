@@ -1,3 +1,4 @@
+#[allow(clippy::module_inception)]
 pub mod authentication {
     tonic::include_proto!("authentication");
 }
@@ -9,33 +10,126 @@ use authentication::{
     ResActivateUser, ResCreateActivationCode, ResCreateRecoveryCode, ResLogin, ResRecoverUserData,
     ResRecoverUserPassword, ResRegister, ResUpdateEmail, ResUpdatePassword, ResUpdateUser,
 };
-use tonic::{Request, Response, Status};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{metadata::MetadataMap, Request, Response, Status};
 
+use crate::config::app_config::CodesBackend;
 use crate::controllers::authentication_controller::{AuthenticationController, UserController};
+use crate::controllers::federation_controller::{FederatedLoginController, FederationController};
+use crate::controllers::oauth_controller::{AuthorizationServerController, OAuthController};
+use crate::controllers::passwordless_controller::{
+    PasswordlessController, PasswordlessLoginController,
+};
+use crate::controllers::personal_access_token_controller::{
+    PersonalAccessTokenController, PersonalAccessTokenManagementController,
+};
+use crate::controllers::webauthn_controller::{
+    PasskeyAuthenticationController, WebauthnController,
+};
+use crate::controllers::webhook_controller::{WebhookAdministrationController, WebhookController};
+use crate::database::storage::Storage;
+use crate::dtos::controllers::dtos_controller_federation::ExternalLoginParams;
+use crate::dtos::controllers::dtos_controller_oauth::RegisterOAuthClientParams;
+use crate::dtos::controllers::dtos_controller_passwordless::{
+    LoginWithCodeParams, RequestLoginCodeParams,
+};
+use crate::dtos::controllers::dtos_controller_personal_access_token::CreatePersonalAccessTokenParams;
 use crate::dtos::controllers::dtos_controller_user::{
     LoginParams, RegisterParams, UpdateParams, UserControllerRecoverPasswordReq,
     UserControllerUpdatePasswordReq,
 };
+use crate::dtos::controllers::dtos_controller_webauthn::{
+    BeginPasskeyLoginParams, FinishPasskeyLoginParams, FinishPasskeyRegistrationParams,
+};
+use crate::dtos::controllers::dtos_controller_webhook::RegisterWebhookParams;
+use crate::error::{AppError, Code};
 use crate::models::authentication_model::UserModel;
-use crate::repositories::user_repository::UserRepositoryPostgres;
-use crate::repositories::users_code_repository::UsersCodeRepositoryRedis;
-use crate::security::jwt::{jwt_decode, jwt_encode};
+use crate::models::federation_model::FederationModel;
+use crate::models::oauth_model::OAuthModel;
+use crate::models::passwordless_model::PasswordlessModel;
+use crate::models::personal_access_token_model::PersonalAccessTokenModel;
+use crate::models::webauthn_model::WebauthnModel;
+use crate::models::webhook_model::WebhookModel;
+use crate::repositories::federated_identity_repository::FederatedIdentityRepositoryPostgres;
+use crate::repositories::oauth_authorization_code_repository::OAuthAuthorizationCodeRepositoryRedis;
+use crate::repositories::oauth_client_repository::OAuthClientRepositoryPostgres;
+use crate::repositories::oauth_consent_repository::OAuthConsentRepositoryPostgres;
+use crate::repositories::oauth_refresh_token_repository::OAuthRefreshTokenRepositoryPostgres;
+use crate::repositories::personal_access_token_repository::PersonalAccessTokenRepositoryPostgres;
+use crate::repositories::user_events_outbox_repository::UserEventsOutboxRepositoryPostgres;
+use crate::repositories::user_repository::{
+    UserRepositoryBackend, UserRepositoryInMemory, UserRepositoryPostgres, UserRepositorySqlite,
+};
+use crate::repositories::users_code_repository::{
+    UsersCodePurpose, UsersCodeRepositoryBackend, UsersCodeRepositoryInMemory,
+    UsersCodeRepositoryPostgres, UsersCodeRepositoryRedis, UsersCodeRepositorySqlite,
+};
+use crate::repositories::webauthn_challenge_repository::WebauthnChallengeRepositoryRedis;
+use crate::repositories::webauthn_credential_repository::WebauthnCredentialRepositoryPostgres;
+use crate::repositories::webhook_repository::WebhookRepositoryPostgres;
+use crate::security::admin::authorize_admin;
+use crate::security::events_subscriber::authorize_events_subscriber;
+use crate::security::jwt::{
+    jwt_config, jwt_decode, jwt_decode_access, jwt_encode, jwt_encode_access, jwt_encode_service,
+};
+use crate::security::oidc::id_token_encode;
+use crate::security::personal_access_token::{
+    is_personal_access_token, USER_DELETE_SCOPE, USER_READ_SCOPE, USER_WRITE_SCOPE,
+};
+use crate::services::events::user_events_watcher::{parse_event_types, UserEventsWatcher};
+use crate::services::federation::jwks_fetcher::JwksFetcherHttp;
+use crate::services::mail::mail_sender::{MailSenderBackend, MailSenderHttp, MailSenderLog};
 use crate::services::sanitizer::sanitize_authentication_input::SanitizeUser;
+use crate::services::webhooks::webhook_sender::WebhookSenderHttp;
 use crate::utils::adapters::app_error_to_grpc_error::app_error_to_grpc_error;
+use crate::utils::adapters::federation_controller_to_grpc_response::map_external_login_to_grpc_response;
+use crate::utils::adapters::oauth_controller_to_grpc_response::map_register_oauth_client_to_grpc_response;
+use crate::utils::adapters::passwordless_controller_to_grpc_response::map_request_login_code_to_grpc_response;
+use crate::utils::adapters::personal_access_token_controller_to_grpc_response::{
+    map_create_personal_access_token_to_grpc_response,
+    map_list_personal_access_tokens_to_grpc_response,
+    map_revoke_personal_access_token_to_grpc_response,
+};
 use crate::utils::adapters::user_controller_to_grpc_response::{
     map_create_recovery_code_to_grpc_response, map_delete_user_to_grpc_response,
     map_recovery_password_to_grpc_response, map_user_activate_to_grpc_response,
     map_user_auth_to_grpc_response, map_user_create_activation_code_to_grpc_response,
-    map_user_login_to_grpc_response, map_user_register_to_grpc_response,
-    map_user_update_email_to_grpc_response, map_user_update_password_to_grpc_response,
-    map_user_update_to_grpc_response,
+    map_user_info_to_grpc_response, map_user_login_to_grpc_response,
+    map_user_register_to_grpc_response, map_user_update_email_to_grpc_response,
+    map_user_update_password_to_grpc_response, map_user_update_to_grpc_response,
 };
+use crate::utils::adapters::user_event_to_grpc_message::map_user_event_to_grpc_message;
+use crate::utils::adapters::webauthn_controller_to_grpc_response::{
+    map_begin_passkey_login_to_grpc_response, map_begin_passkey_registration_to_grpc_response,
+    map_finish_passkey_registration_to_grpc_response,
+};
+use crate::utils::adapters::webhook_controller_to_grpc_response::{
+    map_delete_webhook_to_grpc_response, map_list_webhooks_to_grpc_response,
+    map_register_webhook_to_grpc_response, map_test_webhook_to_grpc_response,
+};
+use crate::utils::generate_code::secret_generator::secret_generator;
 use crate::utils::generate_code::six_number_code_generator::six_number_code_generator;
 use crate::utils::generate_id::uuidv4::new_uuidv4;
 use crate::utils::hash::password::{PASSWORD_HASHER, PASSWORD_VERIFY};
 use crate::AppState;
 
-use self::authentication::{ReqDeleteUser, ResDeleteUser};
+use self::authentication::{
+    ReqBeginPasskeyLogin, ReqBeginPasskeyRegistration, ReqCreatePersonalAccessToken, ReqDeleteUser,
+    ReqDeleteWebhook, ReqExternalLogin, ReqFinishPasskeyLogin, ReqFinishPasskeyRegistration,
+    ReqListPersonalAccessTokens, ReqListWebhooks, ReqLoginWithCode, ReqRegisterOAuthClient,
+    ReqRegisterWebhook, ReqRequestLoginCode, ReqRevokePersonalAccessToken, ReqTestWebhook,
+    ReqUserInfo, ReqWatchUserEvents, ResBeginPasskeyLogin, ResBeginPasskeyRegistration,
+    ResCreatePersonalAccessToken, ResDeleteUser, ResDeleteWebhook, ResExternalLogin,
+    ResFinishPasskeyRegistration, ResListPersonalAccessTokens, ResListWebhooks,
+    ResRegisterOAuthClient, ResRegisterWebhook, ResRequestLoginCode, ResRevokePersonalAccessToken,
+    ResTestWebhook, ResUserInfo, ResWatchUserEvents,
+};
+
+const WATCH_USER_EVENTS_POLL_INTERVAL: Duration = Duration::from_millis(500);
+const WEBHOOK_TEST_TIMEOUT: Duration = Duration::from_secs(10);
+const JWKS_FETCH_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct AuthenticationService {
     app_state: AppState,
@@ -45,33 +139,277 @@ impl AuthenticationService {
     pub fn new(app_state: AppState) -> Self {
         AuthenticationService { app_state }
     }
+
+    async fn user_token(
+        &self,
+        metadata: &MetadataMap,
+        scope: Option<&str>,
+    ) -> Result<String, Status> {
+        let token =
+            authorization(metadata, "Token JWT not found").map_err(app_error_to_grpc_error)?;
+
+        authorize_user_token(&self.app_state, token.to_string(), scope)
+            .await
+            .map_err(app_error_to_grpc_error)
+    }
+}
+
+/// Seconds the user tokens issued by the RPCs stay valid.
+fn token_lifetime() -> Result<u64, AppError> {
+    Ok(jwt_config()?.lifetime)
+}
+
+/// Accept a personal access token wherever a user token is, exchanging it for a user token
+/// when it holds `scope`. Without a scope the operation requires a login token.
+pub async fn authorize_user_token(
+    app_state: &AppState,
+    token: String,
+    scope: Option<&str>,
+) -> Result<String, AppError> {
+    if !is_personal_access_token(&token) {
+        return Ok(token);
+    }
+
+    app_state
+        .storage
+        .require_postgres("Personal access tokens")?;
+
+    match scope {
+        Some(scope) => {
+            create_personal_access_token_controller(app_state)
+                .authenticate(token, scope)
+                .await
+        }
+        None => Err(AppError::new(
+            Code::PermissionDenied,
+            "Personal access tokens are not accepted here, log in instead",
+        )),
+    }
+}
+
+pub fn create_user_repository(app_state: &AppState) -> UserRepositoryBackend<'_> {
+    match &app_state.storage {
+        Storage::Postgres => UserRepositoryBackend::Postgres(UserRepositoryPostgres {
+            pool: &app_state.db_pg_pool,
+        }),
+        Storage::Sqlite(pool) => UserRepositoryBackend::Sqlite(UserRepositorySqlite { pool }),
+        Storage::InMemory(store) => {
+            UserRepositoryBackend::InMemory(UserRepositoryInMemory { store })
+        }
+    }
+}
+
+pub fn create_users_code_repository(
+    app_state: &AppState,
+    purpose: UsersCodePurpose,
+) -> UsersCodeRepositoryBackend<'_> {
+    match (app_state.config.storage.codes, &app_state.storage) {
+        (_, Storage::InMemory(store)) => {
+            UsersCodeRepositoryBackend::InMemory(UsersCodeRepositoryInMemory { store, purpose })
+        }
+        (CodesBackend::Redis, _) => UsersCodeRepositoryBackend::Redis(UsersCodeRepositoryRedis {
+            redis: &app_state.redis,
+            purpose,
+        }),
+        (CodesBackend::Database, Storage::Postgres) => {
+            UsersCodeRepositoryBackend::Postgres(UsersCodeRepositoryPostgres {
+                pool: &app_state.db_pg_pool,
+                purpose,
+            })
+        }
+        (CodesBackend::Database, Storage::Sqlite(pool)) => {
+            UsersCodeRepositoryBackend::Sqlite(UsersCodeRepositorySqlite { pool, purpose })
+        }
+    }
 }
 
 pub type DefaultAuthenticationModel<'a> =
-    UserModel<UserRepositoryPostgres<'a>, UsersCodeRepositoryRedis<'a>>;
-pub fn create_user_model(app_state: &AppState) -> DefaultAuthenticationModel {
-    let pool = &app_state.db_pg_pool;
-    let redis_client = &app_state.redis_client;
+    UserModel<UserRepositoryBackend<'a>, UsersCodeRepositoryBackend<'a>, MailSenderBackend<'a>>;
+pub fn create_user_model(app_state: &AppState) -> DefaultAuthenticationModel<'_> {
     UserModel {
-        user_repository: UserRepositoryPostgres { pool },
-        user_code_repository: UsersCodeRepositoryRedis {
-            client: redis_client,
-        },
+        user_repository: create_user_repository(app_state),
+        user_code_repository: create_users_code_repository(app_state, UsersCodePurpose::Account),
+        mail_sender: create_mail_sender(app_state),
         password_hasher: PASSWORD_HASHER,
         password_verify: PASSWORD_VERIFY,
         new_id: new_uuidv4,
         generate_code: six_number_code_generator,
+        code_ttl_minutes: app_state.config.codes.account_code_ttl_minutes,
     }
 }
 
 type DefaultAuthenticationController<'a> =
     UserController<DefaultAuthenticationModel<'a>, SanitizeUser>;
-pub fn create_user_controller(app_state: &AppState) -> DefaultAuthenticationController {
+pub fn create_user_controller(app_state: &AppState) -> DefaultAuthenticationController<'_> {
     UserController {
         model: create_user_model(app_state),
         sanitize_user: SanitizeUser,
         jwt_encode,
         jwt_decode,
+        jwt_decode_access,
+    }
+}
+
+pub fn create_mail_sender(app_state: &AppState) -> MailSenderBackend<'_> {
+    let mail = &app_state.config.mail;
+    match &mail.api_url {
+        Some(api_url) => MailSenderBackend::Http(MailSenderHttp::new(
+            mail,
+            api_url,
+            Duration::from_secs(mail.timeout_seconds),
+        )),
+        None => MailSenderBackend::Log(MailSenderLog),
+    }
+}
+
+pub type DefaultPasswordlessController<'a> = PasswordlessController<
+    PasswordlessModel<
+        UserRepositoryBackend<'a>,
+        UsersCodeRepositoryBackend<'a>,
+        MailSenderBackend<'a>,
+    >,
+    SanitizeUser,
+>;
+pub fn create_passwordless_controller(app_state: &AppState) -> DefaultPasswordlessController<'_> {
+    PasswordlessController {
+        model: PasswordlessModel {
+            user_repository: create_user_repository(app_state),
+            login_code_repository: create_users_code_repository(app_state, UsersCodePurpose::Login),
+            mail_sender: create_mail_sender(app_state),
+            generate_code: six_number_code_generator,
+            code_ttl_minutes: app_state.config.codes.login_code_ttl_minutes,
+        },
+        sanitize_user: SanitizeUser,
+        jwt_encode,
+    }
+}
+
+pub type DefaultWebauthnController<'a> = WebauthnController<
+    WebauthnModel<
+        UserRepositoryPostgres<'a>,
+        WebauthnCredentialRepositoryPostgres<'a>,
+        WebauthnChallengeRepositoryRedis<'a>,
+    >,
+    SanitizeUser,
+>;
+pub fn create_webauthn_controller(app_state: &AppState) -> DefaultWebauthnController<'_> {
+    let pool = &app_state.db_pg_pool;
+    WebauthnController {
+        model: WebauthnModel {
+            user_repository: UserRepositoryPostgres { pool },
+            credential_repository: WebauthnCredentialRepositoryPostgres { pool },
+            challenge_repository: WebauthnChallengeRepositoryRedis {
+                redis: &app_state.redis,
+            },
+            relying_party: app_state.config.webauthn.clone(),
+            password_verify: PASSWORD_VERIFY,
+            generate_challenge: secret_generator,
+        },
+        sanitize_user: SanitizeUser,
+        jwt_encode,
+        jwt_decode,
+    }
+}
+
+pub type DefaultPersonalAccessTokenController<'a> = PersonalAccessTokenController<
+    PersonalAccessTokenModel<UserRepositoryPostgres<'a>, PersonalAccessTokenRepositoryPostgres<'a>>,
+>;
+pub fn create_personal_access_token_controller(
+    app_state: &AppState,
+) -> DefaultPersonalAccessTokenController<'_> {
+    let pool = &app_state.db_pg_pool;
+    PersonalAccessTokenController {
+        model: PersonalAccessTokenModel {
+            user_repository: UserRepositoryPostgres { pool },
+            token_repository: PersonalAccessTokenRepositoryPostgres { pool },
+            new_id: new_uuidv4,
+            generate_secret: secret_generator,
+        },
+        jwt_encode,
+        jwt_decode,
+    }
+}
+
+type DefaultWebhookController<'a> =
+    WebhookController<WebhookModel<WebhookRepositoryPostgres<'a>, WebhookSenderHttp>>;
+pub fn create_webhook_controller(app_state: &AppState) -> DefaultWebhookController<'_> {
+    WebhookController {
+        model: WebhookModel {
+            webhook_repository: WebhookRepositoryPostgres {
+                pool: &app_state.db_pg_pool,
+            },
+            sender: WebhookSenderHttp::new(WEBHOOK_TEST_TIMEOUT),
+            new_id: new_uuidv4,
+            generate_secret: secret_generator,
+        },
+        authorize_admin,
+        admin_token: app_state.config.tokens.admin.clone(),
+    }
+}
+
+pub type DefaultOAuthModel<'a> = OAuthModel<
+    DefaultAuthenticationModel<'a>,
+    OAuthClientRepositoryPostgres<'a>,
+    OAuthConsentRepositoryPostgres<'a>,
+    OAuthAuthorizationCodeRepositoryRedis<'a>,
+    OAuthRefreshTokenRepositoryPostgres<'a>,
+>;
+pub type DefaultOAuthController<'a> = OAuthController<DefaultOAuthModel<'a>>;
+pub fn create_oauth_controller(app_state: &AppState) -> DefaultOAuthController<'_> {
+    let pool = &app_state.db_pg_pool;
+    OAuthController {
+        model: OAuthModel {
+            user_model: create_user_model(app_state),
+            client_repository: OAuthClientRepositoryPostgres { pool },
+            consent_repository: OAuthConsentRepositoryPostgres { pool },
+            code_repository: OAuthAuthorizationCodeRepositoryRedis {
+                redis: &app_state.redis,
+            },
+            refresh_token_repository: OAuthRefreshTokenRepositoryPostgres { pool },
+            password_hasher: PASSWORD_HASHER,
+            password_verify: PASSWORD_VERIFY,
+            new_id: new_uuidv4,
+            generate_secret: secret_generator,
+        },
+        jwt_encode_access,
+        jwt_encode_service,
+        id_token_encode,
+        oidc: app_state.config.oidc.clone(),
+        authorize_admin,
+        admin_token: app_state.config.tokens.admin.clone(),
+    }
+}
+
+pub type DefaultFederationController<'a> = FederationController<
+    FederationModel<
+        UserRepositoryPostgres<'a>,
+        FederatedIdentityRepositoryPostgres<'a>,
+        JwksFetcherHttp,
+    >,
+>;
+pub fn create_federation_controller(app_state: &AppState) -> DefaultFederationController<'_> {
+    let pool = &app_state.db_pg_pool;
+    FederationController {
+        model: FederationModel {
+            user_repository: UserRepositoryPostgres { pool },
+            identity_repository: FederatedIdentityRepositoryPostgres { pool },
+            jwks_fetcher: JwksFetcherHttp::new(JWKS_FETCH_TIMEOUT),
+            providers: app_state.config.federation.providers.clone(),
+            password_hasher: PASSWORD_HASHER,
+            new_id: new_uuidv4,
+            generate_secret: secret_generator,
+        },
+        jwt_encode,
+    }
+}
+
+/// The `authorization` metadata, `missing` being the message when none was sent.
+fn authorization<'a>(metadata: &'a MetadataMap, missing: &str) -> Result<&'a str, AppError> {
+    match metadata.get("authorization") {
+        Some(token) => token
+            .to_str()
+            .map_err(|_| AppError::new(Code::Unauthenticated, "Authorization is not valid ASCII")),
+        None => Err(AppError::new(Code::Unauthenticated, missing)),
     }
 }
 
@@ -98,7 +436,10 @@ impl Authentication for AuthenticationService {
             })
             .await
         {
-            Ok(response) => Ok(map_user_register_to_grpc_response(response)),
+            Ok(response) => Ok(map_user_register_to_grpc_response(
+                response,
+                token_lifetime().map_err(app_error_to_grpc_error)?,
+            )),
             Err(error) => Err(app_error_to_grpc_error(error)),
         }
     }
@@ -110,7 +451,10 @@ impl Authentication for AuthenticationService {
         let controller = create_user_controller(app_state);
 
         match controller.login(LoginParams { username, password }).await {
-            Ok(response) => Ok(map_user_login_to_grpc_response(response)),
+            Ok(response) => Ok(map_user_login_to_grpc_response(
+                response,
+                token_lifetime().map_err(app_error_to_grpc_error)?,
+            )),
             Err(error) => Err(app_error_to_grpc_error(error)),
         }
     }
@@ -120,15 +464,13 @@ impl Authentication for AuthenticationService {
         request: Request<ReqRecoverUserData>,
     ) -> Result<Response<ResRecoverUserData>, Status> {
         let app_state = &self.app_state;
-        let metadata = request.metadata();
-        let token = match metadata.get("authorization") {
-            Some(t) => t.to_str().unwrap(),
-            None => return Err(Status::unauthenticated("Token JWT not found")),
-        };
+        let token = self
+            .user_token(request.metadata(), Some(USER_READ_SCOPE))
+            .await?;
 
         let controller = create_user_controller(app_state);
 
-        match controller.recover_user_data(token.to_string()).await {
+        match controller.recover_user_data(token).await {
             Ok(response) => Ok(map_user_auth_to_grpc_response(response)),
             Err(error) => Err(app_error_to_grpc_error(error)),
         }
@@ -139,18 +481,16 @@ impl Authentication for AuthenticationService {
         request: Request<ReqUpdateUser>,
     ) -> Result<Response<ResUpdateUser>, Status> {
         let app_state = &self.app_state;
-        let metadata = request.metadata().to_owned();
-        let token = match metadata.get("authorization") {
-            Some(t) => t.to_str().unwrap(),
-            None => return Err(Status::unauthenticated("Token JWT not found")),
-        };
+        let token = self
+            .user_token(request.metadata(), Some(USER_WRITE_SCOPE))
+            .await?;
 
         let ReqUpdateUser { username, email } = request.into_inner();
 
         let controller = create_user_controller(app_state);
 
         match controller
-            .update(token.to_string(), UpdateParams { username, email })
+            .update(token, UpdateParams { username, email })
             .await
         {
             Ok(response) => Ok(map_user_update_to_grpc_response(response)),
@@ -163,17 +503,15 @@ impl Authentication for AuthenticationService {
         request: Request<ReqUpdateEmail>,
     ) -> Result<Response<ResUpdateEmail>, Status> {
         let app_state = &self.app_state;
-        let metadata = request.metadata().to_owned();
-        let token = match metadata.get("authorization") {
-            Some(t) => t.to_str().unwrap(),
-            None => return Err(Status::unauthenticated("Token JWT not found")),
-        };
+        let token = self
+            .user_token(request.metadata(), Some(USER_WRITE_SCOPE))
+            .await?;
 
         let ReqUpdateEmail { email } = request.into_inner();
 
         let controller = create_user_controller(app_state);
 
-        match controller.update_email(token.to_string(), email).await {
+        match controller.update_email(token, email).await {
             Ok(response) => Ok(map_user_update_email_to_grpc_response(response)),
             Err(error) => Err(app_error_to_grpc_error(error)),
         }
@@ -184,11 +522,7 @@ impl Authentication for AuthenticationService {
         request: Request<ReqUpdatePassword>,
     ) -> Result<Response<ResUpdatePassword>, Status> {
         let app_state = &self.app_state;
-        let metadata = request.metadata().to_owned();
-        let token = match metadata.get("authorization") {
-            Some(t) => t.to_str().unwrap(),
-            None => return Err(Status::unauthenticated("Token JWT not found")),
-        };
+        let token = self.user_token(request.metadata(), None).await?;
 
         let ReqUpdatePassword {
             new_password,
@@ -199,7 +533,7 @@ impl Authentication for AuthenticationService {
 
         match controller
             .update_password(
-                token.to_string(),
+                token,
                 UserControllerUpdatePasswordReq {
                     new_password,
                     old_password,
@@ -217,15 +551,13 @@ impl Authentication for AuthenticationService {
         request: Request<ReqCreateActivationCode>,
     ) -> Result<Response<ResCreateActivationCode>, Status> {
         let app_state = &self.app_state;
-        let metadata = request.metadata().to_owned();
-        let token = match metadata.get("authorization") {
-            Some(t) => t.to_str().unwrap(),
-            None => return Err(Status::unauthenticated("Token JWT not found")),
-        };
+        let token = self
+            .user_token(request.metadata(), Some(USER_WRITE_SCOPE))
+            .await?;
 
         let controller = create_user_controller(app_state);
 
-        match controller.create_activation_code(token.to_string()).await {
+        match controller.create_activation_code(token).await {
             Ok(response) => Ok(map_user_create_activation_code_to_grpc_response(response)),
             Err(error) => Err(app_error_to_grpc_error(error)),
         }
@@ -236,16 +568,14 @@ impl Authentication for AuthenticationService {
         request: Request<ReqActivateUser>,
     ) -> Result<Response<ResActivateUser>, Status> {
         let app_state = &self.app_state;
-        let metadata = request.metadata().to_owned();
-        let token = match metadata.get("authorization") {
-            Some(t) => t.to_str().unwrap(),
-            None => return Err(Status::unauthenticated("Token JWT not found")),
-        };
+        let token = self
+            .user_token(request.metadata(), Some(USER_WRITE_SCOPE))
+            .await?;
         let ReqActivateUser { code_key } = request.into_inner();
 
         let controller = create_user_controller(app_state);
 
-        match controller.activate_user(token.to_string(), code_key).await {
+        match controller.activate_user(token, code_key).await {
             Ok(response) => Ok(map_user_activate_to_grpc_response(response)),
             Err(error) => Err(app_error_to_grpc_error(error)),
         }
@@ -297,17 +627,534 @@ impl Authentication for AuthenticationService {
         request: Request<ReqDeleteUser>,
     ) -> Result<Response<ResDeleteUser>, Status> {
         let app_state = &self.app_state;
-        let metadata = request.metadata().to_owned();
-        let token = match metadata.get("authorization") {
-            Some(t) => t.to_str().unwrap(),
-            None => return Err(Status::unauthenticated("Token JWT not found")),
-        };
+        let token = self
+            .user_token(request.metadata(), Some(USER_DELETE_SCOPE))
+            .await?;
 
         let controller = create_user_controller(app_state);
 
-        match controller.delete_user(token.to_string()).await {
+        match controller.delete_user(token).await {
             Ok(response) => Ok(map_delete_user_to_grpc_response(response)),
             Err(error) => Err(app_error_to_grpc_error(error)),
         }
     }
+
+    type WatchUserEventsStream = ReceiverStream<Result<ResWatchUserEvents, Status>>;
+
+    async fn watch_user_events(
+        &self,
+        request: Request<ReqWatchUserEvents>,
+    ) -> Result<Response<Self::WatchUserEventsStream>, Status> {
+        self.app_state
+            .storage
+            .require_postgres("User events")
+            .map_err(app_error_to_grpc_error)?;
+
+        let metadata = request.metadata().to_owned();
+        let token = authorization(&metadata, "Subscriber token not found")
+            .map_err(app_error_to_grpc_error)?;
+
+        authorize_events_subscriber(
+            token,
+            self.app_state.config.tokens.events_subscriber.as_deref(),
+        )
+        .map_err(app_error_to_grpc_error)?;
+
+        let ReqWatchUserEvents {
+            from_offset,
+            event_types,
+        } = request.into_inner();
+        let event_types = parse_event_types(event_types).map_err(app_error_to_grpc_error)?;
+
+        let pool = self.app_state.db_pg_pool.clone();
+        let (sender, receiver) = mpsc::channel(100);
+
+        tokio::spawn(async move {
+            let watcher = UserEventsWatcher {
+                outbox_repository: UserEventsOutboxRepositoryPostgres { pool: &pool },
+                event_types,
+                batch_size: 100,
+            };
+            let mut offset = from_offset;
+
+            loop {
+                let (events, next_offset) = match watcher.poll(offset).await {
+                    Ok(result) => result,
+                    Err(error) => {
+                        let _ = sender.send(Err(app_error_to_grpc_error(error))).await;
+                        return;
+                    }
+                };
+
+                for event in events {
+                    if sender
+                        .send(Ok(map_user_event_to_grpc_message(event)))
+                        .await
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+
+                if next_offset == offset {
+                    tokio::select! {
+                        _ = tokio::time::sleep(WATCH_USER_EVENTS_POLL_INTERVAL) => (),
+                        _ = sender.closed() => return,
+                    }
+                }
+                offset = next_offset;
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+
+    async fn register_webhook(
+        &self,
+        request: Request<ReqRegisterWebhook>,
+    ) -> Result<Response<ResRegisterWebhook>, Status> {
+        self.app_state
+            .storage
+            .require_postgres("Webhooks")
+            .map_err(app_error_to_grpc_error)?;
+
+        let app_state = &self.app_state;
+        let metadata = request.metadata().to_owned();
+        let token = authorization(&metadata, "Administrator token not found")
+            .map_err(app_error_to_grpc_error)?;
+        let ReqRegisterWebhook {
+            url,
+            secret,
+            event_types,
+        } = request.into_inner();
+
+        let controller = create_webhook_controller(app_state);
+
+        match controller
+            .register_webhook(
+                token.to_string(),
+                RegisterWebhookParams {
+                    url,
+                    secret,
+                    event_types,
+                },
+            )
+            .await
+        {
+            Ok(response) => Ok(map_register_webhook_to_grpc_response(response)),
+            Err(error) => Err(app_error_to_grpc_error(error)),
+        }
+    }
+
+    async fn list_webhooks(
+        &self,
+        request: Request<ReqListWebhooks>,
+    ) -> Result<Response<ResListWebhooks>, Status> {
+        self.app_state
+            .storage
+            .require_postgres("Webhooks")
+            .map_err(app_error_to_grpc_error)?;
+
+        let app_state = &self.app_state;
+        let metadata = request.metadata();
+        let token = authorization(metadata, "Administrator token not found")
+            .map_err(app_error_to_grpc_error)?;
+
+        let controller = create_webhook_controller(app_state);
+
+        match controller.list_webhooks(token.to_string()).await {
+            Ok(response) => Ok(map_list_webhooks_to_grpc_response(response)),
+            Err(error) => Err(app_error_to_grpc_error(error)),
+        }
+    }
+
+    async fn test_webhook(
+        &self,
+        request: Request<ReqTestWebhook>,
+    ) -> Result<Response<ResTestWebhook>, Status> {
+        self.app_state
+            .storage
+            .require_postgres("Webhooks")
+            .map_err(app_error_to_grpc_error)?;
+
+        let app_state = &self.app_state;
+        let metadata = request.metadata().to_owned();
+        let token = authorization(&metadata, "Administrator token not found")
+            .map_err(app_error_to_grpc_error)?;
+        let ReqTestWebhook { id } = request.into_inner();
+
+        let controller = create_webhook_controller(app_state);
+
+        match controller.test_webhook(token.to_string(), id).await {
+            Ok(response) => Ok(map_test_webhook_to_grpc_response(response)),
+            Err(error) => Err(app_error_to_grpc_error(error)),
+        }
+    }
+
+    async fn delete_webhook(
+        &self,
+        request: Request<ReqDeleteWebhook>,
+    ) -> Result<Response<ResDeleteWebhook>, Status> {
+        self.app_state
+            .storage
+            .require_postgres("Webhooks")
+            .map_err(app_error_to_grpc_error)?;
+
+        let app_state = &self.app_state;
+        let metadata = request.metadata().to_owned();
+        let token = authorization(&metadata, "Administrator token not found")
+            .map_err(app_error_to_grpc_error)?;
+        let ReqDeleteWebhook { id } = request.into_inner();
+
+        let controller = create_webhook_controller(app_state);
+
+        match controller.delete_webhook(token.to_string(), id).await {
+            Ok(response) => Ok(map_delete_webhook_to_grpc_response(response)),
+            Err(error) => Err(app_error_to_grpc_error(error)),
+        }
+    }
+
+    async fn register_o_auth_client(
+        &self,
+        request: Request<ReqRegisterOAuthClient>,
+    ) -> Result<Response<ResRegisterOAuthClient>, Status> {
+        self.app_state
+            .storage
+            .require_postgres("OAuth clients")
+            .map_err(app_error_to_grpc_error)?;
+
+        let app_state = &self.app_state;
+        let metadata = request.metadata().to_owned();
+        let token = authorization(&metadata, "Administrator token not found")
+            .map_err(app_error_to_grpc_error)?;
+        let ReqRegisterOAuthClient {
+            name,
+            redirect_uris,
+            scopes,
+            confidential,
+            service,
+        } = request.into_inner();
+
+        let controller = create_oauth_controller(app_state);
+
+        match controller
+            .register_client(
+                token.to_string(),
+                RegisterOAuthClientParams {
+                    name,
+                    redirect_uris,
+                    scopes,
+                    confidential,
+                    service,
+                },
+            )
+            .await
+        {
+            Ok(response) => Ok(map_register_oauth_client_to_grpc_response(response)),
+            Err(error) => Err(app_error_to_grpc_error(error)),
+        }
+    }
+
+    async fn user_info(
+        &self,
+        request: Request<ReqUserInfo>,
+    ) -> Result<Response<ResUserInfo>, Status> {
+        let app_state = &self.app_state;
+        let token = self
+            .user_token(request.metadata(), Some(USER_READ_SCOPE))
+            .await?;
+
+        let controller = create_user_controller(app_state);
+
+        match controller.user_info(token).await {
+            Ok(response) => Ok(map_user_info_to_grpc_response(response)),
+            Err(error) => Err(app_error_to_grpc_error(error)),
+        }
+    }
+
+    async fn external_login(
+        &self,
+        request: Request<ReqExternalLogin>,
+    ) -> Result<Response<ResExternalLogin>, Status> {
+        self.app_state
+            .storage
+            .require_postgres("External logins")
+            .map_err(app_error_to_grpc_error)?;
+
+        let ReqExternalLogin { id_token } = request.into_inner();
+        let app_state = &self.app_state;
+
+        let controller = create_federation_controller(app_state);
+
+        match controller
+            .external_login(ExternalLoginParams { id_token })
+            .await
+        {
+            Ok(response) => Ok(map_external_login_to_grpc_response(response)),
+            Err(error) => Err(app_error_to_grpc_error(error)),
+        }
+    }
+
+    async fn request_login_code(
+        &self,
+        request: Request<ReqRequestLoginCode>,
+    ) -> Result<Response<ResRequestLoginCode>, Status> {
+        let ReqRequestLoginCode { email } = request.into_inner();
+        let app_state = &self.app_state;
+
+        let controller = create_passwordless_controller(app_state);
+
+        match controller
+            .request_login_code(RequestLoginCodeParams { email })
+            .await
+        {
+            Ok(response) => Ok(map_request_login_code_to_grpc_response(response)),
+            Err(error) => Err(app_error_to_grpc_error(error)),
+        }
+    }
+
+    async fn login_with_code(
+        &self,
+        request: Request<ReqLoginWithCode>,
+    ) -> Result<Response<ResLogin>, Status> {
+        let ReqLoginWithCode { email, code } = request.into_inner();
+        let app_state = &self.app_state;
+
+        let controller = create_passwordless_controller(app_state);
+
+        match controller
+            .login_with_code(LoginWithCodeParams { email, code })
+            .await
+        {
+            Ok(response) => Ok(map_user_login_to_grpc_response(
+                response,
+                token_lifetime().map_err(app_error_to_grpc_error)?,
+            )),
+            Err(error) => Err(app_error_to_grpc_error(error)),
+        }
+    }
+
+    async fn begin_passkey_registration(
+        &self,
+        request: Request<ReqBeginPasskeyRegistration>,
+    ) -> Result<Response<ResBeginPasskeyRegistration>, Status> {
+        self.app_state
+            .storage
+            .require_postgres("Passkeys")
+            .map_err(app_error_to_grpc_error)?;
+
+        let app_state = &self.app_state;
+        let token = self.user_token(request.metadata(), None).await?;
+
+        let controller = create_webauthn_controller(app_state);
+
+        match controller.begin_registration(token).await {
+            Ok(response) => Ok(map_begin_passkey_registration_to_grpc_response(response)),
+            Err(error) => Err(app_error_to_grpc_error(error)),
+        }
+    }
+
+    async fn finish_passkey_registration(
+        &self,
+        request: Request<ReqFinishPasskeyRegistration>,
+    ) -> Result<Response<ResFinishPasskeyRegistration>, Status> {
+        self.app_state
+            .storage
+            .require_postgres("Passkeys")
+            .map_err(app_error_to_grpc_error)?;
+
+        let app_state = &self.app_state;
+        let token = self.user_token(request.metadata(), None).await?;
+        let ReqFinishPasskeyRegistration {
+            name,
+            client_data_json,
+            attestation_object,
+        } = request.into_inner();
+
+        let controller = create_webauthn_controller(app_state);
+
+        match controller
+            .finish_registration(
+                token,
+                FinishPasskeyRegistrationParams {
+                    name,
+                    client_data_json,
+                    attestation_object,
+                },
+            )
+            .await
+        {
+            Ok(response) => Ok(map_finish_passkey_registration_to_grpc_response(response)),
+            Err(error) => Err(app_error_to_grpc_error(error)),
+        }
+    }
+
+    async fn begin_passkey_login(
+        &self,
+        request: Request<ReqBeginPasskeyLogin>,
+    ) -> Result<Response<ResBeginPasskeyLogin>, Status> {
+        self.app_state
+            .storage
+            .require_postgres("Passkeys")
+            .map_err(app_error_to_grpc_error)?;
+
+        let ReqBeginPasskeyLogin {
+            username,
+            second_factor,
+        } = request.into_inner();
+        let app_state = &self.app_state;
+
+        let controller = create_webauthn_controller(app_state);
+
+        match controller
+            .begin_login(BeginPasskeyLoginParams {
+                username,
+                second_factor,
+            })
+            .await
+        {
+            Ok(response) => Ok(map_begin_passkey_login_to_grpc_response(response)),
+            Err(error) => Err(app_error_to_grpc_error(error)),
+        }
+    }
+
+    async fn finish_passkey_login(
+        &self,
+        request: Request<ReqFinishPasskeyLogin>,
+    ) -> Result<Response<ResLogin>, Status> {
+        self.app_state
+            .storage
+            .require_postgres("Passkeys")
+            .map_err(app_error_to_grpc_error)?;
+
+        let ReqFinishPasskeyLogin {
+            credential_id,
+            client_data_json,
+            authenticator_data,
+            signature,
+            password,
+        } = request.into_inner();
+        let app_state = &self.app_state;
+
+        let controller = create_webauthn_controller(app_state);
+
+        match controller
+            .finish_login(FinishPasskeyLoginParams {
+                credential_id,
+                client_data_json,
+                authenticator_data,
+                signature,
+                password,
+            })
+            .await
+        {
+            Ok(response) => Ok(map_user_login_to_grpc_response(
+                response,
+                token_lifetime().map_err(app_error_to_grpc_error)?,
+            )),
+            Err(error) => Err(app_error_to_grpc_error(error)),
+        }
+    }
+
+    async fn create_personal_access_token(
+        &self,
+        request: Request<ReqCreatePersonalAccessToken>,
+    ) -> Result<Response<ResCreatePersonalAccessToken>, Status> {
+        self.app_state
+            .storage
+            .require_postgres("Personal access tokens")
+            .map_err(app_error_to_grpc_error)?;
+
+        let app_state = &self.app_state;
+        let token = self.user_token(request.metadata(), None).await?;
+        let ReqCreatePersonalAccessToken {
+            name,
+            scopes,
+            expires_at,
+        } = request.into_inner();
+
+        let controller = create_personal_access_token_controller(app_state);
+
+        match controller
+            .create_token(
+                token,
+                CreatePersonalAccessTokenParams {
+                    name,
+                    scopes,
+                    expires_at,
+                },
+            )
+            .await
+        {
+            Ok(response) => Ok(map_create_personal_access_token_to_grpc_response(response)),
+            Err(error) => Err(app_error_to_grpc_error(error)),
+        }
+    }
+
+    async fn list_personal_access_tokens(
+        &self,
+        request: Request<ReqListPersonalAccessTokens>,
+    ) -> Result<Response<ResListPersonalAccessTokens>, Status> {
+        self.app_state
+            .storage
+            .require_postgres("Personal access tokens")
+            .map_err(app_error_to_grpc_error)?;
+
+        let app_state = &self.app_state;
+        let token = self.user_token(request.metadata(), None).await?;
+
+        let controller = create_personal_access_token_controller(app_state);
+
+        match controller.list_tokens(token).await {
+            Ok(response) => Ok(map_list_personal_access_tokens_to_grpc_response(response)),
+            Err(error) => Err(app_error_to_grpc_error(error)),
+        }
+    }
+
+    async fn revoke_personal_access_token(
+        &self,
+        request: Request<ReqRevokePersonalAccessToken>,
+    ) -> Result<Response<ResRevokePersonalAccessToken>, Status> {
+        self.app_state
+            .storage
+            .require_postgres("Personal access tokens")
+            .map_err(app_error_to_grpc_error)?;
+
+        let app_state = &self.app_state;
+        let token = self.user_token(request.metadata(), None).await?;
+        let ReqRevokePersonalAccessToken { id } = request.into_inner();
+
+        let controller = create_personal_access_token_controller(app_state);
+
+        match controller.revoke_token(token, id).await {
+            Ok(response) => Ok(map_revoke_personal_access_token_to_grpc_response(response)),
+            Err(error) => Err(app_error_to_grpc_error(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tonic::metadata::MetadataValue;
+
+    use super::*;
+
+    #[test]
+    fn test_authorization_refuses_non_ascii_token() {
+        let mut metadata = MetadataMap::new();
+        metadata.insert(
+            "authorization",
+            MetadataValue::try_from("tökén".as_bytes()).unwrap(),
+        );
+
+        let error = authorization(&metadata, "Token JWT not found").unwrap_err();
+        assert_eq!(error.code, Code::Unauthenticated);
+    }
+
+    #[test]
+    fn test_authorization_requires_a_token() {
+        let error = authorization(&MetadataMap::new(), "Token JWT not found").unwrap_err();
+        assert_eq!(error.code, Code::Unauthenticated);
+        assert_eq!(error.message, "Token JWT not found");
+    }
 }
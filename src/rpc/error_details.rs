@@ -0,0 +1,3 @@
+pub mod google_rpc {
+    tonic::include_proto!("google.rpc");
+}
@@ -0,0 +1,208 @@
+use sqlx::{Pool, Postgres};
+use std::time::Duration;
+use tonic_health::{server::HealthReporter, ServingStatus};
+
+use crate::{
+    config::app_config::CodesBackend,
+    database::{redis_pool::RedisPool, storage::Storage},
+    utils::adapters::redis_error_to_app_error::redis_error_to_app_error,
+    AppState,
+};
+
+/// Longest wait for Postgres or Redis to answer before reporting NOT_SERVING.
+pub const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The whole server, named by the empty string, and the authentication service.
+const HEALTH_SERVICE_NAMES: [&str; 2] = ["", "authentication.Authentication"];
+
+async fn postgres_is_reachable(pool: &Pool<Postgres>) -> bool {
+    let ping = sqlx::query("SELECT 1").execute(pool);
+    matches!(
+        tokio::time::timeout(HEALTH_CHECK_TIMEOUT, ping).await,
+        Ok(Ok(_))
+    )
+}
+
+/// Goes through the shared connection, so an open circuit breaker reports NOT_SERVING.
+async fn redis_is_reachable(redis: &RedisPool) -> bool {
+    let ping = async {
+        let mut connection = redis.connection().await?;
+        redis::cmd("PING")
+            .query_async::<_, String>(&mut connection)
+            .await
+            .map_err(redis_error_to_app_error)
+    };
+    matches!(
+        tokio::time::timeout(HEALTH_CHECK_TIMEOUT, ping).await,
+        Ok(Ok(_))
+    )
+}
+
+/// NOT_SERVING while Postgres or Redis is unreachable. Postgres is only checked when it
+/// keeps the users and Redis when it keeps the codes, neither with in-memory storage.
+pub async fn serving_status(app_state: &AppState) -> ServingStatus {
+    let redis_keeps_codes = app_state.config.storage.codes == CodesBackend::Redis;
+    let (postgres, redis) = match &app_state.storage {
+        Storage::Postgres => {
+            tokio::join!(
+                postgres_is_reachable(&app_state.db_pg_pool),
+                redis_is_reachable(&app_state.redis)
+            )
+        }
+        Storage::Sqlite(_) if redis_keeps_codes => {
+            (true, redis_is_reachable(&app_state.redis).await)
+        }
+        Storage::Sqlite(_) | Storage::InMemory(_) => (true, true),
+    };
+
+    if postgres && redis {
+        ServingStatus::Serving
+    } else {
+        ServingStatus::NotServing
+    }
+}
+
+/// Keep the status `grpc.health.v1.Health` serves for the server and the authentication
+/// service up to date, checking the storage again every few seconds.
+pub async fn report_health(app_state: AppState, mut reporter: HealthReporter) {
+    loop {
+        let status = serving_status(&app_state).await;
+        for service in HEALTH_SERVICE_NAMES {
+            reporter.set_service_status(service, status).await;
+        }
+
+        tokio::time::sleep(HEALTH_CHECK_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::app_config::Config,
+        database::{in_memory::InMemoryStore, redis_pool::RedisPoolOptions},
+    };
+    use sqlx::{postgres::PgPoolOptions, sqlite::SqlitePoolOptions};
+    use std::sync::Arc;
+    use tokio::net::TcpListener;
+    use tokio_stream::wrappers::TcpListenerStream;
+    use tonic::transport::Server;
+    use tonic_health::{
+        proto::{health_check_response, health_client::HealthClient, HealthCheckRequest},
+        server::health_reporter,
+    };
+
+    const UNREACHABLE_DATABASE: &str = "postgres://postgres@127.0.0.1:1/authentication";
+    const UNREACHABLE_REDIS: &str = "redis://127.0.0.1:1/";
+
+    fn app_state(database_url: &str, redis_url: &str) -> AppState {
+        AppState {
+            db_pg_pool: PgPoolOptions::new().connect_lazy(database_url).unwrap(),
+            redis: RedisPool::new(
+                redis::Client::open(redis_url).unwrap(),
+                RedisPoolOptions::default(),
+            ),
+            storage: Storage::Postgres,
+            config: Arc::new(Config::load().unwrap()),
+        }
+    }
+
+    fn sqlite_app_state(codes: CodesBackend) -> AppState {
+        let mut app_state = app_state(UNREACHABLE_DATABASE, UNREACHABLE_REDIS);
+        let mut config = Config::load().unwrap();
+        config.storage.codes = codes;
+        app_state.config = Arc::new(config);
+        app_state.storage = Storage::Sqlite(
+            SqlitePoolOptions::new()
+                .connect_lazy("sqlite::memory:")
+                .unwrap(),
+        );
+        app_state
+    }
+
+    #[tokio::test]
+    async fn test_serving_when_postgres_and_redis_answer() {
+        dotenv::from_filename(".env.test").ok();
+        let app_state = app_state(
+            &std::env::var("DATABASE_URL").unwrap(),
+            &std::env::var("REDIS_CLIENT").unwrap(),
+        );
+
+        assert_eq!(serving_status(&app_state).await, ServingStatus::Serving);
+    }
+
+    #[tokio::test]
+    async fn test_not_serving_when_a_dependency_is_down() {
+        dotenv::from_filename(".env.test").ok();
+        let database_url = std::env::var("DATABASE_URL").unwrap();
+        let redis_url = std::env::var("REDIS_CLIENT").unwrap();
+
+        for (database_url, redis_url) in [
+            (UNREACHABLE_DATABASE, redis_url.as_str()),
+            (database_url.as_str(), UNREACHABLE_REDIS),
+        ] {
+            let app_state = app_state(database_url, redis_url);
+            assert_eq!(serving_status(&app_state).await, ServingStatus::NotServing);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_storage_serves_without_postgres_and_redis() {
+        dotenv::from_filename(".env.test").ok();
+        let mut app_state = app_state(UNREACHABLE_DATABASE, UNREACHABLE_REDIS);
+        app_state.storage = Storage::InMemory(InMemoryStore::default());
+
+        assert_eq!(serving_status(&app_state).await, ServingStatus::Serving);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_storage_only_needs_redis_for_the_codes() {
+        dotenv::from_filename(".env.test").ok();
+
+        let app_state = sqlite_app_state(CodesBackend::Database);
+        assert_eq!(serving_status(&app_state).await, ServingStatus::Serving);
+
+        let app_state = sqlite_app_state(CodesBackend::Redis);
+        assert_eq!(serving_status(&app_state).await, ServingStatus::NotServing);
+    }
+
+    #[tokio::test]
+    async fn test_reports_the_status_of_every_service() {
+        dotenv::from_filename(".env.test").ok();
+        let app_state = app_state(UNREACHABLE_DATABASE, UNREACHABLE_REDIS);
+        let (reporter, health_server) = health_reporter();
+        tokio::spawn(report_health(app_state, reporter));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(
+            Server::builder()
+                .add_service(health_server)
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+        let mut client = HealthClient::connect(url).await.unwrap();
+
+        // The first check gives up after HEALTH_CHECK_TIMEOUT.
+        tokio::time::sleep(HEALTH_CHECK_TIMEOUT + Duration::from_millis(500)).await;
+        for service in HEALTH_SERVICE_NAMES {
+            let response = client
+                .check(HealthCheckRequest {
+                    service: service.to_string(),
+                })
+                .await
+                .unwrap();
+            assert_eq!(
+                response.into_inner().status,
+                health_check_response::ServingStatus::NotServing as i32
+            );
+        }
+        let unknown = client
+            .check(HealthCheckRequest {
+                service: "unknown.Service".to_string(),
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(unknown.code(), tonic::Code::NotFound);
+    }
+}
@@ -1 +1,5 @@
-pub mod authentication;
\ No newline at end of file
+pub mod authentication;
+pub mod error_details;
+pub mod health;
+pub mod reflection;
+pub mod router;
\ No newline at end of file
@@ -0,0 +1,50 @@
+use tonic_reflection::server::{Builder, ServerReflection, ServerReflectionServer};
+
+/// Descriptors of every proto served and of the protos they import, written by the build
+/// script.
+pub const FILE_DESCRIPTOR_SET: &[u8] =
+    tonic::include_file_descriptor_set!("authentication_descriptor");
+
+/// Server reflection for the authentication and health services, so grpcurl lists and
+/// calls them without the proto files.
+pub fn reflection_server() -> ServerReflectionServer<impl ServerReflection> {
+    Builder::configure()
+        .register_encoded_file_descriptor_set(FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(
+            tonic_health::proto::GRPC_HEALTH_V1_FILE_DESCRIPTOR_SET,
+        )
+        .build()
+        .expect("the descriptors written by the build script are valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+    use prost_types::FileDescriptorSet;
+
+    use super::*;
+
+    #[test]
+    fn test_descriptors_include_the_imported_protos() {
+        let descriptors = FileDescriptorSet::decode(FILE_DESCRIPTOR_SET).unwrap();
+        let files: Vec<&str> = descriptors.file.iter().map(|file| file.name()).collect();
+
+        assert!(files.contains(&"authentication.proto"));
+        assert!(files.contains(&"google/rpc/error_details.proto"));
+        for file in &descriptors.file {
+            for dependency in &file.dependency {
+                assert!(
+                    files.contains(&dependency.as_str()),
+                    "{} imports {}, which reflection would not find",
+                    file.name(),
+                    dependency
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_builds_the_reflection_service() {
+        reflection_server();
+    }
+}
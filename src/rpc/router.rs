@@ -0,0 +1,40 @@
+use tonic::transport::{server::Router, Server};
+use tonic_health::server::health_reporter;
+use tower::{
+    layer::util::{Identity, Stack},
+    Layer,
+};
+
+use crate::{
+    i18n::locale::LocaleLayer,
+    rpc::{
+        authentication::{
+            authentication::authentication_server::AuthenticationServer, AuthenticationService,
+        },
+        health::report_health,
+        reflection::reflection_server,
+    },
+    telemetry::{metrics::MetricsLayer, request_id::RequestIdLayer},
+    AppState,
+};
+
+pub type GrpcRouter = Router<Stack<LocaleLayer, Stack<RequestIdLayer, Identity>>>;
+
+/// The gRPC services as the server serves them, for the binary and the end-to-end tests to
+/// listen with however they need. Spawns the task keeping the health status up to date, so
+/// it must be called within the runtime.
+pub fn grpc_router(app_state: AppState) -> GrpcRouter {
+    let (health_reporter, health_server) = health_reporter();
+    tokio::spawn(report_health(app_state.clone(), health_reporter));
+
+    Server::builder()
+        .layer(RequestIdLayer)
+        .layer(LocaleLayer)
+        .add_service(health_server)
+        .add_service(reflection_server())
+        .add_service(
+            MetricsLayer.layer(AuthenticationServer::new(AuthenticationService::new(
+                app_state,
+            ))),
+        )
+}
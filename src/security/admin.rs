@@ -0,0 +1,28 @@
+use crate::{error::*, security::static_token::verify_static_token};
+
+pub type AuthorizeAdmin = fn(token: &str, admin_token: Option<&str>) -> Result<(), AppError>;
+
+/// Check the token sent to the administration RPCs against the configured `ADMIN_TOKEN`.
+pub fn authorize_admin(token: &str, admin_token: Option<&str>) -> Result<(), AppError> {
+    if !verify_static_token(token, admin_token) {
+        return Err(AppError::new(
+            Code::PermissionDenied,
+            "Administrator token required",
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorize_admin_with_wrong_token() {
+        match authorize_admin("wrong token", Some("changeme-admin")) {
+            Ok(_) => panic!("Should have failed"),
+            Err(error) => assert_eq!(error.code, Code::PermissionDenied),
+        }
+    }
+}
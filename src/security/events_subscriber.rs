@@ -0,0 +1,68 @@
+use crate::{
+    error::*,
+    security::{jwt::jwt_decode_service, static_token::verify_static_token},
+};
+
+pub const USER_EVENTS_READ_SCOPE: &str = "user_events:read";
+
+/// Check the token sent by a subscriber of the user events stream, either the configured
+/// `EVENTS_SUBSCRIBER_TOKEN` or a service token granted the `user_events:read` scope.
+pub fn authorize_events_subscriber(
+    token: &str,
+    subscriber_token: Option<&str>,
+) -> Result<(), AppError> {
+    if verify_static_token(token, subscriber_token) {
+        return Ok(());
+    }
+
+    match jwt_decode_service(token) {
+        Ok(service_token) if service_token.has_scope(USER_EVENTS_READ_SCOPE) => Ok(()),
+        _ => Err(AppError::new(
+            Code::PermissionDenied,
+            "Not authorized to watch user events",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::security::jwt::{jwt_encode, jwt_encode_service};
+
+    use super::*;
+
+    const SUBSCRIBER_TOKEN: Option<&str> = Some("changeme-events-subscriber");
+
+    #[test]
+    fn test_authorize_events_subscriber() {
+        assert!(
+            authorize_events_subscriber("changeme-events-subscriber", SUBSCRIBER_TOKEN).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_authorize_events_subscriber_with_service_token() {
+        dotenv::from_filename(".env.test").ok();
+
+        let service_token = jwt_encode_service(
+            "client".to_string(),
+            vec![USER_EVENTS_READ_SCOPE.to_string()],
+        )
+        .unwrap();
+        let service_token_without_scope = jwt_encode_service("client".to_string(), vec![]).unwrap();
+        let user_token = jwt_encode("uuidv4".to_string(), true, false).unwrap();
+
+        assert!(authorize_events_subscriber(&service_token, SUBSCRIBER_TOKEN).is_ok());
+        assert!(
+            authorize_events_subscriber(&service_token_without_scope, SUBSCRIBER_TOKEN).is_err()
+        );
+        assert!(authorize_events_subscriber(&user_token, SUBSCRIBER_TOKEN).is_err());
+    }
+
+    #[test]
+    fn test_authorize_events_subscriber_with_wrong_token() {
+        match authorize_events_subscriber("wrong token", SUBSCRIBER_TOKEN) {
+            Ok(_) => panic!("Should have failed"),
+            Err(error) => assert_eq!(error.code, Code::PermissionDenied),
+        }
+    }
+}
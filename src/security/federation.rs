@@ -0,0 +1,216 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{
+    jwk::{AlgorithmParameters, Jwk, JwkSet},
+    Algorithm, DecodingKey, Validation,
+};
+use serde::{Deserialize, Deserializer};
+use std::str::FromStr;
+
+use crate::error::*;
+
+/// Upstream OpenID provider trusted for `ExternalLogin`, configured through the
+/// `FEDERATED_IDENTITY_PROVIDERS` env var as a JSON array or the `[[federation.providers]]`
+/// tables of the config file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FederatedProvider {
+    pub name: String,
+    pub issuer: String,
+    pub jwks_uri: String,
+    /// Our client id at the provider, expected in the `aud` claim.
+    pub client_id: String,
+}
+
+/// The providers of a JSON array, as set in `FEDERATED_IDENTITY_PROVIDERS`.
+pub struct FederatedProviders(pub Vec<FederatedProvider>);
+
+impl FromStr for FederatedProviders {
+    type Err = serde_json::Error;
+
+    fn from_str(providers: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(providers).map(FederatedProviders)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ExternalIdTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_flag")]
+    pub email_verified: bool,
+    #[serde(default)]
+    pub preferred_username: Option<String>,
+}
+
+/// Some providers send `email_verified` as the string "true".
+fn deserialize_flag<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Flag {
+        Bool(bool),
+        String(String),
+    }
+
+    Ok(match Flag::deserialize(deserializer)? {
+        Flag::Bool(flag) => flag,
+        Flag::String(flag) => flag == "true",
+    })
+}
+
+fn invalid_token(reason: impl Into<String>) -> AppError {
+    AppError::new(
+        Code::Unauthenticated,
+        format!("Invalid external ID token: {}", reason.into()),
+    )
+}
+
+/// Read the issuer of a token before its signature is checked, only to pick the
+/// provider whose keys will verify it.
+pub fn unverified_issuer(id_token: &str) -> Result<String, AppError> {
+    #[derive(Deserialize)]
+    struct IssuerClaim {
+        iss: String,
+    }
+
+    id_token
+        .split('.')
+        .nth(1)
+        .and_then(|payload| URL_SAFE_NO_PAD.decode(payload).ok())
+        .and_then(|payload| serde_json::from_slice::<IssuerClaim>(&payload).ok())
+        .map(|claim| claim.iss)
+        .ok_or_else(|| invalid_token("malformed token"))
+}
+
+/// Only asymmetric algorithms are accepted, and each one with its own key type.
+fn key_matches_algorithm(jwk: &Jwk, algorithm: Algorithm) -> bool {
+    if jwk
+        .common
+        .algorithm
+        .is_some_and(|expected| expected != algorithm)
+    {
+        return false;
+    }
+
+    matches!(
+        (&jwk.algorithm, algorithm),
+        (
+            AlgorithmParameters::RSA(_),
+            Algorithm::RS256
+                | Algorithm::RS384
+                | Algorithm::RS512
+                | Algorithm::PS256
+                | Algorithm::PS384
+                | Algorithm::PS512,
+        ) | (
+            AlgorithmParameters::EllipticCurve(_),
+            Algorithm::ES256 | Algorithm::ES384
+        ) | (AlgorithmParameters::OctetKeyPair(_), Algorithm::EdDSA)
+    )
+}
+
+pub fn verify_external_id_token(
+    id_token: &str,
+    provider: &FederatedProvider,
+    jwks: &JwkSet,
+) -> Result<ExternalIdTokenClaims, AppError> {
+    let header =
+        jsonwebtoken::decode_header(id_token).map_err(|error| invalid_token(error.to_string()))?;
+
+    let jwk = match &header.kid {
+        Some(kid) => jwks.find(kid),
+        None if jwks.keys.len() == 1 => jwks.keys.first(),
+        None => None,
+    }
+    .ok_or_else(|| invalid_token("unknown signing key"))?;
+
+    if !key_matches_algorithm(jwk, header.alg) {
+        return Err(invalid_token("unexpected signing algorithm"));
+    }
+
+    let decoding_key =
+        DecodingKey::from_jwk(jwk).map_err(|error| invalid_token(error.to_string()))?;
+
+    let mut validation = Validation::new(header.alg);
+    validation.set_issuer(&[&provider.issuer]);
+    validation.set_audience(&[&provider.client_id]);
+
+    match jsonwebtoken::decode::<ExternalIdTokenClaims>(id_token, &decoding_key, &validation) {
+        Ok(token) => Ok(token.claims),
+        Err(error) => Err(invalid_token(error.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonwebtoken::get_current_timestamp;
+    use serde_json::json;
+
+    use crate::security::oidc::OidcSigningKey;
+
+    use super::*;
+
+    fn provider() -> FederatedProvider {
+        FederatedProvider {
+            name: "corporate".to_string(),
+            issuer: "https://sso.example.com".to_string(),
+            jwks_uri: "https://sso.example.com/jwks".to_string(),
+            client_id: "authentication".to_string(),
+        }
+    }
+
+    fn jwks(signing_key: &OidcSigningKey) -> JwkSet {
+        serde_json::from_value(json!({ "keys": [signing_key.public_jwk()] })).unwrap()
+    }
+
+    fn id_token(signing_key: &OidcSigningKey, aud: &str) -> String {
+        signing_key
+            .sign(&json!({
+                "iss": "https://sso.example.com",
+                "sub": "248289761001",
+                "aud": aud,
+                "exp": get_current_timestamp() + 300,
+                "email": "jane@example.com",
+                "email_verified": "true",
+            }))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_verify_external_id_token() {
+        let signing_key = OidcSigningKey::generate().unwrap();
+        let token = id_token(&signing_key, "authentication");
+
+        assert_eq!(
+            unverified_issuer(&token).unwrap(),
+            "https://sso.example.com"
+        );
+
+        let claims = verify_external_id_token(&token, &provider(), &jwks(&signing_key)).unwrap();
+        assert_eq!(claims.sub, "248289761001");
+        assert_eq!(claims.email.as_deref(), Some("jane@example.com"));
+        assert!(claims.email_verified);
+    }
+
+    #[test]
+    fn test_verify_external_id_token_rejects_other_audience_and_key() {
+        let signing_key = OidcSigningKey::generate().unwrap();
+        let other_key = OidcSigningKey::generate().unwrap();
+
+        let error = verify_external_id_token(
+            &id_token(&signing_key, "another-client"),
+            &provider(),
+            &jwks(&signing_key),
+        )
+        .unwrap_err();
+        assert_eq!(error.code, Code::Unauthenticated);
+
+        let error = verify_external_id_token(
+            &id_token(&other_key, "authentication"),
+            &provider(),
+            &jwks(&signing_key),
+        )
+        .unwrap_err();
+        assert_eq!(error.code, Code::Unauthenticated);
+    }
+}
@@ -1,30 +1,160 @@
-use crate::{error::*, utils::env_var::load_env_var::load_env_var};
+use crate::error::*;
 use jsonwebtoken::{get_current_timestamp, DecodingKey, EncodingKey, Header, Validation};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::sync::OnceLock;
+
+pub const TOKEN_TYPE_USER: &str = "user";
+pub const TOKEN_TYPE_SERVICE: &str = "service";
+
+pub const DEFAULT_JWT_LIFETIME_SECONDS: u64 = 60 * 60 * 2;
+pub const DEFAULT_JWT_ISSUER: &str = "authentication";
+pub const DEFAULT_JWT_AUDIENCE: &str = "authentication";
+pub const DEFAULT_JWT_LEEWAY_SECONDS: u64 = 60;
+
+fn default_token_type() -> String {
+    TOKEN_TYPE_USER.to_string()
+}
+
+/// Signing secret and registered claims of the user and service tokens.
+#[derive(Debug, Clone)]
+pub struct JwtConfig {
+    pub secret: String,
+    /// Seconds from issue to expiry.
+    pub lifetime: u64,
+    pub issuer: String,
+    pub audience: String,
+    /// Seconds of clock skew tolerated when checking `exp` and `nbf`.
+    pub leeway: u64,
+}
+
+impl JwtConfig {
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.secret.is_empty() {
+            return Err(AppError::new(Code::Internal, "JWT_SECRET is empty"));
+        }
+        if self.lifetime == 0 {
+            return Err(AppError::new(
+                Code::Internal,
+                "JWT_LIFETIME_SECONDS must be greater than zero",
+            ));
+        }
+        if self.issuer.is_empty() || self.audience.is_empty() {
+            return Err(AppError::new(
+                Code::Internal,
+                "JWT_ISSUER and JWT_AUDIENCE must not be empty",
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn validation(&self) -> Validation {
+        let mut validation = Validation::default();
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&[&self.audience]);
+        validation.set_required_spec_claims(&["exp", "nbf", "iss", "aud"]);
+        validation.validate_nbf = true;
+        validation.leeway = self.leeway;
+        validation
+    }
+}
+
+static JWT_CONFIG: OnceLock<JwtConfig> = OnceLock::new();
+
+/// Install the token settings of the loaded `Config`. Ignored once tokens were issued.
+pub fn init_jwt_config(config: JwtConfig) {
+    let _ = JWT_CONFIG.set(config);
+}
+
+/// Settings installed at startup, or read from the env on first use when none were.
+pub fn jwt_config() -> Result<&'static JwtConfig, AppError> {
+    if let Some(config) = JWT_CONFIG.get() {
+        return Ok(config);
+    }
+
+    let config = JwtConfig::from_env()?;
+    Ok(JWT_CONFIG.get_or_init(|| config))
+}
+
+/// Registered claims shared by every token.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RegisteredClaims {
+    pub iss: String,
+    pub aud: String,
+    pub iat: u64,
+    pub nbf: u64,
+    pub exp: u64,
+}
+
+impl RegisteredClaims {
+    fn issue(config: &JwtConfig) -> RegisteredClaims {
+        let now = get_current_timestamp();
+        RegisteredClaims {
+            iss: config.issuer.clone(),
+            aud: config.audience.clone(),
+            iat: now,
+            nbf: now,
+            exp: now + config.lifetime,
+        }
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JWTAuthenticateToken {
     pub sub: String,
     pub activated: bool,
     pub blocked: bool,
-    pub exp: usize,
+    /// Tokens issued before the claim existed were all user tokens.
+    #[serde(default = "default_token_type")]
+    pub token_type: String,
+    /// Space-delimited scopes granted to the OAuth client the token was issued to. Tokens
+    /// of the user's own logins carry none and are not limited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(flatten)]
+    pub claims: RegisteredClaims,
+}
+
+impl JWTAuthenticateToken {
+    pub fn grants(&self, scope: &str) -> bool {
+        match &self.scope {
+            Some(granted) => granted.split_whitespace().any(|granted| granted == scope),
+            None => true,
+        }
+    }
+}
+
+/// Token of a service client obtained with the client credentials grant,
+/// `sub` is the client id and `scope` the space-delimited granted scopes.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JWTServiceToken {
+    pub sub: String,
+    pub scope: String,
+    pub token_type: String,
+    #[serde(flatten)]
+    pub claims: RegisteredClaims,
+}
+
+impl JWTServiceToken {
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scope
+            .split_whitespace()
+            .any(|granted| granted == scope)
+    }
 }
 
 pub type JwtEncode = fn(id: String, activated: bool, blocked: bool) -> Result<String, AppError>;
+pub type JwtEncodeAccess =
+    fn(id: String, activated: bool, blocked: bool, scopes: Vec<String>) -> Result<String, AppError>;
 pub type JwtDecode = fn(token: &str) -> Result<JWTAuthenticateToken, AppError>;
+pub type JwtEncodeService = fn(client_id: String, scopes: Vec<String>) -> Result<String, AppError>;
+pub type JwtDecodeService = fn(token: &str) -> Result<JWTServiceToken, AppError>;
 
-pub fn jwt_encode(id: String, activated: bool, blocked: bool) -> Result<String, AppError> {
-    let user_token = JWTAuthenticateToken {
-        sub: id,
-        activated,
-        blocked,
-        exp: (get_current_timestamp() + 1000 * 60 * 60 * 2) as usize, // 2 hours
-    };
-
+fn encode_claims<T: Serialize>(config: &JwtConfig, claims: &T) -> Result<String, AppError> {
     match jsonwebtoken::encode(
         &Header::default(),
-        &user_token,
-        &EncodingKey::from_secret(load_env_var("JWT_SECRET")?.as_ref()),
+        claims,
+        &EncodingKey::from_secret(config.secret.as_ref()),
     ) {
         Ok(token) => Ok(token),
         Err(error) => Err(AppError::new(
@@ -34,13 +164,55 @@ pub fn jwt_encode(id: String, activated: bool, blocked: bool) -> Result<String,
     }
 }
 
-pub fn jwt_decode(token: &str) -> Result<JWTAuthenticateToken, AppError> {
-    match jsonwebtoken::decode::<JWTAuthenticateToken>(
+pub fn jwt_encode(id: String, activated: bool, blocked: bool) -> Result<String, AppError> {
+    let config = jwt_config()?;
+    let user_token = JWTAuthenticateToken {
+        sub: id,
+        activated,
+        blocked,
+        token_type: TOKEN_TYPE_USER.to_string(),
+        scope: None,
+        claims: RegisteredClaims::issue(config),
+    };
+
+    encode_claims(config, &user_token)
+}
+
+/// User token handed to an OAuth client, limited to the `scopes` the user granted it.
+pub fn jwt_encode_access(
+    id: String,
+    activated: bool,
+    blocked: bool,
+    scopes: Vec<String>,
+) -> Result<String, AppError> {
+    let config = jwt_config()?;
+    let user_token = JWTAuthenticateToken {
+        sub: id,
+        activated,
+        blocked,
+        token_type: TOKEN_TYPE_USER.to_string(),
+        scope: Some(scopes.join(" ")),
+        claims: RegisteredClaims::issue(config),
+    };
+
+    encode_claims(config, &user_token)
+}
+
+#[derive(Deserialize)]
+struct TokenTypeClaim {
+    #[serde(default = "default_token_type")]
+    token_type: String,
+}
+
+fn decode_claims<T: DeserializeOwned>(token: &str) -> Result<T, AppError> {
+    let config = jwt_config()?;
+
+    match jsonwebtoken::decode::<T>(
         token,
-        &DecodingKey::from_secret(load_env_var("JWT_SECRET")?.as_ref()),
-        &Validation::default(),
+        &DecodingKey::from_secret(config.secret.as_ref()),
+        &config.validation(),
     ) {
-        Ok(user_token) => Ok(user_token.claims),
+        Ok(token) => Ok(token.claims),
         Err(error) => Err(AppError::new(
             Code::InvalidArgument,
             format!("failed to decode token :{}", error),
@@ -48,6 +220,62 @@ pub fn jwt_decode(token: &str) -> Result<JWTAuthenticateToken, AppError> {
     }
 }
 
+/// Decode a token of the user's own login, service tokens and the access tokens issued to
+/// OAuth clients are refused even when correctly signed.
+pub fn jwt_decode(token: &str) -> Result<JWTAuthenticateToken, AppError> {
+    let user_token = jwt_decode_access(token)?;
+
+    if user_token.scope.is_some() {
+        return Err(AppError::new(
+            Code::PermissionDenied,
+            "Tokens issued to OAuth clients only grant access to UserInfo",
+        ));
+    }
+
+    Ok(user_token)
+}
+
+/// Decode a user token, including the access tokens issued to OAuth clients, which only
+/// grant their `scope`. Service tokens are refused even when correctly signed.
+pub fn jwt_decode_access(token: &str) -> Result<JWTAuthenticateToken, AppError> {
+    let TokenTypeClaim { token_type } = decode_claims(token)?;
+
+    if token_type != TOKEN_TYPE_USER {
+        return Err(AppError::new(
+            Code::PermissionDenied,
+            "A user token is required",
+        ));
+    }
+
+    decode_claims(token)
+}
+
+pub fn jwt_encode_service(client_id: String, scopes: Vec<String>) -> Result<String, AppError> {
+    let config = jwt_config()?;
+    let service_token = JWTServiceToken {
+        sub: client_id,
+        scope: scopes.join(" "),
+        token_type: TOKEN_TYPE_SERVICE.to_string(),
+        claims: RegisteredClaims::issue(config),
+    };
+
+    encode_claims(config, &service_token)
+}
+
+/// Decode a service token, user tokens are refused even when correctly signed.
+pub fn jwt_decode_service(token: &str) -> Result<JWTServiceToken, AppError> {
+    let TokenTypeClaim { token_type } = decode_claims(token)?;
+
+    if token_type != TOKEN_TYPE_SERVICE {
+        return Err(AppError::new(
+            Code::PermissionDenied,
+            "A service token is required",
+        ));
+    }
+
+    decode_claims(token)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -70,7 +298,109 @@ mod tests {
         } = jwt_decode(&jwt_token).unwrap();
 
         assert_eq!("uuidv4", sub);
-        assert_eq!(true, activated);
-        assert_eq!(false, blocked);
+        assert!(activated);
+        assert!(!blocked);
+    }
+
+    #[test]
+    fn test_token_expires_after_the_configured_lifetime() {
+        let config = jwt_config().unwrap();
+        let jwt_token = jwt_encode("uuidv4".to_string(), true, false).unwrap();
+        let JWTAuthenticateToken { claims, .. } = jwt_decode(&jwt_token).unwrap();
+
+        assert_eq!(claims.exp - claims.iat, config.lifetime);
+        assert_eq!(claims.iss, config.issuer);
+        assert_eq!(claims.aud, config.audience);
+    }
+
+    #[test]
+    fn test_decode_refuses_other_audience_and_expired_tokens() {
+        let config = jwt_config().unwrap();
+
+        let mut other_audience = RegisteredClaims::issue(config);
+        other_audience.aud = "other".to_string();
+        let mut expired = RegisteredClaims::issue(config);
+        expired.exp = expired.iat - config.leeway - 1;
+
+        for claims in [other_audience, expired] {
+            let token = encode_claims(
+                config,
+                &JWTAuthenticateToken {
+                    sub: "uuidv4".to_string(),
+                    activated: true,
+                    blocked: false,
+                    token_type: TOKEN_TYPE_USER.to_string(),
+                    scope: None,
+                    claims,
+                },
+            )
+            .unwrap();
+
+            match jwt_decode(&token) {
+                Ok(_) => panic!("Should have failed"),
+                Err(error) => assert_eq!(error.code, Code::InvalidArgument),
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_service_token() {
+        let service_token =
+            jwt_encode_service("client".to_string(), vec!["user_events:read".to_string()]).unwrap();
+        let claims = jwt_decode_service(&service_token).unwrap();
+
+        assert_eq!("client", claims.sub);
+        assert!(claims.has_scope("user_events:read"));
+        assert!(!claims.has_scope("user_events"));
+    }
+
+    #[test]
+    fn test_access_token_only_grants_its_scopes() {
+        let access_token = jwt_encode_access(
+            "uuidv4".to_string(),
+            true,
+            false,
+            vec!["openid".to_string()],
+        )
+        .unwrap();
+        let login_token = jwt_encode("uuidv4".to_string(), true, false).unwrap();
+
+        let access_token = jwt_decode_access(&access_token).unwrap();
+        assert!(access_token.grants("openid"));
+        assert!(!access_token.grants("email"));
+        assert!(jwt_decode_access(&login_token).unwrap().grants("email"));
+    }
+
+    #[test]
+    fn test_access_token_is_not_a_login_token() {
+        let access_token = jwt_encode_access(
+            "uuidv4".to_string(),
+            true,
+            false,
+            vec!["openid".to_string()],
+        )
+        .unwrap();
+        let login_token = jwt_encode("uuidv4".to_string(), true, false).unwrap();
+
+        match jwt_decode(&access_token) {
+            Ok(_) => panic!("Should have failed"),
+            Err(error) => assert_eq!(error.code, Code::PermissionDenied),
+        }
+        assert_eq!("uuidv4", jwt_decode(&login_token).unwrap().sub);
+    }
+
+    #[test]
+    fn test_user_and_service_tokens_are_not_interchangeable() {
+        let user_token = jwt_encode("uuidv4".to_string(), true, false).unwrap();
+        let service_token = jwt_encode_service("client".to_string(), vec![]).unwrap();
+
+        match jwt_decode(&service_token) {
+            Ok(_) => panic!("Should have failed"),
+            Err(error) => assert_eq!(error.code, Code::PermissionDenied),
+        }
+        match jwt_decode_service(&user_token) {
+            Ok(_) => panic!("Should have failed"),
+            Err(error) => assert_eq!(error.code, Code::PermissionDenied),
+        }
     }
 }
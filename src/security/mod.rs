@@ -1 +1,11 @@
-pub mod jwt;
\ No newline at end of file
+pub mod admin;
+pub mod events_subscriber;
+pub mod federation;
+pub mod jwt;
+pub mod oidc;
+pub mod personal_access_token;
+pub mod pkce;
+pub mod static_token;
+pub mod tls;
+pub mod webauthn;
+pub mod webhook_signature;
@@ -0,0 +1,253 @@
+use std::sync::OnceLock;
+
+use base64::{
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+    Engine,
+};
+use jsonwebtoken::{get_current_timestamp, Algorithm, EncodingKey, Header};
+use ring::{
+    rand::SystemRandom,
+    signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_FIXED_SIGNING},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::{config::app_config::OidcConfig, error::*};
+
+pub const ID_TOKEN_ALGORITHM: &str = "ES256";
+const ID_TOKEN_LIFETIME_SECONDS: u64 = 60 * 60;
+
+pub struct OidcSigningKey {
+    pub key_id: String,
+    encoding_key: EncodingKey,
+    x: String,
+    y: String,
+}
+
+impl OidcSigningKey {
+    /// Build the key from a PKCS#8 encoded P-256 private key.
+    pub fn from_pkcs8(pkcs8: &[u8]) -> Result<OidcSigningKey, AppError> {
+        let key_pair = match EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, pkcs8) {
+            Ok(key_pair) => key_pair,
+            Err(error) => {
+                return Err(AppError::new(
+                    Code::Internal,
+                    format!("invalid OIDC signing key: {}", error),
+                ))
+            }
+        };
+
+        // Uncompressed SEC1 point: 0x04 || x (32 bytes) || y (32 bytes).
+        let public_key = key_pair.public_key().as_ref();
+
+        Ok(OidcSigningKey {
+            key_id: hex::encode(&Sha256::digest(public_key)[..8]),
+            encoding_key: EncodingKey::from_ec_der(pkcs8),
+            x: URL_SAFE_NO_PAD.encode(&public_key[1..33]),
+            y: URL_SAFE_NO_PAD.encode(&public_key[33..65]),
+        })
+    }
+
+    /// Read a PEM encoded PKCS#8 key, as written by `openssl genpkey -algorithm EC`.
+    pub fn from_pem(pem: &str) -> Result<OidcSigningKey, AppError> {
+        let body: String = pem
+            .lines()
+            .filter(|line| !line.starts_with("-----"))
+            .collect();
+
+        match STANDARD.decode(body.trim()) {
+            Ok(der) => OidcSigningKey::from_pkcs8(&der),
+            Err(error) => Err(AppError::new(
+                Code::Internal,
+                format!("invalid OIDC signing key: {}", error),
+            )),
+        }
+    }
+
+    pub fn generate() -> Result<OidcSigningKey, AppError> {
+        match EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_FIXED_SIGNING, &SystemRandom::new()) {
+            Ok(pkcs8) => OidcSigningKey::from_pkcs8(pkcs8.as_ref()),
+            Err(_) => Err(AppError::new(
+                Code::Internal,
+                "failed to generate the OIDC signing key",
+            )),
+        }
+    }
+
+    /// Sign the claims as an ES256 JWT carrying this key id.
+    pub fn sign<T: Serialize>(&self, claims: &T) -> Result<String, AppError> {
+        let mut header = Header::new(Algorithm::ES256);
+        header.kid = Some(self.key_id.clone());
+
+        match jsonwebtoken::encode(&header, claims, &self.encoding_key) {
+            Ok(token) => Ok(token),
+            Err(error) => Err(AppError::new(
+                Code::Internal,
+                format!("failed to encode ID token :{}", error),
+            )),
+        }
+    }
+
+    pub fn public_jwk(&self) -> Value {
+        json!({
+            "kty": "EC",
+            "crv": "P-256",
+            "alg": ID_TOKEN_ALGORITHM,
+            "use": "sig",
+            "kid": self.key_id,
+            "x": self.x,
+            "y": self.y,
+        })
+    }
+}
+
+static SIGNING_KEY: OnceLock<OidcSigningKey> = OnceLock::new();
+
+/// Key signing the ID tokens, read from the file at `signing_key_path`. The key read
+/// first is kept for the life of the process.
+///
+/// Without that path an ephemeral key is generated, so ID tokens stop validating
+/// once the server restarts.
+pub fn oidc_signing_key(config: &OidcConfig) -> Result<&'static OidcSigningKey, AppError> {
+    if let Some(key) = SIGNING_KEY.get() {
+        return Ok(key);
+    }
+
+    let key = match &config.signing_key_path {
+        Some(path) => match std::fs::read_to_string(path) {
+            Ok(pem) => OidcSigningKey::from_pem(&pem)?,
+            Err(error) => {
+                return Err(AppError::new(
+                    Code::Internal,
+                    format!("failed to read the OIDC signing key {}: {}", path, error),
+                ))
+            }
+        },
+        None => {
+            tracing::warn!(
+                "OIDC_SIGNING_KEY_PATH not set, signing ID tokens with an ephemeral key"
+            );
+            OidcSigningKey::generate()?
+        }
+    };
+
+    Ok(SIGNING_KEY.get_or_init(|| key))
+}
+
+/// Issuer of the ID tokens, as advertised by the discovery document.
+pub fn oidc_issuer(config: &OidcConfig) -> Result<&str, AppError> {
+    match &config.issuer {
+        Some(issuer) => Ok(issuer),
+        None => Err(AppError::new(
+            Code::Internal,
+            "OIDC_ISSUER is not configured",
+        )),
+    }
+}
+
+pub fn oidc_jwks(config: &OidcConfig) -> Result<Value, AppError> {
+    Ok(json!({ "keys": [oidc_signing_key(config)?.public_jwk()] }))
+}
+
+pub struct IdTokenParams {
+    pub sub: String,
+    pub aud: String,
+    pub auth_time: i64,
+    pub nonce: Option<String>,
+    pub preferred_username: Option<String>,
+    pub email: Option<String>,
+    pub email_verified: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    pub aud: String,
+    pub exp: usize,
+    pub iat: usize,
+    pub auth_time: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email_verified: Option<bool>,
+}
+
+pub type IdTokenEncode = fn(config: &OidcConfig, params: IdTokenParams) -> Result<String, AppError>;
+
+pub fn id_token_encode(config: &OidcConfig, params: IdTokenParams) -> Result<String, AppError> {
+    let signing_key = oidc_signing_key(config)?;
+    let now = get_current_timestamp();
+
+    let claims = IdTokenClaims {
+        iss: oidc_issuer(config)?.to_string(),
+        sub: params.sub,
+        aud: params.aud,
+        exp: (now + ID_TOKEN_LIFETIME_SECONDS) as usize,
+        iat: now as usize,
+        auth_time: params.auth_time,
+        nonce: params.nonce,
+        preferred_username: params.preferred_username,
+        email: params.email,
+        email_verified: params.email_verified,
+    };
+
+    signing_key.sign(&claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonwebtoken::{DecodingKey, Validation};
+
+    use super::*;
+
+    #[test]
+    fn test_id_token_is_verifiable_with_the_published_jwk() {
+        let config = OidcConfig {
+            issuer: Some("http://localhost:8080".to_string()),
+            signing_key_path: None,
+        };
+
+        let id_token = id_token_encode(
+            &config,
+            IdTokenParams {
+                sub: "uuidv4".to_string(),
+                aud: "client".to_string(),
+                auth_time: 1682935200,
+                nonce: Some("nonce".to_string()),
+                preferred_username: None,
+                email: None,
+                email_verified: None,
+            },
+        )
+        .unwrap();
+
+        let jwk = &oidc_jwks(&config).unwrap()["keys"][0];
+        let header = jsonwebtoken::decode_header(&id_token).unwrap();
+        assert_eq!(header.kid.as_deref(), jwk["kid"].as_str());
+
+        let mut validation = Validation::new(Algorithm::ES256);
+        validation.set_audience(&["client"]);
+        validation.set_issuer(&["http://localhost:8080"]);
+        let claims = jsonwebtoken::decode::<IdTokenClaims>(
+            &id_token,
+            &DecodingKey::from_ec_components(
+                jwk["x"].as_str().unwrap(),
+                jwk["y"].as_str().unwrap(),
+            )
+            .unwrap(),
+            &validation,
+        )
+        .unwrap()
+        .claims;
+
+        assert_eq!(claims.sub, "uuidv4");
+        assert_eq!(claims.nonce.as_deref(), Some("nonce"));
+        assert_eq!(claims.auth_time, 1682935200);
+    }
+}
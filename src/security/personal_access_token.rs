@@ -0,0 +1,64 @@
+use crate::error::*;
+
+/// Marks personal access tokens, which are told apart from JWTs by it.
+pub const PERSONAL_ACCESS_TOKEN_PREFIX: &str = "pat_";
+/// Length of the token start kept in clear, the marker and 8 characters of the secret.
+pub const PERSONAL_ACCESS_TOKEN_VISIBLE_LENGTH: usize = 12;
+
+pub const USER_READ_SCOPE: &str = "user:read";
+pub const USER_WRITE_SCOPE: &str = "user:write";
+pub const USER_DELETE_SCOPE: &str = "user:delete";
+/// Scopes a personal access token may hold. Changing the password and managing
+/// passkeys or tokens always requires a login token.
+pub const PERSONAL_ACCESS_TOKEN_SCOPES: [&str; 3] =
+    [USER_READ_SCOPE, USER_WRITE_SCOPE, USER_DELETE_SCOPE];
+
+pub fn is_personal_access_token(token: &str) -> bool {
+    token.starts_with(PERSONAL_ACCESS_TOKEN_PREFIX)
+}
+
+pub fn parse_personal_access_token_scopes(scopes: Vec<String>) -> Result<Vec<String>, AppError> {
+    if scopes.is_empty() {
+        return Err(AppError::new(
+            Code::InvalidArgument,
+            "At least one scope is required",
+        ));
+    }
+
+    let mut parsed: Vec<String> = vec![];
+    for scope in scopes {
+        if !PERSONAL_ACCESS_TOKEN_SCOPES.contains(&scope.as_str()) {
+            return Err(AppError::new(
+                Code::InvalidArgument,
+                format!("Unknown scope: {}", scope),
+            ));
+        }
+        if !parsed.contains(&scope) {
+            parsed.push(scope);
+        }
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_personal_access_token_scopes() {
+        let scopes = parse_personal_access_token_scopes(vec![
+            USER_READ_SCOPE.to_string(),
+            USER_WRITE_SCOPE.to_string(),
+            USER_READ_SCOPE.to_string(),
+        ])
+        .unwrap();
+        assert_eq!(scopes, vec![USER_READ_SCOPE, USER_WRITE_SCOPE]);
+
+        let error = parse_personal_access_token_scopes(vec!["admin".to_string()]).unwrap_err();
+        assert_eq!(error.code, Code::InvalidArgument);
+
+        let error = parse_personal_access_token_scopes(vec![]).unwrap_err();
+        assert_eq!(error.code, Code::InvalidArgument);
+    }
+}
@@ -0,0 +1,47 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use sha2::{Digest, Sha256};
+
+pub const PKCE_METHOD_S256: &str = "S256";
+
+/// A code verifier is 43 to 128 characters from the unreserved URL set (RFC 7636, 4.1).
+pub fn is_valid_code_verifier(code_verifier: &str) -> bool {
+    (43..=128).contains(&code_verifier.len())
+        && code_verifier
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~'))
+}
+
+/// S256 challenge of a code verifier: `BASE64URL(SHA256(code_verifier))` without padding.
+pub fn pkce_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+pub fn verify_pkce(code_verifier: &str, code_challenge: &str) -> bool {
+    is_valid_code_verifier(code_verifier) && pkce_challenge(code_verifier) == code_challenge
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Example from RFC 7636, appendix B.
+    const CODE_VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+    const CODE_CHALLENGE: &str = "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM";
+
+    #[test]
+    fn test_verify_pkce() {
+        assert_eq!(pkce_challenge(CODE_VERIFIER), CODE_CHALLENGE);
+        assert!(verify_pkce(CODE_VERIFIER, CODE_CHALLENGE));
+        assert!(!verify_pkce(
+            "eBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk",
+            CODE_CHALLENGE
+        ));
+    }
+
+    #[test]
+    fn test_short_code_verifier_is_rejected() {
+        let code_verifier = "short";
+
+        assert!(!verify_pkce(code_verifier, &pkce_challenge(code_verifier)));
+    }
+}
@@ -0,0 +1,37 @@
+/// Compare, in constant time, a token sent by a client with the configured one.
+/// Nothing matches when no token is configured.
+pub fn verify_static_token(token: &str, expected: Option<&str>) -> bool {
+    match expected {
+        Some(expected) => {
+            !expected.is_empty() && constant_time_eq(token.as_bytes(), expected.as_bytes())
+        }
+        None => false,
+    }
+}
+
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"token", b"token"));
+        assert!(!constant_time_eq(b"token", b"tokem"));
+        assert!(!constant_time_eq(b"token", b"token2"));
+    }
+
+    #[test]
+    fn test_verify_static_token_without_configured_token() {
+        assert!(verify_static_token("token", Some("token")));
+        assert!(!verify_static_token("", Some("")));
+        assert!(!verify_static_token("token", None));
+    }
+}
@@ -0,0 +1,225 @@
+use crate::{config::app_config::TlsConfig, error::*};
+use std::{
+    fs::{self, File},
+    io::{self, BufReader},
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, SystemTime},
+};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::{
+    rustls::{
+        server::{AllowAnyAnonymousOrAuthenticatedClient, AllowAnyAuthenticatedClient},
+        Certificate, PrivateKey, RootCertStore, ServerConfig,
+    },
+    server::TlsStream,
+    TlsAcceptor,
+};
+use tokio_stream::{wrappers::ReceiverStream, Stream};
+
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Pause after failing to accept for a reason other than the client hanging up, as hyper's
+/// `AddrIncoming` does: out of file descriptors, the loop would otherwise spin and flood the
+/// logs until one is closed.
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_secs(1);
+
+fn tls_error(path: &str, message: impl std::fmt::Display) -> AppError {
+    AppError::new(Code::Internal, format!("TLS: {}: {}", path, message))
+}
+
+fn read_pem(path: &str) -> Result<Vec<rustls_pemfile::Item>, AppError> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(error) => return Err(tls_error(path, error)),
+    };
+
+    match rustls_pemfile::read_all(&mut BufReader::new(file)) {
+        Ok(items) => Ok(items),
+        Err(error) => Err(tls_error(path, error)),
+    }
+}
+
+fn read_certificates(path: &str) -> Result<Vec<Certificate>, AppError> {
+    let certificates: Vec<Certificate> = read_pem(path)?
+        .into_iter()
+        .filter_map(|item| match item {
+            rustls_pemfile::Item::X509Certificate(der) => Some(Certificate(der)),
+            _ => None,
+        })
+        .collect();
+
+    if certificates.is_empty() {
+        return Err(tls_error(path, "no certificate found"));
+    }
+
+    Ok(certificates)
+}
+
+fn read_private_key(path: &str) -> Result<PrivateKey, AppError> {
+    read_pem(path)?
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::PKCS8Key(der)
+            | rustls_pemfile::Item::RSAKey(der)
+            | rustls_pemfile::Item::ECKey(der) => Some(PrivateKey(der)),
+            _ => None,
+        })
+        .ok_or_else(|| tls_error(path, "no private key found"))
+}
+
+/// Build the rustls configuration of the gRPC server from the files of `config`.
+pub fn load_server_config(config: &TlsConfig) -> Result<ServerConfig, AppError> {
+    let certificates = read_certificates(&config.cert_path)?;
+    let key = read_private_key(&config.key_path)?;
+
+    let builder = ServerConfig::builder().with_safe_defaults();
+    let builder = match &config.client_ca_path {
+        Some(client_ca_path) => {
+            let mut roots = RootCertStore::empty();
+            for certificate in read_certificates(client_ca_path)? {
+                if let Err(error) = roots.add(&certificate) {
+                    return Err(tls_error(client_ca_path, error));
+                }
+            }
+
+            if config.require_client_cert {
+                builder.with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots))
+            } else {
+                builder
+                    .with_client_cert_verifier(AllowAnyAnonymousOrAuthenticatedClient::new(roots))
+            }
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let mut server_config = match builder.with_single_cert(certificates, key) {
+        Ok(server_config) => server_config,
+        Err(error) => return Err(tls_error(&config.key_path, error)),
+    };
+    server_config.alpn_protocols = vec![b"h2".to_vec()];
+
+    Ok(server_config)
+}
+
+/// TLS settings of the gRPC server, swapped in place when the certificate files change
+/// so renewed certificates are served without a restart.
+pub struct ServerTls {
+    config: TlsConfig,
+    server_config: RwLock<Arc<ServerConfig>>,
+    modified: Mutex<Vec<Option<SystemTime>>>,
+}
+
+impl ServerTls {
+    pub fn load(config: TlsConfig) -> Result<ServerTls, AppError> {
+        let modified = Self::files_modified(&config);
+        let server_config = load_server_config(&config)?;
+
+        Ok(ServerTls {
+            config,
+            server_config: RwLock::new(Arc::new(server_config)),
+            modified: Mutex::new(modified),
+        })
+    }
+
+    fn files_modified(config: &TlsConfig) -> Vec<Option<SystemTime>> {
+        [
+            Some(&config.cert_path),
+            Some(&config.key_path),
+            config.client_ca_path.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|path| fs::metadata(path).and_then(|file| file.modified()).ok())
+        .collect()
+    }
+
+    /// Acceptor with the settings loaded last, connections in progress keep theirs.
+    pub fn acceptor(&self) -> TlsAcceptor {
+        TlsAcceptor::from(self.server_config.read().unwrap().clone())
+    }
+
+    /// Reload the files when any changed since the last load. A failed reload keeps
+    /// serving the previous certificate.
+    pub fn reload_if_changed(&self) -> Result<bool, AppError> {
+        let modified = Self::files_modified(&self.config);
+        let mut last_modified = self.modified.lock().unwrap();
+        if *last_modified == modified {
+            return Ok(false);
+        }
+
+        let server_config = load_server_config(&self.config)?;
+        *self.server_config.write().unwrap() = Arc::new(server_config);
+        *last_modified = modified;
+
+        Ok(true)
+    }
+}
+
+/// Check the certificate files every `interval`, until the server stops.
+pub async fn run_tls_reloader(tls: Arc<ServerTls>, interval: Duration) {
+    loop {
+        tokio::time::sleep(interval).await;
+
+        match tls.reload_if_changed() {
+            Ok(true) => tracing::info!("TLS certificate reloaded"),
+            Ok(false) => {}
+            Err(error) => tracing::error!(
+                error = %error.message,
+                "Keeping the current TLS certificate"
+            ),
+        }
+    }
+}
+
+/// Errors of a single client, which leave the listener as able to accept as before.
+fn is_connection_error(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::ConnectionReset
+    )
+}
+
+/// Connections of `listener` that completed the TLS handshake. Handshakes run apart from
+/// the accept loop, so a slow or failing client holds back no one else.
+pub fn tls_incoming(
+    listener: TcpListener,
+    tls: Arc<ServerTls>,
+) -> impl Stream<Item = Result<TlsStream<TcpStream>, io::Error>> {
+    let (sender, receiver) = tokio::sync::mpsc::channel(64);
+
+    tokio::spawn(async move {
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(error) if is_connection_error(&error) => {
+                    tracing::debug!(%error, "Connection closed before it was accepted");
+                    continue;
+                }
+                Err(error) => {
+                    tracing::warn!(%error, "Failed to accept a connection, retrying in 1s");
+                    tokio::time::sleep(ACCEPT_ERROR_DELAY).await;
+                    continue;
+                }
+            };
+
+            if sender.is_closed() {
+                break;
+            }
+
+            let acceptor = tls.acceptor();
+            let handshake_sender = sender.clone();
+            tokio::spawn(async move {
+                match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                    Ok(Ok(stream)) => {
+                        let _ = handshake_sender.send(Ok(stream)).await;
+                    }
+                    Ok(Err(error)) => tracing::debug!(%error, "TLS handshake failed"),
+                    Err(_) => tracing::debug!("TLS handshake timed out"),
+                }
+            });
+        }
+    });
+
+    ReceiverStream::new(receiver)
+}
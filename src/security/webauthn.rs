@@ -0,0 +1,543 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ciborium::value::Value;
+use ring::signature::{
+    RsaPublicKeyComponents, UnparsedPublicKey, ECDSA_P256_SHA256_ASN1, ED25519,
+    RSA_PKCS1_2048_8192_SHA256,
+};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::error::*;
+
+pub const COSE_ALGORITHM_ES256: i64 = -7;
+pub const COSE_ALGORITHM_EDDSA: i64 = -8;
+pub const COSE_ALGORITHM_RS256: i64 = -257;
+/// Algorithms offered to authenticators, in order of preference.
+pub const SUPPORTED_ALGORITHMS: [i64; 3] = [
+    COSE_ALGORITHM_ES256,
+    COSE_ALGORITHM_EDDSA,
+    COSE_ALGORITHM_RS256,
+];
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_ATTESTED_CREDENTIAL_DATA: u8 = 0x40;
+
+const CLIENT_DATA_TYPE_CREATE: &str = "webauthn.create";
+const CLIENT_DATA_TYPE_GET: &str = "webauthn.get";
+
+/// Relying party passkeys are bound to, `id` is the registrable domain and `origin`
+/// the web origin running the ceremonies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelyingParty {
+    pub id: String,
+    pub name: String,
+    pub origin: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegisteredCredential {
+    pub credential_id: Vec<u8>,
+    /// COSE_Key encoded public key, kept as sent by the authenticator.
+    pub public_key: Vec<u8>,
+    pub algorithm: i64,
+    pub sign_count: u32,
+}
+
+#[derive(Deserialize)]
+struct CollectedClientData {
+    #[serde(rename = "type")]
+    ceremony: String,
+    challenge: String,
+    origin: String,
+    #[serde(rename = "crossOrigin", default)]
+    cross_origin: bool,
+}
+
+fn parse_client_data(client_data_json: &[u8]) -> Result<CollectedClientData, String> {
+    serde_json::from_slice(client_data_json).map_err(|_| "malformed client data".to_string())
+}
+
+/// Read the challenge a ceremony answers, only to find the pending challenge it
+/// must then be verified against.
+pub fn client_data_challenge(client_data_json: &[u8]) -> Result<String, AppError> {
+    match parse_client_data(client_data_json) {
+        Ok(client_data) => Ok(client_data.challenge),
+        Err(reason) => Err(AppError::new(Code::InvalidArgument, reason)),
+    }
+}
+
+fn verify_client_data(
+    client_data_json: &[u8],
+    ceremony: &str,
+    challenge: &str,
+    relying_party: &RelyingParty,
+) -> Result<(), String> {
+    let client_data = parse_client_data(client_data_json)?;
+
+    if client_data.ceremony != ceremony {
+        return Err("unexpected ceremony type".to_string());
+    }
+    if client_data.challenge != challenge {
+        return Err("challenge mismatch".to_string());
+    }
+    if client_data.origin != relying_party.origin || client_data.cross_origin {
+        return Err("unexpected origin".to_string());
+    }
+
+    Ok(())
+}
+
+struct AuthenticatorData<'a> {
+    rp_id_hash: &'a [u8],
+    flags: u8,
+    sign_count: u32,
+    credential_id: Option<&'a [u8]>,
+    public_key: Option<&'a [u8]>,
+}
+
+impl<'a> AuthenticatorData<'a> {
+    /// Layout: rpIdHash (32) || flags (1) || signCount (4) || attestedCredentialData?
+    fn parse(bytes: &'a [u8]) -> Result<AuthenticatorData<'a>, String> {
+        let malformed = || "malformed authenticator data".to_string();
+
+        if bytes.len() < 37 {
+            return Err(malformed());
+        }
+        let flags = bytes[32];
+        let sign_count = u32::from_be_bytes([bytes[33], bytes[34], bytes[35], bytes[36]]);
+
+        let (credential_id, public_key) = if flags & FLAG_ATTESTED_CREDENTIAL_DATA != 0 {
+            // aaguid (16) || credentialIdLength (2) || credentialId || credentialPublicKey
+            let attested = bytes.get(37..).ok_or_else(malformed)?;
+            if attested.len() < 18 {
+                return Err(malformed());
+            }
+            let id_length = u16::from_be_bytes([attested[16], attested[17]]) as usize;
+            let credential_id = attested.get(18..18 + id_length).ok_or_else(malformed)?;
+
+            // The key is followed by the extensions when there are any, so its length is
+            // only known once the CBOR item is read.
+            let mut key_reader = &attested[18 + id_length..];
+            let remaining = key_reader.len();
+            ciborium::de::from_reader::<Value, _>(&mut key_reader).map_err(|_| malformed())?;
+            let key_length = remaining - key_reader.len();
+            let start = 18 + id_length;
+
+            (
+                Some(credential_id),
+                Some(&attested[start..start + key_length]),
+            )
+        } else {
+            (None, None)
+        };
+
+        Ok(AuthenticatorData {
+            rp_id_hash: &bytes[..32],
+            flags,
+            sign_count,
+            credential_id,
+            public_key,
+        })
+    }
+
+    fn verify(
+        &self,
+        relying_party: &RelyingParty,
+        require_user_verification: bool,
+    ) -> Result<(), String> {
+        if self.rp_id_hash != Sha256::digest(relying_party.id.as_bytes()).as_slice() {
+            return Err("unexpected relying party".to_string());
+        }
+        if self.flags & FLAG_USER_PRESENT == 0 {
+            return Err("user not present".to_string());
+        }
+        if require_user_verification && self.flags & FLAG_USER_VERIFIED == 0 {
+            return Err("user not verified".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+fn cose_field(key: &[(Value, Value)], label: i64) -> Option<&Value> {
+    key.iter()
+        .find(|(name, _)| name.as_integer().map(i128::from) == Some(label.into()))
+        .map(|(_, value)| value)
+}
+
+fn cose_integer(key: &[(Value, Value)], label: i64) -> Option<i64> {
+    match cose_field(key, label)? {
+        Value::Integer(value) => i64::try_from(*value).ok(),
+        _ => None,
+    }
+}
+
+fn cose_bytes(key: &[(Value, Value)], label: i64) -> Option<&[u8]> {
+    match cose_field(key, label)? {
+        Value::Bytes(value) => Some(value),
+        _ => None,
+    }
+}
+
+/// Public key of a credential as read from its COSE_Key (RFC 9053).
+enum CredentialPublicKey {
+    Es256(Vec<u8>),
+    EdDsa(Vec<u8>),
+    Rs256 { n: Vec<u8>, e: Vec<u8> },
+}
+
+impl CredentialPublicKey {
+    fn parse(cose_key: &[u8]) -> Result<(CredentialPublicKey, i64), String> {
+        let unsupported = || "unsupported credential public key".to_string();
+
+        let key = match ciborium::de::from_reader::<Value, _>(cose_key) {
+            Ok(Value::Map(key)) => key,
+            _ => return Err("malformed credential public key".to_string()),
+        };
+        let algorithm = cose_integer(&key, 3).ok_or_else(unsupported)?;
+        let key_type = cose_integer(&key, 1).ok_or_else(unsupported)?;
+
+        let public_key = match (algorithm, key_type) {
+            // EC2 on P-256: x (-2) and y (-3) as an uncompressed SEC1 point.
+            (COSE_ALGORITHM_ES256, 2) if cose_integer(&key, -1) == Some(1) => {
+                let x = cose_bytes(&key, -2).ok_or_else(unsupported)?;
+                let y = cose_bytes(&key, -3).ok_or_else(unsupported)?;
+                if x.len() != 32 || y.len() != 32 {
+                    return Err(unsupported());
+                }
+                CredentialPublicKey::Es256([&[0x04], x, y].concat())
+            }
+            // OKP on Ed25519.
+            (COSE_ALGORITHM_EDDSA, 1) if cose_integer(&key, -1) == Some(6) => {
+                CredentialPublicKey::EdDsa(cose_bytes(&key, -2).ok_or_else(unsupported)?.to_vec())
+            }
+            (COSE_ALGORITHM_RS256, 3) => CredentialPublicKey::Rs256 {
+                n: cose_bytes(&key, -1).ok_or_else(unsupported)?.to_vec(),
+                e: cose_bytes(&key, -2).ok_or_else(unsupported)?.to_vec(),
+            },
+            _ => return Err(unsupported()),
+        };
+
+        Ok((public_key, algorithm))
+    }
+
+    fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        match self {
+            CredentialPublicKey::Es256(point) => {
+                UnparsedPublicKey::new(&ECDSA_P256_SHA256_ASN1, point)
+                    .verify(message, signature)
+                    .is_ok()
+            }
+            CredentialPublicKey::EdDsa(point) => UnparsedPublicKey::new(&ED25519, point)
+                .verify(message, signature)
+                .is_ok(),
+            CredentialPublicKey::Rs256 { n, e } => RsaPublicKeyComponents { n, e }
+                .verify(&RSA_PKCS1_2048_8192_SHA256, message, signature)
+                .is_ok(),
+        }
+    }
+}
+
+fn invalid_registration(reason: String) -> AppError {
+    AppError::new(
+        Code::InvalidArgument,
+        format!("Invalid passkey registration: {}", reason),
+    )
+}
+
+fn invalid_assertion(reason: String) -> AppError {
+    AppError::new(
+        Code::Unauthenticated,
+        format!("Invalid passkey assertion: {}", reason),
+    )
+}
+
+/// Verify the response to a registration ceremony. Only the "none" attestation format
+/// is accepted, authenticators are not vouched for by their manufacturer.
+pub fn verify_registration(
+    relying_party: &RelyingParty,
+    challenge: &str,
+    client_data_json: &[u8],
+    attestation_object: &[u8],
+) -> Result<RegisteredCredential, AppError> {
+    verify_client_data(
+        client_data_json,
+        CLIENT_DATA_TYPE_CREATE,
+        challenge,
+        relying_party,
+    )
+    .map_err(invalid_registration)?;
+
+    let attestation = match ciborium::de::from_reader::<Value, _>(attestation_object) {
+        Ok(Value::Map(attestation)) => attestation,
+        _ => return Err(invalid_registration("malformed attestation".to_string())),
+    };
+    let field = |name: &str| {
+        attestation
+            .iter()
+            .find(|(key, _)| key.as_text() == Some(name))
+            .map(|(_, value)| value)
+    };
+
+    if field("fmt").and_then(Value::as_text) != Some("none") {
+        return Err(invalid_registration(
+            "unsupported attestation format".to_string(),
+        ));
+    }
+    if !field("attStmt")
+        .and_then(Value::as_map)
+        .is_some_and(|statement| statement.is_empty())
+    {
+        return Err(invalid_registration(
+            "unexpected attestation statement".to_string(),
+        ));
+    }
+    let auth_data = field("authData")
+        .and_then(Value::as_bytes)
+        .ok_or_else(|| invalid_registration("malformed attestation".to_string()))?;
+
+    let auth_data = AuthenticatorData::parse(auth_data).map_err(invalid_registration)?;
+    auth_data
+        .verify(relying_party, false)
+        .map_err(invalid_registration)?;
+
+    let (credential_id, public_key) = match (auth_data.credential_id, auth_data.public_key) {
+        (Some(credential_id), Some(public_key)) => (credential_id, public_key),
+        _ => {
+            return Err(invalid_registration(
+                "missing attested credential".to_string(),
+            ))
+        }
+    };
+    let (_, algorithm) = CredentialPublicKey::parse(public_key).map_err(invalid_registration)?;
+
+    Ok(RegisteredCredential {
+        credential_id: credential_id.to_vec(),
+        public_key: public_key.to_vec(),
+        algorithm,
+        sign_count: auth_data.sign_count,
+    })
+}
+
+/// Verify the response to an authentication ceremony against the stored public key,
+/// returning the sign counter reported by the authenticator.
+pub fn verify_assertion(
+    relying_party: &RelyingParty,
+    challenge: &str,
+    public_key: &[u8],
+    client_data_json: &[u8],
+    authenticator_data: &[u8],
+    signature: &[u8],
+    require_user_verification: bool,
+) -> Result<u32, AppError> {
+    verify_client_data(
+        client_data_json,
+        CLIENT_DATA_TYPE_GET,
+        challenge,
+        relying_party,
+    )
+    .map_err(invalid_assertion)?;
+
+    let auth_data = AuthenticatorData::parse(authenticator_data).map_err(invalid_assertion)?;
+    auth_data
+        .verify(relying_party, require_user_verification)
+        .map_err(invalid_assertion)?;
+
+    let (public_key, _) = CredentialPublicKey::parse(public_key).map_err(invalid_assertion)?;
+    let signed_data = [authenticator_data, &Sha256::digest(client_data_json)].concat();
+
+    if !public_key.verify(&signed_data, signature) {
+        return Err(invalid_assertion("bad signature".to_string()));
+    }
+
+    Ok(auth_data.sign_count)
+}
+
+pub fn encode_credential_id(credential_id: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(credential_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use ring::{
+        rand::SystemRandom,
+        signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING},
+    };
+    use serde_json::json;
+
+    use super::*;
+
+    const CHALLENGE: &str = "c2VjcmV0LWNoYWxsZW5nZQ";
+
+    fn relying_party() -> RelyingParty {
+        RelyingParty {
+            id: "localhost".to_string(),
+            name: "Authentication".to_string(),
+            origin: "http://localhost:8080".to_string(),
+        }
+    }
+
+    fn cbor(value: Value) -> Vec<u8> {
+        let mut bytes = vec![];
+        ciborium::ser::into_writer(&value, &mut bytes).unwrap();
+        bytes
+    }
+
+    fn client_data(ceremony: &str, challenge: &str) -> Vec<u8> {
+        json!({ "type": ceremony, "challenge": challenge, "origin": "http://localhost:8080" })
+            .to_string()
+            .into_bytes()
+    }
+
+    fn authenticator_data(flags: u8, sign_count: u32, attested: &[u8]) -> Vec<u8> {
+        [
+            Sha256::digest(b"localhost").as_slice(),
+            &[flags],
+            &sign_count.to_be_bytes(),
+            attested,
+        ]
+        .concat()
+    }
+
+    struct Authenticator {
+        key_pair: EcdsaKeyPair,
+    }
+
+    impl Authenticator {
+        fn new() -> Authenticator {
+            let random = SystemRandom::new();
+            let pkcs8 =
+                EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &random).unwrap();
+            Authenticator {
+                key_pair: EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref())
+                    .unwrap(),
+            }
+        }
+
+        fn cose_key(&self) -> Vec<u8> {
+            let point = self.key_pair.public_key().as_ref();
+            cbor(Value::Map(vec![
+                (1.into(), 2.into()),
+                (3.into(), COSE_ALGORITHM_ES256.into()),
+                ((-1).into(), 1.into()),
+                ((-2).into(), Value::Bytes(point[1..33].to_vec())),
+                ((-3).into(), Value::Bytes(point[33..65].to_vec())),
+            ]))
+        }
+
+        fn attestation_object(&self, format: &str) -> Vec<u8> {
+            let credential_id = b"credential-id";
+            let attested = [
+                &[0u8; 16][..],
+                &(credential_id.len() as u16).to_be_bytes(),
+                credential_id,
+                &self.cose_key(),
+            ]
+            .concat();
+
+            cbor(Value::Map(vec![
+                ("fmt".into(), format.into()),
+                ("attStmt".into(), Value::Map(vec![])),
+                (
+                    "authData".into(),
+                    Value::Bytes(authenticator_data(0x41, 0, &attested)),
+                ),
+            ]))
+        }
+
+        fn sign(&self, authenticator_data: &[u8], client_data_json: &[u8]) -> Vec<u8> {
+            let signed_data = [authenticator_data, &Sha256::digest(client_data_json)].concat();
+            self.key_pair
+                .sign(&SystemRandom::new(), &signed_data)
+                .unwrap()
+                .as_ref()
+                .to_vec()
+        }
+    }
+
+    #[test]
+    fn test_registration_and_assertion() {
+        let authenticator = Authenticator::new();
+
+        let credential = verify_registration(
+            &relying_party(),
+            CHALLENGE,
+            &client_data("webauthn.create", CHALLENGE),
+            &authenticator.attestation_object("none"),
+        )
+        .unwrap();
+        assert_eq!(credential.credential_id, b"credential-id");
+        assert_eq!(credential.algorithm, COSE_ALGORITHM_ES256);
+
+        let client_data_json = client_data("webauthn.get", CHALLENGE);
+        let auth_data = authenticator_data(0x05, 7, &[]);
+        let sign_count = verify_assertion(
+            &relying_party(),
+            CHALLENGE,
+            &credential.public_key,
+            &client_data_json,
+            &auth_data,
+            &authenticator.sign(&auth_data, &client_data_json),
+            true,
+        )
+        .unwrap();
+        assert_eq!(sign_count, 7);
+    }
+
+    #[test]
+    fn test_registration_refuses_attestation_and_other_challenges() {
+        let authenticator = Authenticator::new();
+
+        let error = verify_registration(
+            &relying_party(),
+            CHALLENGE,
+            &client_data("webauthn.create", CHALLENGE),
+            &authenticator.attestation_object("packed"),
+        )
+        .unwrap_err();
+        assert_eq!(error.code, Code::InvalidArgument);
+
+        let error = verify_registration(
+            &relying_party(),
+            CHALLENGE,
+            &client_data("webauthn.create", "b3RoZXI"),
+            &authenticator.attestation_object("none"),
+        )
+        .unwrap_err();
+        assert_eq!(error.code, Code::InvalidArgument);
+    }
+
+    #[test]
+    fn test_assertion_refuses_bad_signature_and_missing_verification() {
+        let authenticator = Authenticator::new();
+        let other_authenticator = Authenticator::new();
+        let client_data_json = client_data("webauthn.get", CHALLENGE);
+
+        let auth_data = authenticator_data(0x05, 1, &[]);
+        let error = verify_assertion(
+            &relying_party(),
+            CHALLENGE,
+            &authenticator.cose_key(),
+            &client_data_json,
+            &auth_data,
+            &other_authenticator.sign(&auth_data, &client_data_json),
+            true,
+        )
+        .unwrap_err();
+        assert_eq!(error.code, Code::Unauthenticated);
+
+        let auth_data = authenticator_data(0x01, 1, &[]);
+        let error = verify_assertion(
+            &relying_party(),
+            CHALLENGE,
+            &authenticator.cose_key(),
+            &client_data_json,
+            &auth_data,
+            &authenticator.sign(&auth_data, &client_data_json),
+            true,
+        )
+        .unwrap_err();
+        assert_eq!(error.code, Code::Unauthenticated);
+    }
+}
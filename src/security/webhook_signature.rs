@@ -0,0 +1,41 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Sign a webhook delivery as `sha256=<hex HMAC-SHA256 of "{timestamp}.{body}">`.
+///
+/// Receivers recompute the signature with the shared secret and the `X-Webhook-Timestamp`
+/// header, binding the timestamp to the body so old deliveries cannot be replayed.
+pub fn sign_webhook_payload(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_webhook_payload() {
+        let signature = sign_webhook_payload("secret", 1682935200, r#"{"event_id":1}"#);
+
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert_eq!(
+            signature,
+            sign_webhook_payload("secret", 1682935200, r#"{"event_id":1}"#)
+        );
+        assert_ne!(
+            signature,
+            sign_webhook_payload("other secret", 1682935200, r#"{"event_id":1}"#)
+        );
+        assert_ne!(
+            signature,
+            sign_webhook_payload("secret", 1682935201, r#"{"event_id":1}"#)
+        );
+    }
+}
@@ -1,45 +1,193 @@
-use crate::database::connection::get_postgres_pool;
-use sqlx::{Pool, Postgres};
-use std::env;
-use tonic::transport::Server;
-
-mod controllers;
-mod database;
-mod dtos;
-mod error;
-mod models;
-mod repositories;
-mod rpc;
-mod security;
-mod services;
-mod utils;
-
-use crate::rpc::authentication::{
-    authentication::authentication_server::AuthenticationServer, AuthenticationService,
-};
-
-pub struct AppState {
-    db_pg_pool: Pool<Postgres>,
-    redis_client: redis::Client,
+use authentication_gRPC::config::app_config::{Config, StorageBackend};
+use authentication_gRPC::database::connection::{connect_postgres_pool, lazy_postgres_pool};
+use authentication_gRPC::database::redis_pool::RedisPool;
+use authentication_gRPC::database::storage::Storage;
+use authentication_gRPC::http::metrics::metrics_router;
+use authentication_gRPC::http::oauth::oauth_router;
+use authentication_gRPC::rpc::router::grpc_router;
+use authentication_gRPC::security::jwt::init_jwt_config;
+use authentication_gRPC::security::oidc::oidc_signing_key;
+use authentication_gRPC::security::tls::{run_tls_reloader, tls_incoming, ServerTls};
+use authentication_gRPC::services::events::outbox_dispatcher::run_outbox_dispatcher;
+use authentication_gRPC::services::webhooks::webhook_delivery_worker::run_webhook_delivery_worker;
+use authentication_gRPC::telemetry::logging::init_logging;
+use authentication_gRPC::telemetry::trace_export::shutdown_trace_export;
+use authentication_gRPC::utils::hash::password::set_bcrypt_cost;
+use authentication_gRPC::AppState;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::watch;
+
+/// Keeps users and their codes in the memory of the process, for demos and end-to-end
+/// tests without Postgres or Redis.
+const IN_MEMORY_FLAG: &str = "--in-memory";
+
+/// Time given to in-flight requests and open streams once a shutdown was requested.
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+/// Resolves on SIGTERM, as sent by orchestrators, or on Ctrl-C.
+async fn shutdown_signal() {
+    let terminate = async {
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(error) => {
+                tracing::error!(%error, "Failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate => {}
+    }
+}
+
+async fn shutdown_requested(mut shutdown: watch::Receiver<bool>) {
+    while !*shutdown.borrow() {
+        if shutdown.changed().await.is_err() {
+            return;
+        }
+    }
 }
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     dotenv::from_filename(".env.development").ok();
+    // Set before the config is read, which then no longer asks for Postgres, Redis and the
+    // JWT secret.
+    if std::env::args().skip(1).any(|arg| arg == IN_MEMORY_FLAG) {
+        std::env::set_var("STORAGE_BACKEND", "memory");
+    }
+    let config = Config::load().map_err(|error| error.message)?;
+    init_logging(&config.telemetry);
+    init_jwt_config(config.jwt.clone());
+    set_bcrypt_cost(config.password.bcrypt_cost);
+    oidc_signing_key(&config.oidc).map_err(|error| error.message)?;
+
+    let db_pg_pool = match config.storage.backend {
+        StorageBackend::Postgres => connect_postgres_pool(&config.database).await,
+        // Never connected then, the services needing Postgres are refused.
+        StorageBackend::Sqlite | StorageBackend::Memory => lazy_postgres_pool(&config.database),
+    };
+    let db_pg_pool = db_pg_pool.map_err(|error| error.message)?;
+    let storage = Storage::open(&config.storage)
+        .await
+        .map_err(|error| error.chain())?;
+    let redis = RedisPool::open(&config.redis).map_err(|error| error.chain())?;
 
     let app_state = AppState {
-        db_pg_pool: get_postgres_pool(None).await,
-        redis_client: redis::Client::open(env::var("REDIS_CLIENT").unwrap()).unwrap(),
+        db_pg_pool,
+        redis,
+        storage,
+        config: Arc::new(config),
+    };
+
+    // The user events and their webhooks come from the Postgres outbox.
+    if let Storage::Postgres = app_state.storage {
+        tokio::spawn(run_outbox_dispatcher(
+            app_state.db_pg_pool.clone(),
+            app_state.redis.client().clone(),
+            Duration::from_secs(1),
+        ));
+        tokio::spawn(run_webhook_delivery_worker(
+            app_state.db_pg_pool.clone(),
+            Duration::from_secs(5),
+        ));
+    }
+
+    let (shutdown_sender, shutdown) = watch::channel(false);
+
+    // The OAuth clients, consents and tokens reference the users in Postgres.
+    if let Storage::Postgres = app_state.storage {
+        let http_addr = app_state.config.server.http_address;
+        let oauth_server = axum::Server::bind(&http_addr)
+            .serve(oauth_router(app_state.clone()).into_make_service())
+            .with_graceful_shutdown(shutdown_requested(shutdown.clone()));
+        tokio::spawn(async move {
+            if let Err(error) = oauth_server.await {
+                tracing::error!(%error, "OAuth HTTP server stopped");
+            }
+        });
+
+        tracing::info!(address = %http_addr, "OAuth HTTP server listening");
+    } else {
+        tracing::warn!("OAuth HTTP server not started, it needs STORAGE_BACKEND=postgres");
+    }
+
+    let metrics_addr = app_state.config.server.metrics_address;
+    let metrics_server = axum::Server::bind(&metrics_addr)
+        .serve(metrics_router(app_state.clone()).into_make_service())
+        .with_graceful_shutdown(shutdown_requested(shutdown.clone()));
+    tokio::spawn(async move {
+        if let Err(error) = metrics_server.await {
+            tracing::error!(%error, "Metrics HTTP server stopped");
+        }
+    });
+
+    tracing::info!(address = %metrics_addr, "Metrics HTTP server listening");
+
+    let addr = app_state.config.server.grpc_address;
+    let tls_config = app_state.config.tls.clone();
+    let db_pg_pool = app_state.db_pg_pool.clone();
+    let grpc_server = grpc_router(app_state);
+
+    let mut grpc_task = tokio::spawn(async move {
+        match tls_config {
+            Some(tls_config) => {
+                let reload_interval = Duration::from_secs(tls_config.reload_interval_seconds);
+                let tls = Arc::new(ServerTls::load(tls_config).map_err(|error| error.message)?);
+                tokio::spawn(run_tls_reloader(tls.clone(), reload_interval));
+
+                let listener = TcpListener::bind(addr).await?;
+                tracing::info!(address = %addr, "gRPC server listening with TLS");
+
+                grpc_server
+                    .serve_with_incoming_shutdown(
+                        tls_incoming(listener, tls),
+                        shutdown_requested(shutdown),
+                    )
+                    .await?;
+            }
+            None => {
+                tracing::info!(address = %addr, "gRPC server listening");
+
+                grpc_server
+                    .serve_with_shutdown(addr, shutdown_requested(shutdown))
+                    .await?;
+            }
+        }
+        Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+    });
+
+    let stopped = tokio::select! {
+        _ = shutdown_signal() => None,
+        result = &mut grpc_task => Some(result),
     };
 
-    let addr = "0.0.0.0:50051".parse()?;
-    let authentication_service = AuthenticationService::new(app_state);
+    match stopped {
+        Some(result) => result??,
+        None => {
+            tracing::info!("Shutting down, waiting for in-flight requests");
+            let _ = shutdown_sender.send(true);
 
-    println!("Server listening on {}", addr);
+            match tokio::time::timeout(SHUTDOWN_GRACE_PERIOD, grpc_task).await {
+                Ok(result) => result??,
+                Err(_) => tracing::warn!(
+                    grace_period_seconds = SHUTDOWN_GRACE_PERIOD.as_secs(),
+                    "Requests still running, stopping anyway"
+                ),
+            }
+        }
+    }
 
-    Server::builder()
-        .add_service(AuthenticationServer::new(authentication_service))
-        .serve(addr)
-        .await?;
+    db_pg_pool.close().await;
+    tracing::info!("Server stopped");
+    // Flushing blocks until the collector answered.
+    tokio::task::spawn_blocking(shutdown_trace_export).await?;
     Ok(())
 }
@@ -0,0 +1,110 @@
+use crate::{
+    dtos::events::dtos_user_event::UserEvent, error::AppError, telemetry::metrics::metrics,
+    utils::adapters::redis_error_to_app_error::redis_error_to_app_error,
+};
+use async_trait::async_trait;
+use mockall::automock;
+
+pub const USER_EVENTS_STREAM: &str = "authentication:user_events";
+
+/// Transport used by the outbox dispatcher to deliver user events to other services.
+#[async_trait]
+#[automock]
+pub trait EventPublisher: Send + Sync {
+    async fn publish(&self, event: &UserEvent) -> Result<String, AppError>;
+}
+
+/// Publish every event to each publisher in turn, failing as soon as one of them fails.
+pub struct EventPublisherFanout<'a> {
+    pub publishers: Vec<Box<dyn EventPublisher + 'a>>,
+}
+
+#[async_trait]
+impl EventPublisher for EventPublisherFanout<'_> {
+    async fn publish(&self, event: &UserEvent) -> Result<String, AppError> {
+        let mut receipts = Vec::new();
+        for publisher in &self.publishers {
+            receipts.push(publisher.publish(event).await?);
+        }
+
+        Ok(receipts.join(", "))
+    }
+}
+
+pub struct EventPublisherRedisStreams<'a> {
+    pub client: &'a redis::Client,
+    pub stream: String,
+}
+
+#[async_trait]
+impl EventPublisher for EventPublisherRedisStreams<'_> {
+    async fn publish(&self, event: &UserEvent) -> Result<String, AppError> {
+        let _timer = metrics().redis_call_timer("user_events.publish");
+        let mut connection = self
+            .client
+            .get_async_connection()
+            .await
+            .map_err(redis_error_to_app_error)?;
+
+        let entry_id: String = redis::cmd("XADD")
+            .arg(&self.stream)
+            .arg("*")
+            .arg("event_id")
+            .arg(event.id)
+            .arg("event_type")
+            .arg(&event.event_type)
+            .arg("user_id")
+            .arg(&event.user_id)
+            .arg("payload")
+            .arg(&event.payload)
+            .arg("created_at")
+            .arg(event.created_at.timestamp())
+            .query_async(&mut connection)
+            .await
+            .map_err(redis_error_to_app_error)?;
+
+        Ok(entry_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use chrono::Utc;
+    use redis::{streams::StreamRangeReply, AsyncCommands};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_redis_streams_publish() {
+        dotenv::from_filename(".env.test").ok();
+        let client = redis::Client::open(env::var("REDIS_CLIENT").unwrap()).unwrap();
+        let publisher = EventPublisherRedisStreams {
+            client: &client,
+            stream: "test_redis_streams_publish".to_string(),
+        };
+
+        let entry_id = publisher
+            .publish(&UserEvent {
+                id: 1,
+                event_type: "UserRegistered".to_string(),
+                user_id: "UserFakeID".to_string(),
+                payload: r#"{"id":"UserFakeID"}"#.to_string(),
+                created_at: Utc::now().naive_utc(),
+            })
+            .await
+            .unwrap();
+
+        let mut connection = client.get_async_connection().await.unwrap();
+        let reply: StreamRangeReply = connection
+            .xrange("test_redis_streams_publish", &entry_id, &entry_id)
+            .await
+            .unwrap();
+
+        assert_eq!(reply.ids.len(), 1);
+        let entry = &reply.ids[0];
+        assert_eq!(entry.get::<String>("event_type").unwrap(), "UserRegistered");
+        assert_eq!(entry.get::<String>("user_id").unwrap(), "UserFakeID");
+    }
+}
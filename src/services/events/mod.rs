@@ -0,0 +1,3 @@
+pub mod event_publisher;
+pub mod outbox_dispatcher;
+pub mod user_events_watcher;
@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    error::AppError,
+    repositories::user_events_outbox_repository::{
+        UserEventsOutboxRepository, UserEventsOutboxRepositoryPostgres,
+    },
+    repositories::webhook_delivery_repository::WebhookDeliveryRepositoryPostgres,
+    services::{
+        events::event_publisher::{
+            EventPublisher, EventPublisherFanout, EventPublisherRedisStreams, USER_EVENTS_STREAM,
+        },
+        webhooks::webhook_event_publisher::EventPublisherWebhooks,
+    },
+};
+
+pub struct OutboxDispatcher<R, P> {
+    pub outbox_repository: R,
+    pub publisher: P,
+    pub batch_size: i64,
+}
+
+impl<R: UserEventsOutboxRepository, P: EventPublisher> OutboxDispatcher<R, P> {
+    /// Publish the pending events in order, returning how many were delivered.
+    ///
+    /// It stops at the first failure so that events are never published out of order,
+    /// the remaining events are retried on the next call. Delivery is at-least-once:
+    /// an event published but not marked (e.g. crash between both) is published again.
+    pub async fn dispatch_pending(&self) -> Result<usize, AppError> {
+        let events = self
+            .outbox_repository
+            .fetch_unpublished(self.batch_size)
+            .await?;
+
+        let mut published = 0;
+        for event in events {
+            self.publisher.publish(&event).await?;
+            self.outbox_repository.mark_published(event.id).await?;
+            published += 1;
+        }
+
+        Ok(published)
+    }
+}
+
+/// Background task that drains the outbox into Redis Streams and the webhook deliveries,
+/// waiting `interval` whenever the outbox is empty or the delivery fails.
+pub async fn run_outbox_dispatcher(
+    pool: Pool<Postgres>,
+    redis_client: redis::Client,
+    interval: Duration,
+) {
+    let dispatcher = OutboxDispatcher {
+        outbox_repository: UserEventsOutboxRepositoryPostgres { pool: &pool },
+        publisher: EventPublisherFanout {
+            publishers: vec![
+                Box::new(EventPublisherRedisStreams {
+                    client: &redis_client,
+                    stream: USER_EVENTS_STREAM.to_string(),
+                }),
+                Box::new(EventPublisherWebhooks {
+                    delivery_repository: WebhookDeliveryRepositoryPostgres { pool: &pool },
+                }),
+            ],
+        },
+        batch_size: 100,
+    };
+
+    loop {
+        match dispatcher.dispatch_pending().await {
+            Ok(published) if published > 0 => continue,
+            Ok(_) => (),
+            Err(error) => tracing::error!(error = %error.message, "Failed to dispatch user events"),
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
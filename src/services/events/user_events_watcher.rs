@@ -0,0 +1,55 @@
+use crate::{
+    dtos::events::dtos_user_event::{UserEvent, UserEventType},
+    error::{AppError, Code},
+    repositories::user_events_outbox_repository::UserEventsOutboxRepository,
+};
+
+/// Reads the outbox as an ordered log for subscribers of `WatchUserEvents`.
+///
+/// The outbox id is used as the offset, so a subscriber that reconnects with the
+/// offset of the last event it received does not miss any event. Events are committed in
+/// the order of their ids (see `store_user_event`), so none appears below an offset
+/// already read.
+pub struct UserEventsWatcher<R> {
+    pub outbox_repository: R,
+    pub event_types: Vec<UserEventType>,
+    pub batch_size: i64,
+}
+
+impl<R: UserEventsOutboxRepository> UserEventsWatcher<R> {
+    /// Return the events after `offset` matching `event_types` (all events when empty)
+    /// and the offset the next poll should start from.
+    pub async fn poll(&self, offset: i64) -> Result<(Vec<UserEvent>, i64), AppError> {
+        let events = self
+            .outbox_repository
+            .fetch_after(offset, self.batch_size)
+            .await?;
+
+        let next_offset = events.last().map_or(offset, |event| event.id);
+
+        let events = events
+            .into_iter()
+            .filter(|event| {
+                self.event_types.is_empty()
+                    || UserEventType::parse(&event.event_type)
+                        .is_some_and(|event_type| self.event_types.contains(&event_type))
+            })
+            .collect();
+
+        Ok((events, next_offset))
+    }
+}
+
+pub fn parse_event_types(event_types: Vec<String>) -> Result<Vec<UserEventType>, AppError> {
+    event_types
+        .iter()
+        .map(|event_type| {
+            UserEventType::parse(event_type).ok_or_else(|| {
+                AppError::new(
+                    Code::InvalidArgument,
+                    format!("Unknown event type: {}", event_type),
+                )
+            })
+        })
+        .collect()
+}
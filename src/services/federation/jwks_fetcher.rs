@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use jsonwebtoken::jwk::JwkSet;
+use mockall::automock;
+
+use crate::error::{AppError, Code};
+
+/// Source of the signing keys published by the upstream identity providers.
+#[async_trait]
+#[automock]
+pub trait JwksFetcher: Send + Sync {
+    async fn fetch(&self, jwks_uri: String) -> Result<JwkSet, AppError>;
+}
+
+pub struct JwksFetcherHttp {
+    pub client: reqwest::Client,
+}
+
+impl JwksFetcherHttp {
+    pub fn new(timeout: Duration) -> JwksFetcherHttp {
+        JwksFetcherHttp {
+            client: reqwest::Client::builder()
+                .timeout(timeout)
+                .build()
+                .expect("Unable to build the JWKS HTTP client"),
+        }
+    }
+}
+
+#[async_trait]
+impl JwksFetcher for JwksFetcherHttp {
+    async fn fetch(&self, jwks_uri: String) -> Result<JwkSet, AppError> {
+        let response = match self.client.get(&jwks_uri).send().await {
+            Ok(response) if response.status().is_success() => response,
+            Ok(response) => {
+                return Err(AppError::new(
+                    Code::Internal,
+                    format!(
+                        "failed to fetch JWKS {}: HTTP {}",
+                        jwks_uri,
+                        response.status()
+                    ),
+                ))
+            }
+            Err(error) => {
+                return Err(AppError::new(
+                    Code::Internal,
+                    format!("failed to fetch JWKS {}: {}", jwks_uri, error),
+                ))
+            }
+        };
+
+        match response.bytes().await {
+            Ok(body) => serde_json::from_slice(&body).map_err(|error| {
+                AppError::new(
+                    Code::Internal,
+                    format!("invalid JWKS {}: {}", jwks_uri, error),
+                )
+            }),
+            Err(error) => Err(AppError::new(
+                Code::Internal,
+                format!("failed to fetch JWKS {}: {}", jwks_uri, error),
+            )),
+        }
+    }
+}
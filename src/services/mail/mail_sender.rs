@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use mockall::automock;
+use serde_json::json;
+
+use crate::{
+    config::app_config::MailConfig,
+    error::{AppError, Code},
+    i18n::Email,
+};
+
+/// Transport of the emails carrying the activation, recovery and login codes.
+#[async_trait]
+#[automock]
+pub trait MailSender: Send + Sync {
+    async fn send(&self, to: String, email: Email) -> Result<(), AppError>;
+}
+
+/// Posts each email as JSON to the mail API of `MailConfig`, with its key as bearer token.
+pub struct MailSenderHttp<'a> {
+    pub client: reqwest::Client,
+    pub api_url: &'a str,
+    pub api_key: Option<&'a str>,
+    pub from: &'a str,
+}
+
+impl<'a> MailSenderHttp<'a> {
+    pub fn new(config: &'a MailConfig, api_url: &'a str, timeout: Duration) -> MailSenderHttp<'a> {
+        MailSenderHttp {
+            client: reqwest::Client::builder()
+                .timeout(timeout)
+                .build()
+                .expect("Unable to build the mail HTTP client"),
+            api_url,
+            api_key: config.api_key.as_deref(),
+            from: &config.from,
+        }
+    }
+}
+
+#[async_trait]
+impl MailSender for MailSenderHttp<'_> {
+    async fn send(&self, to: String, email: Email) -> Result<(), AppError> {
+        let body = json!({
+            "from": self.from,
+            "to": to,
+            "subject": email.subject,
+            "text": email.body,
+        })
+        .to_string();
+        let mut request = self
+            .client
+            .post(self.api_url)
+            .header("Content-Type", "application/json")
+            .body(body);
+        if let Some(api_key) = self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        match request.send().await {
+            Ok(response) if response.status().is_success() => Ok(()),
+            Ok(response) => Err(AppError::new(
+                Code::Unavailable,
+                format!("the mail API answered {}", response.status()),
+            )),
+            Err(error) => Err(AppError::new(
+                Code::Unavailable,
+                format!("failed to send email: {}", error),
+            )),
+        }
+    }
+}
+
+/// Logs the recipient and subject of each email instead of sending it, for development
+/// with `MAIL_LOG_ONLY`. The body carries the code, so it is never logged.
+pub struct MailSenderLog;
+
+#[async_trait]
+impl MailSender for MailSenderLog {
+    async fn send(&self, to: String, email: Email) -> Result<(), AppError> {
+        tracing::warn!(
+            to = %to,
+            subject = %email.subject,
+            "MAIL_LOG_ONLY is set, the email is not sent"
+        );
+        Ok(())
+    }
+}
+
+pub enum MailSenderBackend<'a> {
+    Http(MailSenderHttp<'a>),
+    Log(MailSenderLog),
+}
+
+#[async_trait]
+impl MailSender for MailSenderBackend<'_> {
+    async fn send(&self, to: String, email: Email) -> Result<(), AppError> {
+        match self {
+            MailSenderBackend::Http(sender) => sender.send(to, email).await,
+            MailSenderBackend::Log(sender) => sender.send(to, email).await,
+        }
+    }
+}
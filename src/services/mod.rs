@@ -1 +1,5 @@
-pub mod sanitizer;
\ No newline at end of file
+pub mod events;
+pub mod federation;
+pub mod mail;
+pub mod sanitizer;
+pub mod webhooks;
@@ -1,4 +1,7 @@
-use crate::error::*;
+use crate::{
+    error::*,
+    i18n::{t, Message},
+};
 use mockall::automock;
 use sanitizer::prelude::*;
 
@@ -14,7 +17,10 @@ pub struct SanitizeUser;
 impl SanitizeAuthentication for SanitizeUser {
     fn sanitize_username_input(&self, username: String) -> Result<String, AppError> {
         if username.is_empty() {
-            return Err(AppError::new(Code::InvalidArgument, "Username is empty"));
+            return Err(
+                AppError::new(Code::InvalidArgument, t(Message::UsernameEmpty))
+                    .with_field_violation("username", "must not be empty"),
+            );
         };
 
         let mut instance = StringSanitizer::from(username);
@@ -24,7 +30,7 @@ impl SanitizeAuthentication for SanitizeUser {
         if username_sanitized.is_empty() {
             return Err(AppError::new(
                 Code::Internal,
-                "Username is empty after sanitize",
+                t(Message::UsernameEmptyAfterSanitize),
             ));
         };
 
@@ -33,7 +39,8 @@ impl SanitizeAuthentication for SanitizeUser {
 
     fn sanitize_email_input(&self, email: String) -> Result<String, AppError> {
         if email.is_empty() {
-            return Err(AppError::new(Code::InvalidArgument, "Email is empty"));
+            return Err(AppError::new(Code::InvalidArgument, t(Message::EmailEmpty))
+                .with_field_violation("email", "must not be empty"));
         }
 
         let mut instance = StringSanitizer::from(email);
@@ -42,10 +49,10 @@ impl SanitizeAuthentication for SanitizeUser {
         let email_sanitized = instance.get();
 
         if email_sanitized.is_empty() {
-            return Err(AppError::new(
-                Code::InvalidArgument,
-                "Email is empty after sanitize",
-            ));
+            return Err(
+                AppError::new(Code::InvalidArgument, t(Message::EmailEmptyAfterSanitize))
+                    .with_field_violation("email", "must not be blank"),
+            );
         };
 
         Ok(email_sanitized)
@@ -53,7 +60,10 @@ impl SanitizeAuthentication for SanitizeUser {
 
     fn sanitize_password_input(&self, password: String) -> Result<String, AppError> {
         if password.is_empty() {
-            return Err(AppError::new(Code::InvalidArgument, "Password is empty"));
+            return Err(
+                AppError::new(Code::InvalidArgument, t(Message::PasswordEmpty))
+                    .with_field_violation("password", "must not be empty"),
+            );
         }
 
         let mut instance = StringSanitizer::from(password);
@@ -64,8 +74,9 @@ impl SanitizeAuthentication for SanitizeUser {
         if password_sanitized.is_empty() {
             return Err(AppError::new(
                 Code::InvalidArgument,
-                "Password is empty after sanitize",
-            ));
+                t(Message::PasswordEmptyAfterSanitize),
+            )
+            .with_field_violation("password", "must not be blank"));
         };
 
         Ok(password_sanitized)
@@ -22,7 +22,10 @@ mod tests {
 
         match sanitize.sanitize_username_input(username_input_dirty) {
             Ok(_) => panic!("Should have failed"),
-            Err(error) => assert_eq!(error.message, "Username is empty"),
+            Err(error) => {
+                assert_eq!(error.message, "Username is empty");
+                assert_eq!(error.field_violations[0].field, "username");
+            }
         }
 
         match sanitize.sanitize_username_input(username_input_dirty2) {
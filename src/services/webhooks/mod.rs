@@ -0,0 +1,3 @@
+pub mod webhook_delivery_worker;
+pub mod webhook_event_publisher;
+pub mod webhook_sender;
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    error::AppError,
+    repositories::webhook_delivery_repository::{
+        WebhookDeliveryRepository, WebhookDeliveryRepositoryPostgres,
+    },
+    services::webhooks::webhook_sender::{WebhookRequest, WebhookSender, WebhookSenderHttp},
+};
+
+pub struct WebhookDeliveryWorker<R, S> {
+    pub delivery_repository: R,
+    pub sender: S,
+    pub max_attempts: i32,
+    pub base_backoff: chrono::Duration,
+    pub batch_size: i64,
+}
+
+impl<R: WebhookDeliveryRepository, S: WebhookSender> WebhookDeliveryWorker<R, S> {
+    /// Attempt every due delivery once, returning how many were accepted by their receiver.
+    ///
+    /// A delivery is accepted on a 2xx answer. Otherwise it is retried with exponential
+    /// backoff (`base_backoff * 2^(attempts - 1)`) until `max_attempts`, after which it is
+    /// left in the dead-letter state.
+    pub async fn deliver_due(&self) -> Result<usize, AppError> {
+        let deliveries = self.delivery_repository.fetch_due(self.batch_size).await?;
+
+        let mut delivered = 0;
+        for delivery in deliveries {
+            let id = delivery.id;
+            let attempts = delivery.attempts + 1;
+
+            let request = WebhookRequest::signed(&delivery, Utc::now().timestamp());
+
+            let failure = match self.sender.send(request).await {
+                Ok(status) if (200..300).contains(&status) => None,
+                Ok(status) => Some(format!("receiver answered with HTTP {}", status)),
+                Err(error) => Some(error.message),
+            };
+
+            match failure {
+                None => {
+                    self.delivery_repository.mark_delivered(id).await?;
+                    delivered += 1;
+                }
+                Some(error) => {
+                    let next_attempt_at = if attempts >= self.max_attempts {
+                        None
+                    } else {
+                        Some(Utc::now().naive_utc() + self.backoff(attempts))
+                    };
+
+                    self.delivery_repository
+                        .mark_failed(id, attempts, next_attempt_at, error)
+                        .await?;
+                }
+            }
+        }
+
+        Ok(delivered)
+    }
+
+    fn backoff(&self, attempts: i32) -> chrono::Duration {
+        self.base_backoff * 2_i32.pow((attempts - 1).clamp(0, 16) as u32)
+    }
+}
+
+/// Background task delivering the pending webhooks, waiting `interval` between rounds.
+pub async fn run_webhook_delivery_worker(pool: Pool<Postgres>, interval: Duration) {
+    let worker = WebhookDeliveryWorker {
+        delivery_repository: WebhookDeliveryRepositoryPostgres { pool: &pool },
+        sender: WebhookSenderHttp::new(Duration::from_secs(10)),
+        max_attempts: 8,
+        base_backoff: chrono::Duration::seconds(30),
+        batch_size: 100,
+    };
+
+    loop {
+        if let Err(error) = worker.deliver_due().await {
+            tracing::error!(error = %error.message, "Failed to deliver webhooks");
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
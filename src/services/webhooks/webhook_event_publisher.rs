@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+
+use crate::{
+    dtos::events::dtos_user_event::UserEvent, error::AppError,
+    repositories::webhook_delivery_repository::WebhookDeliveryRepository,
+    services::events::event_publisher::EventPublisher,
+};
+
+/// Publish user events to webhooks by enqueuing a delivery for every subscribed webhook,
+/// the deliveries themselves are made by the `WebhookDeliveryWorker`.
+pub struct EventPublisherWebhooks<R> {
+    pub delivery_repository: R,
+}
+
+#[async_trait]
+impl<R: WebhookDeliveryRepository> EventPublisher for EventPublisherWebhooks<R> {
+    async fn publish(&self, event: &UserEvent) -> Result<String, AppError> {
+        let enqueued = self.delivery_repository.enqueue(event).await?;
+
+        Ok(format!("{} webhook deliveries enqueued", enqueued))
+    }
+}
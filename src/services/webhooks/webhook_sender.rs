@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use mockall::automock;
+use serde_json::{json, Value};
+
+use crate::{
+    dtos::repositories::dtos_repository_webhook::WebhookDelivery,
+    error::{AppError, Code},
+    security::webhook_signature::sign_webhook_payload,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookRequest {
+    pub url: String,
+    pub delivery_id: String,
+    pub event_type: String,
+    pub timestamp: i64,
+    pub signature: String,
+    pub body: String,
+}
+
+impl WebhookRequest {
+    /// Build the JSON body of a delivery and sign it with the webhook secret.
+    pub fn signed(delivery: &WebhookDelivery, timestamp: i64) -> WebhookRequest {
+        let data: Value = serde_json::from_str(&delivery.payload).unwrap_or(Value::Null);
+        let body = json!({
+            "event_id": delivery.event_id,
+            "event_type": delivery.event_type,
+            "user_id": delivery.user_id,
+            "data": data,
+        })
+        .to_string();
+        let signature = sign_webhook_payload(&delivery.secret, timestamp, &body);
+
+        WebhookRequest {
+            url: delivery.url.clone(),
+            delivery_id: delivery.id.to_string(),
+            event_type: delivery.event_type.clone(),
+            timestamp,
+            signature,
+            body,
+        }
+    }
+}
+
+/// Transport of webhook deliveries, returns the HTTP status answered by the receiver.
+#[async_trait]
+#[automock]
+pub trait WebhookSender: Send + Sync {
+    async fn send(&self, request: WebhookRequest) -> Result<u16, AppError>;
+}
+
+pub struct WebhookSenderHttp {
+    pub client: reqwest::Client,
+}
+
+impl WebhookSenderHttp {
+    pub fn new(timeout: Duration) -> WebhookSenderHttp {
+        WebhookSenderHttp {
+            client: reqwest::Client::builder()
+                .timeout(timeout)
+                .build()
+                .expect("Unable to build the webhook HTTP client"),
+        }
+    }
+}
+
+#[async_trait]
+impl WebhookSender for WebhookSenderHttp {
+    async fn send(&self, request: WebhookRequest) -> Result<u16, AppError> {
+        match self
+            .client
+            .post(&request.url)
+            .header("Content-Type", "application/json")
+            .header("X-Webhook-Id", &request.delivery_id)
+            .header("X-Webhook-Event", &request.event_type)
+            .header("X-Webhook-Timestamp", request.timestamp.to_string())
+            .header("X-Webhook-Signature", &request.signature)
+            .body(request.body)
+            .send()
+            .await
+        {
+            Ok(response) => Ok(response.status().as_u16()),
+            Err(error) => Err(AppError::new(
+                Code::Internal,
+                format!("failed to deliver webhook: {}", error),
+            )),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{config::app_config::TelemetryConfig, telemetry::trace_export::otlp_tracer};
+use tracing::Subscriber;
+use tracing_subscriber::{
+    fmt::format::FmtSpan, fmt::MakeWriter, layer::SubscriberExt, registry::LookupSpan, EnvFilter,
+};
+
+/// Levels logged when `RUST_LOG` is not set.
+pub const DEFAULT_LOG_FILTER: &str = "info";
+
+/// One JSON object per line, with the fields of the enclosing spans. A line is also
+/// written when a span closes, carrying its busy and idle time as the latency.
+///
+/// Spans and events only ever carry ids, names and outcomes: passwords, codes and
+/// tokens are skipped where the spans are declared.
+pub fn json_subscriber<W>(
+    make_writer: W,
+    filter: EnvFilter,
+) -> impl Subscriber + for<'span> LookupSpan<'span> + Send + Sync
+where
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    tracing_subscriber::fmt()
+        .json()
+        .with_current_span(true)
+        .with_span_list(true)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_env_filter(filter)
+        .with_writer(make_writer)
+        .finish()
+}
+
+/// Send the logs of the whole process to stdout as JSON, and its spans to the OTLP
+/// collector when one is configured.
+pub fn init_logging(telemetry: &TelemetryConfig) {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_FILTER));
+
+    let tracer = telemetry.otlp_endpoint.as_deref().and_then(|endpoint| {
+        match otlp_tracer(endpoint, &telemetry.service_name) {
+            Ok(tracer) => Some(tracer),
+            Err(error) => {
+                eprintln!("{}", error.message);
+                None
+            }
+        }
+    });
+    let subscriber = json_subscriber(std::io::stdout, filter)
+        .with(tracer.map(|tracer| tracing_opentelemetry::layer().with_tracer(tracer)));
+
+    if let Err(error) = tracing::subscriber::set_global_default(subscriber) {
+        eprintln!("Logging was already initialized: {}", error);
+    }
+}
@@ -0,0 +1,322 @@
+use prometheus::{
+    Encoder, HistogramOpts, HistogramTimer, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec,
+    Opts, Registry, TextEncoder,
+};
+use sqlx::{Pool, Postgres};
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    sync::OnceLock,
+    task::{Context, Poll},
+    time::Instant,
+};
+use tonic::{
+    codegen::http::{Request, Response},
+    transport::NamedService,
+};
+use tower::{Layer, Service};
+
+use crate::error::Code;
+
+/// Seconds, from a cached lookup to a bcrypt hash at a high cost.
+const LATENCY_BUCKETS: &[f64] = &[
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0,
+];
+
+/// Prometheus metrics of the server. Process wide, like the JWT settings, because the
+/// password hashers and repositories they are recorded from are created per request.
+pub struct Metrics {
+    registry: Registry,
+    rpc_requests: IntCounterVec,
+    rpc_errors: IntCounterVec,
+    rpc_duration: HistogramVec,
+    password_hash_duration: HistogramVec,
+    redis_call_duration: HistogramVec,
+    db_pool_connections: IntGaugeVec,
+    db_pool_max_connections: IntGauge,
+}
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+pub fn metrics() -> &'static Metrics {
+    METRICS.get_or_init(Metrics::new)
+}
+
+fn histogram(name: &str, help: &str, labels: &[&str]) -> HistogramVec {
+    HistogramVec::new(
+        HistogramOpts::new(name, help).buckets(LATENCY_BUCKETS.to_vec()),
+        labels,
+    )
+    .expect("Valid histogram options")
+}
+
+fn counter(name: &str, help: &str, labels: &[&str]) -> IntCounterVec {
+    IntCounterVec::new(Opts::new(name, help), labels).expect("Valid counter options")
+}
+
+impl Metrics {
+    fn new() -> Metrics {
+        let metrics = Metrics {
+            registry: Registry::new(),
+            rpc_requests: counter(
+                "authentication_rpc_requests_total",
+                "gRPC requests received, by method",
+                &["method"],
+            ),
+            rpc_errors: counter(
+                "authentication_rpc_errors_total",
+                "gRPC requests that failed, by method and error code",
+                &["method", "code"],
+            ),
+            rpc_duration: histogram(
+                "authentication_rpc_duration_seconds",
+                "Time to answer a gRPC request, by method",
+                &["method"],
+            ),
+            password_hash_duration: histogram(
+                "authentication_password_hash_duration_seconds",
+                "Time spent hashing or verifying a password",
+                &["operation"],
+            ),
+            redis_call_duration: histogram(
+                "authentication_redis_call_duration_seconds",
+                "Time of the Redis calls of a repository operation",
+                &["operation"],
+            ),
+            db_pool_connections: IntGaugeVec::new(
+                Opts::new(
+                    "authentication_db_pool_connections",
+                    "Connections of the Postgres pool, idle or in use",
+                ),
+                &["state"],
+            )
+            .expect("Valid gauge options"),
+            db_pool_max_connections: IntGauge::new(
+                "authentication_db_pool_max_connections",
+                "Most connections the Postgres pool opens",
+            )
+            .expect("Valid gauge options"),
+        };
+
+        let collectors: [Box<dyn prometheus::core::Collector>; 7] = [
+            Box::new(metrics.rpc_requests.clone()),
+            Box::new(metrics.rpc_errors.clone()),
+            Box::new(metrics.rpc_duration.clone()),
+            Box::new(metrics.password_hash_duration.clone()),
+            Box::new(metrics.redis_call_duration.clone()),
+            Box::new(metrics.db_pool_connections.clone()),
+            Box::new(metrics.db_pool_max_connections.clone()),
+        ];
+        for collector in collectors {
+            metrics
+                .registry
+                .register(collector)
+                .expect("Metric names are unique");
+        }
+
+        metrics
+    }
+
+    /// Timer observing into the password hash histogram when dropped.
+    pub fn password_hash_timer(&self, operation: &str) -> HistogramTimer {
+        self.password_hash_duration
+            .with_label_values(&[operation])
+            .start_timer()
+    }
+
+    /// Timer observing into the Redis histogram when dropped.
+    pub fn redis_call_timer(&self, operation: &str) -> HistogramTimer {
+        self.redis_call_duration
+            .with_label_values(&[operation])
+            .start_timer()
+    }
+
+    pub fn observe_db_pool(&self, pool: &Pool<Postgres>, max_connections: u32) {
+        let idle = pool.num_idle() as i64;
+        self.db_pool_connections
+            .with_label_values(&["idle"])
+            .set(idle);
+        self.db_pool_connections
+            .with_label_values(&["in_use"])
+            .set(pool.size() as i64 - idle);
+        self.db_pool_max_connections.set(max_connections as i64);
+    }
+
+    /// Every metric in the Prometheus text format.
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("Metrics encode to text");
+
+        String::from_utf8(buffer).expect("The text format is UTF-8")
+    }
+}
+
+tokio::task_local! {
+    static RPC_ERROR_CODE: RefCell<Option<String>>;
+}
+
+/// Remember the `error::Code` the current gRPC request fails with, so the metrics layer
+/// counts the error under it rather than under the coarser gRPC status.
+pub fn record_rpc_error(code: &Code) {
+    let _ = RPC_ERROR_CODE.try_with(|error_code| {
+        *error_code.borrow_mut() = Some(format!("{:?}", code));
+    });
+}
+
+/// Counts the requests, errors and latency of every method of the wrapped gRPC service.
+#[derive(Debug, Clone, Default)]
+pub struct MetricsLayer;
+
+impl<S> Layer<S> for MetricsLayer {
+    type Service = MetricsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MetricsService { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MetricsService<S> {
+    inner: S,
+}
+
+impl<S: NamedService> NamedService for MetricsService<S> {
+    const NAME: &'static str = S::NAME;
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for MetricsService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        let method = request
+            .uri()
+            .path()
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let started_at = Instant::now();
+        let response = self.inner.call(request);
+
+        Box::pin(RPC_ERROR_CODE.scope(RefCell::new(None), async move {
+            let response = response.await;
+
+            let metrics = metrics();
+            metrics.rpc_requests.with_label_values(&[&method]).inc();
+            metrics
+                .rpc_duration
+                .with_label_values(&[&method])
+                .observe(started_at.elapsed().as_secs_f64());
+
+            // Errors raised by the application carry their `error::Code`, the others only
+            // a gRPC status in the headers.
+            let grpc_code = match &response {
+                Ok(response) => response
+                    .headers()
+                    .get("grpc-status")
+                    .map(|status| tonic::Code::from_bytes(status.as_bytes()))
+                    .filter(|code| *code != tonic::Code::Ok),
+                Err(_) => Some(tonic::Code::Unknown),
+            };
+            if let Some(grpc_code) = grpc_code {
+                let code = RPC_ERROR_CODE
+                    .with(|error_code| error_code.borrow_mut().take())
+                    .unwrap_or_else(|| format!("{:?}", grpc_code));
+                metrics
+                    .rpc_errors
+                    .with_label_values(&[&method, &code])
+                    .inc();
+            }
+
+            response
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+
+    /// Answer a request to the `MetricsTest` method through the layer with `status` in the
+    /// headers, after recording a database error when `database_error` is set.
+    async fn call(status: Option<&'static str>, database_error: bool) {
+        let mut service = MetricsLayer.layer(tower::service_fn(move |_: Request<()>| async move {
+            if database_error {
+                record_rpc_error(&Code::DatabaseError);
+            }
+            let mut response = Response::new(());
+            if let Some(status) = status {
+                response
+                    .headers_mut()
+                    .insert("grpc-status", status.parse().unwrap());
+            }
+            Ok::<_, Infallible>(response)
+        }));
+
+        let request = Request::builder()
+            .uri("/authentication.Authentication/MetricsTest")
+            .body(())
+            .unwrap();
+        service.call(request).await.unwrap();
+    }
+
+    /// Value of the `name` sample with the given labels, read from the rendered metrics.
+    fn sample(name: &str, labels: &[(&str, &str)]) -> f64 {
+        metrics()
+            .render()
+            .lines()
+            .filter(|line| line.starts_with(&format!("{}{{", name)))
+            .find(|line| {
+                labels
+                    .iter()
+                    .all(|(label, value)| line.contains(&format!("{}=\"{}\"", label, value)))
+            })
+            .and_then(|line| line.rsplit(' ').next()?.parse().ok())
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn test_counts_requests_and_errors_by_code() {
+        call(None, false).await;
+        call(Some("13"), true).await;
+        call(Some("5"), false).await;
+
+        let method = ("method", "MetricsTest");
+        assert_eq!(sample("authentication_rpc_requests_total", &[method]), 3.0);
+        assert_eq!(
+            sample("authentication_rpc_duration_seconds_count", &[method]),
+            3.0
+        );
+        assert_eq!(
+            sample(
+                "authentication_rpc_errors_total",
+                &[method, ("code", "DatabaseError")]
+            ),
+            1.0
+        );
+        assert_eq!(
+            sample(
+                "authentication_rpc_errors_total",
+                &[method, ("code", "NotFound")]
+            ),
+            1.0
+        );
+
+        let rendered = metrics().render();
+        assert!(rendered.contains("authentication_rpc_requests_total{method=\"MetricsTest\"} 3"));
+    }
+}
@@ -0,0 +1,6 @@
+pub mod logging;
+pub mod metrics;
+pub mod request_id;
+pub mod spans;
+pub mod trace_context;
+pub mod trace_export;
@@ -0,0 +1,130 @@
+use crate::{telemetry::trace_context::remote_context, utils::generate_id::uuidv4::new_uuidv4};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Instant,
+};
+use tonic::codegen::http::{HeaderValue, Request, Response};
+use tower::{Layer, Service};
+use tracing::{field, Instrument};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// Id sent by the caller when it is usable in a header and a log line, a new one otherwise.
+fn request_id<B>(request: &Request<B>) -> HeaderValue {
+    request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .filter(|value| {
+            let value = value.as_bytes();
+            !value.is_empty()
+                && value.len() <= MAX_REQUEST_ID_LENGTH
+                && value.iter().all(|byte| byte.is_ascii_graphic())
+        })
+        .cloned()
+        .unwrap_or_else(|| {
+            HeaderValue::from_str(&new_uuidv4()).expect("A uuid is a valid header value")
+        })
+}
+
+/// Wraps every gRPC call in a `grpc_request` span carrying the `x-request-id`, the method,
+/// the status code and the latency, and echoes the id back in the response headers. The
+/// span continues the trace of the caller when it sent a W3C `traceparent`.
+#[derive(Debug, Clone, Default)]
+pub struct RequestIdLayer;
+
+impl<S> Layer<S> for RequestIdLayer {
+    type Service = RequestIdService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestIdService { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RequestIdService<S> {
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for RequestIdService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<ReqBody>) -> Self::Future {
+        let request_id = request_id(&request);
+        let span = tracing::info_span!(
+            "grpc_request",
+            request_id = request_id.to_str().unwrap_or_default(),
+            method = request.uri().path(),
+            grpc_code = field::Empty,
+            latency_ms = field::Empty,
+        );
+        span.set_parent(remote_context(request.headers()));
+        request
+            .headers_mut()
+            .insert(REQUEST_ID_HEADER, request_id.clone());
+
+        let started_at = Instant::now();
+        let response = span.in_scope(|| self.inner.call(request));
+
+        Box::pin(
+            async move {
+                let mut response = response.await?;
+
+                // Errors are sent in the headers, a call that succeeded has its status in the
+                // trailers after the body.
+                let code = response
+                    .headers()
+                    .get("grpc-status")
+                    .map(|status| tonic::Code::from_bytes(status.as_bytes()))
+                    .unwrap_or(tonic::Code::Ok);
+                let span = tracing::Span::current();
+                span.record("grpc_code", format!("{:?}", code).as_str());
+                span.record("latency_ms", started_at.elapsed().as_millis() as u64);
+                tracing::info!("gRPC request completed");
+
+                response.headers_mut().insert(REQUEST_ID_HEADER, request_id);
+                Ok(response)
+            }
+            .instrument(span),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_a_usable_request_id_and_replaces_the_others() {
+        let request = Request::builder()
+            .header(REQUEST_ID_HEADER, "caller-id-42")
+            .body(())
+            .unwrap();
+        assert_eq!(request_id(&request), "caller-id-42");
+
+        for value in ["", "with space", &"x".repeat(MAX_REQUEST_ID_LENGTH + 1)] {
+            let request = Request::builder()
+                .header(REQUEST_ID_HEADER, value)
+                .body(())
+                .unwrap();
+            assert_ne!(request_id(&request), value);
+            assert_eq!(request_id(&request).len(), 36);
+        }
+
+        let request = Request::builder().body(()).unwrap();
+        assert_eq!(request_id(&request).len(), 36);
+    }
+}
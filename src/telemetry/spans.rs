@@ -0,0 +1,34 @@
+use tracing::Span;
+
+fn sql_span(system: &'static str, operation: &'static str, table: &'static str) -> Span {
+    let name = format!("{} {}", operation, table);
+    tracing::info_span!(
+        "db_query",
+        otel.name = name.as_str(),
+        otel.kind = "client",
+        db.system = system,
+        db.operation = operation,
+        db.sql.table = table,
+    )
+}
+
+/// Span of one Postgres statement, a client span named like `SELECT users` once exported.
+pub fn db_span(operation: &'static str, table: &'static str) -> Span {
+    sql_span("postgresql", operation, table)
+}
+
+/// Span of one SQLite statement, named like the Postgres ones.
+pub fn sqlite_span(operation: &'static str, table: &'static str) -> Span {
+    sql_span("sqlite", operation, table)
+}
+
+/// Span of one Redis command, or of a pipeline of them.
+pub fn redis_span(command: &'static str) -> Span {
+    tracing::info_span!(
+        "redis_command",
+        otel.name = command,
+        otel.kind = "client",
+        db.system = "redis",
+        db.operation = command,
+    )
+}
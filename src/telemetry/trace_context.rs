@@ -0,0 +1,53 @@
+use opentelemetry::{
+    propagation::{Extractor, TextMapPropagator},
+    sdk::propagation::TraceContextPropagator,
+    Context,
+};
+use tonic::codegen::http::HeaderMap;
+
+struct MetadataExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for MetadataExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+/// Trace of the caller, read from the W3C `traceparent` and `tracestate` metadata. Empty
+/// when the caller sent none or an invalid one, making the request the root of a trace.
+pub fn remote_context(headers: &HeaderMap) -> Context {
+    TraceContextPropagator::new().extract(&MetadataExtractor(headers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::{TraceContextExt, TraceId};
+
+    #[test]
+    fn test_reads_the_trace_of_the_caller() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "traceparent",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+                .parse()
+                .unwrap(),
+        );
+
+        let context = remote_context(&headers);
+        let span_context = context.span().span_context().clone();
+        assert!(span_context.is_remote());
+        assert!(span_context.is_sampled());
+        assert_eq!(
+            span_context.trace_id(),
+            TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap()
+        );
+
+        headers.insert("traceparent", "not a traceparent".parse().unwrap());
+        assert!(!remote_context(&headers).span().span_context().is_valid());
+    }
+}
@@ -0,0 +1,110 @@
+use crate::error::*;
+use opentelemetry::{
+    sdk::{trace, trace::Tracer, Resource},
+    KeyValue,
+};
+use opentelemetry_otlp::WithExportConfig;
+
+/// Tracer batching the spans to the OTLP/HTTP collector at `endpoint`, which receives them
+/// on `/v1/traces`; the HTTP exporter posts to the URL as given, so the path is appended here.
+/// Must be created inside the Tokio runtime that sends the batches.
+pub fn otlp_tracer(endpoint: &str, service_name: &str) -> Result<Tracer, AppError> {
+    opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .http()
+                .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/'))),
+        )
+        .with_trace_config(
+            trace::config().with_resource(Resource::new(vec![KeyValue::new(
+                "service.name",
+                service_name.to_string(),
+            )])),
+        )
+        .install_batch(opentelemetry::runtime::Tokio)
+        .map_err(|error| {
+            AppError::new(
+                Code::Internal,
+                format!("Unable to set up the OTLP exporter: {}", error),
+            )
+        })
+}
+
+/// Send the spans still waiting in the batch before the process exits.
+pub fn shutdown_trace_export() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::trace_context::remote_context;
+    use axum::{body::Bytes, extract::State, routing::post, Router};
+    use tokio::sync::mpsc;
+    use tonic::codegen::http::HeaderMap;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+
+    /// Collector accepting every export, handing the request bodies over to the test.
+    async fn collector_stub() -> (String, mpsc::UnboundedReceiver<Bytes>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let router = Router::new()
+            .route(
+                "/v1/traces",
+                post(
+                    |State(sender): State<mpsc::UnboundedSender<Bytes>>, body: Bytes| async move {
+                        sender.send(body).ok();
+                    },
+                ),
+            )
+            .with_state(sender);
+
+        let server =
+            axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(router.into_make_service());
+        let endpoint = format!("http://{}", server.local_addr());
+        tokio::spawn(server);
+
+        (endpoint, receiver)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_spans_are_exported_as_children_of_the_caller_trace() {
+        let (endpoint, mut exports) = collector_stub().await;
+        let tracer = otlp_tracer(&endpoint, "authentication-test").unwrap();
+        let provider = tracer.provider().unwrap();
+
+        let subscriber =
+            tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+        tracing::subscriber::with_default(subscriber, || {
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                "traceparent",
+                format!("00-{}-00f067aa0ba902b7-01", TRACE_ID)
+                    .parse()
+                    .unwrap(),
+            );
+            let span = tracing::info_span!("grpc_request");
+            span.set_parent(remote_context(&headers));
+            span.in_scope(|| tracing::info_span!("db_query").in_scope(|| {}));
+        });
+        tokio::task::spawn_blocking(move || provider.force_flush())
+            .await
+            .unwrap();
+
+        // The batch processor may split the two spans across exports.
+        let mut body = Vec::new();
+        while let Ok(Some(export)) =
+            tokio::time::timeout(std::time::Duration::from_secs(1), exports.recv()).await
+        {
+            body.extend_from_slice(&export);
+        }
+        let contains = |bytes: &[u8]| body.windows(bytes.len()).any(|window| window == bytes);
+        assert!(contains(b"authentication-test"));
+        assert!(contains(b"grpc_request"));
+        assert!(contains(b"db_query"));
+        assert!(contains(&hex::decode(TRACE_ID).unwrap()));
+    }
+}
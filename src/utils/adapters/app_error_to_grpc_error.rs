@@ -1,17 +1,156 @@
+use prost::Message as _;
 use tonic::Status;
 
-use crate::error::*;
+use crate::{
+    error::*,
+    i18n::{t, Message},
+    rpc::error_details::google_rpc::{self, bad_request, BadRequest, ErrorInfo, RetryInfo},
+    telemetry::metrics::record_rpc_error,
+};
+
+/// `ErrorInfo` domain of the reasons sent by this service.
+pub const ERROR_DOMAIN: &str = "authentication";
+
+fn any<M: prost::Message>(name: &str, message: &M) -> prost_types::Any {
+    prost_types::Any {
+        type_url: format!("type.googleapis.com/google.rpc.{}", name),
+        value: message.encode_to_vec(),
+    }
+}
+
+/// The `google.rpc` details of the error: its reason, refused fields and retry delay.
+fn error_details(error: &AppError) -> Vec<prost_types::Any> {
+    let mut details = vec![];
+
+    if let Some(reason) = error.reason {
+        details.push(any(
+            "ErrorInfo",
+            &ErrorInfo {
+                reason: reason.as_str().to_string(),
+                domain: ERROR_DOMAIN.to_string(),
+                metadata: Default::default(),
+            },
+        ));
+    }
+
+    if !error.field_violations.is_empty() {
+        let field_violations = error
+            .field_violations
+            .iter()
+            .map(|violation| bad_request::FieldViolation {
+                field: violation.field.clone(),
+                description: violation.description.clone(),
+            })
+            .collect();
+        details.push(any("BadRequest", &BadRequest { field_violations }));
+    }
+
+    if let Some(retry_after) = error.retry_after {
+        details.push(any(
+            "RetryInfo",
+            &RetryInfo {
+                retry_delay: Some(prost_types::Duration {
+                    seconds: retry_after.as_secs() as i64,
+                    nanos: retry_after.subsec_nanos() as i32,
+                }),
+            },
+        ));
+    }
+
+    details
+}
 
 pub fn app_error_to_grpc_error(error: AppError) -> Status {
-    match error.code {
-        Code::InvalidArgument => Status::new(tonic::Code::InvalidArgument, error.message),
-        Code::NotFound => Status::new(tonic::Code::NotFound, error.message),
-        Code::AlreadyExists => Status::new(tonic::Code::AlreadyExists, error.message),
-        Code::PermissionDenied => Status::new(tonic::Code::PermissionDenied, error.message),
-        Code::Unauthenticated => Status::new(tonic::Code::Unauthenticated, error.message),
-        Code::Internal => Status::new(tonic::Code::Internal, "Internal error"),
-        Code::Unknown => Status::new(tonic::Code::Unknown, "Unknown error"),
-        Code::DatabaseError => Status::new(tonic::Code::Internal, "Internal error"),
-        Code::SQLError => Status::new(tonic::Code::Internal, "Internal error"),
+    record_rpc_error(&error.code);
+    // Clients only get a generic message, the cause is in the server logs.
+    if error.is_internal() {
+        tracing::error!(code = ?error.code, error = %error.chain(), "Internal error");
+    }
+    let details = error_details(&error);
+
+    let (code, message) = match error.code {
+        Code::InvalidArgument => (tonic::Code::InvalidArgument, error.message),
+        Code::NotFound => (tonic::Code::NotFound, error.message),
+        Code::AlreadyExists => (tonic::Code::AlreadyExists, error.message),
+        Code::PermissionDenied => (tonic::Code::PermissionDenied, error.message),
+        Code::Unauthenticated => (tonic::Code::Unauthenticated, error.message),
+        Code::Internal => (tonic::Code::Internal, t(Message::InternalError)),
+        Code::Unknown => (tonic::Code::Unknown, t(Message::UnknownError)),
+        Code::DatabaseError => (tonic::Code::Internal, t(Message::InternalError)),
+        Code::SQLError => (tonic::Code::Internal, t(Message::InternalError)),
+        Code::Unavailable => (tonic::Code::Unavailable, t(Message::ServiceUnavailable)),
+        Code::FailedPrecondition => (tonic::Code::FailedPrecondition, error.message),
+    };
+
+    if details.is_empty() {
+        return Status::new(code, message);
+    }
+
+    let status = google_rpc::Status {
+        code: code as i32,
+        message: message.clone(),
+        details,
+    };
+    Status::with_details(code, message, status.encode_to_vec().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_reason_violations_and_retry_delay_are_sent_as_details() {
+        let status = app_error_to_grpc_error(
+            AppError::new(Code::AlreadyExists, "Username already taken")
+                .with_reason(Reason::UsernameTaken)
+                .with_field_violation("username", "is already taken")
+                .with_retry_after(Duration::from_millis(1500)),
+        );
+
+        assert_eq!(status.code(), tonic::Code::AlreadyExists);
+        assert_eq!(status.message(), "Username already taken");
+
+        let details = google_rpc::Status::decode(status.details()).unwrap();
+        assert_eq!(details.code, tonic::Code::AlreadyExists as i32);
+        let type_urls: Vec<&str> = details
+            .details
+            .iter()
+            .map(|any| any.type_url.as_str())
+            .collect();
+        assert_eq!(
+            type_urls,
+            [
+                "type.googleapis.com/google.rpc.ErrorInfo",
+                "type.googleapis.com/google.rpc.BadRequest",
+                "type.googleapis.com/google.rpc.RetryInfo",
+            ]
+        );
+
+        let error_info = ErrorInfo::decode(details.details[0].value.as_slice()).unwrap();
+        assert_eq!(error_info.reason, "USERNAME_TAKEN");
+        assert_eq!(error_info.domain, ERROR_DOMAIN);
+        let bad_request = BadRequest::decode(details.details[1].value.as_slice()).unwrap();
+        assert_eq!(bad_request.field_violations[0].field, "username");
+        let retry_info = RetryInfo::decode(details.details[2].value.as_slice()).unwrap();
+        let retry_delay = retry_info.retry_delay.unwrap();
+        assert_eq!((retry_delay.seconds, retry_delay.nanos), (1, 500_000_000));
+    }
+
+    #[test]
+    fn test_internal_errors_keep_their_details_but_not_their_message() {
+        let status = app_error_to_grpc_error(
+            AppError::new(Code::SQLError, "A [Pool::acquire] timed out")
+                .with_reason(Reason::ServiceBusy),
+        );
+
+        assert_eq!(status.code(), tonic::Code::Internal);
+        assert_eq!(status.message(), "Internal error");
+        let details = google_rpc::Status::decode(status.details()).unwrap();
+        assert_eq!(details.message, "Internal error");
+        assert_eq!(details.details.len(), 1);
+
+        let status = app_error_to_grpc_error(AppError::new(Code::NotFound, "Code not found"));
+        assert!(status.details().is_empty());
     }
 }
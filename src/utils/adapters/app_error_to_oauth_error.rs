@@ -0,0 +1,23 @@
+use axum::http::StatusCode;
+
+use crate::error::*;
+
+/// Map an error of the token endpoint to its HTTP status and OAuth2 error code (RFC 6749, 5.2).
+pub fn app_error_to_oauth_error(error: &AppError) -> (StatusCode, &'static str) {
+    if error.is_internal() {
+        tracing::error!(code = ?error.code, error = %error.chain(), "Internal error");
+    }
+
+    match error.code {
+        Code::InvalidArgument => (StatusCode::BAD_REQUEST, "invalid_request"),
+        Code::Unauthenticated => (StatusCode::UNAUTHORIZED, "invalid_client"),
+        Code::NotFound | Code::PermissionDenied => (StatusCode::BAD_REQUEST, "invalid_grant"),
+        Code::AlreadyExists
+        | Code::Internal
+        | Code::Unknown
+        | Code::DatabaseError
+        | Code::SQLError
+        | Code::FailedPrecondition => (StatusCode::INTERNAL_SERVER_ERROR, "server_error"),
+        Code::Unavailable => (StatusCode::SERVICE_UNAVAILABLE, "temporarily_unavailable"),
+    }
+}
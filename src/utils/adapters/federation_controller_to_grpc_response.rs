@@ -0,0 +1,22 @@
+use tonic::Response;
+
+use crate::{
+    dtos::controllers::dtos_controller_federation::FederationControllerLoginReturn,
+    rpc::authentication::authentication::{ResExternalLogin, User as UserResponse},
+};
+
+pub fn map_external_login_to_grpc_response(
+    response: FederationControllerLoginReturn,
+) -> Response<ResExternalLogin> {
+    Response::new(ResExternalLogin {
+        user: Some(UserResponse {
+            id: response.user.id,
+            username: response.user.username,
+            email: response.user.email,
+            activated: response.user.activated,
+            blocked: response.user.blocked,
+        }),
+        token: response.token,
+        created: response.created,
+    })
+}
@@ -1,4 +1,12 @@
 pub mod app_error_to_grpc_error;
+pub mod app_error_to_oauth_error;
+pub mod federation_controller_to_grpc_response;
+pub mod oauth_controller_to_grpc_response;
+pub mod passwordless_controller_to_grpc_response;
+pub mod personal_access_token_controller_to_grpc_response;
 pub mod redis_error_to_app_error;
 pub mod sqlx_error_to_app_error;
 pub mod user_controller_to_grpc_response;
+pub mod user_event_to_grpc_message;
+pub mod webauthn_controller_to_grpc_response;
+pub mod webhook_controller_to_grpc_response;
@@ -0,0 +1,15 @@
+use tonic::Response;
+
+use crate::{
+    dtos::controllers::dtos_controller_oauth::OAuthControllerRegisterClientReturn,
+    rpc::authentication::authentication::ResRegisterOAuthClient,
+};
+
+pub fn map_register_oauth_client_to_grpc_response(
+    response: OAuthControllerRegisterClientReturn,
+) -> Response<ResRegisterOAuthClient> {
+    Response::new(ResRegisterOAuthClient {
+        client_id: response.client_id,
+        client_secret: response.client_secret,
+    })
+}
@@ -0,0 +1,14 @@
+use tonic::Response;
+
+use crate::{
+    dtos::controllers::dtos_controller_passwordless::PasswordlessControllerLoginCodeReturn,
+    rpc::authentication::authentication::ResRequestLoginCode,
+};
+
+pub fn map_request_login_code_to_grpc_response(
+    response: PasswordlessControllerLoginCodeReturn,
+) -> Response<ResRequestLoginCode> {
+    Response::new(ResRequestLoginCode {
+        expires_in: response.expires_in,
+    })
+}
@@ -0,0 +1,53 @@
+use tonic::Response;
+
+use crate::{
+    dtos::controllers::dtos_controller_personal_access_token::{
+        PersonalAccessTokenControllerCreateReturn, PersonalAccessTokenResponse,
+    },
+    rpc::authentication::authentication::{
+        PersonalAccessToken, ResCreatePersonalAccessToken, ResListPersonalAccessTokens,
+        ResRevokePersonalAccessToken,
+    },
+};
+
+fn map_personal_access_token_to_grpc_message(
+    token: PersonalAccessTokenResponse,
+) -> PersonalAccessToken {
+    PersonalAccessToken {
+        id: token.id,
+        name: token.name,
+        prefix: token.prefix,
+        scopes: token.scopes,
+        expires_at: token.expires_at,
+        last_used_at: token.last_used_at,
+        created_at: token.created_at,
+    }
+}
+
+pub fn map_create_personal_access_token_to_grpc_response(
+    response: PersonalAccessTokenControllerCreateReturn,
+) -> Response<ResCreatePersonalAccessToken> {
+    Response::new(ResCreatePersonalAccessToken {
+        personal_access_token: Some(map_personal_access_token_to_grpc_message(
+            response.personal_access_token,
+        )),
+        token: response.token,
+    })
+}
+
+pub fn map_list_personal_access_tokens_to_grpc_response(
+    response: Vec<PersonalAccessTokenResponse>,
+) -> Response<ResListPersonalAccessTokens> {
+    Response::new(ResListPersonalAccessTokens {
+        personal_access_tokens: response
+            .into_iter()
+            .map(map_personal_access_token_to_grpc_message)
+            .collect(),
+    })
+}
+
+pub fn map_revoke_personal_access_token_to_grpc_response(
+    message: String,
+) -> Response<ResRevokePersonalAccessToken> {
+    Response::new(ResRevokePersonalAccessToken { message })
+}
@@ -1,11 +1,22 @@
-use crate::error::{AppError, Code};
+use crate::error::{AppError, Code, Reason};
 use sqlx::Error;
+use std::time::Duration;
 
 pub fn sqlx_error_to_app_error(error: Error) -> AppError {
-    match error {
+    let app_error = match &error {
         Error::RowNotFound => AppError::new(Code::NotFound, "DB: nothing found with given parameters"),
         Error::Database(err) => match err.code().as_deref() {
-            Some("23505") => AppError::new(Code::AlreadyExists, "DB: insert or update on table violates unique constraint"),
+            // Postgres, then SQLite's UNIQUE and PRIMARY KEY violations.
+            Some("23505") | Some("2067") | Some("1555") => {
+                let error = AppError::new(Code::AlreadyExists, "DB: insert or update on table violates unique constraint");
+                // SQLite does not name the constraint, only the column, as in "UNIQUE constraint failed: users.username".
+                match (err.constraint(), err.message()) {
+                    (Some("unique_username"), _) | (None, "UNIQUE constraint failed: users.username") => error.with_reason(Reason::UsernameTaken).with_field_violation("username", "is already taken"),
+                    (Some("unique_email"), _) | (None, "UNIQUE constraint failed: users.email") => error.with_reason(Reason::EmailTaken).with_field_violation("email", "is already taken"),
+                    _ => error,
+                }
+            }
+            Some("787") => AppError::new(Code::DatabaseError, "insert or update on table violates foreign key constraint"),
             Some("23514") => AppError::new(Code::DatabaseError, "insert or update on table violates check verification"),
             Some("23506") => AppError::new(Code::DatabaseError, "delete on table violates foreign key constraint"),
             Some("23503") => AppError::new(Code::DatabaseError, "insert or update on table violates foreign key constraint"),
@@ -17,15 +28,17 @@ pub fn sqlx_error_to_app_error(error: Error) -> AppError {
         Error::Io(_) => AppError::new(Code::SQLError, "Error communicating with the database backend"),
         Error::Tls(_) => AppError::new(Code::SQLError, "Error occurred while attempting to establish a TLS connection"),
         Error::Protocol(_) => AppError::new(Code::SQLError, "Unexpected or invalid data encountered while communicating with the database"),
-        Error::TypeNotFound { type_name: _ } => AppError::new(Code::SQLError, "Type in query doesn't exist. Likely due to typo or missing user type"),
-        Error::ColumnIndexOutOfBounds { index: _, len: _ } => AppError::new(Code::SQLError, "Column index was out of bounds"),
+        Error::TypeNotFound { .. } => AppError::new(Code::SQLError, "Type in query doesn't exist. Likely due to typo or missing user type"),
+        Error::ColumnIndexOutOfBounds { .. } => AppError::new(Code::SQLError, "Column index was out of bounds"),
         Error::ColumnNotFound(_) => AppError::new(Code::SQLError, "No column found for the given name"),
-        Error::ColumnDecode { index: _, source: _ } => AppError::new(Code::SQLError, "Error occurred while decoding a value from a specific column"),
+        Error::ColumnDecode { .. } => AppError::new(Code::SQLError, "Error occurred while decoding a value from a specific column"),
         Error::Decode(_) => AppError::new(Code::SQLError, "Error occurred while decoding a value from a specific column"),
-        Error::PoolTimedOut => AppError::new(Code::SQLError, "A [Pool::acquire] timed out due to connections not becoming available or because another task encountered too many errors while trying to open a new connection"),
+        Error::PoolTimedOut => AppError::new(Code::SQLError, "A [Pool::acquire] timed out due to connections not becoming available or because another task encountered too many errors while trying to open a new connection").with_reason(Reason::ServiceBusy).with_retry_after(Duration::from_secs(1)),
         Error::PoolClosed => AppError::new(Code::SQLError, "[Pool::close] was called while we were waiting in [Pool::acquire]"),
         Error::WorkerCrashed => AppError::new(Code::SQLError, "[Pool::close] was called while we were waiting in [Pool::acquire]"),
         Error::Migrate(_) => AppError::new(Code::SQLError, "migrate Error"),
         _ => AppError::new(Code::SQLError, "Error unknown"),
-    }
+    };
+
+    app_error.with_source(error)
 }
@@ -3,16 +3,18 @@ use tonic::Response;
 use crate::{
     dtos::controllers::dtos_controller_user::{
         UserControllerAuthenticationReturn, UserControllerLoginReturn, UserControllerRegisterReturn,
+        UserControllerUserInfoReturn,
     },
     rpc::authentication::authentication::{
         ResActivateUser, ResCreateActivationCode, ResCreateRecoveryCode, ResLogin,
         ResRecoverUserData, ResRecoverUserPassword, ResRegister, ResUpdateEmail, ResUpdatePassword,
-        ResUpdateUser, User as UserResponse, ResDeleteUser,
+        ResUpdateUser, ResUserInfo, User as UserResponse, ResDeleteUser,
     },
 };
 
 pub fn map_user_register_to_grpc_response(
     response: UserControllerRegisterReturn,
+    expires_in: u64,
 ) -> Response<ResRegister> {
     Response::new(ResRegister {
         user: Some(UserResponse {
@@ -23,10 +25,14 @@ pub fn map_user_register_to_grpc_response(
             blocked: response.user.blocked,
         }),
         token: response.token,
+        expires_in: expires_in as i64,
     })
 }
 
-pub fn map_user_login_to_grpc_response(response: UserControllerLoginReturn) -> Response<ResLogin> {
+pub fn map_user_login_to_grpc_response(
+    response: UserControllerLoginReturn,
+    expires_in: u64,
+) -> Response<ResLogin> {
     Response::new(ResLogin {
         user: Some(UserResponse {
             id: response.user.id,
@@ -36,6 +42,7 @@ pub fn map_user_login_to_grpc_response(response: UserControllerLoginReturn) -> R
             blocked: response.user.blocked,
         }),
         token: response.token,
+        expires_in: expires_in as i64,
     })
 }
 
@@ -53,6 +60,17 @@ pub fn map_user_auth_to_grpc_response(
     })
 }
 
+pub fn map_user_info_to_grpc_response(
+    response: UserControllerUserInfoReturn,
+) -> Response<ResUserInfo> {
+    Response::new(ResUserInfo {
+        sub: response.sub,
+        preferred_username: response.preferred_username,
+        email: response.email,
+        email_verified: response.email_verified,
+    })
+}
+
 pub fn map_user_update_to_grpc_response(response: String) -> Response<ResUpdateUser> {
     Response::new(ResUpdateUser { message: response })
 }
@@ -0,0 +1,14 @@
+use crate::{
+    dtos::events::dtos_user_event::UserEvent,
+    rpc::authentication::authentication::ResWatchUserEvents,
+};
+
+pub fn map_user_event_to_grpc_message(event: UserEvent) -> ResWatchUserEvents {
+    ResWatchUserEvents {
+        offset: event.id,
+        event_type: event.event_type,
+        user_id: event.user_id,
+        payload: event.payload,
+        created_at: event.created_at.timestamp(),
+    }
+}
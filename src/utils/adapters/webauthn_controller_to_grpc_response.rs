@@ -0,0 +1,38 @@
+use tonic::Response;
+
+use crate::{
+    dtos::controllers::dtos_controller_webauthn::{
+        PasskeyControllerOptionsReturn, PasskeyResponse,
+    },
+    rpc::authentication::authentication::{
+        Passkey, ResBeginPasskeyLogin, ResBeginPasskeyRegistration, ResFinishPasskeyRegistration,
+    },
+};
+
+pub fn map_begin_passkey_registration_to_grpc_response(
+    response: PasskeyControllerOptionsReturn,
+) -> Response<ResBeginPasskeyRegistration> {
+    Response::new(ResBeginPasskeyRegistration {
+        options: response.options,
+    })
+}
+
+pub fn map_finish_passkey_registration_to_grpc_response(
+    response: PasskeyResponse,
+) -> Response<ResFinishPasskeyRegistration> {
+    Response::new(ResFinishPasskeyRegistration {
+        passkey: Some(Passkey {
+            id: response.id,
+            name: response.name,
+            created_at: response.created_at,
+        }),
+    })
+}
+
+pub fn map_begin_passkey_login_to_grpc_response(
+    response: PasskeyControllerOptionsReturn,
+) -> Response<ResBeginPasskeyLogin> {
+    Response::new(ResBeginPasskeyLogin {
+        options: response.options,
+    })
+}
@@ -0,0 +1,50 @@
+use tonic::Response;
+
+use crate::{
+    dtos::controllers::dtos_controller_webhook::{
+        WebhookControllerRegisterReturn, WebhookResponse,
+    },
+    rpc::authentication::authentication::{
+        ResDeleteWebhook, ResListWebhooks, ResRegisterWebhook, ResTestWebhook, Webhook,
+    },
+};
+
+fn map_webhook_to_grpc_message(webhook: WebhookResponse) -> Webhook {
+    Webhook {
+        id: webhook.id,
+        url: webhook.url,
+        event_types: webhook.event_types,
+        created_at: webhook.created_at,
+    }
+}
+
+pub fn map_register_webhook_to_grpc_response(
+    response: WebhookControllerRegisterReturn,
+) -> Response<ResRegisterWebhook> {
+    Response::new(ResRegisterWebhook {
+        webhook: Some(map_webhook_to_grpc_message(response.webhook)),
+        secret: response.secret,
+    })
+}
+
+pub fn map_list_webhooks_to_grpc_response(
+    response: Vec<WebhookResponse>,
+) -> Response<ResListWebhooks> {
+    Response::new(ResListWebhooks {
+        webhooks: response
+            .into_iter()
+            .map(map_webhook_to_grpc_message)
+            .collect(),
+    })
+}
+
+pub fn map_test_webhook_to_grpc_response(status_code: u16) -> Response<ResTestWebhook> {
+    Response::new(ResTestWebhook {
+        status_code: status_code.into(),
+        message: format!("Webhook answered with HTTP status {}", status_code),
+    })
+}
+
+pub fn map_delete_webhook_to_grpc_response(message: String) -> Response<ResDeleteWebhook> {
+    Response::new(ResDeleteWebhook { message })
+}
@@ -1 +1,2 @@
-pub mod six_number_code_generator;
\ No newline at end of file
+pub mod secret_generator;
+pub mod six_number_code_generator;
@@ -1,20 +1,48 @@
-use crate::error::*;
+use crate::{error::*, telemetry::metrics::metrics};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+pub const DEFAULT_BCRYPT_COST: u32 = 8;
+
+static BCRYPT_COST: AtomicU32 = AtomicU32::new(DEFAULT_BCRYPT_COST);
+
+/// Cost of the hashes created from now on, existing hashes keep verifying.
+pub fn set_bcrypt_cost(cost: u32) {
+    BCRYPT_COST.store(cost, Ordering::Relaxed);
+}
 
 pub type PasswordHasher = fn(password: String) -> Result<String, AppError>;
 pub type PasswordVerify = fn(hash_string: String, password: String) -> Result<bool, AppError>;
 
 pub const PASSWORD_HASHER: PasswordHasher = |password| {
-    let hash = match bcrypt::hash(password, 8) {
+    let _timer = metrics().password_hash_timer("hash");
+    let hash = match bcrypt::hash(password, BCRYPT_COST.load(Ordering::Relaxed)) {
         Ok(hash) => hash,
-        Err(error) => return Err(AppError::new(Code::Internal, error.to_string())),
+        Err(error) => {
+            return Err(
+                AppError::new(Code::Internal, "Unable to hash the password").with_source(error)
+            )
+        }
     };
     Ok(hash)
 };
 
+/// Prefix of every bcrypt hash. The plaintext passwords once stored by the password
+/// recovery were replaced by a value without it, which no password matches.
+const BCRYPT_PREFIX: &str = "$2";
+
 pub const PASSWORD_VERIFY: PasswordVerify = |hash_string, password| {
+    if !hash_string.starts_with(BCRYPT_PREFIX) {
+        return Ok(false);
+    }
+
+    let _timer = metrics().password_hash_timer("verify");
     let result = match bcrypt::verify(password, &hash_string) {
         Ok(result) => result,
-        Err(error) => return Err(AppError::new(Code::Internal, error.to_string())),
+        Err(error) => {
+            return Err(
+                AppError::new(Code::Internal, "Unable to verify the password").with_source(error),
+            )
+        }
     };
     Ok(result)
 };
@@ -37,7 +65,7 @@ mod tests {
         let hash = PASSWORD_HASHER(PASSWORD.to_string()).unwrap();
 
         let result = PASSWORD_VERIFY(hash, PASSWORD.to_string()).unwrap();
-        assert_eq!(result, true);
+        assert!(result);
     }
 
     #[test]
@@ -46,6 +74,15 @@ mod tests {
 
         let result = PASSWORD_VERIFY(hash, "wrong password".to_string()).unwrap();
 
-        assert_eq!(result, false);
+        assert!(!result);
+    }
+
+    #[test]
+    fn test_password_reset_from_plaintext_matches_nothing() {
+        for stored in ["!", PASSWORD] {
+            let result = PASSWORD_VERIFY(stored.to_string(), PASSWORD.to_string()).unwrap();
+
+            assert!(!result);
+        }
     }
 }
@@ -0,0 +1,22 @@
+use sha2::{Digest, Sha256};
+
+/// Hash of a high-entropy bearer secret (refresh tokens, access tokens), stored instead of
+/// the token so a database leak does not expose usable credentials. Unlike passwords these
+/// secrets are random, so a fast digest is enough and allows lookups by hash.
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_token() {
+        let hash = hash_token("token");
+
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, hash_token("token"));
+        assert_ne!(hash, hash_token("other token"));
+    }
+}
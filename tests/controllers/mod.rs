@@ -1 +1,3 @@
+mod personal_access_token_tests;
 mod user_tests;
+mod webauthn_tests;
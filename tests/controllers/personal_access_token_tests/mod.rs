@@ -0,0 +1 @@
+mod personal_access_token_controller_oauth_access_token_test;
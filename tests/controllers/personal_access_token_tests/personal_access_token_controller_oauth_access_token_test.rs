@@ -0,0 +1,61 @@
+use authentication_gRPC::{
+    controllers::personal_access_token_controller::{
+        PersonalAccessTokenController, PersonalAccessTokenManagementController,
+    },
+    dtos::controllers::dtos_controller_personal_access_token::CreatePersonalAccessTokenParams,
+    error::{AppError, Code},
+    models::personal_access_token_model::MockPersonalAccessTokenManagementModel,
+    security::{
+        jwt::{jwt_decode, jwt_encode, jwt_encode_access},
+        personal_access_token::USER_READ_SCOPE,
+    },
+};
+
+/// The model mock expects no call: only the user's own logins manage their tokens.
+fn controller() -> PersonalAccessTokenController<MockPersonalAccessTokenManagementModel> {
+    PersonalAccessTokenController {
+        model: MockPersonalAccessTokenManagementModel::new(),
+        jwt_encode,
+        jwt_decode,
+    }
+}
+
+fn access_token() -> String {
+    jwt_encode_access(
+        "oauth-user-id".to_string(),
+        true,
+        false,
+        vec!["openid".to_string()],
+    )
+    .unwrap()
+}
+
+fn assert_permission_denied<T>(result: Result<T, AppError>) {
+    match result {
+        Ok(_) => panic!("An OAuth access token should have been refused"),
+        Err(error) => assert_eq!(error.code, Code::PermissionDenied),
+    }
+}
+
+#[tokio::test]
+async fn test_create_token_refuses_oauth_access_token() {
+    let req = CreatePersonalAccessTokenParams {
+        name: "ci".to_string(),
+        scopes: vec![USER_READ_SCOPE.to_string()],
+        expires_at: None,
+    };
+
+    assert_permission_denied(controller().create_token(access_token(), req).await);
+}
+
+#[tokio::test]
+async fn test_list_tokens_refuses_oauth_access_token() {
+    assert_permission_denied(controller().list_tokens(access_token()).await);
+}
+
+#[tokio::test]
+async fn test_revoke_token_refuses_oauth_access_token() {
+    let id = "token-id".to_string();
+
+    assert_permission_denied(controller().revoke_token(access_token(), id).await);
+}
@@ -1,6 +1,6 @@
 use authentication_gRPC::{
     controllers::authentication_controller::AuthenticationController,
-    security::jwt::JWTAuthenticateToken,
+    security::jwt::{JWTAuthenticateToken, RegisteredClaims},
 };
 
 use crate::{
@@ -30,7 +30,12 @@ async fn test_activate_user() {
                 sub: USER_ID_FAKE.to_string(),
                 activated: false,
                 blocked: false,
-                exp: 999999,
+                token_type: "user".to_string(),
+                scope: None,
+                claims: RegisteredClaims {
+                    exp: 999999,
+                    ..Default::default()
+                },
             })
         })
         .mount_model(mock_user_model)
@@ -52,7 +57,12 @@ async fn test_activate_user_already_active() {
                 sub: USER_ID_FAKE.to_string(),
                 activated: true,
                 blocked: false,
-                exp: 999999,
+                token_type: "user".to_string(),
+                scope: None,
+                claims: RegisteredClaims {
+                    exp: 999999,
+                    ..Default::default()
+                },
             })
         })
         .build();
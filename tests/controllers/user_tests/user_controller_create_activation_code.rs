@@ -1,6 +1,6 @@
 use authentication_gRPC::{
     controllers::authentication_controller::AuthenticationController,
-    security::jwt::JWTAuthenticateToken,
+    security::jwt::{JWTAuthenticateToken, RegisteredClaims},
 };
 
 use crate::{
@@ -32,7 +32,12 @@ async fn test_create_activation_code() {
                 sub: FAKE_USER_ID.to_string(),
                 activated: false,
                 blocked: false,
-                exp: 99999999,
+                token_type: "user".to_string(),
+                scope: None,
+                claims: RegisteredClaims {
+                    exp: 99999999,
+                    ..Default::default()
+                },
             })
         })
         .build();
@@ -53,7 +58,12 @@ async fn test_create_activation_code_for_user_already_active() {
                 sub: FAKE_USER_ID.to_string(),
                 activated: true,
                 blocked: false,
-                exp: 99999999,
+                token_type: "user".to_string(),
+                scope: None,
+                claims: RegisteredClaims {
+                    exp: 99999999,
+                    ..Default::default()
+                },
             })
         })
         .build();
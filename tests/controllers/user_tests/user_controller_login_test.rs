@@ -77,7 +77,7 @@ async fn test_login() {
     assert_eq!(response.user.id, FAKE_USER_ID);
     assert_eq!(response.user.username, SANITIZED_USERNAME);
     assert_eq!(response.user.email, FAKE_EMAIL);
-    assert_eq!(response.user.activated, false);
-    assert_eq!(response.user.blocked, false);
+    assert!(!response.user.activated);
+    assert!(!response.user.blocked);
     assert_eq!(response.token, FAKE_JWT_TOKEN);
 }
@@ -0,0 +1,92 @@
+use authentication_gRPC::{
+    controllers::authentication_controller::{AuthenticationController, UserController},
+    dtos::controllers::dtos_controller_user::{UpdateParams, UserControllerUpdatePasswordReq},
+    error::{AppError, Code},
+    models::authentication_model::MockAuthenticationModel,
+    security::jwt::{jwt_decode, jwt_decode_access, jwt_encode, jwt_encode_access},
+    services::sanitizer::sanitize_authentication_input::SanitizeUser,
+};
+
+const USER_ID: &str = "oauth-user-id";
+
+/// The model mock expects no call: the token must be refused before the user is reached.
+fn controller() -> UserController<MockAuthenticationModel, SanitizeUser> {
+    UserController {
+        model: MockAuthenticationModel::new(),
+        sanitize_user: SanitizeUser,
+        jwt_encode,
+        jwt_decode,
+        jwt_decode_access,
+    }
+}
+
+/// Token of an OAuth client the user granted every scope UserInfo knows.
+fn access_token() -> String {
+    jwt_encode_access(
+        USER_ID.to_string(),
+        true,
+        false,
+        vec![
+            "openid".to_string(),
+            "profile".to_string(),
+            "email".to_string(),
+        ],
+    )
+    .unwrap()
+}
+
+fn assert_permission_denied<T>(result: Result<T, AppError>) {
+    match result {
+        Ok(_) => panic!("An OAuth access token should have been refused"),
+        Err(error) => assert_eq!(error.code, Code::PermissionDenied),
+    }
+}
+
+#[tokio::test]
+async fn test_recover_user_data_refuses_oauth_access_token() {
+    assert_permission_denied(controller().recover_user_data(access_token()).await);
+}
+
+#[tokio::test]
+async fn test_update_refuses_oauth_access_token() {
+    let params = UpdateParams {
+        username: Some("new_username".to_string()),
+        email: None,
+    };
+
+    assert_permission_denied(controller().update(access_token(), params).await);
+}
+
+#[tokio::test]
+async fn test_update_email_refuses_oauth_access_token() {
+    let email = "new@email.com".to_string();
+
+    assert_permission_denied(controller().update_email(access_token(), email).await);
+}
+
+#[tokio::test]
+async fn test_update_password_refuses_oauth_access_token() {
+    let req = UserControllerUpdatePasswordReq {
+        new_password: "N3w-Passw0rd!".to_string(),
+        old_password: "0ld-Passw0rd!".to_string(),
+    };
+
+    assert_permission_denied(controller().update_password(access_token(), req).await);
+}
+
+#[tokio::test]
+async fn test_create_activation_code_refuses_oauth_access_token() {
+    assert_permission_denied(controller().create_activation_code(access_token()).await);
+}
+
+#[tokio::test]
+async fn test_activate_user_refuses_oauth_access_token() {
+    let code_key = "123456".to_string();
+
+    assert_permission_denied(controller().activate_user(access_token(), code_key).await);
+}
+
+#[tokio::test]
+async fn test_delete_user_refuses_oauth_access_token() {
+    assert_permission_denied(controller().delete_user(access_token()).await);
+}
@@ -1,7 +1,7 @@
 use authentication_gRPC::{
     controllers::authentication_controller::AuthenticationController,
     dtos::models::dtos_model_user::UserModelRecoverUserDataReturn,
-    security::jwt::JWTAuthenticateToken,
+    security::jwt::{JWTAuthenticateToken, RegisteredClaims},
 };
 
 use crate::{
@@ -41,7 +41,12 @@ async fn test_recover_user_data() {
                 sub: FAKE_USER_ID.to_string(),
                 activated: false,
                 blocked: false,
-                exp: 999999,
+                token_type: "user".to_string(),
+                scope: None,
+                claims: RegisteredClaims {
+                    exp: 999999,
+                    ..Default::default()
+                },
             })
         })
         .build();
@@ -53,6 +58,6 @@ async fn test_recover_user_data() {
 
     assert_eq!(response.user.username, FAKE_USERNAME);
     assert_eq!(response.user.email, FAKE_EMAIL);
-    assert_eq!(response.user.activated, false);
-    assert_eq!(response.user.blocked, false);
+    assert!(!response.user.activated);
+    assert!(!response.user.blocked);
 }
@@ -84,7 +84,7 @@ async fn test_register() {
     assert_eq!(response.user.id, FAKE_USER_ID);
     assert_eq!(response.user.username, SANITIZED_USERNAME);
     assert_eq!(response.user.email, SANITIZED_EMAIL);
-    assert_eq!(response.user.activated, false);
-    assert_eq!(response.user.blocked, false);
+    assert!(!response.user.activated);
+    assert!(!response.user.blocked);
     assert_eq!(response.token, FAKE_JWT_TOKEN);
 }
@@ -7,7 +7,8 @@ use crate::{
 };
 use authentication_gRPC::{
     controllers::authentication_controller::AuthenticationController,
-    dtos::models::dtos_model_user::UserModelUpdateParams, security::jwt::JWTAuthenticateToken,
+    dtos::models::dtos_model_user::UserModelUpdateParams,
+    security::jwt::{JWTAuthenticateToken, RegisteredClaims},
 };
 
 const FAKE_USER_ID: &str = "user_id";
@@ -48,7 +49,12 @@ async fn test_update_email() {
                 sub: FAKE_USER_ID.to_string(),
                 activated: true,
                 blocked: false,
-                exp: 99999999,
+                token_type: "user".to_string(),
+                scope: None,
+                claims: RegisteredClaims {
+                    exp: 99999999,
+                    ..Default::default()
+                },
             })
         })
         .build();
@@ -8,7 +8,7 @@ use crate::{
 use authentication_gRPC::{
     controllers::authentication_controller::AuthenticationController,
     dtos::controllers::dtos_controller_user::UserControllerUpdatePasswordReq,
-    security::jwt::JWTAuthenticateToken,
+    security::jwt::{JWTAuthenticateToken, RegisteredClaims},
 };
 
 const FAKE_USER_ID: &str = "user_id";
@@ -46,7 +46,12 @@ async fn test_update_password() {
                 sub: FAKE_USER_ID.to_string(),
                 activated: true,
                 blocked: false,
-                exp: 99999999,
+                token_type: "user".to_string(),
+                scope: None,
+                claims: RegisteredClaims {
+                    exp: 99999999,
+                    ..Default::default()
+                },
             })
         })
         .build();
@@ -83,7 +88,12 @@ async fn test_update_password_with_user_blocked() {
                 sub: FAKE_USER_ID.to_string(),
                 activated: true,
                 blocked: true,
-                exp: 99999999,
+                token_type: "user".to_string(),
+                scope: None,
+                claims: RegisteredClaims {
+                    exp: 99999999,
+                    ..Default::default()
+                },
             })
         })
         .build();
@@ -121,7 +131,12 @@ async fn test_update_password_with_user_not_activated() {
                 sub: FAKE_USER_ID.to_string(),
                 activated: false,
                 blocked: false,
-                exp: 99999999,
+                token_type: "user".to_string(),
+                scope: None,
+                claims: RegisteredClaims {
+                    exp: 99999999,
+                    ..Default::default()
+                },
             })
         })
         .build();
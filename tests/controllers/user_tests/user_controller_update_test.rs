@@ -11,7 +11,7 @@ use authentication_gRPC::{
         controllers::dtos_controller_user::UpdateParams,
         models::dtos_model_user::UserModelUpdateParams,
     },
-    security::jwt::JWTAuthenticateToken,
+    security::jwt::{JWTAuthenticateToken, RegisteredClaims},
 };
 
 const FAKE_USER_ID: &str = "user_id";
@@ -59,7 +59,12 @@ async fn test_update() {
                 sub: FAKE_USER_ID.to_string(),
                 activated: true,
                 blocked: false,
-                exp: 99999999,
+                token_type: "user".to_string(),
+                scope: None,
+                claims: RegisteredClaims {
+                    exp: 99999999,
+                    ..Default::default()
+                },
             })
         })
         .build();
@@ -101,7 +106,12 @@ async fn test_update_user_blocked() {
                 sub: FAKE_USER_ID.to_string(),
                 activated: true,
                 blocked: true,
-                exp: 99999999,
+                token_type: "user".to_string(),
+                scope: None,
+                claims: RegisteredClaims {
+                    exp: 99999999,
+                    ..Default::default()
+                },
             })
         })
         .build();
@@ -144,7 +154,12 @@ async fn test_update_user_not_activated() {
                 sub: FAKE_USER_ID.to_string(),
                 activated: false,
                 blocked: false,
-                exp: 99999999,
+                token_type: "user".to_string(),
+                scope: None,
+                claims: RegisteredClaims {
+                    exp: 99999999,
+                    ..Default::default()
+                },
             })
         })
         .build();
@@ -0,0 +1,113 @@
+use authentication_gRPC::{
+    controllers::authentication_controller::AuthenticationController,
+    dtos::models::dtos_model_user::UserModelRecoverUserDataReturn,
+    security::jwt::{JWTAuthenticateToken, RegisteredClaims},
+};
+
+use crate::{
+    mocks::user_model_mock::{
+        get_mock_user_model, MockUserModelParams, MockUserModelRecoverUserData,
+    },
+    utils::builders::UserControllerBuilderForTest,
+};
+
+#[tokio::test]
+async fn test_user_info() {
+    const FAKE_USER_ID: &str = "user_id";
+    const FAKE_USERNAME: &str = "username";
+    const FAKE_EMAIL: &str = "test@controller.com";
+    const FAKE_JWT_TOKEN: &str = "fake_jwt_token";
+
+    let mock_user_model = get_mock_user_model(MockUserModelParams {
+        recover_user_data: Some(MockUserModelRecoverUserData {
+            calls: 1,
+            param_id_with: FAKE_USER_ID.to_string(),
+            fn_returning: |_| {
+                Ok(UserModelRecoverUserDataReturn {
+                    username: FAKE_USERNAME.to_string(),
+                    email: FAKE_EMAIL.to_string(),
+                    activated: true,
+                    blocked: false,
+                })
+            },
+        }),
+        ..Default::default()
+    });
+
+    let controller_user = UserControllerBuilderForTest::new()
+        .mount_model(mock_user_model)
+        .mount_jwt_decode_access(|_| {
+            Ok(JWTAuthenticateToken {
+                sub: FAKE_USER_ID.to_string(),
+                activated: true,
+                blocked: false,
+                token_type: "user".to_string(),
+                scope: None,
+                claims: RegisteredClaims {
+                    exp: 999999,
+                    ..Default::default()
+                },
+            })
+        })
+        .build();
+
+    let response = controller_user
+        .user_info(FAKE_JWT_TOKEN.to_string())
+        .await
+        .unwrap();
+
+    assert_eq!(response.sub, FAKE_USER_ID);
+    assert_eq!(response.preferred_username.as_deref(), Some(FAKE_USERNAME));
+    assert_eq!(response.email.as_deref(), Some(FAKE_EMAIL));
+    assert_eq!(response.email_verified, Some(true));
+}
+
+#[tokio::test]
+async fn test_user_info_only_releases_the_granted_claims() {
+    const FAKE_USER_ID: &str = "user_id";
+    const FAKE_EMAIL: &str = "test@controller.com";
+    const FAKE_JWT_TOKEN: &str = "fake_jwt_token";
+
+    let mock_user_model = get_mock_user_model(MockUserModelParams {
+        recover_user_data: Some(MockUserModelRecoverUserData {
+            calls: 1,
+            param_id_with: FAKE_USER_ID.to_string(),
+            fn_returning: |_| {
+                Ok(UserModelRecoverUserDataReturn {
+                    username: "username".to_string(),
+                    email: FAKE_EMAIL.to_string(),
+                    activated: false,
+                    blocked: false,
+                })
+            },
+        }),
+        ..Default::default()
+    });
+
+    let controller_user = UserControllerBuilderForTest::new()
+        .mount_model(mock_user_model)
+        .mount_jwt_decode_access(|_| {
+            Ok(JWTAuthenticateToken {
+                sub: FAKE_USER_ID.to_string(),
+                activated: false,
+                blocked: false,
+                token_type: "user".to_string(),
+                scope: Some("openid email".to_string()),
+                claims: RegisteredClaims {
+                    exp: 999999,
+                    ..Default::default()
+                },
+            })
+        })
+        .build();
+
+    let response = controller_user
+        .user_info(FAKE_JWT_TOKEN.to_string())
+        .await
+        .unwrap();
+
+    assert_eq!(response.sub, FAKE_USER_ID);
+    assert!(response.preferred_username.is_none());
+    assert_eq!(response.email.as_deref(), Some(FAKE_EMAIL));
+    assert_eq!(response.email_verified, Some(false));
+}
@@ -1,6 +1,6 @@
 use authentication_gRPC::{
     controllers::authentication_controller::AuthenticationController,
-    security::jwt::JWTAuthenticateToken,
+    security::jwt::{JWTAuthenticateToken, RegisteredClaims},
 };
 
 use crate::{
@@ -29,7 +29,12 @@ async fn test_delete_user() {
                 sub: FAKE_USER_ID.to_string(),
                 activated: true,
                 blocked: false,
-                exp: 99999999,
+                token_type: "user".to_string(),
+                scope: None,
+                claims: RegisteredClaims {
+                    exp: 99999999,
+                    ..Default::default()
+                },
             })
         })
         .build();
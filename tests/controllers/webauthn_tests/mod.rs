@@ -0,0 +1 @@
+mod webauthn_controller_oauth_access_token_test;
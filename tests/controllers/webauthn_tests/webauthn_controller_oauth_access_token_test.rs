@@ -0,0 +1,51 @@
+use authentication_gRPC::{
+    controllers::webauthn_controller::{PasskeyAuthenticationController, WebauthnController},
+    dtos::controllers::dtos_controller_webauthn::FinishPasskeyRegistrationParams,
+    error::{AppError, Code},
+    models::webauthn_model::MockPasskeyAuthenticationModel,
+    security::jwt::{jwt_decode, jwt_encode, jwt_encode_access},
+    services::sanitizer::sanitize_authentication_input::SanitizeUser,
+};
+
+/// The model mock expects no call: the token must be refused before a passkey is reached.
+fn controller() -> WebauthnController<MockPasskeyAuthenticationModel, SanitizeUser> {
+    WebauthnController {
+        model: MockPasskeyAuthenticationModel::new(),
+        sanitize_user: SanitizeUser,
+        jwt_encode,
+        jwt_decode,
+    }
+}
+
+fn access_token() -> String {
+    jwt_encode_access(
+        "oauth-user-id".to_string(),
+        true,
+        false,
+        vec!["openid".to_string()],
+    )
+    .unwrap()
+}
+
+fn assert_permission_denied<T>(result: Result<T, AppError>) {
+    match result {
+        Ok(_) => panic!("An OAuth access token should have been refused"),
+        Err(error) => assert_eq!(error.code, Code::PermissionDenied),
+    }
+}
+
+#[tokio::test]
+async fn test_begin_registration_refuses_oauth_access_token() {
+    assert_permission_denied(controller().begin_registration(access_token()).await);
+}
+
+#[tokio::test]
+async fn test_finish_registration_refuses_oauth_access_token() {
+    let req = FinishPasskeyRegistrationParams {
+        name: "Laptop".to_string(),
+        client_data_json: vec![],
+        attestation_object: vec![],
+    };
+
+    assert_permission_denied(controller().finish_registration(access_token(), req).await);
+}
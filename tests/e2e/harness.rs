@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use authentication_gRPC::{
+    config::app_config::Config,
+    database::{connection::lazy_postgres_pool, redis_pool::RedisPool, storage::Storage},
+    rpc::{
+        authentication::authentication::authentication_client::AuthenticationClient,
+        router::grpc_router,
+    },
+    security::jwt::init_jwt_config,
+    AppState,
+};
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::{transport::Channel, Request};
+
+/// The gRPC server as `server --in-memory` runs it, on a random port. Neither Postgres nor
+/// Redis is reached, each server starting without users. It stops with the test.
+pub struct TestServer {
+    url: String,
+}
+
+impl TestServer {
+    pub async fn start() -> TestServer {
+        let config = Config::from_sources(None, &|name| match name {
+            "STORAGE_BACKEND" => Some("memory".to_string()),
+            "MAIL_LOG_ONLY" => Some("true".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        // The first settings installed stay for the whole test binary, tokens being signed
+        // and checked with them alike.
+        init_jwt_config(config.jwt.clone());
+
+        let app_state = AppState {
+            db_pg_pool: lazy_postgres_pool(&config.database).unwrap(),
+            redis: RedisPool::open(&config.redis).unwrap(),
+            storage: Storage::open(&config.storage).await.unwrap(),
+            config: Arc::new(config),
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        tokio::spawn(grpc_router(app_state).serve_with_incoming(TcpListenerStream::new(listener)));
+
+        TestServer {
+            url: format!("http://127.0.0.1:{}", port),
+        }
+    }
+
+    pub async fn client(&self) -> AuthenticationClient<Channel> {
+        AuthenticationClient::connect(self.url.clone())
+            .await
+            .unwrap()
+    }
+}
+
+/// `message` sent with `token` as the `authorization` metadata, as the clients do.
+pub fn authorized<T>(message: T, token: &str) -> Request<T> {
+    let mut request = Request::new(message);
+    request
+        .metadata_mut()
+        .insert("authorization", token.parse().unwrap());
+    request
+}
@@ -0,0 +1,2 @@
+mod harness;
+mod user_lifecycle_test;
@@ -0,0 +1,344 @@
+use authentication_gRPC::{
+    rpc::authentication::authentication::{
+        authentication_client::AuthenticationClient, ReqActivateUser, ReqCreateActivationCode,
+        ReqCreatePersonalAccessToken, ReqCreateRecoveryCode, ReqDeleteUser, ReqListWebhooks,
+        ReqLogin, ReqRecoverUserData, ReqRecoverUserPassword, ReqRegister, ReqUpdateEmail,
+        ReqUpdatePassword, ReqUpdateUser, ReqUserInfo, ReqWatchUserEvents, ResRegister,
+    },
+    security::jwt::jwt_encode_access,
+};
+use tonic::{metadata::MetadataValue, transport::Channel, Code, Request};
+
+use super::harness::{authorized, TestServer};
+
+const USERNAME: &str = "lifecycle";
+const EMAIL: &str = "lifecycle@test.com";
+const PASSWORD: &str = "password";
+
+async fn register(client: &mut AuthenticationClient<Channel>) -> ResRegister {
+    client
+        .register(ReqRegister {
+            username: USERNAME.to_string(),
+            email: EMAIL.to_string(),
+            password: PASSWORD.to_string(),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+}
+
+async fn login(
+    client: &mut AuthenticationClient<Channel>,
+    username: &str,
+    password: &str,
+) -> Result<String, tonic::Status> {
+    let response = client
+        .login(ReqLogin {
+            username: username.to_string(),
+            password: password.to_string(),
+        })
+        .await?;
+
+    Ok(response.into_inner().token)
+}
+
+#[tokio::test]
+async fn test_user_goes_from_register_to_delete() {
+    let server = TestServer::start().await;
+    let mut client = server.client().await;
+
+    let registered = register(&mut client).await;
+    let user = registered.user.unwrap();
+    assert_eq!(user.username, USERNAME);
+    assert_eq!(user.email, EMAIL);
+    assert!(!user.activated);
+    assert!(registered.expires_in > 0);
+
+    let code = client
+        .create_activation_code(authorized(ReqCreateActivationCode {}, &registered.token))
+        .await
+        .unwrap()
+        .into_inner()
+        .code;
+    client
+        .activate_user(authorized(
+            ReqActivateUser { code_key: code },
+            &registered.token,
+        ))
+        .await
+        .unwrap();
+
+    // The token of the registration still says the user is not activated.
+    let token = login(&mut client, USERNAME, PASSWORD).await.unwrap();
+    let user = client
+        .recover_user_data(authorized(ReqRecoverUserData {}, &token))
+        .await
+        .unwrap()
+        .into_inner()
+        .user
+        .unwrap();
+    assert!(user.activated);
+
+    client
+        .update(authorized(
+            ReqUpdateUser {
+                username: Some("renamed".to_string()),
+                email: None,
+            },
+            &token,
+        ))
+        .await
+        .unwrap();
+    let user = client
+        .recover_user_data(authorized(ReqRecoverUserData {}, &token))
+        .await
+        .unwrap()
+        .into_inner()
+        .user
+        .unwrap();
+    assert_eq!(user.username, "renamed");
+    assert_eq!(user.email, EMAIL);
+
+    let code = client
+        .create_recovery_code(ReqCreateRecoveryCode {
+            email: EMAIL.to_string(),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .code;
+    client
+        .recover_user_password(ReqRecoverUserPassword {
+            email: EMAIL.to_string(),
+            new_password: "recovered".to_string(),
+            code_key: code,
+        })
+        .await
+        .unwrap();
+    let status = login(&mut client, "renamed", PASSWORD).await.unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+    let token = login(&mut client, "renamed", "recovered").await.unwrap();
+
+    client
+        .delete_user(authorized(ReqDeleteUser {}, &token))
+        .await
+        .unwrap();
+    let status = login(&mut client, "renamed", "recovered")
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::NotFound);
+    let status = client
+        .recover_user_data(authorized(ReqRecoverUserData {}, &token))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::NotFound);
+}
+
+#[tokio::test]
+async fn test_user_rpcs_require_a_valid_authorization_token() {
+    let server = TestServer::start().await;
+    let mut client = server.client().await;
+    register(&mut client).await;
+
+    let status = client
+        .recover_user_data(Request::new(ReqRecoverUserData {}))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+    let status = client
+        .delete_user(Request::new(ReqDeleteUser {}))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    let status = client
+        .recover_user_data(authorized(ReqRecoverUserData {}, "not.a.token"))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+
+    let mut request = Request::new(ReqDeleteUser {});
+    request.metadata_mut().insert(
+        "authorization",
+        MetadataValue::try_from("tökén".as_bytes()).unwrap(),
+    );
+    let status = client.delete_user(request).await.unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    // Nothing was deleted by the refused requests.
+    login(&mut client, USERNAME, PASSWORD).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_oauth_access_token_only_reaches_user_info() {
+    let server = TestServer::start().await;
+    let mut client = server.client().await;
+    let user = register(&mut client).await.user.unwrap();
+    let access_token = jwt_encode_access(
+        user.id.clone(),
+        user.activated,
+        user.blocked,
+        vec!["openid".to_string(), "profile".to_string()],
+    )
+    .unwrap();
+
+    let user_info = client
+        .user_info(authorized(ReqUserInfo {}, &access_token))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(user_info.sub, user.id);
+    assert_eq!(user_info.preferred_username.as_deref(), Some(USERNAME));
+
+    let refused = [
+        client
+            .recover_user_data(authorized(ReqRecoverUserData {}, &access_token))
+            .await
+            .map(|_| ()),
+        client
+            .update(authorized(
+                ReqUpdateUser {
+                    username: Some("taken_over".to_string()),
+                    email: None,
+                },
+                &access_token,
+            ))
+            .await
+            .map(|_| ()),
+        client
+            .update_email(authorized(
+                ReqUpdateEmail {
+                    email: "taken@over.com".to_string(),
+                },
+                &access_token,
+            ))
+            .await
+            .map(|_| ()),
+        client
+            .update_password(authorized(
+                ReqUpdatePassword {
+                    new_password: "taken over".to_string(),
+                    old_password: PASSWORD.to_string(),
+                },
+                &access_token,
+            ))
+            .await
+            .map(|_| ()),
+        client
+            .create_activation_code(authorized(ReqCreateActivationCode {}, &access_token))
+            .await
+            .map(|_| ()),
+        client
+            .delete_user(authorized(ReqDeleteUser {}, &access_token))
+            .await
+            .map(|_| ()),
+    ];
+    for result in refused {
+        assert_eq!(result.unwrap_err().code(), Code::PermissionDenied);
+    }
+
+    // The account is untouched.
+    login(&mut client, USERNAME, PASSWORD).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_user_must_be_activated_with_their_own_code_to_update() {
+    let server = TestServer::start().await;
+    let mut client = server.client().await;
+    let token = register(&mut client).await.token;
+
+    let update = || ReqUpdateUser {
+        username: Some("renamed".to_string()),
+        email: None,
+    };
+    let status = client
+        .update(authorized(update(), &token))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::PermissionDenied);
+
+    client
+        .create_activation_code(authorized(ReqCreateActivationCode {}, &token))
+        .await
+        .unwrap();
+    let status = client
+        .activate_user(authorized(
+            ReqActivateUser {
+                code_key: "not the code".to_string(),
+            },
+            &token,
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::NotFound);
+
+    let token = login(&mut client, USERNAME, PASSWORD).await.unwrap();
+    let status = client
+        .update(authorized(update(), &token))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::PermissionDenied);
+}
+
+#[tokio::test]
+async fn test_taken_username_and_email_are_refused() {
+    let server = TestServer::start().await;
+    let mut client = server.client().await;
+    register(&mut client).await;
+
+    let status = client
+        .register(ReqRegister {
+            username: USERNAME.to_string(),
+            email: "other@test.com".to_string(),
+            password: PASSWORD.to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::AlreadyExists);
+
+    let status = client
+        .register(ReqRegister {
+            username: "other".to_string(),
+            email: EMAIL.to_string(),
+            password: PASSWORD.to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::AlreadyExists);
+}
+
+#[tokio::test]
+async fn test_rpcs_needing_postgres_are_refused_with_in_memory_storage() {
+    let server = TestServer::start().await;
+    let mut client = server.client().await;
+    let token = register(&mut client).await.token;
+
+    let status = client
+        .create_personal_access_token(authorized(
+            ReqCreatePersonalAccessToken {
+                name: "ci".to_string(),
+                scopes: vec!["user:read".to_string()],
+                expires_at: None,
+            },
+            &token,
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::FailedPrecondition);
+    let status = client
+        .list_webhooks(authorized(ReqListWebhooks {}, "admin token"))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::FailedPrecondition);
+    let status = client
+        .watch_user_events(authorized(
+            ReqWatchUserEvents {
+                from_offset: 0,
+                event_types: vec![],
+            },
+            "subscriber token",
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::FailedPrecondition);
+}
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use authentication_gRPC::{
+    config::app_config::Config,
+    database::{connection::lazy_postgres_pool, redis_pool::RedisPool, storage::Storage},
+    i18n::locale::{LocaleLayer, ACCEPT_LANGUAGE_HEADER},
+    rpc::authentication::{
+        authentication::{
+            authentication_client::AuthenticationClient,
+            authentication_server::AuthenticationServer, ReqLogin,
+        },
+        AuthenticationService,
+    },
+    AppState,
+};
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::{transport::Server, Code, Request};
+
+/// Serve the gRPC service on a random port. The database is never reached, requests are
+/// refused by input validation first.
+async fn start_server() -> String {
+    let config = Config::from_sources(None, &|name| match name {
+        "DATABASE_URL" => Some("postgres://postgres@127.0.0.1:1/unused".to_string()),
+        "REDIS_CLIENT" => Some("redis://127.0.0.1:1/".to_string()),
+        "JWT_SECRET" => Some("secret".to_string()),
+        "MAIL_LOG_ONLY" => Some("true".to_string()),
+        _ => None,
+    })
+    .unwrap();
+    let app_state = AppState {
+        db_pg_pool: lazy_postgres_pool(&config.database).unwrap(),
+        redis: RedisPool::open(&config.redis).unwrap(),
+        storage: Storage::Postgres,
+        config: Arc::new(config),
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    tokio::spawn(
+        Server::builder()
+            .layer(LocaleLayer)
+            .add_service(AuthenticationServer::new(AuthenticationService::new(
+                app_state,
+            )))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    format!("http://127.0.0.1:{}", port)
+}
+
+async fn login_error(url: &str, accept_language: Option<&str>) -> String {
+    let mut client = AuthenticationClient::connect(url.to_string())
+        .await
+        .unwrap();
+
+    let mut request = Request::new(ReqLogin {
+        username: String::new(),
+        password: String::new(),
+    });
+    if let Some(accept_language) = accept_language {
+        request
+            .metadata_mut()
+            .insert(ACCEPT_LANGUAGE_HEADER, accept_language.parse().unwrap());
+    }
+
+    let status = client.login(request).await.unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+
+    status.message().to_string()
+}
+
+#[tokio::test]
+async fn test_error_messages_follow_accept_language() {
+    let url = start_server().await;
+
+    assert_eq!(
+        login_error(&url, Some("pt-BR,pt;q=0.9,en;q=0.8")).await,
+        "O nome de usuário está vazio"
+    );
+    assert_eq!(
+        login_error(&url, Some("fr, en;q=0.5")).await,
+        "Username is empty"
+    );
+    assert_eq!(login_error(&url, None).await, "Username is empty");
+}
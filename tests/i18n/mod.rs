@@ -0,0 +1 @@
+mod locale_test;
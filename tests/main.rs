@@ -1,4 +1,10 @@
 mod controllers;
+mod e2e;
+mod i18n;
 mod mocks;
 mod models;
+mod repositories;
+mod security;
+mod services;
+mod telemetry;
 mod utils;
@@ -33,13 +33,12 @@ pub fn get_mock_user_input_sanitizer(
 ) -> MockSanitizeAuthentication {
     let mut mock_user_input_sanitize = MockSanitizeAuthentication::new();
 
-    if expectations.username.is_some() {
-        let MockUserInputSanitizeUsername {
-            calls,
-            param_username_with,
-            fn_returning,
-        } = expectations.username.unwrap();
-
+    if let Some(MockUserInputSanitizeUsername {
+        calls,
+        param_username_with,
+        fn_returning,
+    }) = expectations.username
+    {
         mock_user_input_sanitize
             .expect_sanitize_username_input()
             .with(predicate::eq(param_username_with))
@@ -47,13 +46,12 @@ pub fn get_mock_user_input_sanitizer(
             .returning(fn_returning);
     }
 
-    if expectations.email.is_some() {
-        let MockUserInputSanitizeEmail {
-            calls,
-            param_email_with,
-            fn_returning,
-        } = expectations.email.unwrap();
-
+    if let Some(MockUserInputSanitizeEmail {
+        calls,
+        param_email_with,
+        fn_returning,
+    }) = expectations.email
+    {
         mock_user_input_sanitize
             .expect_sanitize_email_input()
             .with(predicate::eq(param_email_with))
@@ -61,13 +59,12 @@ pub fn get_mock_user_input_sanitizer(
             .returning(fn_returning);
     }
 
-    if expectations.password.is_some() {
-        let MockUserInputSanitizePassword {
-            calls,
-            param_password_with,
-            fn_returning,
-        } = expectations.password.unwrap();
-
+    if let Some(MockUserInputSanitizePassword {
+        calls,
+        param_password_with,
+        fn_returning,
+    }) = expectations.password
+    {
         mock_user_input_sanitize
             .expect_sanitize_password_input()
             .with(predicate::eq(param_password_with))
@@ -96,13 +96,12 @@ pub struct MockUserModelParams {
 pub fn get_mock_user_model(expectations: MockUserModelParams) -> MockAuthenticationModel {
     let mut mock_user_model = MockAuthenticationModel::new();
 
-    if expectations.create.is_some() {
-        let MockUserModelCreate {
-            calls,
-            fn_returning,
-            param_user_with,
-        } = expectations.create.unwrap();
-
+    if let Some(MockUserModelCreate {
+        calls,
+        fn_returning,
+        param_user_with,
+    }) = expectations.create
+    {
         mock_user_model
             .expect_create()
             .with(predicate::eq(param_user_with))
@@ -110,14 +109,13 @@ pub fn get_mock_user_model(expectations: MockUserModelParams) -> MockAuthenticat
             .returning(move |user| Box::pin(async move { fn_returning(user) }));
     }
 
-    if expectations.login_verification.is_some() {
-        let MockUserModelLoginVerification {
-            calls,
-            fn_returning,
-            param_username_with,
-            param_password_with,
-        } = expectations.login_verification.unwrap();
-
+    if let Some(MockUserModelLoginVerification {
+        calls,
+        fn_returning,
+        param_username_with,
+        param_password_with,
+    }) = expectations.login_verification
+    {
         mock_user_model
             .expect_login_verification()
             .with(
@@ -130,13 +128,12 @@ pub fn get_mock_user_model(expectations: MockUserModelParams) -> MockAuthenticat
             });
     }
 
-    if expectations.recover_user_data.is_some() {
-        let MockUserModelRecoverUserData {
-            calls,
-            fn_returning,
-            param_id_with,
-        } = expectations.recover_user_data.unwrap();
-
+    if let Some(MockUserModelRecoverUserData {
+        calls,
+        fn_returning,
+        param_id_with,
+    }) = expectations.recover_user_data
+    {
         mock_user_model
             .expect_recover_user_data()
             .with(predicate::eq(param_id_with))
@@ -144,14 +141,13 @@ pub fn get_mock_user_model(expectations: MockUserModelParams) -> MockAuthenticat
             .returning(move |id| Box::pin(async move { fn_returning(id) }));
     }
 
-    if expectations.update.is_some() {
-        let MockUserModelUpdate {
-            calls,
-            fn_returning,
-            param_id_with,
-            param_user_with,
-        } = expectations.update.unwrap();
-
+    if let Some(MockUserModelUpdate {
+        calls,
+        fn_returning,
+        param_id_with,
+        param_user_with,
+    }) = expectations.update
+    {
         mock_user_model
             .expect_update()
             .with(predicate::eq(param_id_with), predicate::eq(param_user_with))
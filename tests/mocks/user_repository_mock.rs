@@ -7,7 +7,6 @@ use authentication_gRPC::{
 };
 use mockall::predicate;
 
-#[derive(Debug, PartialEq)]
 pub struct MockUserRepositoryStore {
     pub calls: usize,
     pub param_user_with: UserRepositoryStoreParams,
@@ -57,13 +56,12 @@ pub struct MockUserRepositoryParams {
 pub fn get_mock_user_repository(expectations: MockUserRepositoryParams) -> MockUserRepository {
     let mut mock_user_repository = MockUserRepository::new();
 
-    if expectations.store.is_some() {
-        let MockUserRepositoryStore {
-            calls,
-            fn_returning,
-            param_user_with,
-        } = expectations.store.unwrap();
-
+    if let Some(MockUserRepositoryStore {
+        calls,
+        fn_returning,
+        param_user_with,
+    }) = expectations.store
+    {
         mock_user_repository
             .expect_store()
             .with(predicate::eq(param_user_with))
@@ -71,13 +69,12 @@ pub fn get_mock_user_repository(expectations: MockUserRepositoryParams) -> MockU
             .returning(move |user| Box::pin(async move { fn_returning(user) }));
     }
 
-    if expectations.consult_by_username.is_some() {
-        let MockUserRepositoryConsultByUsername {
-            calls,
-            fn_returning,
-            param_username_with,
-        } = expectations.consult_by_username.unwrap();
-
+    if let Some(MockUserRepositoryConsultByUsername {
+        calls,
+        fn_returning,
+        param_username_with,
+    }) = expectations.consult_by_username
+    {
         mock_user_repository
             .expect_consult_by_username()
             .with(predicate::eq(param_username_with))
@@ -85,13 +82,12 @@ pub fn get_mock_user_repository(expectations: MockUserRepositoryParams) -> MockU
             .returning(move |username| Box::pin(async move { fn_returning(username) }));
     }
 
-    if expectations.consult_by_id.is_some() {
-        let MockUserRepositoryConsultById {
-            calls,
-            fn_returning,
-            param_id_with,
-        } = expectations.consult_by_id.unwrap();
-
+    if let Some(MockUserRepositoryConsultById {
+        calls,
+        fn_returning,
+        param_id_with,
+    }) = expectations.consult_by_id
+    {
         mock_user_repository
             .expect_consult_by_id()
             .with(predicate::eq(param_id_with))
@@ -112,14 +108,13 @@ pub fn get_mock_user_repository(expectations: MockUserRepositoryParams) -> MockU
             .returning(move |email| Box::pin(async move { fn_returning(email) }));
     }
 
-    if expectations.store_update.is_some() {
-        let MockUserRepositoryStoreUpdate {
-            calls,
-            fn_returning,
-            param_id_with,
-            param_user_with,
-        } = expectations.store_update.unwrap();
-
+    if let Some(MockUserRepositoryStoreUpdate {
+        calls,
+        fn_returning,
+        param_id_with,
+        param_user_with,
+    }) = expectations.store_update
+    {
         mock_user_repository
             .expect_store_update()
             .with(predicate::eq(param_id_with), predicate::eq(param_user_with))
@@ -0,0 +1,314 @@
+use authentication_gRPC::{
+    error::{AppError, Code},
+    models::federation_model::{FederatedLoginModel, FederationModel},
+    repositories::{
+        federated_identity_repository::{
+            FederatedIdentity, FederatedIdentityStoreParams, MockFederatedIdentityRepository,
+        },
+        user_repository::{
+            MockUserRepository, UserRepositoryConsultReturn, UserRepositoryStoreParams,
+            UserRepositoryStoreReturn, UserRepositoryUpdateParams,
+        },
+    },
+    security::{federation::FederatedProvider, oidc::OidcSigningKey},
+    services::federation::jwks_fetcher::MockJwksFetcher,
+};
+use chrono::Utc;
+use jsonwebtoken::{get_current_timestamp, jwk::JwkSet};
+use mockall::predicate;
+use serde_json::{json, Value};
+
+const ISSUER: &str = "https://sso.example.com";
+const JWKS_URI: &str = "https://sso.example.com/jwks";
+const CLIENT_ID: &str = "authentication";
+const SUBJECT: &str = "248289761001";
+const FAKE_USER_ID: &str = "fake_user_id";
+const EMAIL: &str = "jane@example.com";
+
+type TestFederationModel =
+    FederationModel<MockUserRepository, MockFederatedIdentityRepository, MockJwksFetcher>;
+
+fn provider() -> FederatedProvider {
+    FederatedProvider {
+        name: "corporate".to_string(),
+        issuer: ISSUER.to_string(),
+        jwks_uri: JWKS_URI.to_string(),
+        client_id: CLIENT_ID.to_string(),
+    }
+}
+
+fn id_token(signing_key: &OidcSigningKey, claims: Value) -> String {
+    let mut token_claims = json!({
+        "iss": ISSUER,
+        "sub": SUBJECT,
+        "aud": CLIENT_ID,
+        "exp": get_current_timestamp() + 300,
+        "email": EMAIL,
+        "email_verified": true,
+        "preferred_username": "jane.doe",
+    });
+    for (name, value) in claims.as_object().unwrap() {
+        token_claims[name] = value.clone();
+    }
+
+    signing_key.sign(&token_claims).unwrap()
+}
+
+/// Local stand-in of the provider JWKS endpoint.
+fn jwks_fetcher(signing_key: &OidcSigningKey) -> MockJwksFetcher {
+    let jwks: JwkSet =
+        serde_json::from_value(json!({ "keys": [signing_key.public_jwk()] })).unwrap();
+
+    let mut jwks_fetcher = MockJwksFetcher::new();
+    jwks_fetcher
+        .expect_fetch()
+        .with(predicate::eq(JWKS_URI.to_string()))
+        .returning(move |_| {
+            let jwks = jwks.clone();
+            Box::pin(async move { Ok(jwks) })
+        });
+    jwks_fetcher
+}
+
+fn create_model(
+    signing_key: &OidcSigningKey,
+    user_repository: MockUserRepository,
+    identity_repository: MockFederatedIdentityRepository,
+) -> TestFederationModel {
+    FederationModel {
+        user_repository,
+        identity_repository,
+        jwks_fetcher: jwks_fetcher(signing_key),
+        providers: vec![provider()],
+        password_hasher: |password| Ok(password),
+        new_id: || "0f4d2c9a-new-id".to_string(),
+        generate_secret: || "fake_generated_secret".to_string(),
+    }
+}
+
+fn fake_user(activated: bool) -> UserRepositoryConsultReturn {
+    UserRepositoryConsultReturn {
+        id: FAKE_USER_ID.to_string(),
+        username: "jane".to_string(),
+        email: EMAIL.to_string(),
+        password: "hash".to_string(),
+        activated,
+        blocked: false,
+    }
+}
+
+fn identity_repository_without_identity() -> MockFederatedIdentityRepository {
+    let mut identity_repository = MockFederatedIdentityRepository::new();
+    identity_repository
+        .expect_consult()
+        .with(
+            predicate::eq(ISSUER.to_string()),
+            predicate::eq(SUBJECT.to_string()),
+        )
+        .times(1)
+        .returning(|_, _| Box::pin(async { Err(AppError::new(Code::NotFound, "not found")) }));
+    identity_repository
+}
+
+fn expect_identity_stored(
+    identity_repository: &mut MockFederatedIdentityRepository,
+    user_id: &str,
+) {
+    identity_repository
+        .expect_store()
+        .with(predicate::eq(FederatedIdentityStoreParams {
+            issuer: ISSUER.to_string(),
+            subject: SUBJECT.to_string(),
+            user_id: user_id.to_string(),
+            email: Some(EMAIL.to_string()),
+        }))
+        .times(1)
+        .returning(|identity| {
+            Box::pin(async move {
+                Ok(FederatedIdentity {
+                    issuer: identity.issuer,
+                    subject: identity.subject,
+                    user_id: identity.user_id,
+                    email: identity.email,
+                    created_at: Utc::now().naive_utc(),
+                })
+            })
+        });
+}
+
+#[tokio::test]
+async fn test_external_login_with_linked_identity() {
+    let signing_key = OidcSigningKey::generate().unwrap();
+
+    let mut identity_repository = MockFederatedIdentityRepository::new();
+    identity_repository
+        .expect_consult()
+        .times(1)
+        .returning(|issuer, subject| {
+            Box::pin(async move {
+                Ok(FederatedIdentity {
+                    issuer,
+                    subject,
+                    user_id: FAKE_USER_ID.to_string(),
+                    email: Some(EMAIL.to_string()),
+                    created_at: Utc::now().naive_utc(),
+                })
+            })
+        });
+    identity_repository.expect_store().never();
+
+    let mut user_repository = MockUserRepository::new();
+    user_repository
+        .expect_consult_by_id()
+        .with(predicate::eq(FAKE_USER_ID.to_string()))
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(fake_user(true)) }));
+
+    let model = create_model(&signing_key, user_repository, identity_repository);
+
+    let user = model
+        .external_login(id_token(&signing_key, json!({})))
+        .await
+        .unwrap();
+
+    assert_eq!(user.id, FAKE_USER_ID);
+    assert!(!user.created);
+}
+
+#[tokio::test]
+async fn test_external_login_links_account_with_verified_email() {
+    let signing_key = OidcSigningKey::generate().unwrap();
+
+    let mut identity_repository = identity_repository_without_identity();
+    expect_identity_stored(&mut identity_repository, FAKE_USER_ID);
+
+    let mut user_repository = MockUserRepository::new();
+    user_repository
+        .expect_consult_by_email()
+        .with(predicate::eq(EMAIL.to_string()))
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(fake_user(true)) }));
+    user_repository.expect_store().never();
+
+    let model = create_model(&signing_key, user_repository, identity_repository);
+
+    let user = model
+        .external_login(id_token(&signing_key, json!({})))
+        .await
+        .unwrap();
+
+    assert_eq!(user.id, FAKE_USER_ID);
+    assert!(!user.created);
+}
+
+#[tokio::test]
+async fn test_external_login_does_not_link_unverified_email() {
+    let signing_key = OidcSigningKey::generate().unwrap();
+
+    let mut identity_repository = identity_repository_without_identity();
+    identity_repository.expect_store().never();
+
+    let mut user_repository = MockUserRepository::new();
+    user_repository
+        .expect_consult_by_email()
+        .returning(|_| Box::pin(async { Ok(fake_user(true)) }));
+
+    let model = create_model(&signing_key, user_repository, identity_repository);
+
+    let error = model
+        .external_login(id_token(&signing_key, json!({ "email_verified": false })))
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, Code::AlreadyExists);
+}
+
+#[tokio::test]
+async fn test_external_login_provisions_new_account() {
+    let signing_key = OidcSigningKey::generate().unwrap();
+
+    let mut identity_repository = identity_repository_without_identity();
+    expect_identity_stored(&mut identity_repository, "0f4d2c9a-new-id");
+
+    let mut user_repository = MockUserRepository::new();
+    user_repository
+        .expect_consult_by_email()
+        .returning(|_| Box::pin(async { Err(AppError::new(Code::NotFound, "not found")) }));
+    user_repository
+        .expect_consult_by_username()
+        .with(predicate::eq("janedoe".to_string()))
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(fake_user(true)) }));
+    user_repository
+        .expect_consult_by_username()
+        .with(predicate::eq("janedoe0f4d2c".to_string()))
+        .times(1)
+        .returning(|_| Box::pin(async { Err(AppError::new(Code::NotFound, "not found")) }));
+    user_repository
+        .expect_store()
+        .with(predicate::eq(UserRepositoryStoreParams {
+            id: "0f4d2c9a-new-id".to_string(),
+            username: "janedoe0f4d2c".to_string(),
+            email: EMAIL.to_string(),
+            password: "fake_generated_secret".to_string(),
+        }))
+        .times(1)
+        .returning(|user| {
+            Box::pin(async move {
+                Ok(UserRepositoryStoreReturn {
+                    id: user.id,
+                    username: user.username,
+                    email: user.email,
+                    activated: false,
+                    blocked: false,
+                })
+            })
+        });
+    user_repository
+        .expect_store_update()
+        .with(
+            predicate::eq("0f4d2c9a-new-id".to_string()),
+            predicate::eq(UserRepositoryUpdateParams {
+                activated: Some(true),
+                ..Default::default()
+            }),
+        )
+        .times(1)
+        .returning(|_, _| Box::pin(async { Ok("User updated".to_string()) }));
+
+    let model = create_model(&signing_key, user_repository, identity_repository);
+
+    let user = model
+        .external_login(id_token(&signing_key, json!({})))
+        .await
+        .unwrap();
+
+    assert_eq!(user.username, "janedoe0f4d2c");
+    assert!(user.activated);
+    assert!(user.created);
+}
+
+#[tokio::test]
+async fn test_external_login_rejects_untrusted_tokens() {
+    let signing_key = OidcSigningKey::generate().unwrap();
+    let other_key = OidcSigningKey::generate().unwrap();
+
+    let mut identity_repository = MockFederatedIdentityRepository::new();
+    identity_repository.expect_consult().never();
+
+    let model = create_model(&signing_key, MockUserRepository::new(), identity_repository);
+
+    for id_token in [
+        id_token(&signing_key, json!({ "iss": "https://evil.example.com" })),
+        id_token(&signing_key, json!({ "aud": "another-client" })),
+        id_token(
+            &signing_key,
+            json!({ "exp": get_current_timestamp() - 3600 }),
+        ),
+        id_token(&other_key, json!({})),
+    ] {
+        let error = model.external_login(id_token).await.unwrap_err();
+
+        assert_eq!(error.code, Code::Unauthenticated);
+    }
+}
@@ -0,0 +1 @@
+mod federation_model_test;
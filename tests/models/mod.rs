@@ -1 +1,7 @@
+mod federation_tests;
+mod oauth_tests;
+mod passwordless_tests;
+mod personal_access_token_tests;
 mod user_tests;
+mod webauthn_tests;
+mod webhook_tests;
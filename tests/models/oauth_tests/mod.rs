@@ -0,0 +1 @@
+mod oauth_model_test;
@@ -0,0 +1,405 @@
+use authentication_gRPC::{
+    dtos::models::{
+        dtos_model_oauth::{
+            OAuthModelAuthorizeParams, OAuthModelClientCredentials, OAuthModelExchangeCodeParams,
+        },
+        dtos_model_user::{UserModelLoginVerificationReturn, UserModelRecoverUserDataReturn},
+    },
+    error::{AppError, Code},
+    models::{
+        authentication_model::MockAuthenticationModel,
+        oauth_model::{AuthorizationServerModel, OAuthModel},
+    },
+    repositories::{
+        oauth_authorization_code_repository::{
+            MockOAuthAuthorizationCodeRepository, OAuthAuthorizationCode,
+        },
+        oauth_client_repository::{MockOAuthClientRepository, OAuthClientRepositoryConsultReturn},
+        oauth_consent_repository::MockOAuthConsentRepository,
+        oauth_refresh_token_repository::{MockOAuthRefreshTokenRepository, OAuthRefreshToken},
+    },
+    security::pkce::pkce_challenge,
+    utils::hash::token::hash_token,
+};
+use chrono::{Duration, Utc};
+use mockall::predicate;
+
+const FAKE_CLIENT_ID: &str = "fake_client_id";
+const FAKE_USER_ID: &str = "fake_user_id";
+const FAKE_CODE: &str = "fake_generated_secret";
+const REDIRECT_URI: &str = "https://app.example.com/callback";
+const CODE_VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+type TestOAuthModel = OAuthModel<
+    MockAuthenticationModel,
+    MockOAuthClientRepository,
+    MockOAuthConsentRepository,
+    MockOAuthAuthorizationCodeRepository,
+    MockOAuthRefreshTokenRepository,
+>;
+
+fn fake_id() -> String {
+    FAKE_CLIENT_ID.to_string()
+}
+
+fn fake_secret() -> String {
+    FAKE_CODE.to_string()
+}
+
+fn fake_client() -> OAuthClientRepositoryConsultReturn {
+    OAuthClientRepositoryConsultReturn {
+        id: FAKE_CLIENT_ID.to_string(),
+        name: "Web app".to_string(),
+        secret_hash: None,
+        redirect_uris: vec![REDIRECT_URI.to_string()],
+        scopes: vec!["profile".to_string()],
+        service: false,
+        created_at: Utc::now().naive_utc(),
+    }
+}
+
+fn fake_authorization_code(code_challenge: String) -> OAuthAuthorizationCode {
+    OAuthAuthorizationCode {
+        code: FAKE_CODE.to_string(),
+        client_id: FAKE_CLIENT_ID.to_string(),
+        user_id: FAKE_USER_ID.to_string(),
+        redirect_uri: REDIRECT_URI.to_string(),
+        code_challenge,
+        scopes: vec!["profile".to_string()],
+        expire_at: Utc::now().naive_utc() + Duration::minutes(5),
+        nonce: None,
+        auth_time: Utc::now().timestamp(),
+    }
+}
+
+fn create_model(
+    user_model: MockAuthenticationModel,
+    consent_repository: MockOAuthConsentRepository,
+    code_repository: MockOAuthAuthorizationCodeRepository,
+    refresh_token_repository: MockOAuthRefreshTokenRepository,
+) -> TestOAuthModel {
+    let mut client_repository = MockOAuthClientRepository::new();
+    client_repository
+        .expect_consult_by_id()
+        .returning(|_| Box::pin(async { Ok(fake_client()) }));
+
+    OAuthModel {
+        user_model,
+        client_repository,
+        consent_repository,
+        code_repository,
+        refresh_token_repository,
+        password_hasher: |password| Ok(password),
+        password_verify: |hash, password| Ok(hash == password),
+        new_id: fake_id,
+        generate_secret: fake_secret,
+    }
+}
+
+fn user_model_logging_in() -> MockAuthenticationModel {
+    let mut user_model = MockAuthenticationModel::new();
+    user_model
+        .expect_login_verification()
+        .with(
+            predicate::eq("username".to_string()),
+            predicate::eq("password".to_string()),
+        )
+        .times(1)
+        .returning(|_, _| {
+            Box::pin(async {
+                Ok(UserModelLoginVerificationReturn {
+                    id: FAKE_USER_ID.to_string(),
+                    username: "username".to_string(),
+                    email: "test@email.com".to_string(),
+                    activated: true,
+                    blocked: false,
+                })
+            })
+        });
+    user_model
+}
+
+fn user_model_recovering_data() -> MockAuthenticationModel {
+    let mut user_model = MockAuthenticationModel::new();
+    user_model
+        .expect_recover_user_data()
+        .with(predicate::eq(FAKE_USER_ID.to_string()))
+        .returning(|_| {
+            Box::pin(async {
+                Ok(UserModelRecoverUserDataReturn {
+                    username: "username".to_string(),
+                    email: "test@email.com".to_string(),
+                    activated: true,
+                    blocked: false,
+                })
+            })
+        });
+    user_model
+}
+
+fn authorize_params(consent_granted: bool) -> OAuthModelAuthorizeParams {
+    OAuthModelAuthorizeParams {
+        client_id: FAKE_CLIENT_ID.to_string(),
+        redirect_uri: REDIRECT_URI.to_string(),
+        scopes: vec!["profile".to_string()],
+        code_challenge: pkce_challenge(CODE_VERIFIER),
+        code_challenge_method: "S256".to_string(),
+        username: "username".to_string(),
+        password: "password".to_string(),
+        consent_granted,
+        nonce: None,
+    }
+}
+
+fn public_client() -> OAuthModelClientCredentials {
+    OAuthModelClientCredentials {
+        client_id: FAKE_CLIENT_ID.to_string(),
+        client_secret: None,
+    }
+}
+
+#[tokio::test]
+async fn test_authorize_records_consent_and_stores_code() {
+    let mut consent_repository = MockOAuthConsentRepository::new();
+    consent_repository
+        .expect_store()
+        .with(
+            predicate::eq(FAKE_USER_ID.to_string()),
+            predicate::eq(FAKE_CLIENT_ID.to_string()),
+            predicate::eq(vec!["profile".to_string()]),
+        )
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(String::from("Consent stored successfully")) }));
+
+    let mut code_repository = MockOAuthAuthorizationCodeRepository::new();
+    code_repository
+        .expect_store()
+        .withf(|code| {
+            code.code == FAKE_CODE
+                && code.user_id == FAKE_USER_ID
+                && code.code_challenge == pkce_challenge(CODE_VERIFIER)
+        })
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(String::from("stored")) }));
+
+    let model = create_model(
+        user_model_logging_in(),
+        consent_repository,
+        code_repository,
+        MockOAuthRefreshTokenRepository::new(),
+    );
+
+    let code = model.authorize(authorize_params(true)).await.unwrap();
+
+    assert_eq!(code, FAKE_CODE);
+}
+
+#[tokio::test]
+async fn test_authorize_without_consent() {
+    let mut consent_repository = MockOAuthConsentRepository::new();
+    consent_repository
+        .expect_consult()
+        .times(1)
+        .returning(|_, _| Box::pin(async { Err(AppError::new(Code::NotFound, "not found")) }));
+
+    let mut code_repository = MockOAuthAuthorizationCodeRepository::new();
+    code_repository.expect_store().never();
+
+    let model = create_model(
+        user_model_logging_in(),
+        consent_repository,
+        code_repository,
+        MockOAuthRefreshTokenRepository::new(),
+    );
+
+    match model.authorize(authorize_params(false)).await {
+        Ok(_) => panic!("Should have failed"),
+        Err(error) => assert_eq!(error.code, Code::PermissionDenied),
+    }
+}
+
+#[tokio::test]
+async fn test_authorize_requires_s256_challenge() {
+    let model = create_model(
+        MockAuthenticationModel::new(),
+        MockOAuthConsentRepository::new(),
+        MockOAuthAuthorizationCodeRepository::new(),
+        MockOAuthRefreshTokenRepository::new(),
+    );
+
+    let mut params = authorize_params(true);
+    params.code_challenge_method = "plain".to_string();
+
+    match model.authorize(params).await {
+        Ok(_) => panic!("Should have failed"),
+        Err(error) => assert_eq!(error.code, Code::InvalidArgument),
+    }
+}
+
+#[tokio::test]
+async fn test_exchange_authorization_code() {
+    let mut code_repository = MockOAuthAuthorizationCodeRepository::new();
+    code_repository
+        .expect_take()
+        .with(predicate::eq(FAKE_CODE.to_string()))
+        .times(1)
+        .returning(|_| {
+            Box::pin(async { Ok(fake_authorization_code(pkce_challenge(CODE_VERIFIER))) })
+        });
+
+    let mut refresh_token_repository = MockOAuthRefreshTokenRepository::new();
+    refresh_token_repository
+        .expect_store()
+        .withf(|token| token.token_hash == hash_token(FAKE_CODE) && token.user_id == FAKE_USER_ID)
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(String::from("stored")) }));
+
+    let model = create_model(
+        user_model_recovering_data(),
+        MockOAuthConsentRepository::new(),
+        code_repository,
+        refresh_token_repository,
+    );
+
+    let tokens = model
+        .exchange_authorization_code(OAuthModelExchangeCodeParams {
+            client: public_client(),
+            code: FAKE_CODE.to_string(),
+            redirect_uri: REDIRECT_URI.to_string(),
+            code_verifier: CODE_VERIFIER.to_string(),
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(tokens.user_id, FAKE_USER_ID);
+    assert_eq!(tokens.refresh_token, FAKE_CODE);
+    assert_eq!(tokens.scopes, vec!["profile".to_string()]);
+}
+
+#[tokio::test]
+async fn test_exchange_authorization_code_with_wrong_verifier() {
+    let mut code_repository = MockOAuthAuthorizationCodeRepository::new();
+    code_repository.expect_take().times(1).returning(|_| {
+        Box::pin(async { Ok(fake_authorization_code(pkce_challenge(CODE_VERIFIER))) })
+    });
+
+    let mut refresh_token_repository = MockOAuthRefreshTokenRepository::new();
+    refresh_token_repository.expect_store().never();
+
+    let model = create_model(
+        MockAuthenticationModel::new(),
+        MockOAuthConsentRepository::new(),
+        code_repository,
+        refresh_token_repository,
+    );
+
+    match model
+        .exchange_authorization_code(OAuthModelExchangeCodeParams {
+            client: public_client(),
+            code: FAKE_CODE.to_string(),
+            redirect_uri: REDIRECT_URI.to_string(),
+            code_verifier: "eBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".to_string(),
+        })
+        .await
+    {
+        Ok(_) => panic!("Should have failed"),
+        Err(error) => assert_eq!(error.code, Code::PermissionDenied),
+    }
+}
+
+#[tokio::test]
+async fn test_refresh_access_token_rejects_revoked_token() {
+    let mut refresh_token_repository = MockOAuthRefreshTokenRepository::new();
+    refresh_token_repository
+        .expect_consult_by_hash()
+        .with(predicate::eq(hash_token("refresh_token")))
+        .times(1)
+        .returning(|token_hash| {
+            Box::pin(async move {
+                Ok(OAuthRefreshToken {
+                    token_hash,
+                    client_id: FAKE_CLIENT_ID.to_string(),
+                    user_id: FAKE_USER_ID.to_string(),
+                    scopes: vec![],
+                    expires_at: Utc::now().naive_utc() + Duration::days(1),
+                    revoked_at: Some(Utc::now().naive_utc()),
+                })
+            })
+        });
+    refresh_token_repository.expect_revoke().never();
+    refresh_token_repository.expect_store().never();
+
+    let model = create_model(
+        MockAuthenticationModel::new(),
+        MockOAuthConsentRepository::new(),
+        MockOAuthAuthorizationCodeRepository::new(),
+        refresh_token_repository,
+    );
+
+    match model
+        .refresh_access_token(public_client(), "refresh_token".to_string())
+        .await
+    {
+        Ok(_) => panic!("Should have failed"),
+        Err(error) => assert_eq!(error.code, Code::PermissionDenied),
+    }
+}
+
+#[tokio::test]
+async fn test_issue_service_token_to_user_facing_client() {
+    let model = create_model(
+        MockAuthenticationModel::new(),
+        MockOAuthConsentRepository::new(),
+        MockOAuthAuthorizationCodeRepository::new(),
+        MockOAuthRefreshTokenRepository::new(),
+    );
+
+    match model.issue_service_token(public_client(), vec![]).await {
+        Ok(_) => panic!("Should have failed"),
+        Err(error) => assert_eq!(error.code, Code::PermissionDenied),
+    }
+}
+
+#[tokio::test]
+async fn test_issue_service_token_with_scopes_of_the_client() {
+    let mut client_repository = MockOAuthClientRepository::new();
+    client_repository.expect_consult_by_id().returning(|_| {
+        Box::pin(async {
+            Ok(OAuthClientRepositoryConsultReturn {
+                secret_hash: Some("client_secret".to_string()),
+                redirect_uris: vec![],
+                scopes: vec!["user_events:read".to_string()],
+                service: true,
+                ..fake_client()
+            })
+        })
+    });
+
+    let mut model = create_model(
+        MockAuthenticationModel::new(),
+        MockOAuthConsentRepository::new(),
+        MockOAuthAuthorizationCodeRepository::new(),
+        MockOAuthRefreshTokenRepository::new(),
+    );
+    model.client_repository = client_repository;
+
+    let credentials = || OAuthModelClientCredentials {
+        client_id: FAKE_CLIENT_ID.to_string(),
+        client_secret: Some("client_secret".to_string()),
+    };
+
+    let token = model
+        .issue_service_token(credentials(), vec![])
+        .await
+        .unwrap();
+    assert_eq!(token.client_id, FAKE_CLIENT_ID);
+    assert_eq!(token.scopes, vec!["user_events:read".to_string()]);
+
+    match model
+        .issue_service_token(credentials(), vec!["profile".to_string()])
+        .await
+    {
+        Ok(_) => panic!("Should have failed"),
+        Err(error) => assert_eq!(error.code, Code::InvalidArgument),
+    }
+}
@@ -0,0 +1 @@
+mod passwordless_model_test;
@@ -0,0 +1,265 @@
+use authentication_gRPC::{
+    config::app_config::DEFAULT_LOGIN_CODE_TTL_MINUTES,
+    error::{AppError, Code},
+    models::passwordless_model::{
+        PasswordlessLoginModel, PasswordlessModel, LOGIN_CODE_MAX_ATTEMPTS,
+    },
+    repositories::{
+        user_repository::{MockUserRepository, UserRepositoryConsultReturn},
+        users_code_repository::{MockUsersCodeRepository, UsersCode},
+    },
+    services::mail::mail_sender::MockMailSender,
+};
+use chrono::{Duration, Utc};
+use mockall::predicate;
+
+const FAKE_USER_ID: &str = "fake_user_id";
+const FAKE_EMAIL: &str = "test@email.com";
+const FAKE_CODE: &str = "123456";
+
+fn fake_code() -> String {
+    FAKE_CODE.to_string()
+}
+
+fn user_repository_consulting_email() -> MockUserRepository {
+    let mut user_repository = MockUserRepository::new();
+    user_repository
+        .expect_consult_by_email()
+        .with(predicate::eq(FAKE_EMAIL.to_string()))
+        .times(1)
+        .returning(|_| {
+            Box::pin(async {
+                Ok(UserRepositoryConsultReturn {
+                    id: FAKE_USER_ID.to_string(),
+                    username: "username".to_string(),
+                    email: FAKE_EMAIL.to_string(),
+                    password: "hash".to_string(),
+                    activated: true,
+                    blocked: false,
+                })
+            })
+        });
+    user_repository
+}
+
+fn code_repository_with_code(expire_at_minutes: i64) -> MockUsersCodeRepository {
+    let mut code_repository = MockUsersCodeRepository::new();
+    code_repository
+        .expect_get()
+        .with(
+            predicate::eq(FAKE_USER_ID.to_string()),
+            predicate::eq(FAKE_CODE.to_string()),
+        )
+        .returning(move |user_id, code| {
+            Box::pin(async move {
+                Ok(UsersCode {
+                    code,
+                    expire_at: Utc::now().naive_utc() + Duration::minutes(expire_at_minutes),
+                    user_id,
+                })
+            })
+        });
+    code_repository
+        .expect_get()
+        .returning(|_, _| Box::pin(async { Err(AppError::new(Code::NotFound, "Code not found")) }));
+    code_repository
+}
+
+#[tokio::test]
+async fn test_create_login_code() {
+    let mut code_repository = MockUsersCodeRepository::new();
+    code_repository
+        .expect_store()
+        .withf(|code| {
+            code.code == FAKE_CODE
+                && code.user_id == FAKE_USER_ID
+                && code.expire_at
+                    <= Utc::now().naive_utc() + Duration::minutes(DEFAULT_LOGIN_CODE_TTL_MINUTES)
+        })
+        .times(1)
+        .returning(|_| Box::pin(async { Ok("Code stored successfully".to_string()) }));
+    let mut mail_sender = MockMailSender::new();
+    mail_sender
+        .expect_send()
+        .withf(|to, email| {
+            to == FAKE_EMAIL
+                && email.body.contains(FAKE_CODE)
+                && email
+                    .body
+                    .contains(&DEFAULT_LOGIN_CODE_TTL_MINUTES.to_string())
+        })
+        .times(1)
+        .returning(|_, _| Box::pin(async { Ok(()) }));
+
+    let model = PasswordlessModel {
+        user_repository: user_repository_consulting_email(),
+        login_code_repository: code_repository,
+        mail_sender,
+        generate_code: fake_code,
+        code_ttl_minutes: DEFAULT_LOGIN_CODE_TTL_MINUTES,
+    };
+
+    let code = model
+        .create_login_code(FAKE_EMAIL.to_string())
+        .await
+        .unwrap();
+
+    assert_eq!(code.expires_in, DEFAULT_LOGIN_CODE_TTL_MINUTES * 60);
+}
+
+#[tokio::test]
+async fn test_create_login_code_fails_when_the_email_is_not_sent() {
+    let mut code_repository = MockUsersCodeRepository::new();
+    code_repository
+        .expect_store()
+        .times(1)
+        .returning(|_| Box::pin(async { Ok("Code stored successfully".to_string()) }));
+    let mut mail_sender = MockMailSender::new();
+    mail_sender.expect_send().times(1).returning(|_, _| {
+        Box::pin(async { Err(AppError::new(Code::Unavailable, "mail API down")) })
+    });
+
+    let model = PasswordlessModel {
+        user_repository: user_repository_consulting_email(),
+        login_code_repository: code_repository,
+        mail_sender,
+        generate_code: fake_code,
+        code_ttl_minutes: DEFAULT_LOGIN_CODE_TTL_MINUTES,
+    };
+
+    let error = model
+        .create_login_code(FAKE_EMAIL.to_string())
+        .await
+        .err()
+        .unwrap();
+
+    assert_eq!(error.code, Code::Unavailable);
+}
+
+#[tokio::test]
+async fn test_login_with_code_is_single_use() {
+    let mut code_repository = code_repository_with_code(5);
+    code_repository
+        .expect_delete()
+        .with(predicate::eq(FAKE_USER_ID.to_string()))
+        .times(1)
+        .returning(|_| Box::pin(async { Ok("Code deleted successfully".to_string()) }));
+    code_repository.expect_increment_attempts().never();
+
+    let model = PasswordlessModel {
+        user_repository: user_repository_consulting_email(),
+        login_code_repository: code_repository,
+        mail_sender: MockMailSender::new(),
+        generate_code: fake_code,
+        code_ttl_minutes: DEFAULT_LOGIN_CODE_TTL_MINUTES,
+    };
+
+    let user = model
+        .login_with_code(FAKE_EMAIL.to_string(), FAKE_CODE.to_string())
+        .await
+        .unwrap();
+
+    assert_eq!(user.id, FAKE_USER_ID);
+}
+
+#[tokio::test]
+async fn test_login_with_expired_code() {
+    let mut code_repository = code_repository_with_code(-1);
+    code_repository
+        .expect_delete()
+        .times(1)
+        .returning(|_| Box::pin(async { Ok("Code deleted successfully".to_string()) }));
+
+    let model = PasswordlessModel {
+        user_repository: user_repository_consulting_email(),
+        login_code_repository: code_repository,
+        mail_sender: MockMailSender::new(),
+        generate_code: fake_code,
+        code_ttl_minutes: DEFAULT_LOGIN_CODE_TTL_MINUTES,
+    };
+
+    let error = model
+        .login_with_code(FAKE_EMAIL.to_string(), FAKE_CODE.to_string())
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn test_login_with_wrong_code() {
+    let mut code_repository = code_repository_with_code(5);
+    code_repository
+        .expect_increment_attempts()
+        .with(predicate::eq(FAKE_USER_ID.to_string()))
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(1) }));
+    code_repository.expect_delete().never();
+
+    let model = PasswordlessModel {
+        user_repository: user_repository_consulting_email(),
+        login_code_repository: code_repository,
+        mail_sender: MockMailSender::new(),
+        generate_code: fake_code,
+        code_ttl_minutes: DEFAULT_LOGIN_CODE_TTL_MINUTES,
+    };
+
+    let error = model
+        .login_with_code(FAKE_EMAIL.to_string(), "000000".to_string())
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, Code::Unauthenticated);
+}
+
+#[tokio::test]
+async fn test_login_code_discarded_after_max_attempts() {
+    let mut code_repository = code_repository_with_code(5);
+    code_repository
+        .expect_increment_attempts()
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(LOGIN_CODE_MAX_ATTEMPTS) }));
+    code_repository
+        .expect_delete()
+        .with(predicate::eq(FAKE_USER_ID.to_string()))
+        .times(1)
+        .returning(|_| Box::pin(async { Ok("Code deleted successfully".to_string()) }));
+
+    let model = PasswordlessModel {
+        user_repository: user_repository_consulting_email(),
+        login_code_repository: code_repository,
+        mail_sender: MockMailSender::new(),
+        generate_code: fake_code,
+        code_ttl_minutes: DEFAULT_LOGIN_CODE_TTL_MINUTES,
+    };
+
+    let error = model
+        .login_with_code(FAKE_EMAIL.to_string(), "000000".to_string())
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, Code::PermissionDenied);
+}
+
+#[tokio::test]
+async fn test_login_with_code_for_unknown_email() {
+    let mut user_repository = MockUserRepository::new();
+    user_repository
+        .expect_consult_by_email()
+        .returning(|_| Box::pin(async { Err(AppError::new(Code::NotFound, "not found")) }));
+
+    let model = PasswordlessModel {
+        user_repository,
+        login_code_repository: MockUsersCodeRepository::new(),
+        mail_sender: MockMailSender::new(),
+        generate_code: fake_code,
+        code_ttl_minutes: DEFAULT_LOGIN_CODE_TTL_MINUTES,
+    };
+
+    let error = model
+        .login_with_code(FAKE_EMAIL.to_string(), FAKE_CODE.to_string())
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, Code::Unauthenticated);
+}
@@ -0,0 +1 @@
+mod personal_access_token_model_test;
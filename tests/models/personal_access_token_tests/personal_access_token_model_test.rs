@@ -0,0 +1,188 @@
+use authentication_gRPC::{
+    dtos::models::dtos_model_personal_access_token::PersonalAccessTokenModelCreateParams,
+    error::{AppError, Code},
+    models::personal_access_token_model::{
+        PersonalAccessTokenManagementModel, PersonalAccessTokenModel,
+    },
+    repositories::{
+        personal_access_token_repository::{
+            MockPersonalAccessTokenRepository, PersonalAccessToken,
+        },
+        user_repository::{MockUserRepository, UserRepositoryConsultReturn},
+    },
+    security::personal_access_token::{USER_READ_SCOPE, USER_WRITE_SCOPE},
+    utils::hash::token::hash_token,
+};
+use chrono::{Duration, Utc};
+use mockall::predicate;
+
+const FAKE_USER_ID: &str = "fake_user_id";
+const FAKE_TOKEN_ID: &str = "fake_token_id";
+const FAKE_SECRET: &str = "AbCd1234EfGh5678";
+const FAKE_TOKEN: &str = "pat_AbCd1234EfGh5678";
+
+type TestPersonalAccessTokenModel =
+    PersonalAccessTokenModel<MockUserRepository, MockPersonalAccessTokenRepository>;
+
+fn fake_personal_access_token(expires_in: Option<Duration>) -> PersonalAccessToken {
+    let now = Utc::now().naive_utc();
+    PersonalAccessToken {
+        id: FAKE_TOKEN_ID.to_string(),
+        user_id: FAKE_USER_ID.to_string(),
+        name: "CI".to_string(),
+        prefix: "pat_AbCd1234".to_string(),
+        token_hash: hash_token(FAKE_TOKEN),
+        scopes: vec![USER_READ_SCOPE.to_string()],
+        expires_at: expires_in.map(|expires_in| now + expires_in),
+        last_used_at: None,
+        created_at: now,
+    }
+}
+
+fn create_model(
+    user_repository: MockUserRepository,
+    token_repository: MockPersonalAccessTokenRepository,
+) -> TestPersonalAccessTokenModel {
+    PersonalAccessTokenModel {
+        user_repository,
+        token_repository,
+        new_id: || FAKE_TOKEN_ID.to_string(),
+        generate_secret: || FAKE_SECRET.to_string(),
+    }
+}
+
+fn token_repository_consulting(expires_in: Option<Duration>) -> MockPersonalAccessTokenRepository {
+    let mut token_repository = MockPersonalAccessTokenRepository::new();
+    token_repository
+        .expect_consult_by_hash()
+        .with(predicate::eq(hash_token(FAKE_TOKEN)))
+        .returning(move |_| {
+            let token = fake_personal_access_token(expires_in);
+            Box::pin(async move { Ok(token) })
+        });
+    token_repository
+}
+
+#[tokio::test]
+async fn test_create_personal_access_token_keeps_hash_and_prefix() {
+    let mut token_repository = MockPersonalAccessTokenRepository::new();
+    token_repository
+        .expect_store()
+        .withf(|token| {
+            token.user_id == FAKE_USER_ID
+                && token.prefix == "pat_AbCd1234"
+                && token.token_hash == hash_token(FAKE_TOKEN)
+                && token.scopes == vec![USER_READ_SCOPE, USER_WRITE_SCOPE]
+        })
+        .times(1)
+        .returning(|token| {
+            let mut stored = fake_personal_access_token(None);
+            stored.scopes = token.scopes;
+            Box::pin(async move { Ok(stored) })
+        });
+
+    let model = create_model(MockUserRepository::new(), token_repository);
+
+    let created = model
+        .create(
+            FAKE_USER_ID.to_string(),
+            PersonalAccessTokenModelCreateParams {
+                name: "CI".to_string(),
+                scopes: vec![USER_READ_SCOPE.to_string(), USER_WRITE_SCOPE.to_string()],
+                expires_at: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(created.token, FAKE_TOKEN);
+    assert_eq!(created.personal_access_token.prefix, "pat_AbCd1234");
+}
+
+#[tokio::test]
+async fn test_create_personal_access_token_with_unknown_scope() {
+    let model = create_model(
+        MockUserRepository::new(),
+        MockPersonalAccessTokenRepository::new(),
+    );
+
+    let error = model
+        .create(
+            FAKE_USER_ID.to_string(),
+            PersonalAccessTokenModelCreateParams {
+                name: "CI".to_string(),
+                scopes: vec!["admin".to_string()],
+                expires_at: None,
+            },
+        )
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn test_authenticate_personal_access_token() {
+    let mut token_repository = token_repository_consulting(Some(Duration::days(1)));
+    token_repository
+        .expect_touch()
+        .with(predicate::eq(FAKE_TOKEN_ID.to_string()))
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(String::from("Personal access token used")) }));
+
+    let mut user_repository = MockUserRepository::new();
+    user_repository
+        .expect_consult_by_id()
+        .with(predicate::eq(FAKE_USER_ID.to_string()))
+        .returning(|_| {
+            Box::pin(async {
+                Ok(UserRepositoryConsultReturn {
+                    id: FAKE_USER_ID.to_string(),
+                    username: "username".to_string(),
+                    email: "test@email.com".to_string(),
+                    password: "password".to_string(),
+                    activated: true,
+                    blocked: false,
+                })
+            })
+        });
+
+    let model = create_model(user_repository, token_repository);
+
+    let authenticated = model.authenticate(FAKE_TOKEN.to_string()).await.unwrap();
+
+    assert_eq!(authenticated.user.id, FAKE_USER_ID);
+    assert_eq!(authenticated.scopes, vec![USER_READ_SCOPE]);
+}
+
+#[tokio::test]
+async fn test_authenticate_expired_personal_access_token() {
+    let model = create_model(
+        MockUserRepository::new(),
+        token_repository_consulting(Some(Duration::days(-1))),
+    );
+
+    let error = model
+        .authenticate(FAKE_TOKEN.to_string())
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, Code::Unauthenticated);
+}
+
+#[tokio::test]
+async fn test_authenticate_unknown_personal_access_token() {
+    let mut token_repository = MockPersonalAccessTokenRepository::new();
+    token_repository
+        .expect_consult_by_hash()
+        .returning(|_| Box::pin(async { Err(AppError::new(Code::NotFound, "Not found")) }));
+
+    let model = create_model(MockUserRepository::new(), token_repository);
+
+    let error = model
+        .authenticate(FAKE_TOKEN.to_string())
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, Code::Unauthenticated);
+}
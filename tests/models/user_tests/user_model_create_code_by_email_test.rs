@@ -1,8 +1,10 @@
 use authentication_gRPC::{
+    config::app_config::DEFAULT_ACCOUNT_CODE_TTL_MINUTES,
     models::authentication_model::AuthenticationModel,
     repositories::{
         user_repository::UserRepositoryConsultReturn, users_code_repository::UsersCode,
     },
+    services::mail::mail_sender::MockMailSender,
 };
 use chrono::Utc;
 
@@ -28,8 +30,8 @@ async fn test_user_model_code_by_email() {
     const FAKE_CODE: &str = "000001";
 
     fn param_code_withf(code: &UsersCode) -> bool {
-        code.code == FAKE_CODE.to_string()
-            && code.user_id == FAKE_ID.to_string()
+        code.code == FAKE_CODE
+            && code.user_id == FAKE_ID
             && code.expire_at >= Utc::now().naive_utc()
     }
 
@@ -37,7 +39,7 @@ async fn test_user_model_code_by_email() {
         get_mock_users_code_repository(MockUsersCodeRepositoryParams {
             store: Some(MockUsersCodeRepositoryStore {
                 calls: 1,
-                param_code_withf: param_code_withf,
+                param_code_withf,
                 fn_returning: |_| Ok(String::from("Code store successfully")),
             }),
             ..Default::default()
@@ -61,10 +63,24 @@ async fn test_user_model_code_by_email() {
         ..Default::default()
     });
 
+    let mut mail_sender = MockMailSender::new();
+    mail_sender
+        .expect_send()
+        .withf(|to, email| {
+            to == FAKE_EMAIL
+                && email.body.contains(FAKE_CODE)
+                && email
+                    .body
+                    .contains(&DEFAULT_ACCOUNT_CODE_TTL_MINUTES.to_string())
+        })
+        .times(1)
+        .returning(|_, _| Box::pin(async { Ok(()) }));
+
     let model_user = UserModelBuilderForTest::new()
         .mount_user_repository(mock_repository)
         .mount_generate_code(|| FAKE_CODE.to_string())
         .mount_code_repository(mock_users_code_repository)
+        .mount_mail_sender(mail_sender)
         .build();
 
     let code = model_user
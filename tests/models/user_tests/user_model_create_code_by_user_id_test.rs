@@ -1,12 +1,22 @@
 use authentication_gRPC::{
+    config::app_config::DEFAULT_ACCOUNT_CODE_TTL_MINUTES,
     models::authentication_model::AuthenticationModel,
-    repositories::users_code_repository::UsersCode,
+    repositories::{
+        user_repository::UserRepositoryConsultReturn, users_code_repository::UsersCode,
+    },
+    services::mail::mail_sender::MockMailSender,
 };
 use chrono::Utc;
 
 use crate::{
-    mocks::users_code_repository_mock::{
-        get_mock_users_code_repository, MockUsersCodeRepositoryParams, MockUsersCodeRepositoryStore,
+    mocks::{
+        user_repository_mock::{
+            get_mock_user_repository, MockUserRepositoryConsultById, MockUserRepositoryParams,
+        },
+        users_code_repository_mock::{
+            get_mock_users_code_repository, MockUsersCodeRepositoryParams,
+            MockUsersCodeRepositoryStore,
+        },
     },
     utils::builders::UserModelBuilderForTest,
 };
@@ -14,11 +24,12 @@ use crate::{
 #[tokio::test]
 async fn test_user_model_create_code_by_user_id() {
     const FAKE_ID: &str = "userFakeId";
+    const FAKE_EMAIL: &str = "test@email.com";
     const FAKE_CODE: &str = "0000001";
 
     fn param_code_withf(code: &UsersCode) -> bool {
-        code.code == FAKE_CODE.to_string()
-            && code.user_id == FAKE_ID.to_string()
+        code.code == FAKE_CODE
+            && code.user_id == FAKE_ID
             && code.expire_at >= Utc::now().naive_utc()
     }
 
@@ -26,15 +37,48 @@ async fn test_user_model_create_code_by_user_id() {
         get_mock_users_code_repository(MockUsersCodeRepositoryParams {
             store: Some(MockUsersCodeRepositoryStore {
                 calls: 1,
-                param_code_withf: param_code_withf,
+                param_code_withf,
                 fn_returning: |_| Ok(String::from("Code store successfully")),
             }),
             ..Default::default()
         });
 
+    let mock_repository = get_mock_user_repository(MockUserRepositoryParams {
+        consult_by_id: Some(MockUserRepositoryConsultById {
+            calls: 1,
+            param_id_with: FAKE_ID.to_string(),
+            fn_returning: |_| {
+                Ok(UserRepositoryConsultReturn {
+                    id: FAKE_ID.to_string(),
+                    username: "userFakeUsername".to_string(),
+                    email: FAKE_EMAIL.to_string(),
+                    password: "hash".to_string(),
+                    activated: false,
+                    blocked: false,
+                })
+            },
+        }),
+        ..Default::default()
+    });
+
+    let mut mail_sender = MockMailSender::new();
+    mail_sender
+        .expect_send()
+        .withf(|to, email| {
+            to == FAKE_EMAIL
+                && email.body.contains(FAKE_CODE)
+                && email
+                    .body
+                    .contains(&DEFAULT_ACCOUNT_CODE_TTL_MINUTES.to_string())
+        })
+        .times(1)
+        .returning(|_, _| Box::pin(async { Ok(()) }));
+
     let model_user = UserModelBuilderForTest::new()
+        .mount_user_repository(mock_repository)
         .mount_generate_code(|| FAKE_CODE.to_string())
         .mount_code_repository(mock_users_code_repository)
+        .mount_mail_sender(mail_sender)
         .build();
 
     let code = model_user
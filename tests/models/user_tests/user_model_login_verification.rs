@@ -37,7 +37,7 @@ async fn test_login_verification() {
 
     let model_user = UserModelBuilderForTest::new()
         .mount_user_repository(mock_user_repository)
-        .mount_password_verify(|_, _| return Ok(true))
+        .mount_password_verify(|_, _| Ok(true))
         .build();
 
     let user = model_user
@@ -73,7 +73,7 @@ async fn test_login_verification_givin_wrong_password() {
 
     let model_user = UserModelBuilderForTest::new()
         .mount_user_repository(mock_user_repository)
-        .mount_password_verify(|_, _| return Ok(false)) //return false to simulate wrong password
+        .mount_password_verify(|_, _| Ok(false)) //return false to simulate wrong password
         .build();
 
     match model_user
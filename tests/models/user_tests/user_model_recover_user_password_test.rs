@@ -24,6 +24,7 @@ use crate::{
 };
 
 const FAKE_NEW_PASSWORD: &str = "newPassword";
+const FAKE_NEW_PASSWORD_HASH: &str = "newPasswordHash";
 const FAKE_ID: &str = "userFakeId";
 const FAKE_USERNAME: &str = "userFakeUsername";
 const FAKE_EMAIL: &str = "test@email.com";
@@ -32,7 +33,7 @@ const FAKE_CODE: &str = "000001";
 #[tokio::test]
 async fn test_recover_user_password() {
     let user_store_update_params = UserRepositoryUpdateParams {
-        password: Some(FAKE_NEW_PASSWORD.to_string()),
+        password: Some(FAKE_NEW_PASSWORD_HASH.to_string()),
         ..Default::default()
     };
 
@@ -80,6 +81,10 @@ async fn test_recover_user_password() {
     let model_user = UserModelBuilderForTest::new()
         .mount_user_repository(mock_repository)
         .mount_code_repository(mock_users_code_repository)
+        .mount_password_hasher(|password| {
+            assert_eq!(password, FAKE_NEW_PASSWORD);
+            Ok(FAKE_NEW_PASSWORD_HASH.to_string())
+        })
         .build();
 
     let response = model_user
@@ -0,0 +1 @@
+mod webauthn_model_test;
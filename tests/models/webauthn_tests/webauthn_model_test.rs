@@ -0,0 +1,480 @@
+use authentication_gRPC::{
+    dtos::models::dtos_model_webauthn::{
+        WebauthnModelFinishAuthenticationParams, WebauthnModelFinishRegistrationParams,
+    },
+    error::Code,
+    models::webauthn_model::{PasskeyAuthenticationModel, WebauthnModel},
+    repositories::{
+        user_repository::{MockUserRepository, UserRepositoryConsultReturn},
+        webauthn_challenge_repository::{
+            MockWebauthnChallengeRepository, WebauthnCeremony, WebauthnChallenge,
+        },
+        webauthn_credential_repository::{
+            MockWebauthnCredentialRepository, WebauthnCredential, WebauthnCredentialStoreParams,
+        },
+    },
+    security::webauthn::{RelyingParty, COSE_ALGORITHM_ES256},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{Duration, Utc};
+use ciborium::value::Value;
+use mockall::predicate;
+use ring::{
+    rand::SystemRandom,
+    signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING},
+};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+const FAKE_USER_ID: &str = "fake_user_id";
+const FAKE_USERNAME: &str = "username";
+const FAKE_PASSWORD: &str = "password";
+const CHALLENGE: &str = "ZmFrZV9nZW5lcmF0ZWRfc2VjcmV0";
+const RAW_CREDENTIAL_ID: &[u8] = b"credential-id";
+const CREDENTIAL_ID: &str = "Y3JlZGVudGlhbC1pZA";
+const ORIGIN: &str = "http://localhost:8080";
+
+type TestWebauthnModel = WebauthnModel<
+    MockUserRepository,
+    MockWebauthnCredentialRepository,
+    MockWebauthnChallengeRepository,
+>;
+
+/// Software authenticator holding a single P-256 credential.
+struct Authenticator {
+    key_pair: EcdsaKeyPair,
+}
+
+impl Authenticator {
+    fn new() -> Authenticator {
+        let pkcs8 =
+            EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &SystemRandom::new())
+                .unwrap();
+        Authenticator {
+            key_pair: EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref())
+                .unwrap(),
+        }
+    }
+
+    fn cose_key(&self) -> Vec<u8> {
+        let point = self.key_pair.public_key().as_ref();
+        cbor(Value::Map(vec![
+            (1.into(), 2.into()),
+            (3.into(), COSE_ALGORITHM_ES256.into()),
+            ((-1).into(), 1.into()),
+            ((-2).into(), Value::Bytes(point[1..33].to_vec())),
+            ((-3).into(), Value::Bytes(point[33..65].to_vec())),
+        ]))
+    }
+
+    fn attestation_object(&self) -> Vec<u8> {
+        let attested = [
+            &[0u8; 16][..],
+            &(RAW_CREDENTIAL_ID.len() as u16).to_be_bytes(),
+            RAW_CREDENTIAL_ID,
+            &self.cose_key(),
+        ]
+        .concat();
+
+        cbor(Value::Map(vec![
+            ("fmt".into(), "none".into()),
+            ("attStmt".into(), Value::Map(vec![])),
+            (
+                "authData".into(),
+                Value::Bytes(authenticator_data(0x45, 0, &attested)),
+            ),
+        ]))
+    }
+
+    /// Answer an authentication ceremony, `flags` 0x05 is user present and verified.
+    fn assert(&self, flags: u8, sign_count: u32) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let client_data_json = client_data("webauthn.get");
+        let auth_data = authenticator_data(flags, sign_count, &[]);
+        let signed_data = [&auth_data[..], &Sha256::digest(&client_data_json)].concat();
+        let signature = self
+            .key_pair
+            .sign(&SystemRandom::new(), &signed_data)
+            .unwrap()
+            .as_ref()
+            .to_vec();
+
+        (client_data_json, auth_data, signature)
+    }
+}
+
+fn cbor(value: Value) -> Vec<u8> {
+    let mut bytes = vec![];
+    ciborium::ser::into_writer(&value, &mut bytes).unwrap();
+    bytes
+}
+
+fn client_data(ceremony: &str) -> Vec<u8> {
+    json!({ "type": ceremony, "challenge": CHALLENGE, "origin": ORIGIN })
+        .to_string()
+        .into_bytes()
+}
+
+fn authenticator_data(flags: u8, sign_count: u32, attested: &[u8]) -> Vec<u8> {
+    [
+        Sha256::digest(b"localhost").as_slice(),
+        &[flags],
+        &sign_count.to_be_bytes(),
+        attested,
+    ]
+    .concat()
+}
+
+fn create_model(
+    user_repository: MockUserRepository,
+    credential_repository: MockWebauthnCredentialRepository,
+    challenge_repository: MockWebauthnChallengeRepository,
+) -> TestWebauthnModel {
+    WebauthnModel {
+        user_repository,
+        credential_repository,
+        challenge_repository,
+        relying_party: Some(RelyingParty {
+            id: "localhost".to_string(),
+            name: "Authentication".to_string(),
+            origin: ORIGIN.to_string(),
+        }),
+        password_verify: |hash, password| Ok(hash == password),
+        generate_challenge: || "fake_generated_secret".to_string(),
+    }
+}
+
+fn fake_user() -> UserRepositoryConsultReturn {
+    UserRepositoryConsultReturn {
+        id: FAKE_USER_ID.to_string(),
+        username: FAKE_USERNAME.to_string(),
+        email: "test@email.com".to_string(),
+        password: FAKE_PASSWORD.to_string(),
+        activated: true,
+        blocked: false,
+    }
+}
+
+fn user_repository_consulting_id() -> MockUserRepository {
+    let mut user_repository = MockUserRepository::new();
+    user_repository
+        .expect_consult_by_id()
+        .with(predicate::eq(FAKE_USER_ID.to_string()))
+        .returning(|_| Box::pin(async { Ok(fake_user()) }));
+    user_repository
+}
+
+fn fake_credential(authenticator: &Authenticator, sign_count: i64) -> WebauthnCredential {
+    WebauthnCredential {
+        id: CREDENTIAL_ID.to_string(),
+        user_id: FAKE_USER_ID.to_string(),
+        name: "Security key".to_string(),
+        public_key: authenticator.cose_key(),
+        algorithm: COSE_ALGORITHM_ES256 as i32,
+        sign_count,
+        created_at: Utc::now().naive_utc(),
+        last_used_at: None,
+    }
+}
+
+fn credential_repository_with(credential: WebauthnCredential) -> MockWebauthnCredentialRepository {
+    let mut credential_repository = MockWebauthnCredentialRepository::new();
+    credential_repository
+        .expect_consult()
+        .with(predicate::eq(CREDENTIAL_ID.to_string()))
+        .returning(move |_| {
+            let credential = credential.clone();
+            Box::pin(async move { Ok(credential) })
+        });
+    credential_repository
+}
+
+fn challenge_repository_with(
+    ceremony: WebauthnCeremony,
+    user_id: Option<&str>,
+    second_factor: bool,
+) -> MockWebauthnChallengeRepository {
+    let user_id = user_id.map(str::to_string);
+    let mut challenge_repository = MockWebauthnChallengeRepository::new();
+    challenge_repository
+        .expect_take()
+        .with(predicate::eq(CHALLENGE.to_string()))
+        .times(1)
+        .returning(move |challenge| {
+            let user_id = user_id.clone();
+            Box::pin(async move {
+                Ok(WebauthnChallenge {
+                    challenge,
+                    ceremony,
+                    user_id,
+                    second_factor,
+                    expire_at: Utc::now().naive_utc() + Duration::minutes(5),
+                })
+            })
+        });
+    challenge_repository
+}
+
+fn assertion_params(
+    authenticator: &Authenticator,
+    flags: u8,
+    sign_count: u32,
+    password: Option<&str>,
+) -> WebauthnModelFinishAuthenticationParams {
+    let (client_data_json, authenticator_data, signature) = authenticator.assert(flags, sign_count);
+
+    WebauthnModelFinishAuthenticationParams {
+        credential_id: CREDENTIAL_ID.to_string(),
+        client_data_json,
+        authenticator_data,
+        signature,
+        password: password.map(str::to_string),
+    }
+}
+
+#[tokio::test]
+async fn test_start_registration() {
+    let mut credential_repository = MockWebauthnCredentialRepository::new();
+    credential_repository
+        .expect_list_by_user()
+        .with(predicate::eq(FAKE_USER_ID.to_string()))
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(vec![fake_credential(&Authenticator::new(), 0)]) }));
+
+    let mut challenge_repository = MockWebauthnChallengeRepository::new();
+    challenge_repository
+        .expect_store()
+        .withf(|challenge| {
+            challenge.challenge == URL_SAFE_NO_PAD.encode("fake_generated_secret")
+                && challenge.ceremony == WebauthnCeremony::Registration
+                && challenge.user_id.as_deref() == Some(FAKE_USER_ID)
+        })
+        .times(1)
+        .returning(|_| Box::pin(async { Ok("stored".to_string()) }));
+
+    let model = create_model(
+        user_repository_consulting_id(),
+        credential_repository,
+        challenge_repository,
+    );
+
+    let registration = model
+        .start_registration(FAKE_USER_ID.to_string())
+        .await
+        .unwrap();
+
+    assert_eq!(registration.challenge, CHALLENGE);
+    assert_eq!(registration.relying_party.id, "localhost");
+    assert_eq!(registration.username, FAKE_USERNAME);
+    assert_eq!(registration.exclude_credentials, vec![CREDENTIAL_ID]);
+}
+
+#[tokio::test]
+async fn test_finish_registration() {
+    let authenticator = Authenticator::new();
+    let cose_key = authenticator.cose_key();
+
+    let mut credential_repository = MockWebauthnCredentialRepository::new();
+    credential_repository
+        .expect_store()
+        .with(predicate::eq(WebauthnCredentialStoreParams {
+            id: CREDENTIAL_ID.to_string(),
+            user_id: FAKE_USER_ID.to_string(),
+            name: "Security key".to_string(),
+            public_key: cose_key,
+            algorithm: COSE_ALGORITHM_ES256 as i32,
+            sign_count: 0,
+        }))
+        .times(1)
+        .returning(move |credential| {
+            Box::pin(async move {
+                Ok(WebauthnCredential {
+                    id: credential.id,
+                    user_id: credential.user_id,
+                    name: credential.name,
+                    public_key: credential.public_key,
+                    algorithm: credential.algorithm,
+                    sign_count: credential.sign_count,
+                    created_at: Utc::now().naive_utc(),
+                    last_used_at: None,
+                })
+            })
+        });
+
+    let model = create_model(
+        MockUserRepository::new(),
+        credential_repository,
+        challenge_repository_with(WebauthnCeremony::Registration, Some(FAKE_USER_ID), false),
+    );
+
+    let credential = model
+        .finish_registration(
+            FAKE_USER_ID.to_string(),
+            WebauthnModelFinishRegistrationParams {
+                name: "Security key".to_string(),
+                client_data_json: client_data("webauthn.create"),
+                attestation_object: authenticator.attestation_object(),
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(credential.id, CREDENTIAL_ID);
+}
+
+#[tokio::test]
+async fn test_finish_registration_refuses_an_authentication_challenge() {
+    let authenticator = Authenticator::new();
+
+    let model = create_model(
+        MockUserRepository::new(),
+        MockWebauthnCredentialRepository::new(),
+        challenge_repository_with(WebauthnCeremony::Authentication, None, false),
+    );
+
+    let error = model
+        .finish_registration(
+            FAKE_USER_ID.to_string(),
+            WebauthnModelFinishRegistrationParams {
+                name: "Security key".to_string(),
+                client_data_json: client_data("webauthn.create"),
+                attestation_object: authenticator.attestation_object(),
+            },
+        )
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn test_passwordless_login() {
+    let authenticator = Authenticator::new();
+
+    let mut credential_repository = credential_repository_with(fake_credential(&authenticator, 4));
+    credential_repository
+        .expect_update_sign_count()
+        .with(predicate::eq(CREDENTIAL_ID.to_string()), predicate::eq(5))
+        .times(1)
+        .returning(|_, _| Box::pin(async { Ok("updated".to_string()) }));
+
+    let model = create_model(
+        user_repository_consulting_id(),
+        credential_repository,
+        challenge_repository_with(WebauthnCeremony::Authentication, None, false),
+    );
+
+    let user = model
+        .finish_authentication(assertion_params(&authenticator, 0x05, 5, None))
+        .await
+        .unwrap();
+
+    assert_eq!(user.id, FAKE_USER_ID);
+}
+
+#[tokio::test]
+async fn test_passwordless_login_requires_user_verification() {
+    let authenticator = Authenticator::new();
+
+    let model = create_model(
+        user_repository_consulting_id(),
+        credential_repository_with(fake_credential(&authenticator, 0)),
+        challenge_repository_with(WebauthnCeremony::Authentication, None, false),
+    );
+
+    let error = model
+        .finish_authentication(assertion_params(&authenticator, 0x01, 0, None))
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, Code::Unauthenticated);
+}
+
+#[tokio::test]
+async fn test_second_factor_login_checks_the_password() {
+    let authenticator = Authenticator::new();
+
+    let model = create_model(
+        user_repository_consulting_id(),
+        credential_repository_with(fake_credential(&authenticator, 0)),
+        challenge_repository_with(WebauthnCeremony::Authentication, Some(FAKE_USER_ID), true),
+    );
+    let error = model
+        .finish_authentication(assertion_params(&authenticator, 0x01, 0, Some("wrong")))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, Code::Unauthenticated);
+
+    let mut credential_repository = credential_repository_with(fake_credential(&authenticator, 0));
+    credential_repository
+        .expect_update_sign_count()
+        .times(1)
+        .returning(|_, _| Box::pin(async { Ok("updated".to_string()) }));
+    let model = create_model(
+        user_repository_consulting_id(),
+        credential_repository,
+        challenge_repository_with(WebauthnCeremony::Authentication, Some(FAKE_USER_ID), true),
+    );
+    let user = model
+        .finish_authentication(assertion_params(
+            &authenticator,
+            0x01,
+            0,
+            Some(FAKE_PASSWORD),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(user.id, FAKE_USER_ID);
+}
+
+#[tokio::test]
+async fn test_start_second_factor_requires_username() {
+    let model = create_model(
+        MockUserRepository::new(),
+        MockWebauthnCredentialRepository::new(),
+        MockWebauthnChallengeRepository::new(),
+    );
+
+    let error = model.start_authentication(None, true).await.err().unwrap();
+
+    assert_eq!(error.code, Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn test_login_refuses_a_sign_counter_going_back() {
+    let authenticator = Authenticator::new();
+
+    let model = create_model(
+        user_repository_consulting_id(),
+        credential_repository_with(fake_credential(&authenticator, 10)),
+        challenge_repository_with(WebauthnCeremony::Authentication, None, false),
+    );
+
+    let error = model
+        .finish_authentication(assertion_params(&authenticator, 0x05, 10, None))
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, Code::PermissionDenied);
+}
+
+#[tokio::test]
+async fn test_login_with_a_passkey_of_another_user() {
+    let authenticator = Authenticator::new();
+
+    let model = create_model(
+        MockUserRepository::new(),
+        credential_repository_with(fake_credential(&authenticator, 0)),
+        challenge_repository_with(
+            WebauthnCeremony::Authentication,
+            Some("another_user"),
+            false,
+        ),
+    );
+
+    let error = model
+        .finish_authentication(assertion_params(&authenticator, 0x05, 1, None))
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.code, Code::Unauthenticated);
+}
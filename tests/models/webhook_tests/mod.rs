@@ -0,0 +1 @@
+mod webhook_model_test;
@@ -0,0 +1,151 @@
+use authentication_gRPC::{
+    dtos::models::dtos_model_webhook::WebhookModelRegisterParams,
+    error::{AppError, Code},
+    models::webhook_model::{WebhookAdministrationModel, WebhookModel, WEBHOOK_TEST_EVENT},
+    repositories::webhook_repository::{
+        MockWebhookRepository, WebhookRepositoryConsultReturn, WebhookRepositoryStoreParams,
+    },
+    security::webhook_signature::sign_webhook_payload,
+    services::webhooks::webhook_sender::MockWebhookSender,
+};
+use chrono::Utc;
+use mockall::predicate;
+
+const FAKE_ID: &str = "fake_webhook_id";
+const FAKE_URL: &str = "https://receiver.example.com/hooks";
+const FAKE_SECRET: &str = "fake_generated_secret";
+
+fn fake_id() -> String {
+    FAKE_ID.to_string()
+}
+
+fn fake_secret() -> String {
+    FAKE_SECRET.to_string()
+}
+
+fn fake_webhook() -> WebhookRepositoryConsultReturn {
+    WebhookRepositoryConsultReturn {
+        id: FAKE_ID.to_string(),
+        url: FAKE_URL.to_string(),
+        secret: FAKE_SECRET.to_string(),
+        event_types: vec!["UserRegistered".to_string()],
+        created_at: Utc::now().naive_utc(),
+    }
+}
+
+#[tokio::test]
+async fn test_register_webhook_with_generated_secret() {
+    let mut webhook_repository = MockWebhookRepository::new();
+    webhook_repository
+        .expect_store()
+        .with(predicate::eq(WebhookRepositoryStoreParams {
+            id: FAKE_ID.to_string(),
+            url: FAKE_URL.to_string(),
+            secret: FAKE_SECRET.to_string(),
+            event_types: vec!["UserRegistered".to_string()],
+        }))
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(fake_webhook()) }));
+
+    let model = WebhookModel {
+        webhook_repository,
+        sender: MockWebhookSender::new(),
+        new_id: fake_id,
+        generate_secret: fake_secret,
+    };
+
+    let webhook = model
+        .register(WebhookModelRegisterParams {
+            url: FAKE_URL.to_string(),
+            secret: None,
+            event_types: vec!["UserRegistered".to_string()],
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(webhook.id, FAKE_ID);
+    assert_eq!(webhook.secret, FAKE_SECRET);
+}
+
+#[tokio::test]
+async fn test_register_webhook_with_invalid_url_or_event() {
+    let mut webhook_repository = MockWebhookRepository::new();
+    webhook_repository.expect_store().never();
+
+    let model = WebhookModel {
+        webhook_repository,
+        sender: MockWebhookSender::new(),
+        new_id: fake_id,
+        generate_secret: fake_secret,
+    };
+
+    for (url, event_types) in [
+        ("ftp://receiver.example.com", vec![]),
+        ("not a url", vec![]),
+        (FAKE_URL, vec!["UserTeleported".to_string()]),
+    ] {
+        match model
+            .register(WebhookModelRegisterParams {
+                url: url.to_string(),
+                secret: None,
+                event_types,
+            })
+            .await
+        {
+            Ok(_) => panic!("Should have failed for {}", url),
+            Err(error) => assert_eq!(error.code, Code::InvalidArgument),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_test_webhook_sends_signed_request() {
+    let mut webhook_repository = MockWebhookRepository::new();
+    webhook_repository
+        .expect_consult_by_id()
+        .with(predicate::eq(FAKE_ID.to_string()))
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(fake_webhook()) }));
+
+    let mut sender = MockWebhookSender::new();
+    sender
+        .expect_send()
+        .withf(|request| {
+            request.url == FAKE_URL
+                && request.event_type == WEBHOOK_TEST_EVENT
+                && request.signature
+                    == sign_webhook_payload(FAKE_SECRET, request.timestamp, &request.body)
+        })
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(204) }));
+
+    let model = WebhookModel {
+        webhook_repository,
+        sender,
+        new_id: fake_id,
+        generate_secret: fake_secret,
+    };
+
+    assert_eq!(model.test(FAKE_ID.to_string()).await.unwrap(), 204);
+}
+
+#[tokio::test]
+async fn test_delete_unknown_webhook() {
+    let mut webhook_repository = MockWebhookRepository::new();
+    webhook_repository
+        .expect_delete()
+        .times(1)
+        .returning(|_| Box::pin(async { Err(AppError::new(Code::NotFound, "Webhook not found")) }));
+
+    let model = WebhookModel {
+        webhook_repository,
+        sender: MockWebhookSender::new(),
+        new_id: fake_id,
+        generate_secret: fake_secret,
+    };
+
+    match model.delete(FAKE_ID.to_string()).await {
+        Ok(_) => panic!("Should have failed"),
+        Err(error) => assert_eq!(error.code, Code::NotFound),
+    }
+}
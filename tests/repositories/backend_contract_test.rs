@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use authentication_gRPC::{
+    config::app_config::Config,
+    database::{
+        connection::lazy_postgres_pool,
+        in_memory::InMemoryStore,
+        redis_pool::RedisPool,
+        storage::Storage,
+        utils::repository_contract::{
+            store_contract_user, user_repository_contract, users_code_repository_contract,
+            CONTRACT_USER_ID,
+        },
+    },
+    rpc::authentication::{create_user_repository, create_users_code_repository},
+    AppState,
+};
+
+/// State of a server started with `--in-memory`. Neither Postgres nor Redis is reached.
+fn in_memory_app_state() -> AppState {
+    let config = Config::from_sources(None, &|name| match name {
+        "STORAGE_BACKEND" => Some("memory".to_string()),
+        "MAIL_LOG_ONLY" => Some("true".to_string()),
+        _ => None,
+    })
+    .unwrap();
+
+    AppState {
+        db_pg_pool: lazy_postgres_pool(&config.database).unwrap(),
+        redis: RedisPool::open(&config.redis).unwrap(),
+        storage: Storage::InMemory(InMemoryStore::default()),
+        config: Arc::new(config),
+    }
+}
+
+#[tokio::test]
+async fn test_user_repository_of_the_models_follows_the_contract() {
+    let app_state = in_memory_app_state();
+
+    user_repository_contract(&create_user_repository(&app_state)).await;
+}
+
+#[tokio::test]
+async fn test_users_code_repository_of_the_models_follows_the_contract() {
+    let app_state = in_memory_app_state();
+    store_contract_user(&create_user_repository(&app_state)).await;
+
+    users_code_repository_contract(
+        |purpose| create_users_code_repository(&app_state, purpose),
+        CONTRACT_USER_ID,
+    )
+    .await;
+}
@@ -0,0 +1 @@
+mod backend_contract_test;
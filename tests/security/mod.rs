@@ -0,0 +1 @@
+mod tls_test;
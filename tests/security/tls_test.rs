@@ -0,0 +1,224 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use authentication_gRPC::{
+    config::app_config::{Config, TlsConfig},
+    database::{connection::lazy_postgres_pool, redis_pool::RedisPool, storage::Storage},
+    rpc::authentication::{
+        authentication::{
+            authentication_client::AuthenticationClient,
+            authentication_server::AuthenticationServer, ReqLogin,
+        },
+        AuthenticationService,
+    },
+    security::tls::{tls_incoming, ServerTls},
+    AppState,
+};
+use rcgen::{BasicConstraints, Certificate, CertificateParams, IsCa};
+use tokio::net::TcpListener;
+use tonic::{
+    transport::{self, Channel, ClientTlsConfig, Identity, Server},
+    Code,
+};
+
+/// Certificate authority generated for a single test.
+struct TestAuthority {
+    certificate: Certificate,
+}
+
+impl TestAuthority {
+    fn new() -> TestAuthority {
+        let mut params = CertificateParams::new(vec![]);
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        TestAuthority {
+            certificate: Certificate::from_params(params).unwrap(),
+        }
+    }
+
+    fn pem(&self) -> String {
+        self.certificate.serialize_pem().unwrap()
+    }
+
+    /// Certificate chain and private key PEMs of a leaf for `name`.
+    fn issue(&self, name: &str) -> (String, String) {
+        let leaf =
+            Certificate::from_params(CertificateParams::new(vec![name.to_string()])).unwrap();
+        (
+            leaf.serialize_pem_with_signer(&self.certificate).unwrap(),
+            leaf.serialize_private_key_pem(),
+        )
+    }
+}
+
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("authentication_tls_{}", name));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_server_files(dir: &Path, server_authority: &TestAuthority) {
+    let (cert, key) = server_authority.issue("localhost");
+    fs::write(dir.join("server.pem"), cert).unwrap();
+    fs::write(dir.join("server.key"), key).unwrap();
+}
+
+fn tls_config(dir: &Path, client_ca: Option<&TestAuthority>) -> TlsConfig {
+    let client_ca_path = client_ca.map(|authority| {
+        let path = dir.join("client_ca.pem");
+        fs::write(&path, authority.pem()).unwrap();
+        path.to_str().unwrap().to_string()
+    });
+
+    TlsConfig {
+        cert_path: dir.join("server.pem").to_str().unwrap().to_string(),
+        key_path: dir.join("server.key").to_str().unwrap().to_string(),
+        require_client_cert: client_ca_path.is_some(),
+        client_ca_path,
+        reload_interval_seconds: 1,
+    }
+}
+
+/// Serve the gRPC service over TLS on a random port. The database is never reached,
+/// requests are refused by input validation first.
+async fn start_tls_server(tls: Arc<ServerTls>) -> String {
+    let config = Config::from_sources(None, &|name| match name {
+        "DATABASE_URL" => Some("postgres://postgres@127.0.0.1:1/unused".to_string()),
+        "REDIS_CLIENT" => Some("redis://127.0.0.1:1/".to_string()),
+        "JWT_SECRET" => Some("secret".to_string()),
+        "MAIL_LOG_ONLY" => Some("true".to_string()),
+        _ => None,
+    })
+    .unwrap();
+    let app_state = AppState {
+        db_pg_pool: lazy_postgres_pool(&config.database).unwrap(),
+        redis: RedisPool::open(&config.redis).unwrap(),
+        storage: Storage::Postgres,
+        config: Arc::new(config),
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    tokio::spawn(
+        Server::builder()
+            .add_service(AuthenticationServer::new(AuthenticationService::new(
+                app_state,
+            )))
+            .serve_with_incoming(tls_incoming(listener, tls)),
+    );
+
+    format!("https://localhost:{}", port)
+}
+
+async fn login_over_tls(
+    url: &str,
+    server_authority: &TestAuthority,
+    identity: Option<Identity>,
+) -> Result<Code, transport::Error> {
+    let mut tls = ClientTlsConfig::new()
+        .domain_name("localhost")
+        .ca_certificate(transport::Certificate::from_pem(server_authority.pem()));
+    if let Some(identity) = identity {
+        tls = tls.identity(identity);
+    }
+
+    let channel = Channel::from_shared(url.to_string())
+        .unwrap()
+        .tls_config(tls)?
+        .connect()
+        .await?;
+
+    let status = AuthenticationClient::new(channel)
+        .login(ReqLogin {
+            username: String::new(),
+            password: String::new(),
+        })
+        .await
+        .unwrap_err();
+
+    Ok(status.code())
+}
+
+/// Whether the request got past TLS to the service. With TLS 1.3 a refused client
+/// certificate may only surface on the first request rather than on connect.
+fn reaches_service(result: Result<Code, transport::Error>) -> bool {
+    matches!(result, Ok(Code::InvalidArgument))
+}
+
+#[tokio::test]
+async fn test_grpc_over_tls() {
+    let dir = test_dir("plain");
+    let server_authority = TestAuthority::new();
+    write_server_files(&dir, &server_authority);
+
+    let tls = Arc::new(ServerTls::load(tls_config(&dir, None)).unwrap());
+    let url = start_tls_server(tls).await;
+
+    let code = login_over_tls(&url, &server_authority, None).await.unwrap();
+    assert_eq!(code, Code::InvalidArgument);
+
+    let other_authority = TestAuthority::new();
+    assert!(!reaches_service(
+        login_over_tls(&url, &other_authority, None).await
+    ));
+}
+
+#[tokio::test]
+async fn test_mutual_tls_requires_client_certificate() {
+    let dir = test_dir("mutual");
+    let server_authority = TestAuthority::new();
+    let client_authority = TestAuthority::new();
+    write_server_files(&dir, &server_authority);
+
+    let tls = Arc::new(ServerTls::load(tls_config(&dir, Some(&client_authority))).unwrap());
+    let url = start_tls_server(tls).await;
+
+    let (cert, key) = client_authority.issue("service");
+    let code = login_over_tls(&url, &server_authority, Some(Identity::from_pem(cert, key)))
+        .await
+        .unwrap();
+    assert_eq!(code, Code::InvalidArgument);
+
+    assert!(!reaches_service(
+        login_over_tls(&url, &server_authority, None).await
+    ));
+
+    let (cert, key) = TestAuthority::new().issue("intruder");
+    assert!(!reaches_service(
+        login_over_tls(&url, &server_authority, Some(Identity::from_pem(cert, key))).await
+    ));
+}
+
+#[tokio::test]
+async fn test_renewed_certificate_is_served_without_restart() {
+    let dir = test_dir("reload");
+    let first_authority = TestAuthority::new();
+    write_server_files(&dir, &first_authority);
+
+    let tls = Arc::new(ServerTls::load(tls_config(&dir, None)).unwrap());
+    let url = start_tls_server(tls.clone()).await;
+
+    assert!(!tls.reload_if_changed().unwrap());
+
+    let renewed_authority = TestAuthority::new();
+    write_server_files(&dir, &renewed_authority);
+    assert!(tls.reload_if_changed().unwrap());
+
+    assert!(!reaches_service(
+        login_over_tls(&url, &first_authority, None).await
+    ));
+    let code = login_over_tls(&url, &renewed_authority, None)
+        .await
+        .unwrap();
+    assert_eq!(code, Code::InvalidArgument);
+
+    fs::write(dir.join("server.key"), "not a key").unwrap();
+    assert!(tls.reload_if_changed().is_err());
+    let code = login_over_tls(&url, &renewed_authority, None)
+        .await
+        .unwrap();
+    assert_eq!(code, Code::InvalidArgument);
+}
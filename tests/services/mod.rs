@@ -0,0 +1,3 @@
+mod outbox_dispatcher_test;
+mod user_events_watcher_test;
+mod webhook_delivery_worker_test;
@@ -0,0 +1,103 @@
+use authentication_gRPC::{
+    error::{AppError, Code},
+    repositories::user_events_outbox_repository::{MockUserEventsOutboxRepository, UserEvent},
+    services::events::{event_publisher::MockEventPublisher, outbox_dispatcher::OutboxDispatcher},
+};
+use chrono::Utc;
+use mockall::{predicate, Sequence};
+
+fn fake_event(id: i64, event_type: &str) -> UserEvent {
+    UserEvent {
+        id,
+        event_type: event_type.to_string(),
+        user_id: "fake_user_id".to_string(),
+        payload: r#"{"id":"fake_user_id"}"#.to_string(),
+        created_at: Utc::now().naive_utc(),
+    }
+}
+
+#[tokio::test]
+async fn test_dispatch_pending() {
+    let mut outbox_repository = MockUserEventsOutboxRepository::new();
+    outbox_repository
+        .expect_fetch_unpublished()
+        .with(predicate::eq(10))
+        .times(1)
+        .returning(|_| {
+            Box::pin(async {
+                Ok(vec![
+                    fake_event(1, "UserRegistered"),
+                    fake_event(2, "UserActivated"),
+                ])
+            })
+        });
+    outbox_repository
+        .expect_mark_published()
+        .times(2)
+        .returning(|_| Box::pin(async { Ok(String::from("Event marked as published")) }));
+
+    let mut publisher = MockEventPublisher::new();
+    let mut sequence = Sequence::new();
+    for (id, event_type) in [(1, "UserRegistered"), (2, "UserActivated")] {
+        publisher
+            .expect_publish()
+            .withf(move |event| event.id == id && event.event_type == event_type)
+            .times(1)
+            .in_sequence(&mut sequence)
+            .returning(|_| Box::pin(async { Ok(String::from("0-1")) }));
+    }
+
+    let dispatcher = OutboxDispatcher {
+        outbox_repository,
+        publisher,
+        batch_size: 10,
+    };
+
+    let published = dispatcher.dispatch_pending().await.unwrap();
+
+    assert_eq!(published, 2);
+}
+
+#[tokio::test]
+async fn test_dispatch_pending_stops_at_first_failure() {
+    let mut outbox_repository = MockUserEventsOutboxRepository::new();
+    outbox_repository
+        .expect_fetch_unpublished()
+        .times(1)
+        .returning(|_| {
+            Box::pin(async {
+                Ok(vec![
+                    fake_event(1, "UserRegistered"),
+                    fake_event(2, "UserActivated"),
+                ])
+            })
+        });
+    outbox_repository
+        .expect_mark_published()
+        .with(predicate::eq(1))
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(String::from("Event marked as published")) }));
+
+    let mut publisher = MockEventPublisher::new();
+    publisher
+        .expect_publish()
+        .withf(|event| event.id == 1)
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(String::from("0-1")) }));
+    publisher
+        .expect_publish()
+        .withf(|event| event.id == 2)
+        .times(1)
+        .returning(|_| Box::pin(async { Err(AppError::new(Code::Internal, "redis is down")) }));
+
+    let dispatcher = OutboxDispatcher {
+        outbox_repository,
+        publisher,
+        batch_size: 10,
+    };
+
+    match dispatcher.dispatch_pending().await {
+        Ok(_) => panic!("Expected error"),
+        Err(error) => assert_eq!(error.message, "redis is down"),
+    }
+}
@@ -0,0 +1,105 @@
+use authentication_gRPC::{
+    error::Code,
+    repositories::user_events_outbox_repository::{
+        MockUserEventsOutboxRepository, UserEvent, UserEventType,
+    },
+    services::events::user_events_watcher::{parse_event_types, UserEventsWatcher},
+};
+use chrono::Utc;
+use mockall::predicate;
+
+fn fake_event(id: i64, event_type: &str) -> UserEvent {
+    UserEvent {
+        id,
+        event_type: event_type.to_string(),
+        user_id: "fake_user_id".to_string(),
+        payload: r#"{"id":"fake_user_id"}"#.to_string(),
+        created_at: Utc::now().naive_utc(),
+    }
+}
+
+#[tokio::test]
+async fn test_poll_resumes_from_offset() {
+    let mut outbox_repository = MockUserEventsOutboxRepository::new();
+    outbox_repository
+        .expect_fetch_after()
+        .with(predicate::eq(41), predicate::eq(100))
+        .times(1)
+        .returning(|_, _| {
+            Box::pin(async {
+                Ok(vec![
+                    fake_event(42, "UserRegistered"),
+                    fake_event(43, "UserActivated"),
+                ])
+            })
+        });
+
+    let watcher = UserEventsWatcher {
+        outbox_repository,
+        event_types: vec![],
+        batch_size: 100,
+    };
+
+    let (events, next_offset) = watcher.poll(41).await.unwrap();
+
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].id, 42);
+    assert_eq!(next_offset, 43);
+}
+
+#[tokio::test]
+async fn test_poll_filters_event_types() {
+    let mut outbox_repository = MockUserEventsOutboxRepository::new();
+    outbox_repository
+        .expect_fetch_after()
+        .times(1)
+        .returning(|_, _| {
+            Box::pin(async {
+                Ok(vec![
+                    fake_event(1, "UserRegistered"),
+                    fake_event(2, "UserDeleted"),
+                    fake_event(3, "UserActivated"),
+                ])
+            })
+        });
+
+    let watcher = UserEventsWatcher {
+        outbox_repository,
+        event_types: vec![UserEventType::UserDeleted],
+        batch_size: 100,
+    };
+
+    let (events, next_offset) = watcher.poll(0).await.unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event_type, "UserDeleted");
+    assert_eq!(next_offset, 3);
+}
+
+#[tokio::test]
+async fn test_poll_without_new_events_keeps_offset() {
+    let mut outbox_repository = MockUserEventsOutboxRepository::new();
+    outbox_repository
+        .expect_fetch_after()
+        .times(1)
+        .returning(|_, _| Box::pin(async { Ok(vec![]) }));
+
+    let watcher = UserEventsWatcher {
+        outbox_repository,
+        event_types: vec![],
+        batch_size: 100,
+    };
+
+    let (events, next_offset) = watcher.poll(7).await.unwrap();
+
+    assert!(events.is_empty());
+    assert_eq!(next_offset, 7);
+}
+
+#[test]
+fn test_parse_unknown_event_type() {
+    match parse_event_types(vec!["UserBlocked".to_string(), "UserRenamed".to_string()]) {
+        Ok(_) => panic!("Expected error"),
+        Err(error) => assert_eq!(error.code, Code::InvalidArgument),
+    }
+}
@@ -0,0 +1,210 @@
+use std::{
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use authentication_gRPC::{
+    error::{AppError, Code},
+    repositories::webhook_delivery_repository::{MockWebhookDeliveryRepository, WebhookDelivery},
+    security::webhook_signature::sign_webhook_payload,
+    services::webhooks::{
+        webhook_delivery_worker::WebhookDeliveryWorker,
+        webhook_sender::{MockWebhookSender, WebhookSenderHttp},
+    },
+};
+use chrono::Utc;
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, HeaderMap, Request, Response, Server, StatusCode,
+};
+use mockall::predicate;
+
+const FAKE_SECRET: &str = "fake_webhook_secret";
+
+struct ReceivedWebhook {
+    headers: HeaderMap,
+    body: String,
+}
+
+/// Local stand-in for a webhook receiver, answering every request with `status`.
+fn start_stand_in_receiver(status: StatusCode) -> (SocketAddr, Arc<Mutex<Vec<ReceivedWebhook>>>) {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let received_by_server = received.clone();
+
+    let make_service = make_service_fn(move |_| {
+        let received = received_by_server.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                let received = received.clone();
+                async move {
+                    let headers = request.headers().clone();
+                    let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+                    received.lock().unwrap().push(ReceivedWebhook {
+                        headers,
+                        body: String::from_utf8(body.to_vec()).unwrap(),
+                    });
+
+                    let mut response = Response::new(Body::empty());
+                    *response.status_mut() = status;
+                    Ok::<_, Infallible>(response)
+                }
+            }))
+        }
+    });
+
+    let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service);
+    let addr = server.local_addr();
+    tokio::spawn(server);
+
+    (addr, received)
+}
+
+fn fake_delivery(url: String, attempts: i32) -> WebhookDelivery {
+    WebhookDelivery {
+        id: 1,
+        webhook_id: "fake_webhook_id".to_string(),
+        url,
+        secret: FAKE_SECRET.to_string(),
+        event_id: 7,
+        event_type: "UserRegistered".to_string(),
+        user_id: "fake_user_id".to_string(),
+        payload: r#"{"id":"fake_user_id"}"#.to_string(),
+        attempts,
+    }
+}
+
+fn header(headers: &HeaderMap, name: &str) -> String {
+    headers.get(name).unwrap().to_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_deliver_due_signs_request_sent_to_receiver() {
+    let (addr, received) = start_stand_in_receiver(StatusCode::OK);
+    let url = format!("http://{}/hooks", addr);
+
+    let mut delivery_repository = MockWebhookDeliveryRepository::new();
+    delivery_repository
+        .expect_fetch_due()
+        .times(1)
+        .returning(move |_| {
+            let delivery = fake_delivery(url.clone(), 0);
+            Box::pin(async move { Ok(vec![delivery]) })
+        });
+    delivery_repository
+        .expect_mark_delivered()
+        .with(predicate::eq(1))
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(String::from("Delivery marked as delivered")) }));
+    delivery_repository.expect_mark_failed().never();
+
+    let worker = WebhookDeliveryWorker {
+        delivery_repository,
+        sender: WebhookSenderHttp::new(Duration::from_secs(5)),
+        max_attempts: 3,
+        base_backoff: chrono::Duration::seconds(30),
+        batch_size: 10,
+    };
+
+    let delivered = worker.deliver_due().await.unwrap();
+    assert_eq!(delivered, 1);
+
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 1);
+
+    let webhook = &received[0];
+    let timestamp: i64 = header(&webhook.headers, "x-webhook-timestamp")
+        .parse()
+        .unwrap();
+    assert_eq!(header(&webhook.headers, "x-webhook-id"), "1");
+    assert_eq!(header(&webhook.headers, "x-webhook-event"), "UserRegistered");
+    assert_eq!(
+        header(&webhook.headers, "x-webhook-signature"),
+        sign_webhook_payload(FAKE_SECRET, timestamp, &webhook.body)
+    );
+
+    let body: serde_json::Value = serde_json::from_str(&webhook.body).unwrap();
+    assert_eq!(body["event_id"], 7);
+    assert_eq!(body["user_id"], "fake_user_id");
+    assert_eq!(body["data"]["id"], "fake_user_id");
+}
+
+#[tokio::test]
+async fn test_deliver_due_schedules_retry_with_backoff() {
+    let (addr, _) = start_stand_in_receiver(StatusCode::INTERNAL_SERVER_ERROR);
+    let url = format!("http://{}/hooks", addr);
+
+    let mut delivery_repository = MockWebhookDeliveryRepository::new();
+    delivery_repository
+        .expect_fetch_due()
+        .times(1)
+        .returning(move |_| {
+            let delivery = fake_delivery(url.clone(), 2);
+            Box::pin(async move { Ok(vec![delivery]) })
+        });
+    delivery_repository.expect_mark_delivered().never();
+
+    let before = Utc::now().naive_utc();
+    delivery_repository
+        .expect_mark_failed()
+        .withf(move |id, attempts, next_attempt_at, error| {
+            let expected = before + chrono::Duration::seconds(120);
+            *id == 1
+                && *attempts == 3
+                && next_attempt_at.is_some_and(|next| {
+                    next >= expected && next < expected + chrono::Duration::seconds(5)
+                })
+                && error.contains("500")
+        })
+        .times(1)
+        .returning(|_, _, _, _| Box::pin(async { Ok(String::from("Delivery marked as failed")) }));
+
+    let worker = WebhookDeliveryWorker {
+        delivery_repository,
+        sender: WebhookSenderHttp::new(Duration::from_secs(5)),
+        max_attempts: 5,
+        base_backoff: chrono::Duration::seconds(30),
+        batch_size: 10,
+    };
+
+    assert_eq!(worker.deliver_due().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_deliver_due_moves_delivery_to_dead_letter_after_max_attempts() {
+    let mut delivery_repository = MockWebhookDeliveryRepository::new();
+    delivery_repository
+        .expect_fetch_due()
+        .times(1)
+        .returning(|_| {
+            Box::pin(async { Ok(vec![fake_delivery("http://unreachable".to_string(), 4)]) })
+        });
+    delivery_repository
+        .expect_mark_failed()
+        .withf(|id, attempts, next_attempt_at, _| {
+            *id == 1 && *attempts == 5 && next_attempt_at.is_none()
+        })
+        .times(1)
+        .returning(|_, _, _, _| Box::pin(async { Ok(String::from("Delivery marked as failed")) }));
+
+    let mut sender = MockWebhookSender::new();
+    sender.expect_send().times(1).returning(|_| {
+        Box::pin(async {
+            Err(AppError::new(
+                Code::Internal,
+                "failed to deliver webhook: connection refused",
+            ))
+        })
+    });
+
+    let worker = WebhookDeliveryWorker {
+        delivery_repository,
+        sender,
+        max_attempts: 5,
+        base_backoff: chrono::Duration::seconds(30),
+        batch_size: 10,
+    };
+
+    assert_eq!(worker.deliver_due().await.unwrap(), 0);
+}
@@ -0,0 +1,169 @@
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+use authentication_gRPC::{
+    controllers::authentication_controller::{AuthenticationController, UserController},
+    dtos::controllers::dtos_controller_user::LoginParams,
+    error::Code,
+    repositories::{
+        user_repository::{MockUserRepository, UserRepositoryConsultReturn},
+        users_code_repository::{MockUsersCodeRepository, UsersCode},
+    },
+    security::jwt::{JWTAuthenticateToken, RegisteredClaims},
+    services::sanitizer::sanitize_authentication_input::SanitizeUser,
+    telemetry::logging::json_subscriber,
+};
+use chrono::{Duration, Utc};
+use tracing_subscriber::EnvFilter;
+
+use crate::utils::builders::UserModelBuilderForTest;
+
+const USER_ID: &str = "b7f1c5d2-logged-user-id";
+const USERNAME: &str = "logged_user";
+const PASSWORD: &str = "S3cret-Passw0rd!";
+const WRONG_PASSWORD: &str = "Wr0ng-Passw0rd!";
+const PASSWORD_HASH: &str = "$2b$08$storedpasswordhash";
+const TOKEN: &str = "eyJhbGciOiJIUzI1NiJ9.secret-payload.secret-signature";
+const CODE: &str = "activation-code-481516";
+
+#[derive(Clone, Default)]
+struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+impl Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl CapturedLogs {
+    fn lines(&self) -> Vec<serde_json::Value> {
+        String::from_utf8(self.0.lock().unwrap().clone())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+}
+
+#[tokio::test]
+async fn test_logs_are_json_with_user_id_and_without_secrets() {
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+    let _guard = tracing::subscriber::set_default(json_subscriber(
+        move || writer.clone(),
+        EnvFilter::new("trace"),
+    ));
+
+    let mut user_repository = MockUserRepository::new();
+    user_repository
+        .expect_consult_by_username()
+        .times(2)
+        .returning(|username| {
+            Box::pin(async move {
+                Ok(UserRepositoryConsultReturn {
+                    id: USER_ID.to_string(),
+                    username,
+                    email: "logged@user.com".to_string(),
+                    password: PASSWORD_HASH.to_string(),
+                    activated: false,
+                    blocked: false,
+                })
+            })
+        });
+    user_repository
+        .expect_store_update()
+        .times(1)
+        .returning(|_, _| Box::pin(async { Ok(String::from("User updated")) }));
+    let mut code_repository = MockUsersCodeRepository::new();
+    code_repository
+        .expect_get()
+        .times(1)
+        .returning(|user_id, code| {
+            Box::pin(async move {
+                Ok(UsersCode {
+                    code,
+                    expire_at: Utc::now().naive_utc() + Duration::minutes(10),
+                    user_id,
+                })
+            })
+        });
+
+    let controller = UserController {
+        model: UserModelBuilderForTest::new()
+            .mount_user_repository(user_repository)
+            .mount_code_repository(code_repository)
+            .mount_password_verify(|_, password| Ok(password == PASSWORD))
+            .build(),
+        sanitize_user: SanitizeUser,
+        jwt_encode: |_, _, _| Ok(TOKEN.to_string()),
+        jwt_decode: |_| {
+            Ok(JWTAuthenticateToken {
+                sub: USER_ID.to_string(),
+                activated: false,
+                blocked: false,
+                token_type: "user".to_string(),
+                scope: None,
+                claims: RegisteredClaims::default(),
+            })
+        },
+        jwt_decode_access: |_| panic!("UserInfo is not called"),
+    };
+
+    controller
+        .login(LoginParams {
+            username: USERNAME.to_string(),
+            password: PASSWORD.to_string(),
+        })
+        .await
+        .unwrap();
+    match controller
+        .login(LoginParams {
+            username: USERNAME.to_string(),
+            password: WRONG_PASSWORD.to_string(),
+        })
+        .await
+    {
+        Ok(_) => panic!("Should have failed"),
+        Err(error) => assert_eq!(error.code, Code::Unauthenticated),
+    }
+    controller
+        .activate_user(TOKEN.to_string(), CODE.to_string())
+        .await
+        .unwrap();
+
+    let lines = logs.lines();
+    let output = serde_json::to_string(&lines).unwrap();
+    for secret in [PASSWORD, WRONG_PASSWORD, PASSWORD_HASH, TOKEN, CODE] {
+        assert!(!output.contains(secret), "{} was logged", secret);
+    }
+
+    let closed_spans: Vec<&serde_json::Value> = lines
+        .iter()
+        .filter(|line| line["fields"]["message"] == "close")
+        .collect();
+    for name in ["login", "login_verification", "activate_user"] {
+        let span = closed_spans
+            .iter()
+            .find(|line| line["span"]["name"] == name)
+            .unwrap_or_else(|| panic!("No span {}", name));
+        assert_eq!(span["span"]["user_id"], USER_ID);
+        assert!(span["fields"]["time.busy"].is_string());
+    }
+
+    let failure = lines
+        .iter()
+        .find(|line| line["level"] == "WARN")
+        .expect("The failed login is logged");
+    assert_eq!(failure["span"]["name"], "login");
+    assert!(failure["fields"]["error"]
+        .as_str()
+        .unwrap()
+        .contains("Unauthenticated"));
+}
@@ -0,0 +1,2 @@
+mod logging_test;
+mod request_id_test;
\ No newline at end of file
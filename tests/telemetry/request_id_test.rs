@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use authentication_gRPC::{
+    config::app_config::Config,
+    database::{connection::lazy_postgres_pool, redis_pool::RedisPool, storage::Storage},
+    rpc::authentication::{
+        authentication::{
+            authentication_client::AuthenticationClient,
+            authentication_server::AuthenticationServer, ReqLogin,
+        },
+        AuthenticationService,
+    },
+    telemetry::request_id::{RequestIdLayer, REQUEST_ID_HEADER},
+    AppState,
+};
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::{transport::Server, Code, Request};
+
+/// Serve the gRPC service on a random port. The database is never reached, requests are
+/// refused by input validation first.
+async fn start_server() -> String {
+    let config = Config::from_sources(None, &|name| match name {
+        "DATABASE_URL" => Some("postgres://postgres@127.0.0.1:1/unused".to_string()),
+        "REDIS_CLIENT" => Some("redis://127.0.0.1:1/".to_string()),
+        "JWT_SECRET" => Some("secret".to_string()),
+        "MAIL_LOG_ONLY" => Some("true".to_string()),
+        _ => None,
+    })
+    .unwrap();
+    let app_state = AppState {
+        db_pg_pool: lazy_postgres_pool(&config.database).unwrap(),
+        redis: RedisPool::open(&config.redis).unwrap(),
+        storage: Storage::Postgres,
+        config: Arc::new(config),
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    tokio::spawn(
+        Server::builder()
+            .layer(RequestIdLayer)
+            .add_service(AuthenticationServer::new(AuthenticationService::new(
+                app_state,
+            )))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+
+    format!("http://127.0.0.1:{}", port)
+}
+
+async fn login(url: &str, request_id: Option<&str>) -> Option<String> {
+    let mut client = AuthenticationClient::connect(url.to_string())
+        .await
+        .unwrap();
+
+    let mut request = Request::new(ReqLogin {
+        username: String::new(),
+        password: String::new(),
+    });
+    if let Some(request_id) = request_id {
+        request
+            .metadata_mut()
+            .insert(REQUEST_ID_HEADER, request_id.parse().unwrap());
+    }
+
+    let status = client.login(request).await.unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+
+    status
+        .metadata()
+        .get(REQUEST_ID_HEADER)
+        .map(|request_id| request_id.to_str().unwrap().to_string())
+}
+
+#[tokio::test]
+async fn test_request_id_is_echoed_or_generated() {
+    let url = start_server().await;
+
+    assert_eq!(
+        login(&url, Some("caller-request-1")).await.as_deref(),
+        Some("caller-request-1")
+    );
+
+    let generated = login(&url, None).await.unwrap();
+    assert_eq!(generated.len(), 36);
+    assert_ne!(login(&url, None).await.unwrap(), generated);
+}
@@ -1,10 +1,12 @@
 use authentication_gRPC::{
+    config::app_config::DEFAULT_ACCOUNT_CODE_TTL_MINUTES,
     controllers::authentication_controller::UserController,
     models::authentication_model::{MockAuthenticationModel, UserModel},
     repositories::{
         user_repository::MockUserRepository, users_code_repository::MockUsersCodeRepository,
     },
     security::jwt::{JwtDecode, JwtEncode},
+    services::mail::mail_sender::MockMailSender,
     services::sanitizer::sanitize_authentication_input::MockSanitizeAuthentication,
     utils::hash::password::{PasswordHasher, PasswordVerify},
 };
@@ -12,6 +14,7 @@ use authentication_gRPC::{
 pub struct UserModelBuilderForTest {
     user_repository: MockUserRepository,
     user_code_repository: MockUsersCodeRepository,
+    mail_sender: MockMailSender,
     password_hasher: PasswordHasher,
     password_verify: PasswordVerify,
     new_id: fn() -> String,
@@ -23,6 +26,7 @@ impl UserModelBuilderForTest {
         Self {
             user_repository: MockUserRepository::new(),
             user_code_repository: MockUsersCodeRepository::new(),
+            mail_sender: MockMailSender::new(),
             password_hasher: |_| {
                 panic!("password_hasher could not be called by method under test or was forgotten to be assembled in UserModelBuilderForTest")
             },
@@ -48,6 +52,11 @@ impl UserModelBuilderForTest {
         self
     }
 
+    pub fn mount_mail_sender(mut self, mail_sender: MockMailSender) -> Self {
+        self.mail_sender = mail_sender;
+        self
+    }
+
     pub fn mount_password_hasher(mut self, password_hasher: PasswordHasher) -> Self {
         self.password_hasher = password_hasher;
         self
@@ -68,20 +77,23 @@ impl UserModelBuilderForTest {
         self
     }
 
-    pub fn build(self) -> UserModel<MockUserRepository, MockUsersCodeRepository> {
+    pub fn build(self) -> UserModel<MockUserRepository, MockUsersCodeRepository, MockMailSender> {
         UserModel {
             user_repository: self.user_repository,
             password_hasher: self.password_hasher,
             password_verify: self.password_verify,
             new_id: self.new_id,
             user_code_repository: self.user_code_repository,
+            mail_sender: self.mail_sender,
             generate_code: self.generate_code,
+            code_ttl_minutes: DEFAULT_ACCOUNT_CODE_TTL_MINUTES,
         }
     }
 }
 
 pub struct UserControllerBuilderForTest {
     jwt_decode: JwtDecode,
+    jwt_decode_access: JwtDecode,
     jwt_encode: JwtEncode,
     model: MockAuthenticationModel,
     sanitize_user: MockSanitizeAuthentication,
@@ -95,6 +107,9 @@ impl UserControllerBuilderForTest {
             jwt_decode: |_| {
                 panic!("jwt_decode could not be called by method under test or was forgotten to be assembled in UserControllerBuilderForTest")
             },
+            jwt_decode_access: |_| {
+                panic!("jwt_decode_access could not be called by method under test or was forgotten to be assembled in UserControllerBuilderForTest")
+            },
             jwt_encode: |_, _, _| {
                 panic!("jwt_encode could not be called by method under test or was forgotten to be assembled in UserControllerBuilderForTest")
             },
@@ -116,6 +131,11 @@ impl UserControllerBuilderForTest {
         self
     }
 
+    pub fn mount_jwt_decode_access(mut self, jwt_decode_access: JwtDecode) -> Self {
+        self.jwt_decode_access = jwt_decode_access;
+        self
+    }
+
     pub fn mount_jwt_encode(mut self, jwt_encode: JwtEncode) -> Self {
         self.jwt_encode = jwt_encode;
         self
@@ -126,6 +146,7 @@ impl UserControllerBuilderForTest {
             model: self.model,
             sanitize_user: self.sanitize_user,
             jwt_decode: self.jwt_decode,
+            jwt_decode_access: self.jwt_decode_access,
             jwt_encode: self.jwt_encode,
         }
     }